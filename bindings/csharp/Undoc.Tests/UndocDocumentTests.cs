@@ -183,6 +183,7 @@ public class ErrorKindTests
         Assert.Equal(11, (int)UndocErrorKind.ResourceNotFound);
         Assert.Equal(12, (int)UndocErrorKind.Encrypted);
         Assert.Equal(13, (int)UndocErrorKind.Render);
+        Assert.Equal(14, (int)UndocErrorKind.WrongPassword);
         Assert.Equal(100, (int)UndocErrorKind.InvalidArgument);
         Assert.Equal(101, (int)UndocErrorKind.Panic);
         Assert.Equal(102, (int)UndocErrorKind.InvalidOutput);
@@ -5,7 +5,7 @@ namespace Undoc;
 /// on message text.
 /// </summary>
 /// <remarks>
-/// Values 1–13 mirror the library's own failure reasons; values 100+ are raised at the
+/// Values 1–14 mirror the library's own failure reasons; values 100+ are raised at the
 /// interop boundary and have no library-side counterpart. The numbers are part of the
 /// native ABI (<c>UndocErrorKind</c> in <c>undoc.h</c>): a new reason takes the next
 /// free number and existing ones are never renumbered, so treat an unrecognised value
@@ -56,6 +56,9 @@ public enum UndocErrorKind
     /// <summary>Rendering to Markdown, text, or JSON failed.</summary>
     Render = 13,
 
+    /// <summary>The password supplied for an encrypted document is incorrect.</summary>
+    WrongPassword = 14,
+
     /// <summary>An argument was null or not valid UTF-8.</summary>
     InvalidArgument = 100,
 
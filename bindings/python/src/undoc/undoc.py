@@ -20,7 +20,7 @@ import ctypes
 class ErrorKind(IntEnum):
     """Why an undoc call failed, so callers can branch on the reason.
 
-    Values 1-13 mirror the library's own failure reasons; values 100+ are raised at
+    Values 1-14 mirror the library's own failure reasons; values 100+ are raised at
     the interop boundary and have no library-side counterpart. The numbers are part
     of the native ABI (``UndocErrorKind`` in ``undoc.h``): a new reason takes the next
     free number and existing ones are never renumbered, so an unrecognised value is
@@ -41,6 +41,7 @@ class ErrorKind(IntEnum):
     RESOURCE_NOT_FOUND = 11
     ENCRYPTED = 12
     RENDER = 13
+    WRONG_PASSWORD = 14
     INVALID_ARGUMENT = 100
     PANIC = 101
     INVALID_OUTPUT = 102
@@ -534,5 +534,5 @@ class TestErrorKind:
         assert (kinds.ZIP_ARCHIVE, kinds.XML_PARSE, kinds.INVALID_DATA) == (5, 6, 7)
         assert (kinds.MISSING_COMPONENT, kinds.ENCODING) == (8, 9)
         assert (kinds.STYLE_NOT_FOUND, kinds.RESOURCE_NOT_FOUND) == (10, 11)
-        assert (kinds.ENCRYPTED, kinds.RENDER) == (12, 13)
+        assert (kinds.ENCRYPTED, kinds.RENDER, kinds.WRONG_PASSWORD) == (12, 13, 14)
         assert (kinds.INVALID_ARGUMENT, kinds.PANIC, kinds.INVALID_OUTPUT) == (100, 101, 102)
@@ -1,6 +1,6 @@
 //! undoc CLI - Microsoft Office document extraction tool
 //!
-//! A command-line tool for extracting content from DOCX, XLSX, and PPTX files.
+//! A command-line tool for extracting content from DOCX, XLSX, XLSB, PPTX, DOC, XLS, PPT, ODT, ODS, and ODP files.
 
 mod update;
 mod writer;
@@ -11,7 +11,10 @@ use indicatif::{ProgressBar, ProgressStyle};
 use std::fs;
 use std::io::{self, Write};
 use std::path::PathBuf;
-use undoc::render::{CleanupPreset, HeadingConfig, JsonFormat, RenderOptions, TableFallback};
+use undoc::render::{
+    CleanupPreset, CommentStyle, CsvOptions, CsvQuoting, FieldHandling, HeadingConfig, HtmlOptions,
+    JsonFormat, MarkdownDialect, MergedCells, RenderOptions, TableFallback,
+};
 use writer::{MultiFormatWriter, OutputFormat, StreamingWriter};
 
 /// Microsoft Office document extraction to Markdown, text, and JSON
@@ -22,7 +25,7 @@ use writer::{MultiFormatWriter, OutputFormat, StreamingWriter};
     version,
     about = "Extract content from Office documents",
     long_about = "undoc - High-performance Microsoft Office document extraction tool.\n\n\
-                  Converts DOCX, XLSX, and PPTX files to Markdown, plain text, or JSON.\n\n\
+                  Converts DOCX, XLSX, XLSB, PPTX, DOC, XLS, PPT, ODT, ODS, and ODP files to Markdown, plain text, or JSON.\n\n\
                   Usage:\n  \
                   undoc <file>              Extract all formats to output directory\n  \
                   undoc <file> <output>     Extract to specified directory\n  \
@@ -48,6 +51,10 @@ struct Cli {
     /// link/image paths, frontmatter, section anchors)
     #[arg(long, global = true)]
     refine: bool,
+
+    /// Password for an encrypted (password-protected) document
+    #[arg(long, global = true, env = "UNDOC_PASSWORD", hide_env_values = true)]
+    password: Option<String>,
 }
 
 #[derive(Subcommand)]
@@ -125,6 +132,18 @@ enum Commands {
         #[arg(long, default_value = "markdown")]
         table_mode: TableMode,
 
+        /// Markdown dialect to write
+        #[arg(long, default_value = "gfm")]
+        dialect: Dialect,
+
+        /// Write DOCX review comments where they are anchored
+        #[arg(long, default_value = "omit")]
+        comments: Comments,
+
+        /// How DOCX field results (page numbers, cross-references) are written
+        #[arg(long, default_value = "results")]
+        fields: Fields,
+
         /// Apply text cleanup
         #[arg(long)]
         cleanup: Option<CleanupMode>,
@@ -192,6 +211,78 @@ enum Commands {
         compact: bool,
     },
 
+    /// Re-render a document saved by `undoc json`, without the source file
+    Render {
+        /// JSON file written by `undoc json`
+        input: PathBuf,
+
+        /// Output format
+        #[arg(short, long, default_value = "markdown")]
+        to: RenderFormat,
+
+        /// Output file path (default: stdout)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Include YAML frontmatter with metadata (Markdown only)
+        #[arg(short, long)]
+        frontmatter: bool,
+
+        /// Table rendering mode (Markdown only)
+        #[arg(long, default_value = "markdown")]
+        table_mode: TableMode,
+
+        /// Markdown dialect to write (Markdown only)
+        #[arg(long, default_value = "gfm")]
+        dialect: Dialect,
+
+        /// Write DOCX review comments where they are anchored (Markdown only)
+        #[arg(long, default_value = "omit")]
+        comments: Comments,
+
+        /// How DOCX field results (page numbers, cross-references) are written
+        #[arg(long, default_value = "results")]
+        fields: Fields,
+
+        /// Apply text cleanup (Markdown and text)
+        #[arg(long)]
+        cleanup: Option<CleanupMode>,
+
+        /// Maximum heading level (1-6, default: 4; Markdown only)
+        #[arg(long, default_value = "4")]
+        max_heading: u8,
+
+        /// Include DOCX section headers/footers (Markdown and HTML)
+        #[arg(long)]
+        include_headers_footers: bool,
+    },
+
+    /// Export the tables of each sheet (section) to its own CSV file
+    Csv {
+        /// Input file path
+        input: PathBuf,
+
+        /// Output directory (default: <filename>_csv)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+
+        /// Field delimiter: a single character, or "tab" for TSV
+        #[arg(short, long, default_value = ",")]
+        delimiter: String,
+
+        /// When to quote fields
+        #[arg(long, default_value = "necessary")]
+        quote: QuoteMode,
+
+        /// Repeat a merged cell's value in every cell it covers (default: blank)
+        #[arg(long)]
+        repeat_merged: bool,
+
+        /// Write stored cell values (e.g. date serial numbers) instead of formatted text
+        #[arg(long)]
+        raw: bool,
+    },
+
     /// Show document information and metadata
     Info {
         /// Input file path
@@ -230,7 +321,7 @@ enum TableMode {
     Markdown,
     /// HTML tables (for complex layouts)
     Html,
-    /// ASCII art tables
+    /// Box-drawn text tables (merged cells drawn as spans)
     Ascii,
 }
 
@@ -244,6 +335,107 @@ impl From<TableMode> for TableFallback {
     }
 }
 
+/// Markdown dialect
+#[derive(Clone, ValueEnum)]
+enum Dialect {
+    /// GitHub Flavored Markdown
+    Gfm,
+    /// Strict CommonMark (tables and footnotes as HTML)
+    Commonmark,
+    /// Pandoc Markdown (grid tables)
+    Pandoc,
+    /// MultiMarkdown 6
+    Multimarkdown,
+}
+
+impl From<Dialect> for MarkdownDialect {
+    fn from(dialect: Dialect) -> Self {
+        match dialect {
+            Dialect::Gfm => MarkdownDialect::Gfm,
+            Dialect::Commonmark => MarkdownDialect::CommonMark,
+            Dialect::Pandoc => MarkdownDialect::Pandoc,
+            Dialect::Multimarkdown => MarkdownDialect::MultiMarkdown,
+        }
+    }
+}
+
+/// How review comments are written
+#[derive(Clone, ValueEnum)]
+enum Comments {
+    /// Leave comments out
+    Omit,
+    /// Footnote markers with the comments defined at the end
+    Footnotes,
+    /// HTML comments, hidden when the Markdown is displayed
+    Html,
+}
+
+impl From<Comments> for CommentStyle {
+    fn from(comments: Comments) -> Self {
+        match comments {
+            Comments::Omit => CommentStyle::Omit,
+            Comments::Footnotes => CommentStyle::Footnotes,
+            Comments::Html => CommentStyle::Html,
+        }
+    }
+}
+
+/// How field results are written
+#[derive(Clone, ValueEnum)]
+enum Fields {
+    /// Results as stored
+    Results,
+    /// Leave out page numbers
+    DropPageNumbers,
+    /// Link cross-references to their bookmarks
+    LinkReferences,
+}
+
+impl From<Fields> for FieldHandling {
+    fn from(fields: Fields) -> Self {
+        match fields {
+            Fields::Results => FieldHandling::ShowResults,
+            Fields::DropPageNumbers => FieldHandling::DropPageNumbers,
+            Fields::LinkReferences => FieldHandling::LinkReferences,
+        }
+    }
+}
+
+/// Output format of `undoc render`
+#[derive(Clone, ValueEnum)]
+enum RenderFormat {
+    /// Markdown
+    Markdown,
+    /// Plain text
+    Text,
+    /// Standalone HTML
+    Html,
+}
+
+/// CSV quoting mode
+#[derive(Clone, ValueEnum)]
+enum QuoteMode {
+    /// Quote fields holding the delimiter, a quote or a line break
+    Necessary,
+    /// Quote every field
+    Always,
+    /// Quote every field that is not a number
+    NonNumeric,
+    /// Never quote
+    Never,
+}
+
+impl From<QuoteMode> for CsvQuoting {
+    fn from(mode: QuoteMode) -> Self {
+        match mode {
+            QuoteMode::Necessary => CsvQuoting::Necessary,
+            QuoteMode::Always => CsvQuoting::Always,
+            QuoteMode::NonNumeric => CsvQuoting::NonNumeric,
+            QuoteMode::Never => CsvQuoting::Never,
+        }
+    }
+}
+
 /// Cleanup mode
 #[derive(Clone, ValueEnum)]
 enum CleanupMode {
@@ -305,6 +497,8 @@ fn main() {
 }
 
 fn run(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
+    let password = cli.password.as_deref();
+
     // Handle default command (undoc <file> [output])
     if cli.command.is_none() {
         if let Some(input) = cli.input {
@@ -320,6 +514,7 @@ fn run(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
                 include_headers_footers: false,
                 lossless: false,
                 section_markers: false,
+                password,
             });
         } else {
             // No input provided, show help
@@ -373,6 +568,7 @@ fn run(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
                 include_headers_footers,
                 lossless,
                 section_markers,
+                password,
             })?;
         }
 
@@ -381,6 +577,9 @@ fn run(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
             output,
             frontmatter,
             table_mode,
+            dialect,
+            comments,
+            fields,
             cleanup,
             refine,
             max_heading,
@@ -391,7 +590,7 @@ fn run(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
         } => {
             let pb = create_spinner("Parsing document...");
 
-            let doc = undoc::parse_file(&input)?;
+            let doc = parse_input(&input, password)?;
             pb.set_message("Rendering to Markdown...");
 
             let heading_config = HeadingConfig::default().with_default_style_mapping();
@@ -405,6 +604,9 @@ fn run(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
             let mut options = base
                 .with_frontmatter(frontmatter)
                 .with_table_fallback(table_mode.into())
+                .with_dialect(dialect.into())
+                .with_comments(comments.into())
+                .with_field_handling(fields.into())
                 .with_max_heading(max_heading)
                 .with_heading_config(heading_config);
 
@@ -443,7 +645,7 @@ fn run(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
         } => {
             let pb = create_spinner("Parsing document...");
 
-            let doc = undoc::parse_file(&input)?;
+            let doc = parse_input(&input, password)?;
             pb.set_message("Rendering to text...");
 
             // Mirror Markdown/Convert: enable the heading analyzer for
@@ -484,7 +686,7 @@ fn run(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
         } => {
             let pb = create_spinner("Parsing document...");
 
-            let doc = undoc::parse_file(&input)?;
+            let doc = parse_input(&input, password)?;
             pb.set_message("Rendering to JSON...");
 
             let format = if compact {
@@ -506,11 +708,139 @@ fn run(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
             }
         }
 
+        Commands::Render {
+            input,
+            to,
+            output,
+            frontmatter,
+            table_mode,
+            dialect,
+            comments,
+            fields,
+            cleanup,
+            max_heading,
+            include_headers_footers,
+        } => {
+            let json = fs::read_to_string(&input)?;
+            let doc = undoc::Document::from_json(&json)?;
+            let field_handling = FieldHandling::from(fields);
+
+            let mut options = RenderOptions::new()
+                .with_frontmatter(frontmatter)
+                .with_table_fallback(table_mode.into())
+                .with_dialect(dialect.into())
+                .with_comments(comments.into())
+                .with_field_handling(field_handling)
+                .with_max_heading(max_heading)
+                .with_include_headers_footers(include_headers_footers)
+                .with_heading_config(HeadingConfig::default().with_default_style_mapping());
+            if let Some(preset) = cleanup.and_then(CleanupMode::to_preset) {
+                options = options.with_cleanup_preset(preset);
+            }
+
+            let (rendered, name) = match to {
+                RenderFormat::Markdown => (undoc::render::to_markdown(&doc, &options)?, "Markdown"),
+                RenderFormat::Text => (undoc::render::to_text(&doc, &options)?, "text"),
+                RenderFormat::Html => {
+                    let html_options = HtmlOptions::new()
+                        .with_include_headers_footers(include_headers_footers)
+                        .with_field_handling(field_handling);
+                    (undoc::render::to_html(&doc, &html_options)?, "HTML")
+                }
+            };
+            write_output(output.as_ref(), &rendered)?;
+
+            if let Some(output) = output {
+                println!(
+                    "{} Rendered to {}: {}",
+                    "✓".green().bold(),
+                    name,
+                    output.display()
+                );
+            }
+        }
+
+        Commands::Csv {
+            input,
+            output,
+            delimiter,
+            quote,
+            repeat_merged,
+            raw,
+        } => {
+            let delimiter = match delimiter.as_str() {
+                "tab" | "\\t" => '\t',
+                other => {
+                    let mut chars = other.chars();
+                    match (chars.next(), chars.next()) {
+                        (Some(c), None) => c,
+                        _ => {
+                            return Err(format!("delimiter must be one character: {other:?}").into())
+                        }
+                    }
+                }
+            };
+            let options = CsvOptions::new()
+                .with_delimiter(delimiter)
+                .with_quoting(quote.into())
+                .with_merged_cells(if repeat_merged {
+                    MergedCells::Repeat
+                } else {
+                    MergedCells::Blank
+                })
+                .with_raw_values(raw);
+
+            let pb = create_spinner("Parsing document...");
+
+            let doc = parse_input(&input, password)?;
+            pb.set_message("Rendering to CSV...");
+
+            let output_dir = output.unwrap_or_else(|| {
+                let stem = input.file_stem().unwrap_or_default().to_string_lossy();
+                let parent = input.parent().unwrap_or(std::path::Path::new("."));
+                parent.join(format!("{}_csv", stem))
+            });
+            fs::create_dir_all(&output_dir)?;
+
+            let extension = if delimiter == '\t' { "tsv" } else { "csv" };
+            let mut used = std::collections::HashSet::new();
+            let mut written = Vec::new();
+            let sheets = undoc::render::to_csv(&doc, &options)?;
+            for (section, csv) in doc.sections.iter().zip(sheets) {
+                let stem = csv_file_stem(section.name.as_deref(), section.index);
+                let stem = if used.insert(stem.clone()) {
+                    stem
+                } else {
+                    format!("{}_{}", stem, section.index + 1)
+                };
+                let path = output_dir.join(format!("{stem}.{extension}"));
+                fs::write(&path, csv)?;
+                written.push(path);
+            }
+
+            pb.finish_and_clear();
+
+            println!(
+                "{} Wrote {} {} file(s) to {}",
+                "✓".green().bold(),
+                written.len(),
+                extension.to_uppercase(),
+                output_dir.display()
+            );
+            for path in &written {
+                println!(
+                    "  {} {}",
+                    "✓".green(),
+                    path.file_name().unwrap_or_default().to_string_lossy()
+                );
+            }
+        }
+
         Commands::Info { input } => {
             let pb = create_spinner("Analyzing document...");
 
-            let format = undoc::detect_format_from_path(&input)?;
-            let doc = undoc::parse_file(&input)?;
+            let doc = parse_input(&input, password)?;
+            let format = doc.format;
 
             pb.finish_and_clear();
 
@@ -522,6 +852,9 @@ fn run(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
                 input.file_name().unwrap_or_default().to_string_lossy()
             );
             println!("{}: {:?}", "Format".bold(), format);
+            if doc.variant != undoc::FormatVariant::Standard {
+                println!("{}: {}", "Variant".bold(), doc.variant);
+            }
             println!("{}: {}", "Sections".bold(), doc.sections.len());
             println!("{}: {}", "Resources".bold(), doc.resources.len());
 
@@ -553,7 +886,7 @@ fn run(cli: Cli) -> Result<(), Box<dyn std::error::Error>> {
         Commands::Extract { input, output } => {
             let pb = create_spinner("Extracting resources...");
 
-            let doc = undoc::parse_file(&input)?;
+            let doc = parse_input(&input, password)?;
 
             fs::create_dir_all(&output)?;
 
@@ -609,6 +942,7 @@ struct ConvertParams<'a> {
     include_headers_footers: bool,
     lossless: bool,
     section_markers: bool,
+    password: Option<&'a str>,
 }
 
 fn run_convert(p: ConvertParams<'_>) -> Result<(), Box<dyn std::error::Error>> {
@@ -661,14 +995,19 @@ fn run_convert(p: ConvertParams<'_>) -> Result<(), Box<dyn std::error::Error>> {
 
     pb.set_message("Generating output...");
 
-    let format = undoc::detect_format_from_path(p.input)?;
+    // An encrypted package only shows its format once decrypted, and the streaming
+    // parser cannot decrypt, so it always takes the batch path.
+    let format = match undoc::detect_format_from_path(p.input) {
+        Err(undoc::Error::Encrypted) if p.password.is_some() => None,
+        format => Some(format?),
+    };
     let mfw = MultiFormatWriter::new(&output_dir, p.formats, &options);
 
     let (summary, image_count, media_count) = match format {
-        undoc::FormatType::Pptx | undoc::FormatType::Xlsx => {
+        Some(format @ (undoc::FormatType::Pptx | undoc::FormatType::Xlsx)) => {
             run_convert_streaming(p.input, p.no_images, &output_dir, mfw, format)?
         }
-        _ => run_convert_batch(p.input, p.no_images, &output_dir, mfw)?,
+        _ => run_convert_batch(p.input, p.password, p.no_images, &output_dir, mfw)?,
     };
     let word_count = summary.word_count;
 
@@ -716,11 +1055,19 @@ fn run_convert(p: ConvertParams<'_>) -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
+/// Parse the input document, decrypting it first when a password was given.
+fn parse_input(input: &std::path::Path, password: Option<&str>) -> undoc::Result<undoc::Document> {
+    match password {
+        Some(password) => undoc::parse_file_with_password(input, password),
+        None => undoc::parse_file(input),
+    }
+}
+
 fn print_version() {
     println!("{} {}", "undoc".green().bold(), env!("CARGO_PKG_VERSION"));
     println!("High-performance Microsoft Office document extraction to Markdown");
     println!();
-    println!("Supported formats: DOCX, XLSX, PPTX");
+    println!("Supported formats: DOCX, XLSX, XLSB, PPTX, DOC, XLS, PPT, ODT, ODS, ODP");
     println!("Repository: https://github.com/iyulab/undoc");
 }
 
@@ -741,11 +1088,12 @@ type ConvertResult = (writer::WriteSummary, usize, usize);
 
 fn run_convert_batch(
     input: &std::path::Path,
+    password: Option<&str>,
     no_images: bool,
     output_dir: &std::path::Path,
     mfw: MultiFormatWriter<'_>,
 ) -> Result<ConvertResult, Box<dyn std::error::Error>> {
-    let doc = undoc::parse_file(input)?;
+    let doc = parse_input(input, password)?;
     let summary = mfw.write_document(&doc)?;
     let (image_count, media_count) = extract_resources_to_dir(&doc, no_images, output_dir)?;
     Ok((summary, image_count, media_count))
@@ -859,6 +1207,26 @@ fn extract_resources_to_dir(
     Ok((image_count, media_count))
 }
 
+/// A file name for a section's CSV: its name with path and reserved characters
+/// replaced, or `section_N` when it has none.
+fn csv_file_stem(name: Option<&str>, index: usize) -> String {
+    let sanitized: String = name
+        .unwrap_or_default()
+        .trim()
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .collect();
+    if sanitized.is_empty() || sanitized.chars().all(|c| c == '.') {
+        format!("section_{}", index + 1)
+    } else {
+        sanitized
+    }
+}
+
 fn write_output(path: Option<&PathBuf>, content: &str) -> Result<(), Box<dyn std::error::Error>> {
     match path {
         Some(p) => {
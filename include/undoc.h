@@ -33,7 +33,7 @@ typedef struct UndocDocument UndocDocument;
 /**
  * Why the last call failed, as returned by undoc_last_error_kind().
  *
- * Values 1..=13 mirror the library's own failure reasons; values 100+ are raised at
+ * Values 1..=14 mirror the library's own failure reasons; values 100+ are raised at
  * the FFI boundary and have no library-side counterpart. These numbers are a stable
  * ABI contract: a new reason takes the next free number and existing ones are never
  * reused or renumbered. Treat an unrecognised value as a generic failure rather than
@@ -54,6 +54,7 @@ typedef enum UndocErrorKind {
     UNDOC_ERROR_RESOURCE_NOT_FOUND = 11,  /* A referenced resource is absent */
     UNDOC_ERROR_ENCRYPTED          = 12,  /* The document is encrypted */
     UNDOC_ERROR_RENDER             = 13,  /* Rendering the output failed */
+    UNDOC_ERROR_WRONG_PASSWORD     = 14,  /* The password for an encrypted document is wrong */
     UNDOC_ERROR_INVALID_ARGUMENT   = 100, /* An argument was NULL or not valid UTF-8 */
     UNDOC_ERROR_PANIC              = 101, /* A panic was caught at the boundary */
     UNDOC_ERROR_INVALID_OUTPUT     = 102  /* Output holds a NUL byte, cannot cross ABI */
@@ -1,6 +1,7 @@
 //! ZIP container abstraction for OOXML documents.
 
 use crate::decode::{normalize_line_endings, resolve_general_ref};
+use crate::detect::FormatVariant;
 use crate::error::{Error, Result};
 use crate::model::Metadata;
 use std::borrow::Cow;
@@ -27,6 +28,27 @@ fn entry_error(path: &str, err: zip::result::ZipError) -> Error {
     }
 }
 
+/// Relationship type namespace of Transitional OOXML (ECMA-376 / ISO/IEC 29500 Transitional).
+pub const TRANSITIONAL_RELATIONSHIPS_NS: &str =
+    "http://schemas.openxmlformats.org/officeDocument/2006/relationships/";
+
+/// Relationship type namespace of Strict OOXML (ISO/IEC 29500 Strict).
+pub const STRICT_RELATIONSHIPS_NS: &str =
+    "http://purl.oclc.org/ooxml/officeDocument/relationships/";
+
+/// Spell a relationship type the Transitional way.
+///
+/// Strict documents name the same relationships under `http://purl.oclc.org/ooxml/`;
+/// the part after the namespace (`image`, `slideLayout`, ...) is unchanged, so mapping
+/// the namespace is enough to compare either against a Transitional constant. Types
+/// outside both namespaces — package relationships, vendor extensions — pass through.
+pub fn canonical_rel_type(rel_type: &str) -> Cow<'_, str> {
+    match rel_type.strip_prefix(STRICT_RELATIONSHIPS_NS) {
+        Some(name) => Cow::Owned(format!("{TRANSITIONAL_RELATIONSHIPS_NS}{name}")),
+        None => Cow::Borrowed(rel_type),
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum RelationshipPolicy {
     Required,
@@ -38,7 +60,7 @@ enum RelationshipPolicy {
 pub struct Relationship {
     /// Relationship ID (e.g., "rId1")
     pub id: String,
-    /// Relationship type URI
+    /// Relationship type URI, as written (Transitional or Strict)
     pub rel_type: String,
     /// Target path (relative or absolute)
     pub target: String,
@@ -51,7 +73,8 @@ pub struct Relationship {
 pub struct Relationships {
     /// Map from relationship ID to relationship data
     pub by_id: HashMap<String, Relationship>,
-    /// Map from relationship type to list of relationships
+    /// Map from relationship type, in its Transitional spelling (see
+    /// [`canonical_rel_type`]), to list of relationships
     pub by_type: HashMap<String, Vec<Relationship>>,
 }
 
@@ -66,10 +89,11 @@ impl Relationships {
         self.by_id.get(id)
     }
 
-    /// Get relationships by type.
+    /// Get relationships by type. Transitional and Strict spellings of a type are
+    /// interchangeable, both in the query and in the parsed file.
     pub fn get_by_type(&self, rel_type: &str) -> Vec<&Relationship> {
         self.by_type
-            .get(rel_type)
+            .get(canonical_rel_type(rel_type).as_ref())
             .map(|v| v.iter().collect())
             .unwrap_or_default()
     }
@@ -77,7 +101,7 @@ impl Relationships {
     /// Add a relationship.
     pub fn add(&mut self, rel: Relationship) {
         self.by_type
-            .entry(rel.rel_type.clone())
+            .entry(canonical_rel_type(&rel.rel_type).into_owned())
             .or_default()
             .push(rel.clone());
         self.by_id.insert(rel.id.clone(), rel);
@@ -321,6 +345,26 @@ impl OoxmlContainer {
         Ok(data)
     }
 
+    /// Read an optional binary part, `None` when it is absent.
+    pub fn read_binary_optional(&self, path: &str) -> Result<Option<Vec<u8>>> {
+        match self.read_binary(path) {
+            Ok(data) => Ok(Some(data)),
+            Err(Error::MissingComponent(_)) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Variant named by the package's main-part content type: macro-enabled, template
+    /// or slide show. A package without `[Content_Types].xml`, or whose main part is not
+    /// one of the known types, is [`FormatVariant::Standard`].
+    pub fn format_variant(&self) -> FormatVariant {
+        self.read_xml_optional("[Content_Types].xml")
+            .ok()
+            .flatten()
+            .and_then(|content_types| crate::detect::classify_content_types(&content_types))
+            .map_or(FormatVariant::Standard, |(_, variant)| variant)
+    }
+
     /// Check if a file exists in the archive.
     pub fn exists(&self, path: &str) -> bool {
         let archive = self.archive.borrow();
@@ -580,7 +624,7 @@ fn parse_relationship_element(
         match attr.key.as_ref() {
             b"Id" => id = String::from_utf8_lossy(attr.value.as_ref()).to_string(),
             b"Type" => rel_type = String::from_utf8_lossy(attr.value.as_ref()).to_string(),
-            b"Target" => target = String::from_utf8_lossy(attr.value.as_ref()).to_string(),
+            b"Target" => target = crate::decode::attr_value_lossy(&attr),
             b"TargetMode" => {
                 external =
                     String::from_utf8_lossy(attr.value.as_ref()).eq_ignore_ascii_case("external")
@@ -701,6 +745,36 @@ mod tests {
         assert_eq!(rels.get_by_type("http://test/type1").len(), 2);
     }
 
+    #[test]
+    fn test_strict_relationship_types_match_transitional_lookups() {
+        let mut rels = Relationships::new();
+        rels.add(Relationship {
+            id: "rId1".to_string(),
+            rel_type: "http://purl.oclc.org/ooxml/officeDocument/relationships/slideLayout"
+                .to_string(),
+            target: "../slideLayouts/slideLayout1.xml".to_string(),
+            external: false,
+        });
+
+        let transitional = rels.get_by_type(
+            "http://schemas.openxmlformats.org/officeDocument/2006/relationships/slideLayout",
+        );
+        assert_eq!(transitional.len(), 1);
+        // The relationship itself keeps the type it was written with.
+        assert!(transitional[0]
+            .rel_type
+            .starts_with(STRICT_RELATIONSHIPS_NS));
+        assert_eq!(
+            rels.get_by_type("http://purl.oclc.org/ooxml/officeDocument/relationships/slideLayout")
+                .len(),
+            1
+        );
+        assert_eq!(
+            canonical_rel_type("http://schemas.openxmlformats.org/package/2006/relationships/metadata/core-properties"),
+            "http://schemas.openxmlformats.org/package/2006/relationships/metadata/core-properties"
+        );
+    }
+
     #[test]
     fn test_relationship_projection_helpers_preserve_targets_and_types() {
         let mut rels = Relationships::new();
@@ -49,8 +49,9 @@
 use std::borrow::Cow;
 
 use quick_xml::escape::resolve_predefined_entity;
+use quick_xml::events::attributes::Attribute;
 use quick_xml::events::{BytesRef, BytesText};
-use quick_xml::Reader;
+use quick_xml::{Reader, XmlVersion};
 
 use crate::error::{Error, Result};
 
@@ -177,6 +178,19 @@ pub(crate) fn decode_text_strict(text: &BytesText<'_>, location: &str) -> Result
     Ok(normalize_line_endings(Cow::Borrowed(raw)).into_owned())
 }
 
+/// Decode an attribute value into an owned `String`, resolving its entity
+/// references (`&amp;` in a hyperlink target, `&quot;` in alt text) and
+/// normalizing it as XML 1.0 specifies.
+///
+/// A value whose references do not resolve is kept verbatim, like a stray `&`
+/// in text content (see [`reader_for`]).
+pub(crate) fn attr_value_lossy(attr: &Attribute<'_>) -> String {
+    match attr.normalized_value(XmlVersion::Implicit1_0) {
+        Ok(value) => value.into_owned(),
+        Err(_) => String::from_utf8_lossy(attr.value.as_ref()).into_owned(),
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
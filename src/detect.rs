@@ -1,4 +1,4 @@
-//! Format detection for Office Open XML documents.
+//! Format detection for Office Open XML, OpenDocument and legacy binary Office documents.
 
 use crate::container::decode_xml_bytes;
 use crate::error::{Error, Result};
@@ -29,20 +29,97 @@ const CFB_MAGIC: [u8; 8] = [0xD0, 0xCF, 0x11, 0xE0, 0xA1, 0xB1, 0x1A, 0xE1];
 /// stream is the one that says "there is a document in here, behind a password".
 const ENCRYPTED_PACKAGE_STREAM: &str = "/EncryptedPackage";
 
-/// Content type for DOCX main document part.
-const DOCX_CONTENT_TYPE: &str =
-    "application/vnd.openxmlformats-officedocument.wordprocessingml.document.main+xml";
-
-/// Content type for XLSX workbook part.
-const XLSX_CONTENT_TYPE: &str =
-    "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml";
+/// Main-part content types of the OOXML packages, with the format and variant each
+/// names. Transitional and Strict (ISO/IEC 29500 Strict) packages share them: Strict
+/// changes namespaces and relationship types, not content types.
+///
+/// The XLSB workbook part says `macroEnabled` whether or not the file holds macros, so
+/// it is listed as a standard variant. That package looks like an XLSX one from the
+/// outside, but its parts are BIFF12 records.
+const OOXML_MAIN_CONTENT_TYPES: &[(&str, FormatType, FormatVariant)] = &[
+    (
+        "application/vnd.openxmlformats-officedocument.wordprocessingml.document.main+xml",
+        FormatType::Docx,
+        FormatVariant::Standard,
+    ),
+    (
+        "application/vnd.ms-word.document.macroEnabled.main+xml",
+        FormatType::Docx,
+        FormatVariant::MacroEnabled,
+    ),
+    (
+        "application/vnd.openxmlformats-officedocument.wordprocessingml.template.main+xml",
+        FormatType::Docx,
+        FormatVariant::Template,
+    ),
+    (
+        "application/vnd.ms-word.template.macroEnabledTemplate.main+xml",
+        FormatType::Docx,
+        FormatVariant::MacroEnabledTemplate,
+    ),
+    (
+        "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml",
+        FormatType::Xlsx,
+        FormatVariant::Standard,
+    ),
+    (
+        "application/vnd.ms-excel.sheet.macroEnabled.main+xml",
+        FormatType::Xlsx,
+        FormatVariant::MacroEnabled,
+    ),
+    (
+        "application/vnd.openxmlformats-officedocument.spreadsheetml.template.main+xml",
+        FormatType::Xlsx,
+        FormatVariant::Template,
+    ),
+    (
+        "application/vnd.ms-excel.template.macroEnabled.main+xml",
+        FormatType::Xlsx,
+        FormatVariant::MacroEnabledTemplate,
+    ),
+    (
+        "application/vnd.openxmlformats-officedocument.presentationml.presentation.main+xml",
+        FormatType::Pptx,
+        FormatVariant::Standard,
+    ),
+    (
+        "application/vnd.ms-powerpoint.presentation.macroEnabled.main+xml",
+        FormatType::Pptx,
+        FormatVariant::MacroEnabled,
+    ),
+    (
+        "application/vnd.openxmlformats-officedocument.presentationml.template.main+xml",
+        FormatType::Pptx,
+        FormatVariant::Template,
+    ),
+    (
+        "application/vnd.ms-powerpoint.template.macroEnabled.main+xml",
+        FormatType::Pptx,
+        FormatVariant::MacroEnabledTemplate,
+    ),
+    (
+        "application/vnd.openxmlformats-officedocument.presentationml.slideshow.main+xml",
+        FormatType::Pptx,
+        FormatVariant::Slideshow,
+    ),
+    (
+        "application/vnd.ms-powerpoint.slideshow.macroEnabled.main+xml",
+        FormatType::Pptx,
+        FormatVariant::MacroEnabledSlideshow,
+    ),
+    (
+        "application/vnd.ms-excel.sheet.binary.macroEnabled.main",
+        FormatType::Xlsb,
+        FormatVariant::Standard,
+    ),
+];
 
-/// Content type for PPTX presentation part.
-const PPTX_CONTENT_TYPE: &str =
-    "application/vnd.openxmlformats-officedocument.presentationml.presentation.main+xml";
+/// Prefix of the OpenDocument media types, which a package names in its `mimetype` entry.
+const OPENDOCUMENT_MEDIA_TYPE_PREFIX: &str = "application/vnd.oasis.opendocument.";
 
 /// Detected Office document format.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
 #[serde(rename_all = "lowercase")]
 pub enum FormatType {
     /// Microsoft Word document (.docx)
@@ -52,6 +129,20 @@ pub enum FormatType {
     Xlsx,
     /// Microsoft PowerPoint presentation (.pptx)
     Pptx,
+    /// Microsoft Excel binary workbook (.xlsb)
+    Xlsb,
+    /// Legacy Microsoft Word 97-2003 document (.doc)
+    Doc,
+    /// Legacy Microsoft Excel 97-2003 workbook (.xls)
+    Xls,
+    /// Legacy Microsoft PowerPoint 97-2003 presentation (.ppt)
+    Ppt,
+    /// OpenDocument text document (.odt)
+    Odt,
+    /// OpenDocument spreadsheet (.ods)
+    Ods,
+    /// OpenDocument presentation (.odp)
+    Odp,
 }
 
 impl FormatType {
@@ -61,6 +152,13 @@ impl FormatType {
             FormatType::Docx => "docx",
             FormatType::Xlsx => "xlsx",
             FormatType::Pptx => "pptx",
+            FormatType::Xlsb => "xlsb",
+            FormatType::Doc => "doc",
+            FormatType::Xls => "xls",
+            FormatType::Ppt => "ppt",
+            FormatType::Odt => "odt",
+            FormatType::Ods => "ods",
+            FormatType::Odp => "odp",
         }
     }
 
@@ -70,6 +168,13 @@ impl FormatType {
             FormatType::Docx => "Word Document",
             FormatType::Xlsx => "Excel Workbook",
             FormatType::Pptx => "PowerPoint Presentation",
+            FormatType::Xlsb => "Excel Binary Workbook",
+            FormatType::Doc => "Word 97-2003 Document",
+            FormatType::Xls => "Excel 97-2003 Workbook",
+            FormatType::Ppt => "PowerPoint 97-2003 Presentation",
+            FormatType::Odt => "OpenDocument Text",
+            FormatType::Ods => "OpenDocument Spreadsheet",
+            FormatType::Odp => "OpenDocument Presentation",
         }
     }
 }
@@ -80,10 +185,95 @@ impl std::fmt::Display for FormatType {
     }
 }
 
+/// The kind of package within a format: a plain document, a template, a slide show, and
+/// whether it may carry macros.
+///
+/// OOXML packages name their variant in the main-part content type, so `.docm`, `.dotx`,
+/// `.xlsm` and the rest parse as their base [`FormatType`] and report the variant here.
+/// Other formats are always [`FormatVariant::Standard`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum FormatVariant {
+    /// A plain document, workbook or presentation (.docx, .xlsx, .pptx)
+    #[default]
+    Standard,
+    /// Macro-enabled (.docm, .xlsm, .pptm)
+    MacroEnabled,
+    /// Template (.dotx, .xltx, .potx)
+    Template,
+    /// Macro-enabled template (.dotm, .xltm, .potm)
+    MacroEnabledTemplate,
+    /// PowerPoint slide show (.ppsx)
+    Slideshow,
+    /// Macro-enabled PowerPoint slide show (.ppsm)
+    MacroEnabledSlideshow,
+}
+
+impl FormatVariant {
+    /// Whether the package type allows VBA macros. A macro-enabled file need not
+    /// actually contain any.
+    pub fn is_macro_enabled(&self) -> bool {
+        matches!(
+            self,
+            FormatVariant::MacroEnabled
+                | FormatVariant::MacroEnabledTemplate
+                | FormatVariant::MacroEnabledSlideshow
+        )
+    }
+
+    /// Whether the package is a template.
+    pub fn is_template(&self) -> bool {
+        matches!(
+            self,
+            FormatVariant::Template | FormatVariant::MacroEnabledTemplate
+        )
+    }
+
+    /// Returns the file extension of this variant of `format`, such as `docm` for a
+    /// macro-enabled DOCX; the format's own extension when the pair has none.
+    pub fn extension(&self, format: FormatType) -> &'static str {
+        match (format, self) {
+            (FormatType::Docx, FormatVariant::MacroEnabled) => "docm",
+            (FormatType::Docx, FormatVariant::Template) => "dotx",
+            (FormatType::Docx, FormatVariant::MacroEnabledTemplate) => "dotm",
+            (FormatType::Xlsx, FormatVariant::MacroEnabled) => "xlsm",
+            (FormatType::Xlsx, FormatVariant::Template) => "xltx",
+            (FormatType::Xlsx, FormatVariant::MacroEnabledTemplate) => "xltm",
+            (FormatType::Pptx, FormatVariant::MacroEnabled) => "pptm",
+            (FormatType::Pptx, FormatVariant::Template) => "potx",
+            (FormatType::Pptx, FormatVariant::MacroEnabledTemplate) => "potm",
+            (FormatType::Pptx, FormatVariant::Slideshow) => "ppsx",
+            (FormatType::Pptx, FormatVariant::MacroEnabledSlideshow) => "ppsm",
+            _ => format.extension(),
+        }
+    }
+
+    /// Returns a human-readable name for this variant.
+    pub fn name(&self) -> &'static str {
+        match self {
+            FormatVariant::Standard => "Standard",
+            FormatVariant::MacroEnabled => "Macro-Enabled",
+            FormatVariant::Template => "Template",
+            FormatVariant::MacroEnabledTemplate => "Macro-Enabled Template",
+            FormatVariant::Slideshow => "Slide Show",
+            FormatVariant::MacroEnabledSlideshow => "Macro-Enabled Slide Show",
+        }
+    }
+}
+
+impl std::fmt::Display for FormatVariant {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
 /// Detect the format type from a file path.
 ///
 /// This function reads the file, verifies it's a valid ZIP archive,
 /// and inspects the `[Content_Types].xml` to determine the specific format.
+/// OLE2 compound files are classified by their streams instead: a legacy
+/// binary format is returned when its parser is compiled in.
 ///
 /// # Example
 ///
@@ -116,12 +306,40 @@ pub fn detect_format_from_bytes(data: &[u8]) -> Result<FormatType> {
     detect_format_from_reader(std::io::Cursor::new(data))
 }
 
+/// Detect the format type and its variant from a file path.
+///
+/// Like [`detect_format_from_path`], but also tells a macro-enabled, template or slide
+/// show package from a plain one.
+///
+/// # Example
+///
+/// ```no_run
+/// use undoc::detect::detect_variant_from_path;
+///
+/// let (format, variant) = detect_variant_from_path("report.docm")?;
+/// println!("{} ({}), .{}", format, variant, variant.extension(format));
+/// # Ok::<(), undoc::Error>(())
+/// ```
+#[cfg(not(target_arch = "wasm32"))]
+pub fn detect_variant_from_path(path: impl AsRef<Path>) -> Result<(FormatType, FormatVariant)> {
+    let file = File::open(path.as_ref())?;
+    detect_variant_from_reader(BufReader::new(file))
+}
+
+/// Detect the format type and its variant from a byte slice.
+pub fn detect_variant_from_bytes(data: &[u8]) -> Result<(FormatType, FormatVariant)> {
+    detect_variant_from_reader(std::io::Cursor::new(data))
+}
+
 /// Classify the container by its leading bytes.
 ///
 /// Runs before the ZIP layer gets involved, so that a file we can *recognise* but not
 /// open is reported as such instead of surfacing as a damaged archive — which would send
 /// the caller off to repair a file that is not broken.
-fn classify_container_magic<R: Read + Seek>(reader: &mut R) -> Result<()> {
+///
+/// Returns `Some` when the container is a binary format this build can open outright,
+/// and `None` when the caller should go on to the ZIP layer.
+fn classify_container_magic<R: Read + Seek>(reader: &mut R) -> Result<Option<FormatType>> {
     let mut head = [0u8; CFB_MAGIC.len()];
     let mut filled = 0;
     while filled < head.len() {
@@ -133,14 +351,14 @@ fn classify_container_magic<R: Read + Seek>(reader: &mut R) -> Result<()> {
     reader.seek(std::io::SeekFrom::Start(0))?;
 
     if filled == CFB_MAGIC.len() && head == CFB_MAGIC {
-        return Err(classify_cfb_container(reader));
+        return classify_cfb_container(reader).map(Some);
     }
 
     if filled < ZIP_MAGIC.len() || head[..ZIP_MAGIC.len()] != ZIP_MAGIC {
         return Err(Error::UnknownFormat);
     }
 
-    Ok(())
+    Ok(None)
 }
 
 /// Say which kind of CFB container this is, having established that it is one.
@@ -152,25 +370,40 @@ fn classify_container_magic<R: Read + Seek>(reader: &mut R) -> Result<()> {
 /// `UnsupportedFormat` tells them to convert the file. Reporting the disjunction leaves
 /// them to guess.
 ///
-/// Never returns `Ok`: neither kind can be opened by this library. The naming of the
-/// legacy format is best-effort — an unrecognised CFB is still reported as unsupported,
-/// which is what the header proved.
-fn classify_cfb_container<R: Read + Seek>(reader: &mut R) -> Error {
+/// Returns `Ok` only for a legacy format whose parser is compiled in; everything else
+/// is an error naming what was found. The naming of the legacy format is best-effort —
+/// an unrecognised CFB is still reported as unsupported, which is what the header proved.
+fn classify_cfb_container<R: Read + Seek>(reader: &mut R) -> Result<FormatType> {
     let container = match cfb::CompoundFile::open(reader) {
         Ok(container) => container,
         // A CFB header whose directory will not parse. Still an Office container, still
         // unopenable; guessing which kind would claim more than was established.
         Err(_) => {
-            return Error::UnsupportedFormat(
+            return Err(Error::UnsupportedFormat(
                 "OLE/CFB container whose directory could not be read — a legacy binary \
                  Office format (.doc/.xls/.ppt) or an ECMA-376 encrypted document"
                     .to_string(),
-            )
+            ))
         }
     };
 
     if container.exists(ENCRYPTED_PACKAGE_STREAM) {
-        return Error::Encrypted;
+        return Err(Error::Encrypted);
+    }
+
+    #[cfg(feature = "doc")]
+    if container.exists("/WordDocument") {
+        return Ok(FormatType::Doc);
+    }
+
+    #[cfg(feature = "xls")]
+    if container.exists("/Workbook") || container.exists("/Book") {
+        return Ok(FormatType::Xls);
+    }
+
+    #[cfg(feature = "ppt")]
+    if container.exists("/PowerPoint Document") {
+        return Ok(FormatType::Ppt);
     }
 
     // Well-known root streams of the pre-2007 binary formats. Checked only to make the
@@ -185,12 +418,12 @@ fn classify_cfb_container<R: Read + Seek>(reader: &mut R) -> Error {
         None
     };
 
-    Error::UnsupportedFormat(match legacy {
+    Err(Error::UnsupportedFormat(match legacy {
         Some(format) => format!("legacy binary Office format: {format}"),
         None => "OLE/CFB container that is not a recognised Office document or encrypted \
              OOXML package"
             .to_string(),
-    })
+    }))
 }
 
 /// Detect the format type from a reader.
@@ -201,11 +434,26 @@ fn classify_cfb_container<R: Read + Seek>(reader: &mut R) -> Error {
 /// data prepended to it (a self-extracting stub, say) is reported as
 /// [`Error::UnknownFormat`] instead of being recovered from its central directory.
 pub fn detect_format_from_reader<R: Read + Seek>(reader: R) -> Result<FormatType> {
+    detect_variant_from_reader(reader).map(|(format, _)| format)
+}
+
+/// Detect the format type and its variant from a reader.
+///
+/// See [`detect_format_from_reader`] for how the reader is consumed.
+pub fn detect_variant_from_reader<R: Read + Seek>(
+    reader: R,
+) -> Result<(FormatType, FormatVariant)> {
     let mut reader = reader;
-    classify_container_magic(&mut reader)?;
+    if let Some(format) = classify_container_magic(&mut reader)? {
+        return Ok((format, FormatVariant::Standard));
+    }
 
     let mut archive = zip::ZipArchive::new(reader)?;
 
+    if let Some(format) = classify_opendocument(&mut archive)? {
+        return Ok((format, FormatVariant::Standard));
+    }
+
     // Try to read [Content_Types].xml
     let content_types = match archive.by_name("[Content_Types].xml") {
         Ok(mut file) => {
@@ -222,16 +470,107 @@ pub fn detect_format_from_reader<R: Read + Seek>(reader: R) -> Result<FormatType
         Err(e) => return Err(Error::from(e)),
     };
 
-    // Check content types to determine format
-    if content_types.contains(DOCX_CONTENT_TYPE) {
-        Ok(FormatType::Docx)
-    } else if content_types.contains(XLSX_CONTENT_TYPE) {
-        Ok(FormatType::Xlsx)
-    } else if content_types.contains(PPTX_CONTENT_TYPE) {
-        Ok(FormatType::Pptx)
-    } else {
+    // Check content types to determine format. XLSB has to be recognised here even
+    // when its parser is left out, or the folder fallback would take its `xl/` for XLSX.
+    match classify_content_types(&content_types) {
+        Some((FormatType::Xlsb, _)) if !cfg!(feature = "xlsb") => Err(Error::UnsupportedFormat(
+            "Excel binary workbook (.xlsb)".to_string(),
+        )),
+        Some(found) => Ok(found),
         // Fallback: check for format-specific folders
-        detect_by_folder_structure(&mut archive)
+        None => {
+            detect_by_folder_structure(&mut archive).map(|format| (format, FormatVariant::Standard))
+        }
+    }
+}
+
+/// Find the format and variant named by the main-part content type in the text of a
+/// `[Content_Types].xml`.
+pub(crate) fn classify_content_types(content_types: &str) -> Option<(FormatType, FormatVariant)> {
+    OOXML_MAIN_CONTENT_TYPES
+        .iter()
+        .find(|(content_type, _, _)| content_types.contains(content_type))
+        .map(|&(_, format, variant)| (format, variant))
+}
+
+/// Classify an OpenDocument package by its media type.
+///
+/// The media type is the content of the `mimetype` entry, which the format stores first
+/// and uncompressed; a package written without it still names the type on the root
+/// entry of `META-INF/manifest.xml`. Returns `None` when the archive is not an
+/// OpenDocument package, and an error naming the type when it is one this build cannot
+/// open.
+fn classify_opendocument<R: Read + Seek>(
+    archive: &mut zip::ZipArchive<R>,
+) -> Result<Option<FormatType>> {
+    let media_type = match read_archive_entry(archive, "mimetype")? {
+        Some(bytes) => Some(String::from_utf8_lossy(&bytes).trim().to_string()),
+        None => match read_archive_entry(archive, "META-INF/manifest.xml")? {
+            Some(bytes) => manifest_media_type(&decode_xml_bytes(&bytes)?),
+            None => None,
+        },
+    };
+    let Some(media_type) = media_type else {
+        return Ok(None);
+    };
+    let Some(kind) = media_type.strip_prefix(OPENDOCUMENT_MEDIA_TYPE_PREFIX) else {
+        return Ok(None);
+    };
+
+    match kind {
+        #[cfg(feature = "odt")]
+        "text" | "text-template" => Ok(Some(FormatType::Odt)),
+        #[cfg(feature = "ods")]
+        "spreadsheet" | "spreadsheet-template" => Ok(Some(FormatType::Ods)),
+        #[cfg(feature = "odp")]
+        "presentation" | "presentation-template" => Ok(Some(FormatType::Odp)),
+        _ => Err(Error::UnsupportedFormat(format!(
+            "OpenDocument package of type {media_type}"
+        ))),
+    }
+}
+
+/// Read an archive entry, `None` when it is absent.
+fn read_archive_entry<R: Read + Seek>(
+    archive: &mut zip::ZipArchive<R>,
+    name: &str,
+) -> Result<Option<Vec<u8>>> {
+    match archive.by_name(name) {
+        Ok(mut file) => {
+            let mut bytes = Vec::new();
+            file.read_to_end(&mut bytes)?;
+            Ok(Some(bytes))
+        }
+        Err(zip::result::ZipError::FileNotFound) => Ok(None),
+        Err(e) => Err(Error::from(e)),
+    }
+}
+
+/// The media type the manifest gives the package root (`manifest:full-path="/"`).
+fn manifest_media_type(manifest: &str) -> Option<String> {
+    use quick_xml::events::Event;
+
+    let mut reader = quick_xml::Reader::from_str(manifest);
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(e) | Event::Empty(e)) if e.local_name().as_ref() == b"file-entry" => {
+                let mut path = None;
+                let mut media_type = None;
+                for attr in e.attributes().flatten() {
+                    let value = String::from_utf8_lossy(&attr.value).into_owned();
+                    match attr.key.local_name().as_ref() {
+                        b"full-path" => path = Some(value),
+                        b"media-type" => media_type = Some(value),
+                        _ => {}
+                    }
+                }
+                if path.as_deref() == Some("/") {
+                    return media_type;
+                }
+            }
+            Ok(Event::Eof) | Err(_) => return None,
+            _ => {}
+        }
     }
 }
 
@@ -268,6 +607,16 @@ mod tests {
         assert_eq!(FormatType::Docx.to_string(), "Word Document");
         assert_eq!(FormatType::Xlsx.to_string(), "Excel Workbook");
         assert_eq!(FormatType::Pptx.to_string(), "PowerPoint Presentation");
+        assert_eq!(FormatType::Doc.to_string(), "Word 97-2003 Document");
+        assert_eq!(FormatType::Xls.to_string(), "Excel 97-2003 Workbook");
+        assert_eq!(
+            FormatType::Ppt.to_string(),
+            "PowerPoint 97-2003 Presentation"
+        );
+        assert_eq!(FormatType::Odt.to_string(), "OpenDocument Text");
+        assert_eq!(FormatType::Ods.to_string(), "OpenDocument Spreadsheet");
+        assert_eq!(FormatType::Odp.to_string(), "OpenDocument Presentation");
+        assert_eq!(FormatType::Xlsb.to_string(), "Excel Binary Workbook");
     }
 
     #[test]
@@ -275,6 +624,13 @@ mod tests {
         assert_eq!(FormatType::Docx.extension(), "docx");
         assert_eq!(FormatType::Xlsx.extension(), "xlsx");
         assert_eq!(FormatType::Pptx.extension(), "pptx");
+        assert_eq!(FormatType::Doc.extension(), "doc");
+        assert_eq!(FormatType::Xls.extension(), "xls");
+        assert_eq!(FormatType::Ppt.extension(), "ppt");
+        assert_eq!(FormatType::Odt.extension(), "odt");
+        assert_eq!(FormatType::Ods.extension(), "ods");
+        assert_eq!(FormatType::Odp.extension(), "odp");
+        assert_eq!(FormatType::Xlsb.extension(), "xlsb");
     }
 
     #[test]
@@ -337,11 +693,16 @@ mod tests {
 
     #[test]
     fn test_legacy_binary_office_names_the_format_it_found() {
-        for (stream, expected) in [
+        // Only the formats whose parser is compiled out; the others are detected.
+        const UNSUPPORTED: &[(&str, &str)] = &[
+            #[cfg(not(feature = "doc"))]
             ("/WordDocument", "Word 97-2003"),
+            #[cfg(not(feature = "xls"))]
             ("/Workbook", "Excel 97-2003"),
+            #[cfg(not(feature = "ppt"))]
             ("/PowerPoint Document", "PowerPoint 97-2003"),
-        ] {
+        ];
+        for &(stream, expected) in UNSUPPORTED {
             let err = detect_format_from_bytes(&cfb_with_streams(&[stream])).unwrap_err();
 
             assert_eq!(
@@ -357,6 +718,47 @@ mod tests {
         }
     }
 
+    /// With the parser compiled in, a Word binary file is a format like any other.
+    #[cfg(feature = "doc")]
+    #[test]
+    fn test_word_binary_is_detected_as_doc() {
+        let format = detect_format_from_bytes(&cfb_with_streams(&["/WordDocument"])).unwrap();
+
+        assert_eq!(format, FormatType::Doc);
+    }
+
+    /// Both stream names: `Workbook` is BIFF8, `Book` the Excel 5.0/95 name the parser
+    /// then reports as unsupported by version rather than by container.
+    #[cfg(feature = "xls")]
+    #[test]
+    fn test_excel_binary_is_detected_as_xls() {
+        for stream in ["/Workbook", "/Book"] {
+            let format = detect_format_from_bytes(&cfb_with_streams(&[stream])).unwrap();
+
+            assert_eq!(format, FormatType::Xls, "{stream}");
+        }
+    }
+
+    #[cfg(feature = "ppt")]
+    #[test]
+    fn test_powerpoint_binary_is_detected_as_ppt() {
+        let format =
+            detect_format_from_bytes(&cfb_with_streams(&["/PowerPoint Document"])).unwrap();
+
+        assert_eq!(format, FormatType::Ppt);
+    }
+
+    /// Encryption is decided before the legacy streams are looked at.
+    #[test]
+    fn test_encrypted_package_wins_over_legacy_streams() {
+        let data = cfb_with_streams(&["/EncryptedPackage", "/WordDocument"]);
+
+        assert!(matches!(
+            detect_format_from_bytes(&data),
+            Err(Error::Encrypted)
+        ));
+    }
+
     /// A CFB that is neither encrypted OOXML nor a format we can name stays unsupported.
     /// Naming is best-effort; the header is what was proved.
     #[test]
@@ -390,6 +792,204 @@ mod tests {
         assert_eq!(err.kind(), crate::ErrorKind::ZipArchive, "got: {err}");
     }
 
+    fn zip_with(files: &[(&str, &str)]) -> Vec<u8> {
+        use std::io::{Cursor, Write};
+
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let options = zip::write::SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Stored);
+        for (path, contents) in files {
+            zip.start_file(*path, options).unwrap();
+            zip.write_all(contents.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap().into_inner()
+    }
+
+    #[cfg(feature = "odt")]
+    #[test]
+    fn test_opendocument_text_is_detected_by_mimetype() {
+        let data = zip_with(&[
+            ("mimetype", "application/vnd.oasis.opendocument.text"),
+            ("content.xml", "<office:document-content/>"),
+        ]);
+        assert_eq!(detect_format_from_bytes(&data).unwrap(), FormatType::Odt);
+    }
+
+    #[cfg(feature = "ods")]
+    #[test]
+    fn test_opendocument_spreadsheet_is_detected_by_mimetype() {
+        let data = zip_with(&[("mimetype", "application/vnd.oasis.opendocument.spreadsheet")]);
+        assert_eq!(detect_format_from_bytes(&data).unwrap(), FormatType::Ods);
+    }
+
+    #[cfg(feature = "odp")]
+    #[test]
+    fn test_opendocument_presentation_is_detected_by_mimetype() {
+        let data = zip_with(&[(
+            "mimetype",
+            "application/vnd.oasis.opendocument.presentation",
+        )]);
+        assert_eq!(detect_format_from_bytes(&data).unwrap(), FormatType::Odp);
+    }
+
+    #[cfg(feature = "odt")]
+    #[test]
+    fn test_opendocument_without_mimetype_falls_back_to_manifest() {
+        let manifest = r#"<manifest:manifest
+            xmlns:manifest="urn:oasis:names:tc:opendocument:xmlns:manifest:1.0">
+            <manifest:file-entry manifest:full-path="/"
+                manifest:media-type="application/vnd.oasis.opendocument.text-template"/>
+            <manifest:file-entry manifest:full-path="content.xml" manifest:media-type="text/xml"/>
+        </manifest:manifest>"#;
+        let data = zip_with(&[("META-INF/manifest.xml", manifest)]);
+        assert_eq!(detect_format_from_bytes(&data).unwrap(), FormatType::Odt);
+    }
+
+    #[test]
+    fn test_unsupported_opendocument_type_is_named() {
+        let data = zip_with(&[("mimetype", "application/vnd.oasis.opendocument.graphics")]);
+        let err = detect_format_from_bytes(&data).unwrap_err();
+        assert!(
+            matches!(&err, Error::UnsupportedFormat(msg) if msg.contains("opendocument.graphics")),
+            "got: {err}"
+        );
+    }
+
+    #[test]
+    fn test_binary_workbook_is_not_taken_for_xlsx() {
+        let content_types = r#"<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">
+            <Override PartName="/xl/workbook.bin"
+                ContentType="application/vnd.ms-excel.sheet.binary.macroEnabled.main"/>
+        </Types>"#;
+        let data = zip_with(&[
+            ("[Content_Types].xml", content_types),
+            ("xl/workbook.bin", ""),
+        ]);
+        let result = detect_format_from_bytes(&data);
+        #[cfg(feature = "xlsb")]
+        assert_eq!(result.unwrap(), FormatType::Xlsb);
+        #[cfg(not(feature = "xlsb"))]
+        assert!(matches!(result, Err(Error::UnsupportedFormat(_))));
+    }
+
+    #[test]
+    fn test_ooxml_variants_are_detected_by_content_type() {
+        let cases = [
+            (
+                "word/document.xml",
+                "application/vnd.ms-word.document.macroEnabled.main+xml",
+                FormatType::Docx,
+                FormatVariant::MacroEnabled,
+                "docm",
+            ),
+            (
+                "word/document.xml",
+                "application/vnd.openxmlformats-officedocument.wordprocessingml.template.main+xml",
+                FormatType::Docx,
+                FormatVariant::Template,
+                "dotx",
+            ),
+            (
+                "word/document.xml",
+                "application/vnd.ms-word.template.macroEnabledTemplate.main+xml",
+                FormatType::Docx,
+                FormatVariant::MacroEnabledTemplate,
+                "dotm",
+            ),
+            (
+                "xl/workbook.xml",
+                "application/vnd.ms-excel.sheet.macroEnabled.main+xml",
+                FormatType::Xlsx,
+                FormatVariant::MacroEnabled,
+                "xlsm",
+            ),
+            (
+                "xl/workbook.xml",
+                "application/vnd.openxmlformats-officedocument.spreadsheetml.template.main+xml",
+                FormatType::Xlsx,
+                FormatVariant::Template,
+                "xltx",
+            ),
+            (
+                "ppt/presentation.xml",
+                "application/vnd.ms-powerpoint.presentation.macroEnabled.main+xml",
+                FormatType::Pptx,
+                FormatVariant::MacroEnabled,
+                "pptm",
+            ),
+            (
+                "ppt/presentation.xml",
+                "application/vnd.openxmlformats-officedocument.presentationml.template.main+xml",
+                FormatType::Pptx,
+                FormatVariant::Template,
+                "potx",
+            ),
+            (
+                "ppt/presentation.xml",
+                "application/vnd.openxmlformats-officedocument.presentationml.slideshow.main+xml",
+                FormatType::Pptx,
+                FormatVariant::Slideshow,
+                "ppsx",
+            ),
+        ];
+        for (part, content_type, format, variant, extension) in cases {
+            // Leave the part out so the folder fallback cannot hide a missed content type.
+            let content_types = format!(
+                r#"<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">
+                    <Override PartName="/{part}" ContentType="{content_type}"/>
+                </Types>"#
+            );
+            let data = zip_with(&[("[Content_Types].xml", &content_types)]);
+            assert_eq!(
+                detect_format_from_bytes(&data).unwrap(),
+                format,
+                "{extension}"
+            );
+            assert_eq!(
+                detect_variant_from_bytes(&data).unwrap(),
+                (format, variant),
+                "{extension}"
+            );
+            assert_eq!(variant.extension(format), extension);
+        }
+    }
+
+    #[test]
+    fn test_plain_and_folder_detected_packages_are_standard() {
+        let content_types = r#"<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">
+            <Override PartName="/word/document.xml"
+                ContentType="application/vnd.openxmlformats-officedocument.wordprocessingml.document.main+xml"/>
+        </Types>"#;
+        let data = zip_with(&[("[Content_Types].xml", content_types)]);
+        assert_eq!(
+            detect_variant_from_bytes(&data).unwrap(),
+            (FormatType::Docx, FormatVariant::Standard)
+        );
+
+        let data = zip_with(&[
+            ("[Content_Types].xml", "<Types/>"),
+            ("ppt/presentation.xml", ""),
+        ]);
+        assert_eq!(
+            detect_variant_from_bytes(&data).unwrap(),
+            (FormatType::Pptx, FormatVariant::Standard)
+        );
+    }
+
+    #[test]
+    fn test_format_variant_flags_and_serde() {
+        assert!(FormatVariant::MacroEnabledSlideshow.is_macro_enabled());
+        assert!(!FormatVariant::Slideshow.is_macro_enabled());
+        assert!(FormatVariant::MacroEnabledTemplate.is_template());
+        assert!(!FormatVariant::MacroEnabled.is_template());
+        assert_eq!(FormatVariant::Template.extension(FormatType::Odt), "odt");
+
+        let json = serde_json::to_string(&FormatVariant::MacroEnabledTemplate).unwrap();
+        assert_eq!(json, "\"macro_enabled_template\"");
+        let back: FormatVariant = serde_json::from_str(&json).unwrap();
+        assert_eq!(back, FormatVariant::MacroEnabledTemplate);
+    }
+
     #[test]
     fn test_detect_docx_from_file() {
         let path = "test-files/file-sample_1MB.docx";
@@ -0,0 +1,427 @@
+//! File Information Block and piece table of a Word 97-2003 document.
+//!
+//! The FIB at the start of the `WordDocument` stream is the index of the whole file: it
+//! says which table stream (`0Table` or `1Table`) holds the structures, how many
+//! characters each subdocument has, and where in the table stream every PLC lives. The
+//! piece table (the `Clx`) then maps character positions onto byte offsets in the
+//! `WordDocument` stream — text is not stored contiguously once a file has been
+//! fast-saved, so reading it any other way scrambles documents that were edited.
+
+use crate::error::{Error, Result};
+use crate::ole::{cp1252_char, u16_at, u32_at};
+
+/// `wIdent` of a Word binary document.
+const WORD_IDENT: u16 = 0xA5EC;
+
+/// Lowest `nFib` written by Word 97. Word 6.0/95 files share the stream name but use an
+/// incompatible FIB and 8-bit-only text model.
+const MIN_NFIB_WORD97: u16 = 0x00C0;
+
+/// Offset of the `fibRgLw97` member array, relative to `fibRgLw` start.
+const LW_CCP_TEXT: usize = 3;
+const LW_CCP_FTN: usize = 4;
+const LW_CCP_HDD: usize = 5;
+const LW_CCP_MCR: usize = 6;
+const LW_CCP_ATN: usize = 7;
+const LW_CCP_EDN: usize = 8;
+
+/// Indexes of the `(fc, lcb)` pairs used here, in `fibRgFcLcb97` order.
+const FC_STSHF: usize = 1;
+const FC_PLCFFND_REF: usize = 2;
+const FC_PLCFFND_TXT: usize = 3;
+const FC_PLCF_BTE_CHPX: usize = 12;
+const FC_PLCF_BTE_PAPX: usize = 13;
+const FC_CLX: usize = 33;
+const FC_PLCFEND_REF: usize = 46;
+const FC_PLCFEND_TXT: usize = 47;
+const FC_PLF_LST: usize = 73;
+const FC_PLF_LFO: usize = 74;
+
+/// A location in the table stream: byte offset and length.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(super) struct FcLcb {
+    pub fc: u32,
+    pub lcb: u32,
+}
+
+impl FcLcb {
+    /// The bytes this pair addresses, or `None` when it is empty or out of range.
+    pub fn slice<'a>(&self, stream: &'a [u8]) -> Option<&'a [u8]> {
+        if self.lcb == 0 {
+            return None;
+        }
+        let start = self.fc as usize;
+        stream.get(start..start.checked_add(self.lcb as usize)?)
+    }
+}
+
+/// The parts of the FIB this parser reads.
+#[derive(Debug, Clone, Default)]
+pub(super) struct Fib {
+    /// Whether the structures live in `1Table` rather than `0Table`.
+    pub use_1table: bool,
+    pub ccp_text: u32,
+    pub ccp_ftn: u32,
+    pub ccp_hdd: u32,
+    pub ccp_mcr: u32,
+    pub ccp_atn: u32,
+    pub ccp_edn: u32,
+    pub stshf: FcLcb,
+    pub plcffnd_ref: FcLcb,
+    pub plcffnd_txt: FcLcb,
+    pub plcfend_ref: FcLcb,
+    pub plcfend_txt: FcLcb,
+    pub plcf_bte_chpx: FcLcb,
+    pub plcf_bte_papx: FcLcb,
+    pub clx: FcLcb,
+    pub plf_lst: FcLcb,
+    pub plf_lfo: FcLcb,
+}
+
+impl Fib {
+    /// Parse the FIB from the head of the `WordDocument` stream.
+    pub fn parse(word: &[u8]) -> Result<Self> {
+        let ident = u16_at(word, 0).ok_or_else(|| truncated("FibBase"))?;
+        if ident != WORD_IDENT {
+            return Err(Error::InvalidData(format!(
+                "WordDocument stream has wIdent {ident:#06x}, expected {WORD_IDENT:#06x}"
+            )));
+        }
+        let n_fib = u16_at(word, 2).ok_or_else(|| truncated("FibBase"))?;
+        if n_fib < MIN_NFIB_WORD97 {
+            return Err(Error::UnsupportedFormat(
+                "Word 6.0/95 binary document (only Word 97-2003 is supported)".to_string(),
+            ));
+        }
+
+        let flags = u16_at(word, 0x0A).ok_or_else(|| truncated("FibBase"))?;
+        // fEncrypted covers both RC4 and XOR obfuscation; either way the text below the
+        // FIB is ciphertext and reading on would produce garbage, not an error.
+        if flags & 0x0100 != 0 {
+            return Err(Error::Encrypted);
+        }
+
+        let mut fib = Fib {
+            use_1table: flags & 0x0200 != 0,
+            ..Fib::default()
+        };
+
+        // FibBase is 32 bytes, followed by three counted arrays: fibRgW (u16s),
+        // fibRgLw (u32s) and fibRgFcLcb (u32 pairs).
+        let csw = u16_at(word, 32).ok_or_else(|| truncated("fibRgW"))? as usize;
+        let lw_count_at = 34 + csw * 2;
+        let cslw = u16_at(word, lw_count_at).ok_or_else(|| truncated("fibRgLw"))? as usize;
+        let lw_at = lw_count_at + 2;
+        let lw = |i: usize| -> u32 {
+            if i < cslw {
+                u32_at(word, lw_at + i * 4).unwrap_or(0)
+            } else {
+                0
+            }
+        };
+        fib.ccp_text = lw(LW_CCP_TEXT);
+        fib.ccp_ftn = lw(LW_CCP_FTN);
+        fib.ccp_hdd = lw(LW_CCP_HDD);
+        fib.ccp_mcr = lw(LW_CCP_MCR);
+        fib.ccp_atn = lw(LW_CCP_ATN);
+        fib.ccp_edn = lw(LW_CCP_EDN);
+
+        let fc_count_at = lw_at + cslw * 4;
+        let cb_rg_fc_lcb =
+            u16_at(word, fc_count_at).ok_or_else(|| truncated("fibRgFcLcb"))? as usize;
+        let fc_at = fc_count_at + 2;
+        let pair = |i: usize| -> FcLcb {
+            if i >= cb_rg_fc_lcb {
+                return FcLcb::default();
+            }
+            let at = fc_at + i * 8;
+            FcLcb {
+                fc: u32_at(word, at).unwrap_or(0),
+                lcb: u32_at(word, at + 4).unwrap_or(0),
+            }
+        };
+        fib.stshf = pair(FC_STSHF);
+        fib.plcffnd_ref = pair(FC_PLCFFND_REF);
+        fib.plcffnd_txt = pair(FC_PLCFFND_TXT);
+        fib.plcfend_ref = pair(FC_PLCFEND_REF);
+        fib.plcfend_txt = pair(FC_PLCFEND_TXT);
+        fib.plcf_bte_chpx = pair(FC_PLCF_BTE_CHPX);
+        fib.plcf_bte_papx = pair(FC_PLCF_BTE_PAPX);
+        fib.clx = pair(FC_CLX);
+        fib.plf_lst = pair(FC_PLF_LST);
+        fib.plf_lfo = pair(FC_PLF_LFO);
+
+        Ok(fib)
+    }
+
+    /// Name of the table stream this FIB points at.
+    pub fn table_stream(&self) -> &'static str {
+        if self.use_1table {
+            "/1Table"
+        } else {
+            "/0Table"
+        }
+    }
+
+    /// First character position of the footnote subdocument.
+    pub fn footnote_start(&self) -> u32 {
+        self.ccp_text
+    }
+
+    /// First character position of the endnote subdocument.
+    pub fn endnote_start(&self) -> Result<u32> {
+        [self.ccp_ftn, self.ccp_hdd, self.ccp_mcr, self.ccp_atn]
+            .into_iter()
+            .try_fold(self.ccp_text, u32::checked_add)
+            .ok_or_else(|| overflow("the endnote position"))
+    }
+}
+
+/// Character positions or stream offsets past `u32`, which only a damaged or crafted
+/// file has.
+pub(super) fn overflow(what: &str) -> Error {
+    Error::InvalidData(format!("{what} is out of range"))
+}
+
+fn truncated(what: &str) -> Error {
+    Error::InvalidData(format!("WordDocument stream too short for {what}"))
+}
+
+/// One piece: a run of character positions stored contiguously in `WordDocument`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) struct Piece {
+    pub cp_start: u32,
+    pub cp_end: u32,
+    /// Byte offset of the first character in the `WordDocument` stream.
+    pub fc: u32,
+    /// 8-bit Windows-1252 text rather than UTF-16LE.
+    pub compressed: bool,
+}
+
+/// The piece table: pieces in character-position order.
+#[derive(Debug, Clone, Default)]
+pub(super) struct PieceTable {
+    pieces: Vec<Piece>,
+}
+
+impl PieceTable {
+    /// Parse the `Clx` structure: any number of `Prc` property blocks, then the `Pcdt`.
+    pub fn parse(clx: &[u8]) -> Result<Self> {
+        let mut pos = 0usize;
+        while pos < clx.len() {
+            match clx[pos] {
+                // Prc: clxt, cbGrpprl (i16), grpprl. Piece-level property modifiers are
+                // rare and formatting-only, so they are stepped over.
+                0x01 => {
+                    let cb = u16_at(clx, pos + 1).ok_or_else(|| bad_clx("Prc"))? as usize;
+                    pos += 3 + cb;
+                }
+                // Pcdt: clxt, lcb (u32), PlcPcd.
+                0x02 => {
+                    let lcb = u32_at(clx, pos + 1).ok_or_else(|| bad_clx("Pcdt"))? as usize;
+                    let plc = clx
+                        .get(pos + 5..(pos + 5).saturating_add(lcb))
+                        .ok_or_else(|| bad_clx("PlcPcd"))?;
+                    return Ok(Self::parse_plc_pcd(plc));
+                }
+                other => {
+                    return Err(Error::InvalidData(format!(
+                        "unexpected clxt {other:#04x} in piece table"
+                    )))
+                }
+            }
+        }
+        Err(bad_clx("Pcdt"))
+    }
+
+    /// `PlcPcd`: n+1 character positions, then n 8-byte piece descriptors.
+    ///
+    /// The positions go up. A piece that starts before the one kept ahead of it ends
+    /// would repeat text already read, so it is dropped; each character position is
+    /// then read at most once.
+    fn parse_plc_pcd(plc: &[u8]) -> Self {
+        let n = plc.len().saturating_sub(4) / 12;
+        let pcd_at = (n + 1) * 4;
+        let mut pieces: Vec<Piece> = Vec::new();
+        for i in 0..n {
+            let (Some(cp_start), Some(cp_end), Some(fc_raw)) = (
+                u32_at(plc, i * 4),
+                u32_at(plc, (i + 1) * 4),
+                u32_at(plc, pcd_at + i * 8 + 2),
+            ) else {
+                break;
+            };
+            if cp_end <= cp_start || pieces.last().is_some_and(|last| cp_start < last.cp_end) {
+                continue;
+            }
+            let compressed = fc_raw & 0x4000_0000 != 0;
+            let fc = fc_raw & 0x3FFF_FFFF;
+            pieces.push(Piece {
+                cp_start,
+                cp_end,
+                fc: if compressed { fc / 2 } else { fc },
+                compressed,
+            });
+        }
+        Self { pieces }
+    }
+
+    /// Read the characters in `[cp_start, cp_end)` together with the stream offset each
+    /// came from — the offset is what formatting runs are keyed on — and its character
+    /// position.
+    ///
+    /// The range comes from the FIB, which may claim far more text than the stream
+    /// holds, so only what the pieces can supply is reserved.
+    pub fn text(&self, word: &[u8], cp_start: u32, cp_end: u32) -> Result<Vec<(u16, u32, u32)>> {
+        let available: usize = self
+            .pieces
+            .iter()
+            .map(|piece| {
+                let start = piece.cp_start.max(cp_start);
+                piece.cp_end.min(cp_end).saturating_sub(start) as usize
+            })
+            .sum();
+        let mut out = Vec::with_capacity(available.min(word.len()));
+        for piece in &self.pieces {
+            let start = piece.cp_start.max(cp_start);
+            let end = piece.cp_end.min(cp_end);
+            if start >= end {
+                continue;
+            }
+            for cp in start..end {
+                let offset = cp - piece.cp_start;
+                let unit = if piece.compressed {
+                    let fc = piece
+                        .fc
+                        .checked_add(offset)
+                        .ok_or_else(|| overflow("a piece offset"))?;
+                    word.get(fc as usize)
+                        .map(|&b| (cp1252_char(b) as u32 as u16, fc, cp))
+                } else {
+                    let fc = offset
+                        .checked_mul(2)
+                        .and_then(|bytes| piece.fc.checked_add(bytes))
+                        .ok_or_else(|| overflow("a piece offset"))?;
+                    u16_at(word, fc as usize).map(|u| (u, fc, cp))
+                };
+                match unit {
+                    Some(unit) => out.push(unit),
+                    // A piece pointing past the stream end: the file is damaged from here
+                    // on, and stopping keeps what was readable.
+                    None => return Ok(out),
+                }
+            }
+        }
+        Ok(out)
+    }
+}
+
+fn bad_clx(what: &str) -> Error {
+    Error::InvalidData(format!("piece table truncated in {what}"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plc_pcd(pieces: &[(u32, u32, u32, bool)]) -> Vec<u8> {
+        let mut cps = Vec::new();
+        let mut pcds = Vec::new();
+        for (i, &(cp_start, cp_end, fc, compressed)) in pieces.iter().enumerate() {
+            cps.extend_from_slice(&cp_start.to_le_bytes());
+            if i + 1 == pieces.len() {
+                cps.extend_from_slice(&cp_end.to_le_bytes());
+            }
+            let raw = if compressed {
+                (fc * 2) | 0x4000_0000
+            } else {
+                fc
+            };
+            pcds.extend_from_slice(&0u16.to_le_bytes());
+            pcds.extend_from_slice(&raw.to_le_bytes());
+            pcds.extend_from_slice(&0u16.to_le_bytes());
+        }
+        let mut clx = vec![0x02];
+        clx.extend_from_slice(&((cps.len() + pcds.len()) as u32).to_le_bytes());
+        clx.extend_from_slice(&cps);
+        clx.extend_from_slice(&pcds);
+        clx
+    }
+
+    #[test]
+    fn test_piece_table_mixes_compressed_and_unicode_pieces() {
+        // "ab" compressed at offset 0, "한" as UTF-16 at offset 2.
+        let mut word = b"ab".to_vec();
+        word.extend_from_slice(&0xD55Cu16.to_le_bytes());
+        let clx = plc_pcd(&[(0, 2, 0, true), (2, 3, 2, false)]);
+
+        let table = PieceTable::parse(&clx).unwrap();
+        let text: Vec<u16> = table
+            .text(&word, 0, 3)
+            .unwrap()
+            .iter()
+            .map(|&(u, _, _)| u)
+            .collect();
+
+        assert_eq!(String::from_utf16(&text).unwrap(), "ab한");
+    }
+
+    #[test]
+    fn test_piece_table_skips_prc_blocks() {
+        let mut clx = vec![0x01, 0x02, 0x00, 0xAA, 0xBB];
+        clx.extend_from_slice(&plc_pcd(&[(0, 1, 0, true)]));
+
+        let table = PieceTable::parse(&clx).unwrap();
+        assert_eq!(table.text(b"z", 0, 1).unwrap(), vec![(b'z' as u16, 0, 0)]);
+    }
+
+    #[test]
+    fn test_piece_past_stream_end_stops_cleanly() {
+        let clx = plc_pcd(&[(0, 4, 0, true)]);
+        let table = PieceTable::parse(&clx).unwrap();
+        assert_eq!(table.text(b"ab", 0, 4).unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_overlapping_pieces_are_read_once() {
+        // Positions 0, 3, 1, 4: the second piece runs backwards and the third starts
+        // inside the first, which would read "bc" again.
+        let clx = plc_pcd(&[(0, 3, 0, true), (3, 1, 0, true), (1, 4, 1, true)]);
+        let table = PieceTable::parse(&clx).unwrap();
+        let units = table.text(b"abcdef", 0, 4).unwrap();
+        assert_eq!(
+            units,
+            vec![
+                (b'a' as u16, 0, 0),
+                (b'b' as u16, 1, 1),
+                (b'c' as u16, 2, 2)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_crafted_positions_are_bounded() {
+        // A FIB claiming ~4 billion characters reserves only what the pieces hold.
+        let clx = plc_pcd(&[(0, 2, 0, true)]);
+        let table = PieceTable::parse(&clx).unwrap();
+        assert_eq!(table.text(b"ab", 0, 0xFFFF_FFF0).unwrap().len(), 2);
+
+        let clx = plc_pcd(&[(0, 0xFFFF_FFFF, 0x100, false)]);
+        let table = PieceTable::parse(&clx).unwrap();
+        let err = table.text(b"ab", 0x9000_0000, 0x9000_0001).unwrap_err();
+        assert!(matches!(err, Error::InvalidData(_)), "got: {err}");
+
+        let fib = Fib {
+            ccp_text: u32::MAX,
+            ccp_ftn: 1,
+            ..Fib::default()
+        };
+        assert!(matches!(fib.endnote_start(), Err(Error::InvalidData(_))));
+    }
+
+    #[test]
+    fn test_fib_rejects_foreign_stream() {
+        let err = Fib::parse(&[0u8; 64]).unwrap_err();
+        assert!(matches!(err, Error::InvalidData(_)), "got: {err}");
+    }
+}
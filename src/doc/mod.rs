@@ -0,0 +1,24 @@
+//! DOC (Word 97-2003) document parser.
+//!
+//! This module provides parsing for legacy Microsoft Word documents in the
+//! binary (.doc) format: an OLE2 compound file whose `WordDocument` and
+//! `0Table`/`1Table` streams hold the text, its piece table and formatting.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use undoc::doc::DocParser;
+//!
+//! let mut parser = DocParser::open("document.doc")?;
+//! let doc = parser.parse()?;
+//!
+//! println!("Title: {:?}", doc.metadata.title);
+//! println!("Content: {}", doc.plain_text());
+//! # Ok::<(), undoc::Error>(())
+//! ```
+
+mod fib;
+mod parser;
+mod properties;
+
+pub use parser::DocParser;
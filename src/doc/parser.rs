@@ -0,0 +1,914 @@
+//! DOC parser implementation.
+
+use std::collections::HashMap;
+
+use crate::error::{Error, Result};
+use crate::model::{
+    Block, Cell, Document, Field, HeadingLevel, ListInfo, ListType, Metadata, Paragraph, Row,
+    Section, Table, TextRun, TextStyle,
+};
+use crate::ole::{self, CompoundFile};
+
+use super::fib::{overflow, FcLcb, Fib, PieceTable};
+use super::properties::{Chp, ListTable, Pap, PropRuns, StyleSheet};
+
+/// Paragraph mark.
+const PARAGRAPH_MARK: u16 = 0x0D;
+/// Cell mark, which is also the row-end mark of a table terminating paragraph.
+const CELL_MARK: u16 = 0x07;
+const LINE_BREAK: u16 = 0x0B;
+const PAGE_BREAK: u16 = 0x0C;
+const FIELD_BEGIN: u16 = 0x13;
+const FIELD_SEPARATOR: u16 = 0x14;
+const FIELD_END: u16 = 0x15;
+/// Auto-numbered footnote or endnote reference, when the run is `fSpec`.
+const NOTE_REFERENCE: u16 = 0x02;
+const NON_BREAKING_HYPHEN: u16 = 0x1E;
+
+/// Parser for legacy Word 97-2003 (.doc) documents.
+pub struct DocParser {
+    container: CompoundFile,
+    word: Vec<u8>,
+    fib: Fib,
+    pieces: PieceTable,
+    papx: PropRuns,
+    chpx: PropRuns,
+    styles: StyleSheet,
+    lists: ListTable,
+    /// Character position of each footnote reference → its marker label.
+    note_refs: HashMap<u32, String>,
+    /// Note definitions in output order: `(label, text)`.
+    notes: Vec<(String, String)>,
+}
+
+/// A field whose begin mark has been seen but not its end mark.
+struct OpenField {
+    instruction: String,
+    /// Past the separator: characters now belong to the displayed result.
+    in_result: bool,
+    /// Where the result links to, for a `HYPERLINK` field; set at the separator.
+    link: Option<String>,
+}
+
+/// Table being assembled from in-table paragraphs.
+#[derive(Default)]
+struct TableBuilder {
+    table: Table,
+    /// Cells of the row being read, one per cell mark, merged ones included.
+    cells: Vec<Cell>,
+    /// Paragraphs of the cell being read.
+    paragraphs: Vec<Paragraph>,
+    /// Column index → (row, cell) of the vertical merge that starts there.
+    vmerge_origins: HashMap<usize, (usize, usize)>,
+}
+
+impl TableBuilder {
+    fn close_cell(&mut self) {
+        let content = std::mem::take(&mut self.paragraphs);
+        let mut cell = Cell::new();
+        if !content.is_empty() {
+            cell.content = content;
+        }
+        self.cells.push(cell);
+    }
+
+    /// Close the row at a table terminating paragraph, applying its layout.
+    fn close_row(&mut self, pap: &Pap) {
+        if !self.paragraphs.is_empty() {
+            self.close_cell();
+        }
+        let cells = std::mem::take(&mut self.cells);
+        let def = pap.table_def.clone().unwrap_or_default();
+        if self.table.column_widths.is_none() && !def.widths.is_empty() {
+            self.table.column_widths = Some(def.widths.clone());
+        }
+
+        let row_idx = self.table.rows.len();
+        let mut row = Row {
+            is_header: pap.table_header,
+            ..Row::new()
+        };
+        for (col, mut cell) in cells.into_iter().enumerate() {
+            let layout = def.cells.get(col).copied().unwrap_or_default();
+
+            // Word writes a cell mark for every cell of the grid, including the ones a
+            // merge covers; the model keeps only the cell that owns the span.
+            if layout.merged {
+                if let Some(owner) = row.cells.last_mut() {
+                    owner.col_span += 1;
+                    continue;
+                }
+            }
+            if layout.vert_merge && !layout.vert_restart {
+                if let Some(&(r, c)) = self.vmerge_origins.get(&col) {
+                    if let Some(origin) = self
+                        .table
+                        .rows
+                        .get_mut(r)
+                        .and_then(|row| row.cells.get_mut(c))
+                    {
+                        origin.row_span += 1;
+                        continue;
+                    }
+                }
+            }
+            if layout.vert_merge {
+                self.vmerge_origins.insert(col, (row_idx, row.cells.len()));
+            } else {
+                self.vmerge_origins.remove(&col);
+            }
+
+            cell.is_header = pap.table_header;
+            row.cells.push(cell);
+        }
+        self.table.add_row(row);
+    }
+
+    fn finish(mut self) -> Option<Table> {
+        // A table interrupted before its last row-end mark: keep what was read.
+        if !self.paragraphs.is_empty() || !self.cells.is_empty() {
+            self.close_row(&Pap::default());
+        }
+        (!self.table.is_empty()).then_some(self.table)
+    }
+}
+
+impl DocParser {
+    /// Open a DOC file for parsing.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn open(path: impl AsRef<std::path::Path>) -> Result<Self> {
+        let data = std::fs::read(path)?;
+        Self::from_bytes(data)
+    }
+
+    /// Create a parser from bytes.
+    pub fn from_bytes(data: Vec<u8>) -> Result<Self> {
+        let mut container = ole::open_compound(data)?;
+
+        let word = ole::read_stream(&mut container, "/WordDocument")?;
+        let fib = Fib::parse(&word)?;
+        let table = ole::read_stream(&mut container, fib.table_stream())?;
+
+        let clx = fib.clx.slice(&table).ok_or_else(|| {
+            Error::MissingComponent(format!("piece table in {}", fib.table_stream()))
+        })?;
+        let pieces = PieceTable::parse(clx)?;
+
+        // Formatting, styles and lists are all best-effort: without them the text is
+        // still right, just plainer.
+        let papx = PropRuns::papx(&word, &table, fib.plcf_bte_papx);
+        let chpx = PropRuns::chpx(&word, &table, fib.plcf_bte_chpx);
+        let styles = fib
+            .stshf
+            .slice(&table)
+            .map(StyleSheet::parse)
+            .unwrap_or_default();
+        let lists = ListTable::parse(fib.plf_lst.slice(&table), fib.plf_lfo.slice(&table));
+
+        let mut parser = Self {
+            container,
+            word,
+            fib,
+            pieces,
+            papx,
+            chpx,
+            styles,
+            lists,
+            note_refs: HashMap::new(),
+            notes: Vec::new(),
+        };
+        parser.read_notes(&table)?;
+        Ok(parser)
+    }
+
+    /// Parse the document and return a Document model.
+    pub fn parse(&mut self) -> Result<Document> {
+        let mut doc = Document::new();
+        doc.format = crate::detect::FormatType::Doc;
+        doc.metadata = self.parse_metadata();
+
+        let mut section = self.parse_main_text()?;
+
+        // Append note definitions at end of section, as the DOCX parser does.
+        for (label, text) in &self.notes {
+            section.add_block(Block::Paragraph(Paragraph::with_text(format!(
+                "[^{}]: {}",
+                label, text
+            ))));
+        }
+
+        doc.add_section(section);
+        Ok(doc)
+    }
+
+    fn parse_metadata(&mut self) -> Metadata {
+        ole::parse_summary_information(&mut self.container)
+    }
+
+    /// Read footnote and endnote references and their text.
+    ///
+    /// References are auto-numbered in document order, so their labels follow their
+    /// index: `1`, `2`, ... for footnotes and `e1`, `e2`, ... for endnotes, the same
+    /// markers the DOCX parser emits.
+    fn read_notes(&mut self, table: &[u8]) -> Result<()> {
+        let kinds = [
+            (
+                self.fib.plcffnd_ref,
+                self.fib.plcffnd_txt,
+                self.fib.footnote_start(),
+                "",
+            ),
+            (
+                self.fib.plcfend_ref,
+                self.fib.plcfend_txt,
+                self.fib.endnote_start()?,
+                "e",
+            ),
+        ];
+        for (refs, texts, base, prefix) in kinds {
+            let ref_cps = plc_cps(refs, table, 2);
+            let text_cps = plc_cps(texts, table, 0);
+            for (i, cp) in ref_cps.iter().copied().enumerate() {
+                let label = format!("{}{}", prefix, i + 1);
+                let text = match (text_cps.get(i), text_cps.get(i + 1)) {
+                    (Some(&start), Some(&end)) => {
+                        let (Some(start), Some(end)) =
+                            (base.checked_add(start), base.checked_add(end))
+                        else {
+                            return Err(overflow("a note position"));
+                        };
+                        let units = self.pieces.text(&self.word, start, end)?;
+                        self.plain_text(&units)
+                    }
+                    _ => String::new(),
+                };
+                self.note_refs.insert(cp, label.clone());
+                self.notes.push((label, text));
+            }
+        }
+        Ok(())
+    }
+
+    /// Flatten note text to a single line: visible characters only, paragraphs joined.
+    fn plain_text(&self, units: &[(u16, u32, u32)]) -> String {
+        let mut fields: Vec<OpenField> = Vec::new();
+        let mut text: Vec<u16> = Vec::new();
+        for &(unit, fc, _) in units {
+            if self.step_field(&mut fields, unit) {
+                continue;
+            }
+            if !fields.iter().all(|f| f.in_result) || self.chpx.chp_at(fc).vanish {
+                continue;
+            }
+            match unit {
+                PARAGRAPH_MARK | CELL_MARK | LINE_BREAK | PAGE_BREAK | 0x09 => {
+                    text.push(b' ' as u16)
+                }
+                NON_BREAKING_HYPHEN => text.push(0x2011),
+                u if u < 0x20 => {}
+                u => text.push(u),
+            }
+        }
+        String::from_utf16_lossy(&text)
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    /// Track a field mark. Returns `true` when `unit` was one.
+    fn step_field(&self, fields: &mut Vec<OpenField>, unit: u16) -> bool {
+        match unit {
+            FIELD_BEGIN => fields.push(OpenField {
+                instruction: String::new(),
+                in_result: false,
+                link: None,
+            }),
+            FIELD_SEPARATOR => {
+                if let Some(field) = fields.last_mut() {
+                    field.in_result = true;
+                    field.link = Field::new(field.instruction.as_str()).link_target();
+                }
+            }
+            FIELD_END => {
+                fields.pop();
+            }
+            _ => return false,
+        }
+        true
+    }
+
+    /// Walk the main document text, paragraph by paragraph.
+    fn parse_main_text(&mut self) -> Result<Section> {
+        let units = self.pieces.text(&self.word, 0, self.fib.ccp_text)?;
+        let mut section = Section::new(0);
+        let mut fields: Vec<OpenField> = Vec::new();
+        let mut table: Option<TableBuilder> = None;
+        let mut list_counters: HashMap<(u16, u8), u32> = HashMap::new();
+
+        let mut start = 0usize;
+        while start < units.len() {
+            let end = units[start..]
+                .iter()
+                .position(|&(u, _, _)| u == PARAGRAPH_MARK || u == CELL_MARK)
+                .map(|i| start + i + 1)
+                .unwrap_or(units.len());
+            let slice = &units[start..end];
+            let (mark, mark_fc, _) = slice[slice.len() - 1];
+
+            let (istd, grpprl) = self.papx.pap_at(mark_fc);
+            let mut pap = Pap::default();
+            self.styles.resolve_pap(istd, &mut pap);
+            pap.apply(grpprl);
+
+            let body = if mark == PARAGRAPH_MARK || mark == CELL_MARK {
+                &slice[..slice.len() - 1]
+            } else {
+                slice
+            };
+            let mut para = self.build_paragraph(body, &mut fields);
+
+            if pap.in_table {
+                let builder = table.get_or_insert_with(TableBuilder::default);
+                if pap.ttp {
+                    builder.close_row(&pap);
+                } else {
+                    if !para.is_empty() {
+                        builder.paragraphs.push(para);
+                    }
+                    if mark == CELL_MARK {
+                        builder.close_cell();
+                    }
+                }
+            } else {
+                if let Some(t) = table.take().and_then(TableBuilder::finish) {
+                    section.add_block(Block::Table(t));
+                }
+                self.apply_paragraph_properties(&mut para, istd, &pap, &mut list_counters);
+                section.add_block(Block::Paragraph(para));
+            }
+
+            start = end;
+        }
+
+        if let Some(t) = table.take().and_then(TableBuilder::finish) {
+            section.add_block(Block::Table(t));
+        }
+        Ok(section)
+    }
+
+    /// Heading, alignment, list and style name for a body paragraph.
+    fn apply_paragraph_properties(
+        &self,
+        para: &mut Paragraph,
+        istd: u16,
+        pap: &Pap,
+        list_counters: &mut HashMap<(u16, u8), u32>,
+    ) {
+        para.alignment = pap.alignment;
+        if let Some(style) = self.styles.get(istd) {
+            if !style.name.is_empty() {
+                para.style_name = Some(style.name.clone());
+            }
+        }
+
+        let level = self
+            .styles
+            .heading_level(istd)
+            .or(pap.outline_level.map(|l| l + 1));
+        if let Some(level) = level {
+            para.heading = HeadingLevel::from_number(level);
+        }
+
+        if let Some((list_type, start)) = self.lists.kind(pap.ilfo, pap.ilvl) {
+            // A shallower item restarts the numbering of every deeper level.
+            list_counters.retain(|&(ilfo, ilvl), _| ilfo != pap.ilfo || ilvl <= pap.ilvl);
+            let counter = list_counters
+                .entry((pap.ilfo, pap.ilvl))
+                .and_modify(|n| *n += 1)
+                .or_insert(start);
+            para.list_info = Some(ListInfo {
+                list_type,
+                level: pap.ilvl,
+                number: (list_type == ListType::Numbered).then_some(*counter),
+            });
+        }
+    }
+
+    /// Turn the characters of one paragraph (mark excluded) into runs.
+    fn build_paragraph(&self, units: &[(u16, u32, u32)], fields: &mut Vec<OpenField>) -> Paragraph {
+        let mut para = Paragraph::new();
+        let mut pending: Vec<u16> = Vec::new();
+        let mut current: Option<(Chp, Option<String>)> = None;
+
+        for &(unit, fc, cp) in units {
+            if self.step_field(fields, unit) {
+                continue;
+            }
+            if !fields.iter().all(|f| f.in_result) {
+                // Field instruction text: kept for the field, never displayed.
+                if let Some(field) = fields.last_mut().filter(|f| !f.in_result) {
+                    field
+                        .instruction
+                        .push_str(&String::from_utf16_lossy(&[unit]));
+                }
+                continue;
+            }
+
+            let chp = self.chpx.chp_at(fc);
+            if chp.vanish {
+                continue;
+            }
+            let hyperlink = fields.iter().rev().find_map(|f| f.link.clone());
+            let key = (chp, hyperlink);
+            if current.as_ref() != Some(&key) {
+                flush_run(&mut para, &mut pending, current.as_ref());
+                current = Some(key);
+            }
+
+            match unit {
+                LINE_BREAK | PAGE_BREAK => {
+                    flush_run(&mut para, &mut pending, current.as_ref());
+                    if para.runs.is_empty() {
+                        para.runs.push(TextRun::plain(""));
+                    }
+                    if let Some(run) = para.runs.last_mut() {
+                        if unit == PAGE_BREAK {
+                            run.page_break = true;
+                        } else {
+                            run.line_break = true;
+                        }
+                    }
+                }
+                0x09 => pending.push(unit),
+                NON_BREAKING_HYPHEN => pending.push(0x2011),
+                NOTE_REFERENCE if current.as_ref().is_some_and(|(c, _)| c.special) => {
+                    if let Some(label) = self.note_refs.get(&cp) {
+                        pending.extend(format!("[^{}]", label).encode_utf16());
+                    }
+                }
+                // Pictures, drawn objects, annotation anchors and other placeholders.
+                u if u < 0x20 => {}
+                u => pending.push(u),
+            }
+        }
+        flush_run(&mut para, &mut pending, current.as_ref());
+        para
+    }
+}
+
+/// Emit the buffered characters as one run with the given formatting.
+fn flush_run(para: &mut Paragraph, pending: &mut Vec<u16>, format: Option<&(Chp, Option<String>)>) {
+    if pending.is_empty() {
+        return;
+    }
+    let text = String::from_utf16_lossy(pending);
+    pending.clear();
+    let (chp, hyperlink) = match format {
+        Some((chp, link)) => (chp.clone(), link.clone()),
+        None => (Chp::default(), None),
+    };
+    para.runs.push(TextRun {
+        text,
+        style: TextStyle {
+            bold: chp.bold,
+            italic: chp.italic,
+            underline: chp.underline,
+            strikethrough: chp.strike,
+            superscript: chp.superscript,
+            subscript: chp.subscript,
+            size: chp.size,
+            color: chp.color,
+            ..TextStyle::default()
+        },
+        hyperlink,
+        line_break: false,
+        page_break: false,
+        revision: chp.revision,
+        math: None,
+        form_field: None,
+        field: None,
+    });
+}
+
+/// The character positions of a PLC whose data elements are `data_size` bytes.
+fn plc_cps(plc: FcLcb, table: &[u8], data_size: usize) -> Vec<u32> {
+    let Some(plc) = plc.slice(table) else {
+        return Vec::new();
+    };
+    // n data elements and n+1 positions; a PLC without data has every 4 bytes a CP.
+    let n = if data_size == 0 {
+        (plc.len() / 4).saturating_sub(1)
+    } else {
+        plc.len().saturating_sub(4) / (4 + data_size)
+    };
+    let count = if data_size == 0 { n + 1 } else { n };
+    (0..count).filter_map(|i| ole::u32_at(plc, i * 4)).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::{to_markdown, RenderOptions};
+
+    const TEXT_FC: u32 = 1024;
+
+    /// A Word 97 document assembled byte by byte: UTF-16 text in one piece, one
+    /// paragraph FKP, one character FKP and an optional stylesheet.
+    #[derive(Default)]
+    struct DocFixture {
+        text: Vec<u16>,
+        /// `(cp_end, istd, grpprl)` per paragraph, in order.
+        paragraphs: Vec<(usize, u16, Vec<u8>)>,
+        /// `(cp_start, cp_end, grpprl)` character runs.
+        runs: Vec<(usize, usize, Vec<u8>)>,
+        styles: Vec<(u16, &'static str)>,
+        encrypted: bool,
+    }
+
+    impl DocFixture {
+        /// Add a paragraph ending in `mark`.
+        fn para(&mut self, text: &str, mark: u16, istd: u16, grpprl: Vec<u8>) -> &mut Self {
+            self.text.extend(text.encode_utf16());
+            self.text.push(mark);
+            self.paragraphs.push((self.text.len(), istd, grpprl));
+            self
+        }
+
+        /// Apply character properties to the first occurrence of `needle`.
+        fn format(&mut self, needle: &str, grpprl: Vec<u8>) -> &mut Self {
+            let needle: Vec<u16> = needle.encode_utf16().collect();
+            let start = self
+                .text
+                .windows(needle.len())
+                .position(|w| w == needle.as_slice())
+                .expect("needle in text");
+            self.runs.push((start, start + needle.len(), grpprl));
+            self
+        }
+
+        fn fc(cp: usize) -> u32 {
+            TEXT_FC + cp as u32 * 2
+        }
+
+        fn build(&self) -> Vec<u8> {
+            let mut word = vec![0u8; TEXT_FC as usize];
+            for unit in &self.text {
+                word.extend_from_slice(&unit.to_le_bytes());
+            }
+            let text_end = word.len();
+            while !word.len().is_multiple_of(512) {
+                word.push(0);
+            }
+
+            // Paragraph FKP.
+            let papx_pn = word.len() / 512;
+            let mut page = vec![0u8; 512];
+            let mut bounds = vec![Self::fc(0)];
+            let mut top = 511usize;
+            for (i, (cp_end, istd, grpprl)) in self.paragraphs.iter().enumerate() {
+                bounds.push(Self::fc(*cp_end));
+                let mut body = istd.to_le_bytes().to_vec();
+                body.extend_from_slice(grpprl);
+                let encoded: Vec<u8> = if body.len() % 2 == 1 {
+                    let mut v = vec![body.len().div_ceil(2) as u8];
+                    v.extend_from_slice(&body);
+                    v
+                } else {
+                    let mut v = vec![0, (body.len() / 2) as u8];
+                    v.extend_from_slice(&body);
+                    v
+                };
+                top -= encoded.len();
+                top -= top % 2;
+                page[top..top + encoded.len()].copy_from_slice(&encoded);
+                let n = self.paragraphs.len();
+                page[4 * (n + 1) + i * 13] = (top / 2) as u8;
+            }
+            for (i, fc) in bounds.iter().enumerate() {
+                page[i * 4..i * 4 + 4].copy_from_slice(&fc.to_le_bytes());
+            }
+            page[511] = self.paragraphs.len() as u8;
+            word.extend_from_slice(&page);
+
+            // Character FKP: the formatted runs plus unformatted gaps between them.
+            let chpx_pn = word.len() / 512;
+            let mut runs: Vec<(usize, usize, Vec<u8>)> = Vec::new();
+            let mut cursor = 0;
+            let mut sorted = self.runs.clone();
+            sorted.sort_by_key(|r| r.0);
+            for (s, e, g) in sorted {
+                if s > cursor {
+                    runs.push((cursor, s, Vec::new()));
+                }
+                runs.push((s, e, g));
+                cursor = e;
+            }
+            if cursor < self.text.len() {
+                runs.push((cursor, self.text.len(), Vec::new()));
+            }
+            let mut page = vec![0u8; 512];
+            let mut top = 511usize;
+            let n = runs.len();
+            for (i, (s, e, grpprl)) in runs.iter().enumerate() {
+                page[i * 4..i * 4 + 4].copy_from_slice(&Self::fc(*s).to_le_bytes());
+                page[(i + 1) * 4..(i + 1) * 4 + 4].copy_from_slice(&Self::fc(*e).to_le_bytes());
+                if grpprl.is_empty() {
+                    continue;
+                }
+                top -= grpprl.len() + 1;
+                top -= top % 2;
+                page[top] = grpprl.len() as u8;
+                page[top + 1..top + 1 + grpprl.len()].copy_from_slice(grpprl);
+                page[4 * (n + 1) + i] = (top / 2) as u8;
+            }
+            page[511] = n as u8;
+            word.extend_from_slice(&page);
+
+            // Table stream: Clx, the two PlcBtes, the stylesheet.
+            let mut table = Vec::new();
+            let clx_fc = table.len() as u32;
+            table.push(0x02);
+            table.extend_from_slice(&16u32.to_le_bytes());
+            table.extend_from_slice(&0u32.to_le_bytes());
+            table.extend_from_slice(&(self.text.len() as u32).to_le_bytes());
+            table.extend_from_slice(&0u16.to_le_bytes());
+            table.extend_from_slice(&TEXT_FC.to_le_bytes());
+            table.extend_from_slice(&0u16.to_le_bytes());
+            let clx = (clx_fc, table.len() as u32 - clx_fc);
+
+            let bte = |table: &mut Vec<u8>, pn: usize| {
+                let at = table.len() as u32;
+                table.extend_from_slice(&TEXT_FC.to_le_bytes());
+                table.extend_from_slice(&(text_end as u32).to_le_bytes());
+                table.extend_from_slice(&(pn as u32).to_le_bytes());
+                (at, 12u32)
+            };
+            let papx = bte(&mut table, papx_pn);
+            let chpx = bte(&mut table, chpx_pn);
+
+            let stsh_fc = table.len() as u32;
+            if !self.styles.is_empty() {
+                table.extend_from_slice(&18u16.to_le_bytes());
+                let mut stshi = vec![0u8; 18];
+                stshi[0..2].copy_from_slice(&(self.styles.len() as u16).to_le_bytes());
+                stshi[2..4].copy_from_slice(&10u16.to_le_bytes());
+                table.extend_from_slice(&stshi);
+                for (sti, name) in &self.styles {
+                    let mut std = Vec::new();
+                    std.extend_from_slice(&sti.to_le_bytes());
+                    std.extend_from_slice(&(1u16 | (0x0FFF << 4)).to_le_bytes());
+                    std.extend_from_slice(&[0u8; 6]);
+                    let name: Vec<u16> = name.encode_utf16().collect();
+                    std.extend_from_slice(&(name.len() as u16).to_le_bytes());
+                    for u in name {
+                        std.extend_from_slice(&u.to_le_bytes());
+                    }
+                    std.extend_from_slice(&[0, 0]);
+                    table.extend_from_slice(&(std.len() as u16).to_le_bytes());
+                    table.extend_from_slice(&std);
+                }
+            }
+            let stsh = (stsh_fc, table.len() as u32 - stsh_fc);
+
+            // FIB: FibBase, fibRgW (14), fibRgLw (22), fibRgFcLcb (93 pairs).
+            let mut flags = 0x0200u16;
+            if self.encrypted {
+                flags |= 0x0100;
+            }
+            word[0..2].copy_from_slice(&0xA5ECu16.to_le_bytes());
+            word[2..4].copy_from_slice(&0x00C1u16.to_le_bytes());
+            word[0x0A..0x0C].copy_from_slice(&flags.to_le_bytes());
+            word[32..34].copy_from_slice(&14u16.to_le_bytes());
+            let lw = 34 + 28;
+            word[lw..lw + 2].copy_from_slice(&22u16.to_le_bytes());
+            let ccp_text = lw + 2 + 3 * 4;
+            word[ccp_text..ccp_text + 4].copy_from_slice(&(self.text.len() as u32).to_le_bytes());
+            let fc_lcb = lw + 2 + 22 * 4;
+            word[fc_lcb..fc_lcb + 2].copy_from_slice(&93u16.to_le_bytes());
+            let mut set_pair = |index: usize, (fc, lcb): (u32, u32)| {
+                let at = fc_lcb + 2 + index * 8;
+                word[at..at + 4].copy_from_slice(&fc.to_le_bytes());
+                word[at + 4..at + 8].copy_from_slice(&lcb.to_le_bytes());
+            };
+            set_pair(1, stsh);
+            set_pair(12, chpx);
+            set_pair(13, papx);
+            set_pair(33, clx);
+
+            let mut container = cfb::CompoundFile::create(std::io::Cursor::new(Vec::new()))
+                .expect("create CFB container");
+            use std::io::Write;
+            container
+                .create_stream("/WordDocument")
+                .unwrap()
+                .write_all(&word)
+                .unwrap();
+            container
+                .create_stream("/1Table")
+                .unwrap()
+                .write_all(&table)
+                .unwrap();
+            container.flush().unwrap();
+            container.into_inner().into_inner()
+        }
+    }
+
+    fn bold() -> Vec<u8> {
+        vec![0x35, 0x08, 0x01]
+    }
+
+    fn in_table(ttp: bool) -> Vec<u8> {
+        let mut grpprl = vec![0x16, 0x24, 0x01];
+        if ttp {
+            grpprl.extend_from_slice(&[0x17, 0x24, 0x01]);
+        }
+        grpprl
+    }
+
+    fn table_def(flags: &[u16]) -> Vec<u8> {
+        let mut operand = vec![0, 0, flags.len() as u8];
+        for i in 0..=flags.len() {
+            operand.extend_from_slice(&((i * 1440) as i16).to_le_bytes());
+        }
+        for f in flags {
+            operand.extend_from_slice(&f.to_le_bytes());
+            operand.extend_from_slice(&[0u8; 18]);
+        }
+        let cb = (operand.len() - 1) as u16;
+        operand[..2].copy_from_slice(&cb.to_le_bytes());
+        let mut grpprl = vec![0x08, 0xD6];
+        grpprl.extend_from_slice(&operand);
+        grpprl
+    }
+
+    fn parse(fixture: &DocFixture) -> Document {
+        DocParser::from_bytes(fixture.build())
+            .unwrap()
+            .parse()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_paragraphs_headings_and_bold() {
+        let mut f = DocFixture {
+            styles: vec![(0, "Normal"), (1, "heading 1")],
+            ..Default::default()
+        };
+        f.para("Report", PARAGRAPH_MARK, 1, Vec::new())
+            .para("Some bold text.", PARAGRAPH_MARK, 0, Vec::new())
+            .format("bold", bold());
+
+        let doc = parse(&f);
+        assert_eq!(doc.format, crate::detect::FormatType::Doc);
+        let md = to_markdown(&doc, &RenderOptions::default()).unwrap();
+
+        assert!(md.contains("# Report"), "{md}");
+        assert!(md.contains("Some **bold** text."), "{md}");
+    }
+
+    #[test]
+    fn test_outline_level_makes_a_heading() {
+        let mut f = DocFixture::default();
+        f.para("Chapter", PARAGRAPH_MARK, 0, vec![0x40, 0x26, 0x01]);
+
+        let doc = parse(&f);
+        let Block::Paragraph(p) = &doc.sections[0].content[0] else {
+            panic!("expected paragraph");
+        };
+        assert_eq!(p.heading, HeadingLevel::H2);
+    }
+
+    #[test]
+    fn test_table_rows_and_horizontal_merge() {
+        let mut f = DocFixture::default();
+        let mut ttp1 = in_table(true);
+        ttp1.extend_from_slice(&table_def(&[0, 0]));
+        let mut ttp2 = in_table(true);
+        ttp2.extend_from_slice(&table_def(&[0, 0x0002]));
+        f.para("Before", PARAGRAPH_MARK, 0, Vec::new())
+            .para("A", CELL_MARK, 0, in_table(false))
+            .para("B", CELL_MARK, 0, in_table(false))
+            .para("", CELL_MARK, 0, ttp1)
+            .para("Wide", CELL_MARK, 0, in_table(false))
+            .para("", CELL_MARK, 0, in_table(false))
+            .para("", CELL_MARK, 0, ttp2)
+            .para("After", PARAGRAPH_MARK, 0, Vec::new());
+
+        let doc = parse(&f);
+        let blocks = &doc.sections[0].content;
+        assert_eq!(blocks.len(), 3);
+        let Block::Table(table) = &blocks[1] else {
+            panic!("expected table, got {:?}", blocks[1]);
+        };
+        assert_eq!(table.rows.len(), 2);
+        assert_eq!(table.rows[0].cells[1].plain_text(), "B");
+        assert_eq!(table.rows[1].cells.len(), 1);
+        assert_eq!(table.rows[1].cells[0].col_span, 2);
+        assert_eq!(table.column_widths, Some(vec![1440, 1440]));
+    }
+
+    #[test]
+    fn test_vertical_merge_sets_row_span() {
+        let mut f = DocFixture::default();
+        let mut ttp1 = in_table(true);
+        ttp1.extend_from_slice(&table_def(&[0x0060, 0]));
+        let mut ttp2 = in_table(true);
+        ttp2.extend_from_slice(&table_def(&[0x0020, 0]));
+        f.para("Tall", CELL_MARK, 0, in_table(false))
+            .para("x", CELL_MARK, 0, in_table(false))
+            .para("", CELL_MARK, 0, ttp1)
+            .para("", CELL_MARK, 0, in_table(false))
+            .para("y", CELL_MARK, 0, in_table(false))
+            .para("", CELL_MARK, 0, ttp2);
+
+        let doc = parse(&f);
+        let Block::Table(table) = &doc.sections[0].content[0] else {
+            panic!("expected table");
+        };
+        assert_eq!(table.rows[0].cells[0].row_span, 2);
+        assert_eq!(table.rows[1].cells.len(), 1);
+        assert_eq!(table.rows[1].cells[0].plain_text(), "y");
+    }
+
+    #[test]
+    fn test_hyperlink_field_shows_result_with_link() {
+        let mut f = DocFixture::default();
+        let text = "See \u{13} HYPERLINK \"https://example.com\" \u{14}the site\u{15} now.";
+        f.para(text, PARAGRAPH_MARK, 0, Vec::new());
+
+        let doc = parse(&f);
+        let Block::Paragraph(p) = &doc.sections[0].content[0] else {
+            panic!("expected paragraph");
+        };
+        assert_eq!(p.plain_text(), "See the site now.");
+        let link = p.runs.iter().find(|r| r.text == "the site").unwrap();
+        assert_eq!(link.hyperlink.as_deref(), Some("https://example.com"));
+    }
+
+    #[test]
+    fn test_bookmark_hyperlink_keeps_result_without_link() {
+        let mut f = DocFixture::default();
+        let text = "See \u{13} HYPERLINK \\l \"_Toc1\" \\o \"tip\" \u{14}Intro\u{15}.";
+        f.para(text, PARAGRAPH_MARK, 0, Vec::new());
+
+        let doc = parse(&f);
+        let Block::Paragraph(p) = &doc.sections[0].content[0] else {
+            panic!("expected paragraph");
+        };
+        assert_eq!(p.plain_text(), "See Intro.");
+        assert!(p.runs.iter().all(|r| r.hyperlink.is_none()));
+    }
+
+    #[test]
+    fn test_line_break_and_special_characters() {
+        let mut f = DocFixture::default();
+        f.para(
+            "one\u{0B}two\u{1E}three\u{01}",
+            PARAGRAPH_MARK,
+            0,
+            Vec::new(),
+        );
+
+        let doc = parse(&f);
+        let Block::Paragraph(p) = &doc.sections[0].content[0] else {
+            panic!("expected paragraph");
+        };
+        assert!(p.runs[0].line_break);
+        assert_eq!(p.runs[1].text, "two\u{2011}three");
+    }
+
+    #[test]
+    fn test_note_reference_is_found_by_character_position() {
+        let mut f = DocFixture::default();
+        f.para("Claim\u{02}.", PARAGRAPH_MARK, 0, Vec::new())
+            .format("\u{02}", vec![0x55, 0x08, 0x01]);
+        let mut parser = DocParser::from_bytes(f.build()).unwrap();
+        parser.note_refs.insert(5, "1".to_string());
+
+        // The reference, read on its own, is still the character at position 5.
+        let units = parser.pieces.text(&parser.word, 5, 7).unwrap();
+        let para = parser.build_paragraph(&units, &mut Vec::new());
+        assert_eq!(para.plain_text(), "[^1].");
+    }
+
+    #[test]
+    fn test_encrypted_document_reports_encrypted() {
+        let mut f = DocFixture {
+            encrypted: true,
+            ..Default::default()
+        };
+        f.para("secret", PARAGRAPH_MARK, 0, Vec::new());
+
+        let err = DocParser::from_bytes(f.build()).err().unwrap();
+        assert!(matches!(err, Error::Encrypted), "got: {err}");
+    }
+
+    #[test]
+    fn test_parse_bytes_dispatches_to_doc_parser() {
+        let mut f = DocFixture::default();
+        f.para("Hello from Word 97", PARAGRAPH_MARK, 0, Vec::new());
+
+        let doc = crate::parse_bytes(&f.build()).unwrap();
+        assert_eq!(doc.plain_text().trim(), "Hello from Word 97");
+    }
+}
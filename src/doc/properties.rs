@@ -0,0 +1,586 @@
+//! Character and paragraph properties of a Word 97-2003 document.
+//!
+//! Formatting in the binary format is a stack of property modifiers (`Sprm`s). Direct
+//! formatting sits in 512-byte formatted-disk pages (FKPs) in the `WordDocument` stream,
+//! keyed by stream offset; paragraph styles sit in the stylesheet (`STSH`) in the table
+//! stream; list formatting sits in the `PlfLst`/`PlfLfo` pair. This module reads the
+//! subset the document model can express and folds it into small resolved structs, so
+//! the parser never touches a raw `Sprm`.
+
+use std::collections::HashMap;
+
+use crate::model::{ListType, RevisionType, TextAlignment};
+use crate::ole::{decode_utf16le, i16_at, u16_at, u32_at};
+
+use super::fib::FcLcb;
+
+/// Size of a formatted disk page.
+const FKP_SIZE: usize = 512;
+
+/// Sprm opcodes this parser understands.
+pub(super) mod sprm {
+    pub const C_F_RMARK_DEL: u16 = 0x0800;
+    pub const C_F_RMARK_INS: u16 = 0x0801;
+    pub const C_F_BOLD: u16 = 0x0835;
+    pub const C_F_ITALIC: u16 = 0x0836;
+    pub const C_F_STRIKE: u16 = 0x0837;
+    pub const C_F_VANISH: u16 = 0x083C;
+    pub const C_F_SPEC: u16 = 0x0855;
+    pub const C_KUL: u16 = 0x2A3E;
+    pub const C_ISS: u16 = 0x2A48;
+    pub const C_F_DSTRIKE: u16 = 0x2A53;
+    pub const C_HPS: u16 = 0x4A43;
+    pub const C_CV: u16 = 0x6870;
+    pub const P_JC80: u16 = 0x2403;
+    pub const P_ILVL: u16 = 0x260A;
+    pub const P_ILFO: u16 = 0x460B;
+    pub const P_F_IN_TABLE: u16 = 0x2416;
+    pub const P_F_TTP: u16 = 0x2417;
+    pub const P_OUT_LVL: u16 = 0x2640;
+    pub const P_JC: u16 = 0x2461;
+    pub const P_ITAP: u16 = 0x6649;
+    pub const P_CHG_TABS: u16 = 0xC615;
+    pub const T_TABLE_HEADER: u16 = 0x3404;
+    pub const T_DEF_TABLE: u16 = 0xD608;
+}
+
+/// Iterate the `(sprm, operand)` pairs of a `grpprl`.
+///
+/// Stops at the first truncated entry: a damaged property list costs formatting, never
+/// text.
+pub(super) fn sprms(grpprl: &[u8]) -> impl Iterator<Item = (u16, &[u8])> {
+    let mut pos = 0usize;
+    std::iter::from_fn(move || {
+        let op = u16_at(grpprl, pos)?;
+        let body = pos + 2;
+        let (skip, len) = match op >> 13 {
+            0 | 1 => (0, 1),
+            2 | 4 | 5 => (0, 2),
+            3 => (0, 4),
+            7 => (0, 3),
+            // Variable length. Two opcodes use a wider length prefix than the rest.
+            _ => match op {
+                sprm::T_DEF_TABLE => (2, (u16_at(grpprl, body)? as usize).saturating_sub(1)),
+                sprm::P_CHG_TABS => (1, *grpprl.get(body)? as usize),
+                _ => (1, *grpprl.get(body)? as usize),
+            },
+        };
+        let operand = grpprl.get(body..body + skip + len)?;
+        pos = body + skip + len;
+        Some((op, operand))
+    })
+}
+
+/// A run of stream bytes sharing one property list.
+#[derive(Debug, Clone)]
+struct PropRun {
+    fc_start: u32,
+    fc_end: u32,
+    /// Style index for paragraph runs; unused for character runs.
+    istd: u16,
+    grpprl: Vec<u8>,
+}
+
+/// Direct formatting runs read from the FKPs, ordered by stream offset.
+#[derive(Debug, Clone, Default)]
+pub(super) struct PropRuns {
+    runs: Vec<PropRun>,
+}
+
+impl PropRuns {
+    /// Read the character runs named by `PlcBteChpx`.
+    pub fn chpx(word: &[u8], table: &[u8], plc: FcLcb) -> Self {
+        Self::read(word, table, plc, |page, crun, i| {
+            let offset = *page.get(4 * (crun + 1) + i)? as usize * 2;
+            if offset == 0 {
+                return Some((0, Vec::new()));
+            }
+            let cb = *page.get(offset)? as usize;
+            Some((0, page.get(offset + 1..offset + 1 + cb)?.to_vec()))
+        })
+    }
+
+    /// Read the paragraph runs named by `PlcBtePapx`.
+    pub fn papx(word: &[u8], table: &[u8], plc: FcLcb) -> Self {
+        Self::read(word, table, plc, |page, crun, i| {
+            // rgbx entries are 13 bytes: a word offset, then a 12-byte PHE.
+            let offset = *page.get(4 * (crun + 1) + i * 13)? as usize * 2;
+            if offset == 0 {
+                return Some((0, Vec::new()));
+            }
+            let cb = *page.get(offset)? as usize;
+            let grpprl_and_istd = if cb == 0 {
+                let cb = *page.get(offset + 1)? as usize;
+                page.get(offset + 2..offset + 2 + 2 * cb)?
+            } else {
+                page.get(offset + 1..offset + 1 + (2 * cb).saturating_sub(1))?
+            };
+            let istd = u16_at(grpprl_and_istd, 0)?;
+            Some((istd, grpprl_and_istd[2..].to_vec()))
+        })
+    }
+
+    fn read(
+        word: &[u8],
+        table: &[u8],
+        plc: FcLcb,
+        entry: impl Fn(&[u8], usize, usize) -> Option<(u16, Vec<u8>)>,
+    ) -> Self {
+        let mut runs = Vec::new();
+        let Some(plc) = plc.slice(table) else {
+            return Self { runs };
+        };
+
+        // PlcBte: n+1 stream offsets, then n page numbers.
+        let n = plc.len().saturating_sub(4) / 8;
+        for i in 0..n {
+            let Some(pn) = u32_at(plc, (n + 1) * 4 + i * 4) else {
+                break;
+            };
+            let page_at = (pn & 0x003F_FFFF) as usize * FKP_SIZE;
+            let Some(page) = word.get(page_at..page_at + FKP_SIZE) else {
+                continue;
+            };
+            let crun = page[FKP_SIZE - 1] as usize;
+            for j in 0..crun {
+                let (Some(fc_start), Some(fc_end)) =
+                    (u32_at(page, j * 4), u32_at(page, (j + 1) * 4))
+                else {
+                    break;
+                };
+                if let Some((istd, grpprl)) = entry(page, crun, j) {
+                    runs.push(PropRun {
+                        fc_start,
+                        fc_end,
+                        istd,
+                        grpprl,
+                    });
+                }
+            }
+        }
+
+        runs.sort_by_key(|r| r.fc_start);
+        Self { runs }
+    }
+
+    /// The run covering stream offset `fc`, as `(istd, grpprl)`.
+    fn at(&self, fc: u32) -> Option<(u16, &[u8])> {
+        let idx = self
+            .runs
+            .partition_point(|r| r.fc_start <= fc)
+            .checked_sub(1)?;
+        let run = &self.runs[idx];
+        (fc < run.fc_end).then_some((run.istd, run.grpprl.as_slice()))
+    }
+
+    /// Resolved character properties at stream offset `fc`.
+    pub fn chp_at(&self, fc: u32) -> Chp {
+        let mut chp = Chp::default();
+        if let Some((_, grpprl)) = self.at(fc) {
+            chp.apply(grpprl);
+        }
+        chp
+    }
+
+    /// Style index and direct paragraph properties at stream offset `fc`.
+    pub fn pap_at(&self, fc: u32) -> (u16, &[u8]) {
+        self.at(fc).unwrap_or((0, &[]))
+    }
+}
+
+/// Character properties, as far as the model can carry them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(super) struct Chp {
+    pub bold: bool,
+    pub italic: bool,
+    pub underline: bool,
+    pub strike: bool,
+    pub superscript: bool,
+    pub subscript: bool,
+    /// Hidden text — Word does not display it, so neither do we.
+    pub vanish: bool,
+    /// The character is a special placeholder (footnote reference, picture, ...).
+    pub special: bool,
+    /// Font size in half-points, as OOXML `w:sz` carries it.
+    pub size: Option<u32>,
+    /// `RRGGBB`
+    pub color: Option<String>,
+    pub revision: RevisionType,
+}
+
+impl Chp {
+    fn apply(&mut self, grpprl: &[u8]) {
+        for (op, operand) in sprms(grpprl) {
+            let byte = operand.first().copied().unwrap_or(0);
+            match op {
+                sprm::C_F_BOLD => self.bold = toggle(byte),
+                sprm::C_F_ITALIC => self.italic = toggle(byte),
+                sprm::C_F_STRIKE | sprm::C_F_DSTRIKE => self.strike = toggle(byte),
+                sprm::C_F_VANISH => self.vanish = toggle(byte),
+                sprm::C_F_SPEC => self.special = byte != 0,
+                sprm::C_KUL => self.underline = byte != 0,
+                sprm::C_ISS => {
+                    self.superscript = byte == 1;
+                    self.subscript = byte == 2;
+                }
+                sprm::C_HPS => self.size = u16_at(operand, 0).map(u32::from),
+                sprm::C_CV => {
+                    // COLORREF: 0x00BBGGRR, or fAuto in the high byte.
+                    if let Some(cv) = u32_at(operand, 0) {
+                        self.color = (cv >> 24 == 0).then(|| {
+                            format!(
+                                "{:02X}{:02X}{:02X}",
+                                cv & 0xFF,
+                                (cv >> 8) & 0xFF,
+                                (cv >> 16) & 0xFF
+                            )
+                        });
+                    }
+                }
+                sprm::C_F_RMARK_DEL if toggle(byte) => self.revision = RevisionType::Deleted,
+                sprm::C_F_RMARK_INS if toggle(byte) => self.revision = RevisionType::Inserted,
+                _ => {}
+            }
+        }
+    }
+}
+
+/// A toggle operand: 0/1 set the property, 0x80/0x81 mean "as the style" and "the
+/// opposite of the style". Styles' character properties are not resolved, so the two
+/// relative values are read as off and on — which matches every style that does not
+/// itself set the property, i.e. nearly all of them.
+fn toggle(operand: u8) -> bool {
+    matches!(operand, 1 | 0x81)
+}
+
+/// Paragraph properties, as far as the model can carry them.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(super) struct Pap {
+    pub alignment: TextAlignment,
+    /// Outline level 0-8; 9 (body text) is folded to `None`.
+    pub outline_level: Option<u8>,
+    /// 1-based index into the list override table; 0 means not a list item.
+    pub ilfo: u16,
+    pub ilvl: u8,
+    pub in_table: bool,
+    /// Table terminating paragraph: the row-end mark, which carries the row's layout.
+    pub ttp: bool,
+    pub table_header: bool,
+    pub table_def: Option<TableDef>,
+}
+
+impl Pap {
+    pub fn apply(&mut self, grpprl: &[u8]) {
+        for (op, operand) in sprms(grpprl) {
+            let byte = operand.first().copied().unwrap_or(0);
+            match op {
+                sprm::P_JC80 | sprm::P_JC => {
+                    self.alignment = match byte {
+                        1 => TextAlignment::Center,
+                        2 => TextAlignment::Right,
+                        3..=5 => TextAlignment::Justify,
+                        _ => TextAlignment::Left,
+                    }
+                }
+                sprm::P_OUT_LVL => self.outline_level = (byte < 9).then_some(byte),
+                sprm::P_ILFO => self.ilfo = u16_at(operand, 0).unwrap_or(0),
+                sprm::P_ILVL => self.ilvl = byte.min(8),
+                sprm::P_F_IN_TABLE => self.in_table = byte != 0,
+                sprm::P_F_TTP => self.ttp = byte != 0,
+                sprm::P_ITAP if u32_at(operand, 0).unwrap_or(0) > 0 => self.in_table = true,
+                sprm::T_TABLE_HEADER => self.table_header = byte != 0,
+                sprm::T_DEF_TABLE => self.table_def = TableDef::parse(operand),
+                _ => {}
+            }
+        }
+    }
+}
+
+/// One cell's layout flags from `sprmTDefTable`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(super) struct CellDef {
+    /// Covered by the horizontally merged cell to its left.
+    pub merged: bool,
+    /// Part of a vertical merge.
+    pub vert_merge: bool,
+    /// First cell of a vertical merge.
+    pub vert_restart: bool,
+}
+
+/// Row layout from `sprmTDefTable`: column boundaries and per-cell merge flags.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub(super) struct TableDef {
+    /// Cell widths in twips.
+    pub widths: Vec<u32>,
+    pub cells: Vec<CellDef>,
+}
+
+impl TableDef {
+    fn parse(operand: &[u8]) -> Option<Self> {
+        // Skip the two-byte cb: itcMac, rgdxaCenter[itcMac + 1], rgTc80[..].
+        let body = operand.get(2..)?;
+        let itc_mac = *body.first()? as usize;
+        let centers: Vec<i16> = (0..=itc_mac)
+            .map_while(|i| i16_at(body, 1 + i * 2))
+            .collect();
+        let widths = centers
+            .windows(2)
+            .map(|w| (w[1] as i32 - w[0] as i32).max(0) as u32)
+            .collect();
+        let tc_at = 1 + (itc_mac + 1) * 2;
+        let cells = (0..itc_mac)
+            .map(|i| {
+                let flags = u16_at(body, tc_at + i * 20).unwrap_or(0);
+                CellDef {
+                    merged: flags & 0x0002 != 0,
+                    vert_merge: flags & 0x0020 != 0,
+                    vert_restart: flags & 0x0040 != 0,
+                }
+            })
+            .collect();
+        Some(Self { widths, cells })
+    }
+}
+
+/// One entry of the stylesheet.
+#[derive(Debug, Clone, Default)]
+pub(super) struct Style {
+    /// Built-in style identifier; 1-9 are Heading 1-9.
+    pub sti: u16,
+    pub name: String,
+    pub base: Option<u16>,
+    /// The style's paragraph `grpprl`, applied beneath direct formatting.
+    pub papx: Vec<u8>,
+}
+
+/// The stylesheet, indexed by `istd`.
+#[derive(Debug, Clone, Default)]
+pub(super) struct StyleSheet {
+    styles: Vec<Option<Style>>,
+}
+
+/// `istdBase` value meaning "based on nothing".
+const ISTD_NIL: u16 = 0x0FFF;
+
+impl StyleSheet {
+    /// Parse the `STSH` structure.
+    pub fn parse(stsh: &[u8]) -> Self {
+        let mut styles = Vec::new();
+        let Some(cb_stshi) = u16_at(stsh, 0).map(usize::from) else {
+            return Self { styles };
+        };
+        let cstd = u16_at(stsh, 2).unwrap_or(0) as usize;
+        let cb_base = u16_at(stsh, 4).unwrap_or(10) as usize;
+
+        let mut pos = 2 + cb_stshi;
+        for _ in 0..cstd {
+            let Some(cb_std) = u16_at(stsh, pos).map(usize::from) else {
+                break;
+            };
+            pos += 2;
+            let std = stsh.get(pos..pos + cb_std);
+            pos += cb_std;
+            styles.push(
+                std.filter(|s| !s.is_empty())
+                    .and_then(|s| Self::parse_std(s, cb_base)),
+            );
+        }
+        Self { styles }
+    }
+
+    fn parse_std(std: &[u8], cb_base: usize) -> Option<Style> {
+        let sti = u16_at(std, 0)? & 0x0FFF;
+        let word2 = u16_at(std, 2)?;
+        let stk = word2 & 0x000F;
+        let base = word2 >> 4;
+        let cch = u16_at(std, cb_base)? as usize;
+        let name_bytes = std.get(cb_base + 2..cb_base + 2 + cch * 2)?;
+        let name = decode_utf16le(name_bytes);
+
+        // The UPXs follow the name's terminating null, each padded to an even offset.
+        // A paragraph style's first UPX is its paragraph properties: istd, then grpprl.
+        let mut papx = Vec::new();
+        if stk == 1 {
+            let mut at = cb_base + 2 + cch * 2 + 2;
+            at += at % 2;
+            if let Some(cb) = u16_at(std, at).map(usize::from) {
+                if let Some(upx) = std.get(at + 2..at + 2 + cb) {
+                    papx = upx.get(2..).unwrap_or_default().to_vec();
+                }
+            }
+        }
+
+        Some(Style {
+            sti,
+            name,
+            base: (base != ISTD_NIL).then_some(base),
+            papx,
+        })
+    }
+
+    pub fn get(&self, istd: u16) -> Option<&Style> {
+        self.styles.get(istd as usize)?.as_ref()
+    }
+
+    /// Paragraph properties a style contributes, base styles first.
+    pub fn resolve_pap(&self, istd: u16, pap: &mut Pap) {
+        // Collect the chain bottom-up, bounded against cyclic `istdBase` links.
+        let mut chain = Vec::new();
+        let mut current = Some(istd);
+        while let Some(i) = current {
+            if chain.len() >= 16 || chain.contains(&i) {
+                break;
+            }
+            let Some(style) = self.get(i) else { break };
+            chain.push(i);
+            current = style.base;
+        }
+        for i in chain.into_iter().rev() {
+            if let Some(style) = self.get(i) {
+                pap.apply(&style.papx);
+            }
+        }
+    }
+
+    /// Heading level implied by the style itself: the built-in Heading 1-9 styles, by
+    /// their identifier rather than their (localised) name.
+    pub fn heading_level(&self, istd: u16) -> Option<u8> {
+        let sti = self.get(istd)?.sti;
+        (1..=9).contains(&sti).then_some(sti as u8)
+    }
+}
+
+/// `nfc` value for a bullet.
+const NFC_BULLET: u8 = 0x17;
+/// `nfc` value for "no number".
+const NFC_NONE: u8 = 0xFF;
+
+/// List kinds by list override, resolved from `PlfLst` and `PlfLfo`.
+#[derive(Debug, Clone, Default)]
+pub(super) struct ListTable {
+    /// `lsid` → per-level number format and start value.
+    lists: HashMap<i32, Vec<(u8, u32)>>,
+    /// `ilfo - 1` → `lsid`.
+    overrides: Vec<i32>,
+}
+
+impl ListTable {
+    pub fn parse(plf_lst: Option<&[u8]>, plf_lfo: Option<&[u8]>) -> Self {
+        let mut table = Self::default();
+
+        if let Some(lst) = plf_lst {
+            let count = i16_at(lst, 0).unwrap_or(0).max(0) as usize;
+            // LSTF records (28 bytes each) come first; the LVLs of every list follow
+            // them in the same order.
+            let mut lvl_at = 2 + count * 28;
+            for i in 0..count {
+                let at = 2 + i * 28;
+                let (Some(lsid), Some(flags)) = (u32_at(lst, at), lst.get(at + 26)) else {
+                    break;
+                };
+                let levels = if flags & 0x01 != 0 { 1 } else { 9 };
+                let mut formats = Vec::with_capacity(levels);
+                for _ in 0..levels {
+                    // LVLF is 28 bytes; then grpprlPapx, grpprlChpx and the number text.
+                    let (Some(start), Some(&nfc), Some(&cb_chpx), Some(&cb_papx)) = (
+                        u32_at(lst, lvl_at),
+                        lst.get(lvl_at + 4),
+                        lst.get(lvl_at + 24),
+                        lst.get(lvl_at + 25),
+                    ) else {
+                        break;
+                    };
+                    formats.push((nfc, start));
+                    let xst_at = lvl_at + 28 + cb_papx as usize + cb_chpx as usize;
+                    let cch = u16_at(lst, xst_at).unwrap_or(0) as usize;
+                    lvl_at = xst_at + 2 + cch * 2;
+                }
+                table.lists.insert(lsid as i32, formats);
+            }
+        }
+
+        if let Some(lfo) = plf_lfo {
+            let count = u32_at(lfo, 0).unwrap_or(0).min(0x7FFF) as usize;
+            table.overrides = (0..count)
+                .map_while(|i| u32_at(lfo, 4 + i * 16).map(|v| v as i32))
+                .collect();
+        }
+
+        table
+    }
+
+    /// List type and start value for a paragraph's `(ilfo, ilvl)`.
+    ///
+    /// An override that cannot be resolved is still a list item — Word only writes
+    /// `ilfo` for numbered or bulleted paragraphs — so it falls back to a bullet.
+    pub fn kind(&self, ilfo: u16, ilvl: u8) -> Option<(ListType, u32)> {
+        if ilfo == 0 || ilfo >= 0x07FF {
+            return None;
+        }
+        let format = self
+            .overrides
+            .get(ilfo as usize - 1)
+            .and_then(|lsid| self.lists.get(lsid))
+            .and_then(|levels| levels.get(ilvl as usize).or_else(|| levels.first()));
+        match format {
+            Some(&(NFC_NONE, _)) => None,
+            Some(&(NFC_BULLET, _)) | None => Some((ListType::Bullet, 1)),
+            Some(&(_, start)) => Some((ListType::Numbered, start)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sprm_operand_sizes() {
+        let grpprl = [
+            0x35, 0x08, 0x01, // sprmCFBold, 1 byte
+            0x43, 0x4A, 0x18, 0x00, // sprmCHps, 2 bytes
+            0x70, 0x68, 0x00, 0x00, 0xFF, 0x00, // sprmCCv, 4 bytes
+        ];
+        let ops: Vec<u16> = sprms(&grpprl).map(|(op, _)| op).collect();
+        assert_eq!(ops, vec![sprm::C_F_BOLD, sprm::C_HPS, sprm::C_CV]);
+
+        let mut chp = Chp::default();
+        chp.apply(&grpprl);
+        assert!(chp.bold);
+        assert_eq!(chp.size, Some(24));
+        assert_eq!(chp.color.as_deref(), Some("0000FF"));
+    }
+
+    #[test]
+    fn test_truncated_grpprl_stops_without_panicking() {
+        let ops: Vec<u16> = sprms(&[0x35, 0x08, 0x01, 0x70, 0x68, 0x00])
+            .map(|(op, _)| op)
+            .collect();
+        assert_eq!(ops, vec![sprm::C_F_BOLD]);
+    }
+
+    #[test]
+    fn test_table_def_reads_merge_flags() {
+        // cb, itcMac=2, three centers, two TC80s.
+        let mut operand = vec![0, 0, 2];
+        for x in [0i16, 1440, 2880] {
+            operand.extend_from_slice(&x.to_le_bytes());
+        }
+        for flags in [0x0060u16, 0x0002] {
+            operand.extend_from_slice(&flags.to_le_bytes());
+            operand.extend_from_slice(&[0u8; 18]);
+        }
+        let cb = (operand.len() - 1) as u16;
+        operand[..2].copy_from_slice(&cb.to_le_bytes());
+
+        let mut grpprl = sprm::T_DEF_TABLE.to_le_bytes().to_vec();
+        grpprl.extend_from_slice(&operand);
+        let mut pap = Pap::default();
+        pap.apply(&grpprl);
+
+        let def = pap.table_def.unwrap();
+        assert_eq!(def.widths, vec![1440, 1440]);
+        assert!(def.cells[0].vert_merge && def.cells[0].vert_restart);
+        assert!(def.cells[1].merged);
+    }
+}
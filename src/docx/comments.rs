@@ -0,0 +1,318 @@
+//! DOCX review comment parsing.
+//!
+//! `word/comments.xml` holds each comment's author, date and text. Word 2013 and later
+//! add `word/commentsExtended.xml`, which threads replies and marks threads done, and
+//! `word/commentsIds.xml`, which gives each comment a durable ID. Both key a comment
+//! by the `w14:paraId` of its last paragraph.
+//!
+//! The text a comment is anchored to lives in `word/document.xml`, between
+//! `w:commentRangeStart` and `w:commentRangeEnd`; the paragraph parser reports it here
+//! as it goes.
+
+use crate::error::Result;
+use crate::model::Comment;
+use std::collections::HashMap;
+
+/// The comments of a document, with the ranges the paragraph parser has open.
+#[derive(Debug, Clone, Default)]
+pub struct CommentMap {
+    comments: Vec<Comment>,
+    /// Indices into `comments` of the ranges started and not yet ended
+    open: Vec<usize>,
+}
+
+impl CommentMap {
+    /// Parse `word/comments.xml`, threaded and resolved by `word/commentsExtended.xml`
+    /// and given durable IDs by `word/commentsIds.xml` when those are present.
+    pub fn parse(
+        comments_xml: &str,
+        extended_xml: Option<&str>,
+        ids_xml: Option<&str>,
+    ) -> Result<Self> {
+        let (mut comments, para_ids) = parse_comments(comments_xml)?;
+        // w14:paraId of a comment's last paragraph → index of the comment
+        let by_para: HashMap<&str, usize> = para_ids
+            .iter()
+            .enumerate()
+            .filter_map(|(i, para_id)| para_id.as_deref().map(|p| (p, i)))
+            .collect();
+
+        if let Some(xml) = extended_xml {
+            for (para_id, attrs) in parse_para_id_elements(xml, b"w15:commentEx", b"w15:paraId")? {
+                let Some(&index) = by_para.get(para_id.as_str()) else {
+                    continue;
+                };
+                if let Some(parent) = attrs.get("w15:paraIdParent") {
+                    let parent_id = by_para
+                        .get(parent.as_str())
+                        .map(|&i| comments[i].id.clone());
+                    comments[index].parent_id = parent_id;
+                }
+                comments[index].resolved = attrs
+                    .get("w15:done")
+                    .is_some_and(|v| v == "1" || v == "true");
+            }
+        }
+
+        if let Some(xml) = ids_xml {
+            for (para_id, attrs) in
+                parse_para_id_elements(xml, b"w16cid:commentId", b"w16cid:paraId")?
+            {
+                if let Some(&index) = by_para.get(para_id.as_str()) {
+                    comments[index].durable_id = attrs.get("w16cid:durableId").cloned();
+                }
+            }
+        }
+
+        Ok(Self {
+            comments,
+            open: Vec::new(),
+        })
+    }
+
+    /// Check if a comment with this ID exists.
+    pub fn contains(&self, id: &str) -> bool {
+        self.comments.iter().any(|c| c.id == id)
+    }
+
+    /// Start capturing the anchored text of a comment (`w:commentRangeStart`).
+    pub fn start_range(&mut self, id: &str) {
+        if let Some(index) = self.comments.iter().position(|c| c.id == id) {
+            self.comments[index].anchor = Some(String::new());
+            if !self.open.contains(&index) {
+                self.open.push(index);
+            }
+        }
+    }
+
+    /// Stop capturing the anchored text of a comment (`w:commentRangeEnd`).
+    pub fn end_range(&mut self, id: &str) {
+        self.open.retain(|&index| self.comments[index].id != id);
+    }
+
+    /// Add document text to every range that is open.
+    pub fn capture(&mut self, text: &str) {
+        for &index in &self.open {
+            if let Some(anchor) = self.comments[index].anchor.as_mut() {
+                anchor.push_str(text);
+            }
+        }
+    }
+
+    /// Mark the end of a paragraph in every range that is open.
+    pub fn end_paragraph(&mut self) {
+        self.capture("\n");
+    }
+
+    /// Take the comments out, anchors trimmed of the paragraph ends at their edges.
+    pub fn take(&mut self) -> Vec<Comment> {
+        self.open.clear();
+        let mut comments = std::mem::take(&mut self.comments);
+        for comment in &mut comments {
+            if let Some(anchor) = comment.anchor.as_mut() {
+                *anchor = anchor.trim_matches('\n').to_string();
+            }
+        }
+        comments
+    }
+}
+
+/// Parse `word/comments.xml` into comments, each with the `w14:paraId` of its last
+/// paragraph.
+fn parse_comments(xml: &str) -> Result<(Vec<Comment>, Vec<Option<String>>)> {
+    let mut comments = Vec::new();
+    let mut para_ids = Vec::new();
+    let mut reader = crate::decode::reader_for(xml);
+    reader.config_mut().trim_text(false);
+
+    let mut buf = Vec::new();
+    let mut current: Option<Comment> = None;
+    let mut last_para_id: Option<String> = None;
+    let mut paragraphs: Vec<String> = Vec::new();
+    let mut in_text = false;
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(quick_xml::events::Event::Start(ref e)) => match e.name().as_ref() {
+                b"w:comment" => {
+                    let mut comment = Comment::default();
+                    for attr in e.attributes().flatten() {
+                        let value = crate::decode::attr_value_lossy(&attr);
+                        match attr.key.as_ref() {
+                            b"w:id" => comment.id = value,
+                            b"w:author" => comment.author = Some(value),
+                            b"w:initials" => comment.initials = Some(value),
+                            b"w:date" => comment.date = Some(value),
+                            _ => {}
+                        }
+                    }
+                    current = Some(comment);
+                    last_para_id = None;
+                    paragraphs.clear();
+                }
+                b"w:p" if current.is_some() => {
+                    paragraphs.push(String::new());
+                    for attr in e.attributes().flatten() {
+                        if attr.key.as_ref() == b"w14:paraId" {
+                            last_para_id = Some(crate::decode::attr_value_lossy(&attr));
+                        }
+                    }
+                }
+                b"w:t" if current.is_some() => in_text = true,
+                _ => {}
+            },
+            Ok(quick_xml::events::Event::Empty(ref e)) if current.is_some() => {
+                match e.name().as_ref() {
+                    b"w:p" => paragraphs.push(String::new()),
+                    b"w:tab" => {
+                        if let Some(paragraph) = paragraphs.last_mut() {
+                            paragraph.push('\t');
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Ok(quick_xml::events::Event::Text(ref e)) if in_text => {
+                if let Some(paragraph) = paragraphs.last_mut() {
+                    paragraph.push_str(&crate::decode::decode_text_lossy(e));
+                }
+            }
+            Ok(quick_xml::events::Event::GeneralRef(ref e)) if in_text => {
+                if let Some(paragraph) = paragraphs.last_mut() {
+                    paragraph.push_str(&crate::decode::resolve_general_ref(e));
+                }
+            }
+            Ok(quick_xml::events::Event::End(ref e)) => match e.name().as_ref() {
+                b"w:t" => in_text = false,
+                b"w:comment" => {
+                    if let Some(mut comment) = current.take() {
+                        comment.text = paragraphs.join("\n").trim().to_string();
+                        comments.push(comment);
+                        para_ids.push(last_para_id.take());
+                    }
+                }
+                _ => {}
+            },
+            Ok(quick_xml::events::Event::Eof) => break,
+            Err(e) => return Err(e.into()),
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok((comments, para_ids))
+}
+
+/// The elements named `tag` in `xml`, each as the value of its `para_id_attr` and a
+/// map of all its attributes.
+fn parse_para_id_elements(
+    xml: &str,
+    tag: &[u8],
+    para_id_attr: &[u8],
+) -> Result<Vec<(String, HashMap<String, String>)>> {
+    let mut elements = Vec::new();
+    let mut reader = crate::decode::reader_for(xml);
+    let mut buf = Vec::new();
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(quick_xml::events::Event::Start(ref e) | quick_xml::events::Event::Empty(ref e))
+                if e.name().as_ref() == tag =>
+            {
+                let mut para_id = None;
+                let mut attrs = HashMap::new();
+                for attr in e.attributes().flatten() {
+                    let value = crate::decode::attr_value_lossy(&attr);
+                    if attr.key.as_ref() == para_id_attr {
+                        para_id = Some(value.clone());
+                    }
+                    attrs.insert(
+                        String::from_utf8_lossy(attr.key.as_ref()).to_string(),
+                        value,
+                    );
+                }
+                if let Some(para_id) = para_id {
+                    elements.push((para_id, attrs));
+                }
+            }
+            Ok(quick_xml::events::Event::Eof) => break,
+            Err(e) => return Err(e.into()),
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok(elements)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COMMENTS: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+<w:comments xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main"
+    xmlns:w14="http://schemas.microsoft.com/office/word/2010/wordml">
+    <w:comment w:id="0" w:author="Alice" w:date="2024-03-01T09:00:00Z" w:initials="A">
+        <w:p w14:paraId="00000001"><w:r><w:annotationRef/></w:r><w:r><w:t>Check this</w:t></w:r></w:p>
+        <w:p w14:paraId="00000002"><w:r><w:t xml:space="preserve">figure &amp; source.</w:t></w:r></w:p>
+    </w:comment>
+    <w:comment w:id="1" w:author="Bob" w:date="2024-03-02T10:00:00Z">
+        <w:p w14:paraId="00000003"><w:r><w:t>Done.</w:t></w:r></w:p>
+    </w:comment>
+</w:comments>"#;
+
+    const EXTENDED: &str = r#"<w15:commentsEx xmlns:w15="http://schemas.microsoft.com/office/word/2012/wordml">
+    <w15:commentEx w15:paraId="00000002" w15:done="1"/>
+    <w15:commentEx w15:paraId="00000003" w15:paraIdParent="00000002" w15:done="0"/>
+</w15:commentsEx>"#;
+
+    const IDS: &str = r#"<w16cid:commentsIds xmlns:w16cid="http://schemas.microsoft.com/office/word/2016/wordml/cid">
+    <w16cid:commentId w16cid:paraId="00000002" w16cid:durableId="5A1B2C3D"/>
+</w16cid:commentsIds>"#;
+
+    #[test]
+    fn test_parse_comments() {
+        let comments = CommentMap::parse(COMMENTS, None, None).unwrap().take();
+        assert_eq!(comments.len(), 2);
+        assert_eq!(comments[0].id, "0");
+        assert_eq!(comments[0].author.as_deref(), Some("Alice"));
+        assert_eq!(comments[0].initials.as_deref(), Some("A"));
+        assert_eq!(comments[0].date.as_deref(), Some("2024-03-01T09:00:00Z"));
+        assert_eq!(comments[0].text, "Check this\nfigure & source.");
+        assert!(comments[1].parent_id.is_none());
+        assert!(!comments[0].resolved);
+    }
+
+    #[test]
+    fn test_threads_resolved_state_and_durable_ids() {
+        let comments = CommentMap::parse(COMMENTS, Some(EXTENDED), Some(IDS))
+            .unwrap()
+            .take();
+        assert!(comments[0].resolved);
+        assert!(!comments[1].resolved);
+        assert_eq!(comments[1].parent_id.as_deref(), Some("0"));
+        assert!(comments[1].is_reply());
+        assert_eq!(comments[0].durable_id.as_deref(), Some("5A1B2C3D"));
+        assert!(comments[1].durable_id.is_none());
+    }
+
+    #[test]
+    fn test_anchor_ranges() {
+        let mut map = CommentMap::parse(COMMENTS, None, None).unwrap();
+        map.capture("before ");
+        map.start_range("0");
+        map.capture("first");
+        map.end_paragraph();
+        map.start_range("1");
+        map.capture("second");
+        map.end_range("0");
+        map.capture(" after");
+        map.end_range("1");
+        map.start_range("missing");
+        map.end_paragraph();
+
+        let comments = map.take();
+        assert_eq!(comments[0].anchor.as_deref(), Some("first\nsecond"));
+        assert_eq!(comments[1].anchor.as_deref(), Some("second after"));
+    }
+}
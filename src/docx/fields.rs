@@ -0,0 +1,442 @@
+//! DOCX fields: complex fields (`w:fldChar`) and simple fields (`w:fldSimple`).
+//!
+//! A complex field is a `begin` mark, the runs of its instruction (`w:instrText`), an
+//! optional `separate` mark followed by the runs of its last result, and an `end` mark.
+//! A simple field holds its instruction in `w:instr` and its result runs inside it. The
+//! paragraph parser reads the result runs as ordinary text and reports the marks and
+//! the instruction text here; the field is then put on the first result run as a
+//! [`Field`]. A field inside another field's instruction, such as the `MERGEFIELD` an
+//! `IF` tests, becomes part of that instruction. A field still open when its paragraph
+//! ends, such as a table of contents, keeps the result runs read so far.
+//!
+//! `HYPERLINK` fields link their result runs like `w:hyperlink` does.
+//!
+//! Legacy form fields (`FORMTEXT`, `FORMCHECKBOX`, `FORMDROPDOWN`) are complex fields
+//! whose `begin` mark holds their properties in `w:ffData`. They become
+//! [`FormField`]s next to the content controls, on the run their result starts with —
+//! or, for a checkbox, which has no result text, on a checkbox glyph added for it.
+
+use super::sdt::place;
+use crate::error::Result;
+use crate::model::{Field, FormField, FormFieldItem, FormFieldType, TextRun};
+
+/// The fields open in a paragraph.
+#[derive(Debug, Default)]
+pub struct FieldStack {
+    open: Vec<OpenField>,
+}
+
+#[derive(Debug, Default)]
+struct OpenField {
+    /// The legacy form field this field is
+    form: Option<LegacyFormField>,
+    /// The instruction read so far
+    instruction: String,
+    /// Index of the first run read after the `begin` mark
+    begin: usize,
+    /// Index of the first result run, once the `separate` mark is read
+    result_start: Option<usize>,
+}
+
+impl FieldStack {
+    /// Handle a `w:fldChar` mark. `content` is what the element holds, if anything:
+    /// the `w:ffData` of a legacy form field.
+    pub fn fld_char(
+        &mut self,
+        e: &quick_xml::events::BytesStart,
+        content: Option<&str>,
+        runs: &mut Vec<TextRun>,
+    ) -> Result<()> {
+        match attribute(e, b"w:fldCharType").as_deref() {
+            Some("begin") => {
+                let form = match content {
+                    Some(xml) => LegacyFormField::parse(xml)?,
+                    None => None,
+                };
+                self.open.push(OpenField {
+                    form,
+                    begin: runs.len(),
+                    ..OpenField::default()
+                });
+            }
+            Some("separate") => {
+                if let Some(field) = self.open.last_mut() {
+                    // Runs before the separate mark are instruction, not result.
+                    let begin = field.begin.min(runs.len());
+                    for run in runs.drain(begin..) {
+                        field.instruction.push_str(&run.text);
+                    }
+                    field.result_start = Some(runs.len());
+                }
+            }
+            Some("end") => self.close(runs),
+            _ => {}
+        }
+        Ok(())
+    }
+
+    /// Add the text of a `w:instrText` to the instruction of the field being read.
+    pub fn instruction(&mut self, text: &str) {
+        if let Some(field) = self.open.last_mut().filter(|f| f.result_start.is_none()) {
+            field.instruction.push_str(text);
+        }
+    }
+
+    /// Open a `w:fldSimple`, whose result runs come next.
+    pub fn simple(&mut self, e: &quick_xml::events::BytesStart, runs: &[TextRun]) {
+        self.open.push(OpenField {
+            instruction: attribute(e, b"w:instr").unwrap_or_default(),
+            begin: runs.len(),
+            result_start: Some(runs.len()),
+            ..OpenField::default()
+        });
+    }
+
+    /// Close the innermost open field: the end mark of a complex field, or the end of
+    /// a `w:fldSimple`.
+    pub fn close(&mut self, runs: &mut Vec<TextRun>) {
+        let Some(mut field) = self.open.pop() else {
+            return;
+        };
+        let begin = field.begin.min(runs.len());
+        let start = match field.result_start {
+            Some(start) => start.min(runs.len()),
+            None => {
+                for run in runs.drain(begin..) {
+                    field.instruction.push_str(&run.text);
+                }
+                runs.len()
+            }
+        };
+
+        // Inside another field's instruction, the result is part of that instruction.
+        if let Some(outer) = self.open.last_mut().filter(|f| f.result_start.is_none()) {
+            for run in runs.drain(begin..) {
+                outer.instruction.push_str(&run.text);
+            }
+            return;
+        }
+        if let Some(form) = field.form {
+            form.attach(runs, start);
+            return;
+        }
+
+        let mut field = Field::new(field.instruction);
+        if let Some(target) = field.link_target() {
+            for run in runs[start..]
+                .iter_mut()
+                .filter(|run| run.hyperlink.is_none())
+            {
+                run.hyperlink = Some(target.clone());
+            }
+        }
+        let has_result = start < runs.len();
+        // The run a nested field's result starts with holds that field already.
+        if runs.get(start).is_none_or(|run| run.field.is_some()) {
+            runs.insert(start, TextRun::default());
+        }
+        if has_result {
+            field.result_runs = runs.len() - start;
+        }
+        runs[start].field = Some(Box::new(field));
+    }
+
+    /// Close the fields still open at the end of a paragraph. Their results go on past
+    /// it; the runs read so far are theirs.
+    pub fn finish(mut self, runs: &mut Vec<TextRun>) {
+        while !self.open.is_empty() {
+            self.close(runs);
+        }
+    }
+}
+
+/// The value of attribute `key` of `e`.
+fn attribute(e: &quick_xml::events::BytesStart, key: &[u8]) -> Option<String> {
+    e.attributes()
+        .flatten()
+        .find(|attr| attr.key.as_ref() == key)
+        .map(|attr| crate::decode::attr_value_lossy(&attr))
+}
+
+/// A legacy form field's `w:ffData` properties.
+#[derive(Debug, Default)]
+struct LegacyFormField {
+    field: FormField,
+    /// Index of the drop-down entry chosen (`w:result`, else `w:default`)
+    selected: Option<usize>,
+}
+
+impl LegacyFormField {
+    /// Parse the content of a `begin` mark. `None` when it holds no `w:ffData`.
+    fn parse(xml: &str) -> Result<Option<Self>> {
+        use quick_xml::events::Event;
+
+        let mut form = LegacyFormField::default();
+        let mut found = false;
+        let mut reader = crate::decode::reader_for(xml);
+        let mut buf = Vec::new();
+        let mut default: Option<String> = None;
+        let mut result: Option<usize> = None;
+
+        loop {
+            match reader.read_event_into(&mut buf) {
+                Ok(Event::Start(ref e) | Event::Empty(ref e)) => {
+                    let value = attribute(e, b"w:val");
+                    let field = &mut form.field;
+                    match e.name().as_ref() {
+                        b"w:ffData" => found = true,
+                        b"w:name" => field.tag = value.filter(|v| !v.is_empty()),
+                        b"w:textInput" => field.field_type = FormFieldType::Text,
+                        // The kind of text: regular, number, date, currentDate, ...
+                        b"w:type" => {
+                            if matches!(
+                                value.as_deref(),
+                                Some("date" | "currentDate" | "currentTime")
+                            ) {
+                                field.field_type = FormFieldType::Date;
+                            }
+                        }
+                        b"w:checkBox" => field.field_type = FormFieldType::Checkbox,
+                        b"w:ddList" => field.field_type = FormFieldType::DropDown,
+                        b"w:listEntry" => {
+                            let entry = value.unwrap_or_default();
+                            field.items.push(FormFieldItem {
+                                display_text: entry.clone(),
+                                value: entry,
+                            });
+                        }
+                        b"w:default" => default = Some(value.unwrap_or_default()),
+                        b"w:result" => result = value.and_then(|v| v.parse().ok()),
+                        b"w:checked" => field.checked = Some(is_on(value.as_deref())),
+                        _ => {}
+                    }
+                }
+                Ok(Event::Eof) => break,
+                Err(e) => return Err(e.into()),
+                _ => {}
+            }
+            buf.clear();
+        }
+
+        if !found {
+            return Ok(None);
+        }
+        let field = &mut form.field;
+        field.legacy = true;
+        match field.field_type {
+            // w:checked overrides the default state
+            FormFieldType::Checkbox => {
+                let default = default.as_deref().map(|v| is_on(Some(v)));
+                field.checked = field.checked.or(default).or(Some(false));
+            }
+            FormFieldType::DropDown => {
+                form.selected = result.or_else(|| default.and_then(|v| v.parse().ok()));
+            }
+            _ => {}
+        }
+        Ok(Some(form))
+    }
+
+    /// Put the field on the result runs from `start` on.
+    fn attach(self, runs: &mut Vec<TextRun>, start: usize) {
+        let start = start.min(runs.len());
+        let mut field = self.field;
+        let result: String = runs[start..].iter().map(|run| run.text.as_str()).collect();
+        let result = result.trim();
+        match field.field_type {
+            FormFieldType::Checkbox => {
+                // Word draws the box itself; there is no text for it.
+                let glyph = if field.checked == Some(true) {
+                    "\u{2612}"
+                } else {
+                    "\u{2610}"
+                };
+                runs.insert(start, TextRun::plain(glyph));
+            }
+            FormFieldType::DropDown => {
+                let chosen = field
+                    .items
+                    .get(self.selected.unwrap_or(0))
+                    .map(|item| item.display_text.clone());
+                field.value = chosen.or_else(|| Some(result.to_string()));
+                if result.is_empty() {
+                    if let Some(value) = field.value.clone().filter(|v| !v.is_empty()) {
+                        runs.insert(start, TextRun::plain(value));
+                    }
+                }
+            }
+            _ => field.value = Some(result.to_string()),
+        }
+        place(runs, start, field);
+    }
+}
+
+/// The value of an on/off property, which is on when it has no value.
+fn is_on(value: Option<&str>) -> bool {
+    !matches!(value, Some("0" | "false" | "off"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::FieldType;
+
+    fn fld_char(stack: &mut FieldStack, xml: &str, content: Option<&str>, runs: &mut Vec<TextRun>) {
+        let mut reader = crate::decode::reader_for(xml);
+        let mut buf = Vec::new();
+        if let Ok(quick_xml::events::Event::Empty(e) | quick_xml::events::Event::Start(e)) =
+            reader.read_event_into(&mut buf)
+        {
+            stack.fld_char(&e, content, runs).unwrap();
+        }
+    }
+
+    const BEGIN: &str = r#"<w:fldChar w:fldCharType="begin">"#;
+    const SEPARATE: &str = r#"<w:fldChar w:fldCharType="separate"/>"#;
+    const END: &str = r#"<w:fldChar w:fldCharType="end"/>"#;
+
+    #[test]
+    fn test_legacy_checkbox() {
+        let mut stack = FieldStack::default();
+        let mut runs = vec![TextRun::plain("Agree ")];
+        let ff_data = r#"<w:ffData><w:name w:val="Check1"/><w:checkBox><w:sizeAuto/><w:default w:val="0"/><w:checked/></w:checkBox></w:ffData>"#;
+        fld_char(&mut stack, BEGIN, Some(ff_data), &mut runs);
+        fld_char(&mut stack, END, None, &mut runs);
+
+        assert_eq!(runs.len(), 2);
+        assert_eq!(runs[1].text, "\u{2612}");
+        let field = runs[1].form_field.as_deref().unwrap();
+        assert_eq!(field.tag.as_deref(), Some("Check1"));
+        assert_eq!(field.checkbox_state(), Some(true));
+        assert!(field.legacy);
+    }
+
+    #[test]
+    fn test_legacy_dropdown_and_text() {
+        let mut stack = FieldStack::default();
+        let mut runs = Vec::new();
+        let ff_data = r#"<w:ffData><w:name w:val="Size"/><w:ddList><w:result w:val="1"/><w:listEntry w:val="Small"/><w:listEntry w:val="Large"/></w:ddList></w:ffData>"#;
+        fld_char(&mut stack, BEGIN, Some(ff_data), &mut runs);
+        fld_char(&mut stack, SEPARATE, None, &mut runs);
+        fld_char(&mut stack, END, None, &mut runs);
+        assert_eq!(runs.len(), 1);
+        assert_eq!(runs[0].text, "Large");
+        let field = runs[0].form_field.as_deref().unwrap();
+        assert_eq!(field.field_type, FormFieldType::DropDown);
+        assert_eq!(field.value.as_deref(), Some("Large"));
+        assert_eq!(field.items.len(), 2);
+
+        let mut runs = vec![TextRun::plain("Name: ")];
+        let ff_data = r#"<w:ffData><w:name w:val="Text1"/><w:textInput><w:default w:val="Your name"/></w:textInput></w:ffData>"#;
+        fld_char(&mut stack, BEGIN, Some(ff_data), &mut runs);
+        fld_char(&mut stack, SEPARATE, None, &mut runs);
+        runs.push(TextRun::plain("Ada"));
+        fld_char(&mut stack, END, None, &mut runs);
+        assert_eq!(runs.len(), 2);
+        let field = runs[1].form_field.as_deref().unwrap();
+        assert_eq!(field.field_type, FormFieldType::Text);
+        assert_eq!(field.value.as_deref(), Some("Ada"));
+    }
+
+    #[test]
+    fn test_fields_without_form_data_are_not_form_fields() {
+        let mut stack = FieldStack::default();
+        let mut runs = Vec::new();
+        fld_char(&mut stack, BEGIN, Some(""), &mut runs);
+        stack.instruction(" PAGE ");
+        fld_char(&mut stack, SEPARATE, None, &mut runs);
+        runs.push(TextRun::plain("3"));
+        fld_char(&mut stack, END, None, &mut runs);
+        assert!(runs[0].form_field.is_none());
+        let field = runs[0].field.as_deref().unwrap();
+        assert_eq!(field.field_type, FieldType::Page);
+        assert_eq!(field.instruction, "PAGE");
+        assert_eq!(field.result_runs, 1);
+    }
+
+    #[test]
+    fn test_nested_fields_and_hyperlinks() {
+        // { HYPERLINK "https://example.com/" \l "_Toc1" }Intro { PAGEREF _Toc1 \h },
+        // with the hyperlink split over two instruction runs and a MERGEFIELD inside an
+        // IF instruction
+        let mut stack = FieldStack::default();
+        let mut runs = vec![TextRun::plain("See ")];
+        fld_char(&mut stack, BEGIN, None, &mut runs);
+        stack.instruction(" HYPERLINK \"https://example.com/\" \\l ");
+        stack.instruction("\"_Toc1\" ");
+        fld_char(&mut stack, SEPARATE, None, &mut runs);
+        runs.push(TextRun::plain("Intro "));
+        fld_char(&mut stack, BEGIN, None, &mut runs);
+        stack.instruction(" PAGEREF _Toc1 \\h ");
+        fld_char(&mut stack, SEPARATE, None, &mut runs);
+        runs.push(TextRun::plain("3"));
+        fld_char(&mut stack, END, None, &mut runs);
+        fld_char(&mut stack, END, None, &mut runs);
+
+        assert_eq!(runs.len(), 3);
+        let link = runs[1].field.as_deref().unwrap();
+        assert_eq!(link.field_type, FieldType::Hyperlink);
+        assert_eq!(link.result_runs, 2);
+        let page = runs[2].field.as_deref().unwrap();
+        assert_eq!(page.bookmark().as_deref(), Some("_Toc1"));
+        let target = Some("https://example.com/#_Toc1");
+        assert_eq!(runs[1].hyperlink.as_deref(), target);
+        assert_eq!(runs[2].hyperlink.as_deref(), target);
+        assert_eq!(runs[0].hyperlink, None);
+
+        let mut runs = Vec::new();
+        fld_char(&mut stack, BEGIN, None, &mut runs);
+        stack.instruction("IF ");
+        fld_char(&mut stack, BEGIN, None, &mut runs);
+        stack.instruction("MERGEFIELD Plan");
+        fld_char(&mut stack, SEPARATE, None, &mut runs);
+        runs.push(TextRun::plain("Pro"));
+        fld_char(&mut stack, END, None, &mut runs);
+        stack.instruction(" = \"Pro\" \"Thanks\" \"\"");
+        fld_char(&mut stack, SEPARATE, None, &mut runs);
+        runs.push(TextRun::plain("Thanks"));
+        fld_char(&mut stack, END, None, &mut runs);
+        assert_eq!(runs.len(), 1);
+        let field = runs[0].field.as_deref().unwrap();
+        assert_eq!(field.instruction, "IF Pro = \"Pro\" \"Thanks\" \"\"");
+    }
+
+    #[test]
+    fn test_simple_and_unfinished_fields() {
+        let mut stack = FieldStack::default();
+        let mut runs = Vec::new();
+        let mut reader = crate::decode::reader_for(
+            r#"<w:fldSimple w:instr=" DATE \@ &quot;d MMMM yyyy&quot; "/>"#,
+        );
+        let mut buf = Vec::new();
+        if let Ok(quick_xml::events::Event::Empty(e)) = reader.read_event_into(&mut buf) {
+            stack.simple(&e, &runs);
+        }
+        runs.push(TextRun::plain("1 March 2024"));
+        stack.close(&mut runs);
+        let field = runs[0].field.as_deref().unwrap();
+        assert_eq!(field.field_type, FieldType::Date);
+        assert_eq!(field.instruction, "DATE \\@ \"d MMMM yyyy\"");
+
+        // A table of contents goes on past its first paragraph.
+        let mut runs = Vec::new();
+        fld_char(&mut stack, BEGIN, None, &mut runs);
+        stack.instruction("TOC \\o \"1-3\" \\h");
+        fld_char(&mut stack, SEPARATE, None, &mut runs);
+        runs.push(TextRun::plain("Intro"));
+        stack.finish(&mut runs);
+        let field = runs[0].field.as_deref().unwrap();
+        assert_eq!(field.field_type, FieldType::Toc);
+        assert_eq!(field.result_runs, 1);
+
+        // Without a result, the field gets an empty run of its own.
+        let mut stack = FieldStack::default();
+        let mut runs = vec![TextRun::plain("Page ")];
+        fld_char(&mut stack, BEGIN, None, &mut runs);
+        stack.instruction("PAGE");
+        fld_char(&mut stack, END, None, &mut runs);
+        assert_eq!(runs.len(), 2);
+        assert!(runs[1].text.is_empty());
+        assert_eq!(runs[1].field.as_deref().unwrap().result_runs, 0);
+    }
+}
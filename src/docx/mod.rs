@@ -1,7 +1,8 @@
-//! DOCX (Word) document parser.
+//! DOCX (Word) document parser and writer.
 //!
 //! This module provides parsing for Microsoft Word documents in the
-//! Office Open XML (.docx) format.
+//! Office Open XML (.docx) format, and [`DocxWriter`] to write a
+//! [`Document`](crate::model::Document) back out as one.
 //!
 //! # Example
 //!
@@ -16,8 +17,13 @@
 //! # Ok::<(), undoc::Error>(())
 //! ```
 
+mod comments;
+mod fields;
 mod numbering;
 mod parser;
+mod sdt;
 pub(crate) mod styles;
+mod writer;
 
 pub use parser::DocxParser;
+pub use writer::DocxWriter;
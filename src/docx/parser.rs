@@ -11,7 +11,10 @@ use crate::model::{
     VerticalAlignment,
 };
 
+use super::comments::CommentMap;
+use super::fields::FieldStack;
 use super::numbering::NumberingMap;
+use super::sdt::{ContentControl, TableScope};
 use super::styles::StyleMap;
 
 /// Parser for DOCX (Word) documents.
@@ -24,6 +27,8 @@ pub struct DocxParser {
     footnotes: HashMap<String, String>,
     /// Endnote id → plain text content
     endnotes: HashMap<String, String>,
+    /// Review comments, filled in with their anchored text as paragraphs are parsed
+    comments: CommentMap,
 }
 
 impl DocxParser {
@@ -73,6 +78,20 @@ impl DocxParser {
             None => HashMap::new(),
         };
 
+        // Parse review comments — absent is OK, malformed bytes must surface.
+        let comments = match container.read_xml_optional("word/comments.xml")? {
+            Some(xml) => CommentMap::parse(
+                &xml,
+                container
+                    .read_xml_optional("word/commentsExtended.xml")?
+                    .as_deref(),
+                container
+                    .read_xml_optional("word/commentsIds.xml")?
+                    .as_deref(),
+            )?,
+            None => CommentMap::default(),
+        };
+
         Ok(Self {
             container,
             styles,
@@ -80,6 +99,7 @@ impl DocxParser {
             relationships,
             footnotes,
             endnotes,
+            comments,
         })
     }
 
@@ -87,6 +107,7 @@ impl DocxParser {
     pub fn parse(&mut self) -> Result<Document> {
         let mut doc = Document::new();
         doc.format = crate::detect::FormatType::Docx;
+        doc.variant = self.container.format_variant();
 
         // Parse metadata
         doc.metadata = self.parse_metadata()?;
@@ -133,6 +154,7 @@ impl DocxParser {
         }
 
         doc.add_section(main_section);
+        doc.comments = self.comments.take();
 
         // Extract resources (images)
         self.extract_resources(&mut doc)?;
@@ -290,6 +312,8 @@ impl DocxParser {
                                       // Every header/footer reference (default / first / even), in document order.
         let mut header_rids: Vec<String> = Vec::new();
         let mut footer_rids: Vec<String> = Vec::new();
+        // Block-level content controls open, each with the index of its first block
+        let mut controls: Vec<(Option<ContentControl>, usize)> = Vec::new();
 
         loop {
             match reader.read_event_into(&mut buf) {
@@ -312,6 +336,20 @@ impl DocxParser {
                             }
                             paragraph_xml.push('>');
                         }
+                        b"w:sdt" if in_body && !in_paragraph && table_depth == 0 => {
+                            controls.push((None, section.content.len()));
+                        }
+                        b"w:sdtPr" if in_body && !in_paragraph && table_depth == 0 => {
+                            let span = reader.read_to_end(name).map_err(|e| {
+                                Error::xml_parse_with_context(e.to_string(), "word/document.xml")
+                            })?;
+                            let properties = xml
+                                .get(span.start as usize..span.end as usize)
+                                .unwrap_or_default();
+                            if let Some(control) = controls.last_mut() {
+                                control.0 = ContentControl::parse(properties)?;
+                            }
+                        }
                         // Only body-level tables enter table mode. A table nested
                         // inside a text box lives within a paragraph; it must stay
                         // in paragraph_xml so the text-box path extracts its text,
@@ -445,6 +483,11 @@ impl DocxParser {
                             }
                             in_paragraph = false;
                         }
+                        b"w:sdt" if in_body && !in_paragraph && table_depth == 0 => {
+                            if let Some((Some(control), start)) = controls.pop() {
+                                control.attach_to_blocks(&mut section.content[start..]);
+                            }
+                        }
                         b"w:tbl" if table_depth > 0 => {
                             table_xml.push_str("</w:tbl>");
                             table_depth -= 1;
@@ -673,6 +716,9 @@ impl DocxParser {
         let mut current_style = TextStyle::default();
         let mut current_hyperlink: Option<String> = None;
         let mut current_image_alt: Option<String> = None;
+        // Content controls open in this paragraph, each with the index of its first run
+        let mut controls: Vec<(Option<ContentControl>, usize)> = Vec::new();
+        let mut fields = FieldStack::default();
 
         loop {
             match reader.read_event_into(&mut buf) {
@@ -687,14 +733,47 @@ impl DocxParser {
                     }
                     _ if mc_fallback_depth > 0 => {} // Skip everything inside mc:Fallback
                     _ if txbx_content_depth > 0 => {} // Skip everything inside w:txbxContent
+                    // Equations: m:oMathPara is set on lines of its own, m:oMath in the text
+                    b"w:sdt" => controls.push((None, para.runs.len())),
+                    // A fldChar with content is a begin mark holding a form field's w:ffData
+                    b"w:fldChar" => {
+                        let span = reader.read_to_end(e.name())?;
+                        let content = xml
+                            .get(span.start as usize..span.end as usize)
+                            .unwrap_or_default();
+                        fields.fld_char(e, Some(content), &mut para.runs)?;
+                    }
+                    b"w:sdtPr" => {
+                        let span = reader.read_to_end(e.name())?;
+                        let properties = xml
+                            .get(span.start as usize..span.end as usize)
+                            .unwrap_or_default();
+                        if let Some(control) = controls.last_mut() {
+                            control.0 = ContentControl::parse(properties)?;
+                        }
+                    }
+                    b"m:oMathPara" | b"m:oMath" => {
+                        if let Some(mut run) = crate::math::read_equation(&mut reader, xml, e)? {
+                            run.revision = if in_del {
+                                RevisionType::Deleted
+                            } else if in_ins {
+                                RevisionType::Inserted
+                            } else {
+                                RevisionType::None
+                            };
+                            para.runs.push(run);
+                        }
+                    }
                     b"w:pPr" => in_ppr = true,
                     b"w:rPr" => in_rpr = true,
                     b"w:r" => {
                         in_run = true;
                         current_style = TextStyle::default();
                     }
-                    b"w:t" => in_text = true,
-                    b"w:instrText" => in_instr_text = true,
+                    // Deleted text of a tracked change is w:delText, not w:t
+                    b"w:t" | b"w:delText" => in_text = true,
+                    b"w:instrText" | b"w:delInstrText" => in_instr_text = true,
+                    b"w:fldSimple" => fields.simple(e, &para.runs),
                     b"w:drawing" => {
                         in_drawing = true;
                         current_image_alt = None;
@@ -718,6 +797,11 @@ impl DocxParser {
                 },
                 Ok(quick_xml::events::Event::Empty(ref e)) => match e.name().as_ref() {
                     _ if mc_fallback_depth > 0 || txbx_content_depth > 0 => {} // Skip
+                    b"w:fldChar" => fields.fld_char(e, None, &mut para.runs)?,
+                    b"w:fldSimple" => {
+                        fields.simple(e, &para.runs);
+                        fields.close(&mut para.runs);
+                    }
                     b"w:pStyle" if in_ppr => {
                         for attr in e.attributes().flatten() {
                             if attr.key.as_ref() == b"w:val" {
@@ -739,7 +823,9 @@ impl DocxParser {
                                 let val = String::from_utf8_lossy(&attr.value);
                                 para.alignment = match val.as_ref() {
                                     "center" => TextAlignment::Center,
-                                    "right" => TextAlignment::Right,
+                                    // Strict writes `start`/`end` where Transitional
+                                    // writes `left`/`right`.
+                                    "right" | "end" => TextAlignment::Right,
                                     "both" | "distribute" => TextAlignment::Justify,
                                     _ => TextAlignment::Left,
                                 };
@@ -817,8 +903,7 @@ impl DocxParser {
                     b"wp:docPr" if in_drawing => {
                         for attr in e.attributes().flatten() {
                             if attr.key.as_ref() == b"descr" {
-                                current_image_alt =
-                                    Some(String::from_utf8_lossy(&attr.value).to_string());
+                                current_image_alt = Some(crate::decode::attr_value_lossy(&attr));
                             }
                         }
                     }
@@ -876,6 +961,9 @@ impl DocxParser {
                                     line_break: false,
                                     page_break: true,
                                     revision: current_revision,
+                                    math: None,
+                                    form_field: None,
+                                    field: None,
                                 });
                             }
                         } else {
@@ -890,6 +978,9 @@ impl DocxParser {
                                     line_break: true,
                                     page_break: false,
                                     revision: current_revision,
+                                    math: None,
+                                    form_field: None,
+                                    field: None,
                                 });
                             }
                         }
@@ -910,6 +1001,9 @@ impl DocxParser {
                             line_break: false,
                             page_break: false,
                             revision: current_revision,
+                            math: None,
+                            form_field: None,
+                            field: None,
                         });
                     }
                     // Carriage return handling - convert <w:cr/> to newline
@@ -931,6 +1025,9 @@ impl DocxParser {
                                 line_break: true,
                                 page_break: false,
                                 revision: current_revision,
+                                math: None,
+                                form_field: None,
+                                field: None,
                             });
                         }
                     }
@@ -950,6 +1047,9 @@ impl DocxParser {
                             line_break: false,
                             page_break: false,
                             revision: current_revision,
+                            math: None,
+                            form_field: None,
+                            field: None,
                         });
                     }
                     // Soft hyphen handling (optional hyphen, usually invisible)
@@ -968,6 +1068,9 @@ impl DocxParser {
                             line_break: false,
                             page_break: false,
                             revision: current_revision,
+                            math: None,
+                            form_field: None,
+                            field: None,
                         });
                     }
                     // Non-breaking space handling
@@ -986,6 +1089,9 @@ impl DocxParser {
                             line_break: false,
                             page_break: false,
                             revision: current_revision,
+                            math: None,
+                            form_field: None,
+                            field: None,
                         });
                     }
                     // Footnote reference handling
@@ -1000,6 +1106,36 @@ impl DocxParser {
                             }
                         }
                     }
+                    // Review comment ranges: the text between start and end is the anchor
+                    b"w:commentRangeStart" | b"w:commentRangeEnd" => {
+                        for attr in e.attributes().flatten() {
+                            if attr.key.as_ref() == b"w:id" {
+                                let id = String::from_utf8_lossy(&attr.value);
+                                if e.name().as_ref() == b"w:commentRangeStart" {
+                                    self.comments.start_range(&id);
+                                } else {
+                                    self.comments.end_range(&id);
+                                }
+                            }
+                        }
+                    }
+                    // Review comment reference: the comment is placed in this paragraph
+                    b"w:commentReference" if in_run => {
+                        for attr in e.attributes().flatten() {
+                            if attr.key.as_ref() == b"w:id" {
+                                let id = String::from_utf8_lossy(&attr.value).to_string();
+                                if self.comments.contains(&id) && !para.comment_ids.contains(&id) {
+                                    para.comment_ids.push(id);
+                                }
+                            }
+                        }
+                    }
+                    // Bookmark: a place REF, PAGEREF and HYPERLINK fields can lead to
+                    b"w:bookmarkStart" => {
+                        if let Some(name) = bookmark_name(e) {
+                            para.bookmarks.push(name);
+                        }
+                    }
                     // Endnote reference handling
                     b"w:endnoteReference" if in_run => {
                         for attr in e.attributes().flatten() {
@@ -1014,6 +1150,17 @@ impl DocxParser {
                     }
                     _ => {}
                 },
+                // Field instructions: kept as the field's, not as text
+                Ok(quick_xml::events::Event::Text(ref e))
+                    if in_instr_text && mc_fallback_depth == 0 && txbx_content_depth == 0 =>
+                {
+                    fields.instruction(&crate::decode::decode_text_lossy(e));
+                }
+                Ok(quick_xml::events::Event::GeneralRef(ref e))
+                    if in_instr_text && mc_fallback_depth == 0 && txbx_content_depth == 0 =>
+                {
+                    fields.instruction(&crate::decode::resolve_general_ref(e));
+                }
                 Ok(quick_xml::events::Event::Text(ref e))
                     if in_run
                         && in_text
@@ -1025,6 +1172,9 @@ impl DocxParser {
                     // Also skip text inside mc:Fallback and w:txbxContent (extracted separately)
                     let text = crate::decode::decode_text_lossy(e);
                     if !text.is_empty() {
+                        if !in_del {
+                            self.comments.capture(&text);
+                        }
                         let current_revision = if in_del {
                             RevisionType::Deleted
                         } else if in_ins {
@@ -1039,6 +1189,9 @@ impl DocxParser {
                             line_break: false,
                             page_break: false,
                             revision: current_revision,
+                            math: None,
+                            form_field: None,
+                            field: None,
                         };
                         para.runs.push(run);
                     }
@@ -1055,6 +1208,9 @@ impl DocxParser {
                 {
                     let text = crate::decode::resolve_general_ref(e);
                     if !text.is_empty() {
+                        if !in_del {
+                            self.comments.capture(&text);
+                        }
                         let current_revision = if in_del {
                             RevisionType::Deleted
                         } else if in_ins {
@@ -1069,6 +1225,9 @@ impl DocxParser {
                             line_break: false,
                             page_break: false,
                             revision: current_revision,
+                            math: None,
+                            form_field: None,
+                            field: None,
                         };
                         para.runs.push(run);
                     }
@@ -1081,11 +1240,17 @@ impl DocxParser {
                         txbx_content_depth -= 1;
                     }
                     _ if mc_fallback_depth > 0 || txbx_content_depth > 0 => {} // Skip
+                    b"w:sdt" => {
+                        if let Some((Some(control), start)) = controls.pop() {
+                            control.attach_to_runs(&mut para.runs, start);
+                        }
+                    }
                     b"w:pPr" => in_ppr = false,
                     b"w:rPr" => in_rpr = false,
                     b"w:r" => in_run = false,
-                    b"w:t" => in_text = false,
-                    b"w:instrText" => in_instr_text = false,
+                    b"w:t" | b"w:delText" => in_text = false,
+                    b"w:instrText" | b"w:delInstrText" => in_instr_text = false,
+                    b"w:fldSimple" => fields.close(&mut para.runs),
                     b"w:hyperlink" => current_hyperlink = None,
                     b"w:drawing" => {
                         in_drawing = false;
@@ -1102,9 +1267,11 @@ impl DocxParser {
             }
             buf.clear();
         }
+        fields.finish(&mut para.runs);
 
         // Parse numbering (list info)
         para.list_info = self.parse_list_info(xml);
+        self.comments.end_paragraph();
 
         Ok(para)
     }
@@ -1290,7 +1457,7 @@ impl DocxParser {
 
     /// Parse a table element.
     #[allow(clippy::only_used_in_recursion)] // &self needed for recursive nested table parsing
-    fn parse_table(&self, xml: &str) -> Result<Table> {
+    fn parse_table(&mut self, xml: &str) -> Result<Table> {
         use crate::model::InlineImage;
 
         let mut table = Table::new();
@@ -1330,6 +1497,9 @@ impl DocxParser {
         // 1+ = we're inside a nested table and should collect its XML
         let mut nested_table_depth: u32 = 0;
         let mut nested_table_xml = String::new();
+        // Content controls open in the table, each with where its content starts
+        let mut controls: Vec<(Option<ContentControl>, TableScope)> = Vec::new();
+        let mut fields = FieldStack::default();
 
         loop {
             match reader.read_event_into(&mut buf) {
@@ -1361,6 +1531,29 @@ impl DocxParser {
                             nested_table_xml.clear();
                             nested_table_xml.push_str("<w:tbl>");
                         }
+                        b"w:sdt" => {
+                            let scope = if let Some(para) = current_paragraph.as_ref() {
+                                TableScope::Runs(para.runs.len())
+                            } else if in_cell {
+                                TableScope::Paragraphs(cell_paragraphs.len())
+                            } else if let Some(row) = current_row.as_ref() {
+                                TableScope::Cells(row.cells.len())
+                            } else {
+                                TableScope::Rows(table.rows.len())
+                            };
+                            controls.push((None, scope));
+                        }
+                        b"w:sdtPr" => {
+                            let span = reader.read_to_end(name).map_err(|e| {
+                                Error::xml_parse_with_context(e.to_string(), "table")
+                            })?;
+                            let properties = xml
+                                .get(span.start as usize..span.end as usize)
+                                .unwrap_or_default();
+                            if let Some(control) = controls.last_mut() {
+                                control.0 = ContentControl::parse(properties)?;
+                            }
+                        }
                         b"w:tr" => {
                             in_row = true;
                             current_row = Some(Row {
@@ -1385,13 +1578,35 @@ impl DocxParser {
                             in_paragraph = true;
                             current_paragraph = Some(Paragraph::new());
                         }
+                        b"w:fldChar" => {
+                            let span = reader.read_to_end(name).map_err(|e| {
+                                Error::xml_parse_with_context(e.to_string(), "table")
+                            })?;
+                            let content = xml
+                                .get(span.start as usize..span.end as usize)
+                                .unwrap_or_default();
+                            if let Some(para) = current_paragraph.as_mut() {
+                                fields.fld_char(e, Some(content), &mut para.runs)?;
+                            }
+                        }
                         b"w:r" if in_paragraph => {
                             in_run = true;
                             current_style = TextStyle::default();
                         }
                         b"w:rPr" if in_run => in_rpr = true,
                         b"w:t" => in_text = true,
-                        b"w:instrText" => in_instr_text = true,
+                        b"m:oMathPara" | b"m:oMath" if in_paragraph => {
+                            let run = crate::math::read_equation(&mut reader, xml, e)?;
+                            if let (Some(run), Some(para)) = (run, current_paragraph.as_mut()) {
+                                para.runs.push(run);
+                            }
+                        }
+                        b"w:instrText" | b"w:delInstrText" => in_instr_text = true,
+                        b"w:fldSimple" => {
+                            if let Some(para) = current_paragraph.as_ref() {
+                                fields.simple(e, &para.runs);
+                            }
+                        }
                         b"w:drawing" => {
                             in_drawing = true;
                             current_image_alt = None;
@@ -1420,6 +1635,51 @@ impl DocxParser {
                     }
 
                     match name.as_ref() {
+                        b"w:fldChar" => {
+                            if let Some(para) = current_paragraph.as_mut() {
+                                fields.fld_char(e, None, &mut para.runs)?;
+                            }
+                        }
+                        b"w:fldSimple" => {
+                            if let Some(para) = current_paragraph.as_mut() {
+                                fields.simple(e, &para.runs);
+                                fields.close(&mut para.runs);
+                            }
+                        }
+                        // Review comments anchored in the cell, as in body paragraphs
+                        b"w:commentRangeStart" | b"w:commentRangeEnd" => {
+                            for attr in e.attributes().flatten() {
+                                if attr.key.as_ref() == b"w:id" {
+                                    let id = String::from_utf8_lossy(&attr.value);
+                                    if name.as_ref() == b"w:commentRangeStart" {
+                                        self.comments.start_range(&id);
+                                    } else {
+                                        self.comments.end_range(&id);
+                                    }
+                                }
+                            }
+                        }
+                        b"w:commentReference" if in_run => {
+                            for attr in e.attributes().flatten() {
+                                if attr.key.as_ref() == b"w:id" {
+                                    let id = String::from_utf8_lossy(&attr.value).to_string();
+                                    if let Some(para) = current_paragraph.as_mut() {
+                                        if self.comments.contains(&id)
+                                            && !para.comment_ids.contains(&id)
+                                        {
+                                            para.comment_ids.push(id);
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                        b"w:bookmarkStart" => {
+                            if let (Some(para), Some(bookmark)) =
+                                (current_paragraph.as_mut(), bookmark_name(e))
+                            {
+                                para.bookmarks.push(bookmark);
+                            }
+                        }
                         b"w:tblHeader" if in_row => {
                             is_header_row = true;
                         }
@@ -1480,7 +1740,7 @@ impl DocxParser {
                             for attr in e.attributes().flatten() {
                                 if attr.key.as_ref() == b"descr" {
                                     current_image_alt =
-                                        Some(String::from_utf8_lossy(&attr.value).to_string());
+                                        Some(crate::decode::attr_value_lossy(&attr));
                                 }
                             }
                         }
@@ -1522,10 +1782,14 @@ impl DocxParser {
                         continue;
                     }
 
+                    if in_instr_text {
+                        fields.instruction(&crate::decode::decode_text_lossy(e));
+                    }
                     // Only extract text from w:t elements, skip w:instrText (field codes)
                     if in_run && in_text && !in_instr_text {
                         let text = crate::decode::decode_text_lossy(e);
                         if !text.is_empty() {
+                            self.comments.capture(&text);
                             if let Some(ref mut para) = current_paragraph {
                                 let run = TextRun {
                                     text,
@@ -1534,6 +1798,9 @@ impl DocxParser {
                                     line_break: false,
                                     page_break: false,
                                     revision: RevisionType::None,
+                                    math: None,
+                                    form_field: None,
+                                    field: None,
                                 };
                                 para.runs.push(run);
                             }
@@ -1550,9 +1817,13 @@ impl DocxParser {
                         continue;
                     }
 
+                    if in_instr_text {
+                        fields.instruction(&crate::decode::resolve_general_ref(e));
+                    }
                     if in_run && in_text && !in_instr_text {
                         let text = crate::decode::resolve_general_ref(e);
                         if !text.is_empty() {
+                            self.comments.capture(&text);
                             if let Some(ref mut para) = current_paragraph {
                                 let run = TextRun {
                                     text,
@@ -1561,6 +1832,9 @@ impl DocxParser {
                                     line_break: false,
                                     page_break: false,
                                     revision: RevisionType::None,
+                                    math: None,
+                                    form_field: None,
+                                    field: None,
                                 };
                                 para.runs.push(run);
                             }
@@ -1590,6 +1864,42 @@ impl DocxParser {
                     }
 
                     match name.as_ref() {
+                        b"w:sdt" => {
+                            if let Some((Some(control), scope)) = controls.pop() {
+                                match scope {
+                                    TableScope::Runs(start) => {
+                                        if let Some(para) = current_paragraph.as_mut() {
+                                            control.attach_to_runs(&mut para.runs, start);
+                                        }
+                                    }
+                                    TableScope::Paragraphs(start) => {
+                                        // Empty paragraphs are not kept, but an empty field is
+                                        if cell_paragraphs.len() == start {
+                                            cell_paragraphs.push(Paragraph::new());
+                                        }
+                                        control.attach_to_paragraphs(&mut cell_paragraphs[start..]);
+                                    }
+                                    TableScope::Cells(start) => {
+                                        if let Some(row) = current_row.as_mut() {
+                                            let cells = row.cells.iter_mut().skip(start);
+                                            control.attach_to_paragraphs(
+                                                cells.flat_map(|cell| cell.content.iter_mut()),
+                                            );
+                                        }
+                                    }
+                                    TableScope::Rows(start) => {
+                                        let cells = table
+                                            .rows
+                                            .iter_mut()
+                                            .skip(start)
+                                            .flat_map(|row| row.cells.iter_mut());
+                                        control.attach_to_paragraphs(
+                                            cells.flat_map(|cell| cell.content.iter_mut()),
+                                        );
+                                    }
+                                }
+                            }
+                        }
                         b"w:tr" => {
                             if let Some(mut row) = current_row.take() {
                                 row.is_header = is_header_row;
@@ -1621,6 +1931,7 @@ impl DocxParser {
                                     vertical_alignment: VerticalAlignment::default(),
                                     is_header: is_header_row,
                                     background: None,
+                                    value: None,
                                 };
                                 // Track as vMerge origin: row_idx = table.rows.len() (index
                                 // the current row will have once pushed in </w:tr> handler)
@@ -1650,18 +1961,23 @@ impl DocxParser {
                         }
                         b"w:p" if in_cell => {
                             // Save the completed paragraph
-                            if let Some(para) = current_paragraph.take() {
+                            if let Some(mut para) = current_paragraph.take() {
+                                std::mem::take(&mut fields).finish(&mut para.runs);
+                                self.comments.end_paragraph();
                                 // Only add non-empty paragraphs
                                 if !para.is_empty() {
                                     // Skip duplicate paragraphs (same text content as previous)
                                     // Word may store duplicate paragraphs in same cell but only displays one
-                                    let is_duplicate = cell_paragraphs
-                                        .last()
-                                        .map(|last| last.plain_text() == para.plain_text())
-                                        .unwrap_or(false);
-
-                                    if !is_duplicate {
-                                        cell_paragraphs.push(para);
+                                    match cell_paragraphs.last_mut() {
+                                        Some(last) if last.plain_text() == para.plain_text() => {
+                                            for id in para.comment_ids {
+                                                if !last.comment_ids.contains(&id) {
+                                                    last.comment_ids.push(id);
+                                                }
+                                            }
+                                            last.bookmarks.extend(para.bookmarks);
+                                        }
+                                        _ => cell_paragraphs.push(para),
                                     }
                                 }
                             }
@@ -1672,7 +1988,12 @@ impl DocxParser {
                         }
                         b"w:rPr" => in_rpr = false,
                         b"w:t" => in_text = false,
-                        b"w:instrText" => in_instr_text = false,
+                        b"w:instrText" | b"w:delInstrText" => in_instr_text = false,
+                        b"w:fldSimple" => {
+                            if let Some(para) = current_paragraph.as_mut() {
+                                fields.close(&mut para.runs);
+                            }
+                        }
                         b"w:drawing" => {
                             in_drawing = false;
                             current_image_alt = None;
@@ -1826,7 +2147,7 @@ fn vml_inline_image(e: &quick_xml::events::BytesStart) -> Option<crate::model::I
     for attr in e.attributes().flatten() {
         match attr.key.as_ref() {
             b"r:id" => rel_id = Some(String::from_utf8_lossy(&attr.value).to_string()),
-            b"o:title" => title = Some(String::from_utf8_lossy(&attr.value).to_string()),
+            b"o:title" => title = Some(crate::decode::attr_value_lossy(&attr)),
             _ => {}
         }
     }
@@ -1887,7 +2208,17 @@ fn push_end_tag(buf: &mut String, name: &[u8]) {
 }
 
 /// Escape XML special characters.
-fn escape_xml(s: &str) -> String {
+/// The name of a `w:bookmarkStart`, leaving out `_GoBack`, which Word keeps for the
+/// last edit rather than as a place to link to.
+fn bookmark_name(e: &quick_xml::events::BytesStart<'_>) -> Option<String> {
+    e.attributes()
+        .flatten()
+        .find(|attr| attr.key.as_ref() == b"w:name")
+        .map(|attr| String::from_utf8_lossy(&attr.value).to_string())
+        .filter(|name| !name.is_empty() && name != "_GoBack")
+}
+
+pub(super) fn escape_xml(s: &str) -> String {
     s.replace('&', "&amp;")
         .replace('<', "&lt;")
         .replace('>', "&gt;")
@@ -1896,7 +2227,7 @@ fn escape_xml(s: &str) -> String {
 }
 
 /// Guess MIME type from file extension.
-fn guess_mime_type(path: &str) -> Option<String> {
+pub(super) fn guess_mime_type(path: &str) -> Option<String> {
     let ext = std::path::Path::new(path)
         .extension()
         .and_then(|e| e.to_str())
@@ -1950,6 +2281,8 @@ fn deduplicate_paragraph_block(paragraphs: Vec<Paragraph>) -> Vec<Paragraph> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::model::{FieldType, FormFieldType, MathMode};
+    use crate::render::FieldHandling;
 
     #[test]
     fn test_open_docx() {
@@ -2043,6 +2376,7 @@ mod tests {
             relationships: crate::container::Relationships::default(),
             footnotes: HashMap::new(),
             endnotes: HashMap::new(),
+            comments: CommentMap::default(),
         };
 
         let para = parser.parse_paragraph(xml).unwrap();
@@ -2081,6 +2415,7 @@ mod tests {
             relationships: crate::container::Relationships::default(),
             footnotes: HashMap::new(),
             endnotes: HashMap::new(),
+            comments: CommentMap::default(),
         };
 
         let para = parser.parse_paragraph(xml).unwrap();
@@ -2121,6 +2456,7 @@ mod tests {
             relationships: crate::container::Relationships::default(),
             footnotes: HashMap::new(),
             endnotes: HashMap::new(),
+            comments: CommentMap::default(),
         };
 
         let para = parser.parse_paragraph(xml).unwrap();
@@ -2157,6 +2493,7 @@ mod tests {
             relationships: crate::container::Relationships::default(),
             footnotes: HashMap::new(),
             endnotes: HashMap::new(),
+            comments: CommentMap::default(),
         };
 
         let para = parser.parse_paragraph(xml).unwrap();
@@ -2193,6 +2530,7 @@ mod tests {
             relationships: crate::container::Relationships::default(),
             footnotes: HashMap::new(),
             endnotes: HashMap::new(),
+            comments: CommentMap::default(),
         };
 
         let para = parser.parse_paragraph(xml).unwrap();
@@ -2225,6 +2563,7 @@ mod tests {
             relationships: crate::container::Relationships::default(),
             footnotes: HashMap::new(),
             endnotes: HashMap::new(),
+            comments: CommentMap::default(),
         };
 
         let para = parser.parse_paragraph(xml).unwrap();
@@ -2270,6 +2609,7 @@ mod tests {
             relationships: crate::container::Relationships::default(),
             footnotes: HashMap::new(),
             endnotes: HashMap::new(),
+            comments: CommentMap::default(),
         };
 
         let para = parser.parse_paragraph(xml).unwrap();
@@ -2318,6 +2658,7 @@ mod tests {
             relationships: crate::container::Relationships::default(),
             footnotes: HashMap::new(),
             endnotes: HashMap::new(),
+            comments: CommentMap::default(),
         };
 
         let para = parser.parse_paragraph(xml).unwrap();
@@ -2343,6 +2684,28 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_tracked_deletion_reads_del_text() {
+        // Word stores the text of a deletion in w:delText
+        let xml = r#"<w:p>
+            <w:r><w:t xml:space="preserve">Keep </w:t></w:r>
+            <w:del w:id="1" w:author="A">
+                <w:r><w:delText xml:space="preserve">gone </w:delText></w:r>
+            </w:del>
+            <w:r><w:t>text</w:t></w:r>
+        </w:p>"#;
+
+        let para = empty_test_parser().parse_paragraph(xml).unwrap();
+        let deleted: Vec<&str> = para
+            .runs
+            .iter()
+            .filter(|r| r.revision == RevisionType::Deleted)
+            .map(|r| r.text.as_str())
+            .collect();
+        assert_eq!(deleted, vec!["gone "]);
+        assert_eq!(para.plain_text(), "Keep gone text");
+    }
+
     // =========================================================================
     // Footnote / Endnote Tests
     // =========================================================================
@@ -2454,6 +2817,7 @@ mod tests {
             relationships: crate::container::Relationships::default(),
             footnotes,
             endnotes: HashMap::new(),
+            comments: CommentMap::default(),
         };
 
         let para = parser.parse_paragraph(xml).unwrap();
@@ -2494,6 +2858,7 @@ mod tests {
             relationships: crate::container::Relationships::default(),
             footnotes: HashMap::new(),
             endnotes,
+            comments: CommentMap::default(),
         };
 
         let para = parser.parse_paragraph(xml).unwrap();
@@ -2526,6 +2891,7 @@ mod tests {
             relationships: crate::container::Relationships::default(),
             footnotes: HashMap::new(), // No footnotes
             endnotes: HashMap::new(),
+            comments: CommentMap::default(),
         };
 
         let para = parser.parse_paragraph(xml).unwrap();
@@ -2740,6 +3106,7 @@ mod tests {
             "word/numbering.xml",
             "word/footnotes.xml",
             "word/endnotes.xml",
+            "word/comments.xml",
         ] {
             let data = create_minimal_docx_with_malformed_optional_part(part_path);
             let err = match DocxParser::from_bytes(data) {
@@ -2753,6 +3120,329 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_comment_ranges_and_references() {
+        let mut parser = empty_test_parser();
+        parser.comments = CommentMap::parse(
+            r#"<w:comments xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main">
+    <w:comment w:id="3" w:author="Alice"><w:p><w:r><w:t>Cite this.</w:t></w:r></w:p></w:comment>
+</w:comments>"#,
+            None,
+            None,
+        )
+        .unwrap();
+
+        let first = parser
+            .parse_paragraph(
+                r#"<w:p><w:r><w:t xml:space="preserve">Before </w:t></w:r><w:commentRangeStart w:id="3"/><w:r><w:t>the claim</w:t></w:r></w:p>"#,
+            )
+            .unwrap();
+        let second = parser
+            .parse_paragraph(
+                r#"<w:p><w:r><w:t>continues</w:t></w:r><w:del><w:r><w:delText>gone</w:delText></w:r></w:del><w:commentRangeEnd w:id="3"/><w:r><w:commentReference w:id="3"/></w:r><w:r><w:t xml:space="preserve"> after.</w:t></w:r><w:r><w:commentReference w:id="9"/></w:r></w:p>"#,
+            )
+            .unwrap();
+
+        assert!(first.comment_ids.is_empty());
+        assert_eq!(second.comment_ids, ["3"]);
+        assert_eq!(second.plain_text(), "continuesgone after.");
+        let comments = parser.comments.take();
+        assert_eq!(comments[0].anchor.as_deref(), Some("the claim\ncontinues"));
+        assert_eq!(comments[0].text, "Cite this.");
+    }
+
+    #[test]
+    fn test_comments_inside_table_cells() {
+        let mut parser = empty_test_parser();
+        parser.comments = CommentMap::parse(
+            r#"<w:comments xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main">
+    <w:comment w:id="4" w:author="Bob"><w:p><w:r><w:t>Check the total.</w:t></w:r></w:p></w:comment>
+</w:comments>"#,
+            None,
+            None,
+        )
+        .unwrap();
+
+        let table = parser
+            .parse_table(
+                r#"<w:tbl><w:tr><w:tc><w:p><w:r><w:t>Total</w:t></w:r></w:p></w:tc><w:tc><w:p><w:commentRangeStart w:id="4"/><w:r><w:t>42</w:t></w:r><w:commentRangeEnd w:id="4"/><w:r><w:commentReference w:id="4"/></w:r></w:p></w:tc></w:tr></w:tbl>"#,
+            )
+            .unwrap();
+
+        let cells = &table.rows[0].cells;
+        assert!(cells[0].content[0].comment_ids.is_empty());
+        assert_eq!(cells[1].content[0].comment_ids, ["4"]);
+        let comments = parser.comments.take();
+        assert_eq!(comments[0].anchor.as_deref(), Some("42"));
+    }
+
+    #[test]
+    fn test_equations_become_math_runs() {
+        let mut parser = empty_test_parser();
+        let inline = parser
+            .parse_paragraph(
+                r#"<w:p><w:r><w:t xml:space="preserve">Area </w:t></w:r><m:oMath><m:r><m:t>A=π</m:t></m:r><m:sSup><m:e><m:r><m:t>r</m:t></m:r></m:e><m:sup><m:r><m:t>2</m:t></m:r></m:sup></m:sSup></m:oMath><w:r><w:t>.</w:t></w:r></w:p>"#,
+            )
+            .unwrap();
+        assert_eq!(inline.runs.len(), 3);
+        assert_eq!(inline.runs[1].text, "A=\\pi r^{2}");
+        assert_eq!(inline.runs[1].math, Some(MathMode::Inline));
+        assert!(!inline.runs[0].is_math());
+
+        let display = parser
+            .parse_paragraph(
+                r#"<w:p><m:oMathPara><m:oMath><m:f><m:num><m:r><m:t>1</m:t></m:r></m:num><m:den><m:r><m:t>2</m:t></m:r></m:den></m:f></m:oMath></m:oMathPara></w:p>"#,
+            )
+            .unwrap();
+        assert_eq!(display.runs.len(), 1);
+        assert_eq!(display.runs[0].text, "\\frac{1}{2}");
+        assert_eq!(display.runs[0].math, Some(MathMode::Display));
+
+        let table = parser
+            .parse_table(
+                r#"<w:tbl><w:tr><w:tc><w:p><m:oMath><m:r><m:t>x≥0</m:t></m:r></m:oMath></w:p></w:tc></w:tr></w:tbl>"#,
+            )
+            .unwrap();
+        let cell = &table.rows[0].cells[0].content[0];
+        assert_eq!(cell.runs[0].text, "x\\geq 0");
+        assert!(cell.runs[0].is_math());
+    }
+
+    #[test]
+    fn test_content_controls_become_form_fields() {
+        let doc_xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main"
+            xmlns:w14="http://schemas.microsoft.com/office/word/2010/wordml">
+  <w:body>
+    <w:sdt>
+      <w:sdtPr><w:docPartObj><w:docPartGallery w:val="Table of Contents"/></w:docPartObj></w:sdtPr>
+      <w:sdtContent><w:p><w:r><w:t>Contents</w:t></w:r></w:p></w:sdtContent>
+    </w:sdt>
+    <w:p>
+      <w:r><w:t xml:space="preserve">Name: </w:t></w:r>
+      <w:sdt>
+        <w:sdtPr><w:alias w:val="Full name"/><w:tag w:val="name"/><w:text/></w:sdtPr>
+        <w:sdtContent><w:r><w:t>Ada Lovelace</w:t></w:r></w:sdtContent>
+      </w:sdt>
+    </w:p>
+    <w:sdt>
+      <w:sdtPr><w:tag w:val="notes"/><w:showingPlcHdr/></w:sdtPr>
+      <w:sdtContent><w:p><w:r><w:t>Click to enter text.</w:t></w:r></w:p></w:sdtContent>
+    </w:sdt>
+    <w:tbl>
+      <w:tr>
+        <w:sdt>
+          <w:sdtPr><w:tag w:val="country"/><w:dropDownList><w:listItem w:displayText="Korea" w:value="KR"/></w:dropDownList></w:sdtPr>
+          <w:sdtContent><w:tc><w:p><w:r><w:t>Korea</w:t></w:r></w:p></w:tc></w:sdtContent>
+        </w:sdt>
+        <w:tc>
+          <w:p>
+            <w:sdt>
+              <w:sdtPr><w:tag w:val="agree"/><w14:checkbox><w14:checked w14:val="1"/></w14:checkbox></w:sdtPr>
+              <w:sdtContent><w:r><w:t>☒</w:t></w:r></w:sdtContent>
+            </w:sdt>
+            <w:r><w:t xml:space="preserve"> I agree</w:t></w:r>
+          </w:p>
+        </w:tc>
+      </w:tr>
+    </w:tbl>
+  </w:body>
+</w:document>"#;
+
+        let mut parser = DocxParser::from_bytes(create_minimal_docx(doc_xml)).unwrap();
+        let doc = parser.parse().unwrap();
+        let fields = doc.form_fields();
+        let names: Vec<_> = fields.iter().map(|f| f.name().unwrap()).collect();
+        assert_eq!(names, ["name", "notes", "country", "agree"]);
+
+        assert_eq!(fields[0].alias.as_deref(), Some("Full name"));
+        assert_eq!(fields[0].field_type, FormFieldType::Text);
+        assert_eq!(fields[0].value.as_deref(), Some("Ada Lovelace"));
+        assert_eq!(fields[1].value, None);
+        assert_eq!(
+            fields[1].placeholder.as_deref(),
+            Some("Click to enter text.")
+        );
+        assert_eq!(fields[2].field_type, FormFieldType::DropDown);
+        assert_eq!(fields[2].value.as_deref(), Some("Korea"));
+        assert_eq!(fields[2].items[0].value, "KR");
+        assert_eq!(fields[3].checked, Some(true));
+
+        // The content is still read as text
+        let text = doc.plain_text();
+        assert!(text.contains("Contents"), "{text}");
+        assert!(text.contains("Name: Ada Lovelace"), "{text}");
+        assert!(text.contains("☒ I agree"), "{text}");
+    }
+
+    #[test]
+    fn test_legacy_form_fields() {
+        let doc_xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main">
+  <w:body>
+    <w:p>
+      <w:r><w:fldChar w:fldCharType="begin"><w:ffData><w:name w:val="Check1"/><w:enabled/><w:checkBox><w:sizeAuto/><w:default w:val="1"/></w:checkBox></w:ffData></w:fldChar></w:r>
+      <w:r><w:instrText xml:space="preserve"> FORMCHECKBOX </w:instrText></w:r>
+      <w:r><w:fldChar w:fldCharType="end"/></w:r>
+      <w:r><w:t xml:space="preserve"> Subscribe</w:t></w:r>
+    </w:p>
+    <w:tbl>
+      <w:tr>
+        <w:tc>
+          <w:p>
+            <w:r><w:fldChar w:fldCharType="begin"><w:ffData><w:name w:val="Plan"/><w:ddList><w:result w:val="2"/><w:listEntry w:val="Free"/><w:listEntry w:val="Pro"/><w:listEntry w:val="Team"/></w:ddList></w:ffData></w:fldChar></w:r>
+            <w:r><w:instrText xml:space="preserve"> FORMDROPDOWN </w:instrText></w:r>
+            <w:r><w:fldChar w:fldCharType="separate"/></w:r>
+            <w:r><w:fldChar w:fldCharType="end"/></w:r>
+          </w:p>
+        </w:tc>
+      </w:tr>
+    </w:tbl>
+  </w:body>
+</w:document>"#;
+
+        let mut parser = DocxParser::from_bytes(create_minimal_docx(doc_xml)).unwrap();
+        let doc = parser.parse().unwrap();
+        let fields = doc.form_fields();
+        assert_eq!(fields.len(), 2);
+        assert_eq!(fields[0].tag.as_deref(), Some("Check1"));
+        assert_eq!(fields[0].checkbox_state(), Some(true));
+        assert!(fields[0].legacy);
+        assert_eq!(fields[1].field_type, FormFieldType::DropDown);
+        assert_eq!(fields[1].value.as_deref(), Some("Team"));
+
+        let md = crate::render::to_markdown(&doc, &Default::default()).unwrap();
+        assert!(md.starts_with("[x] Subscribe"), "{md}");
+        assert!(md.contains("| Team |"), "{md}");
+    }
+
+    #[test]
+    fn test_fields_keep_their_instructions() {
+        let doc_xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main">
+  <w:body>
+    <w:p>
+      <w:r><w:t xml:space="preserve">See </w:t></w:r>
+      <w:r><w:fldChar w:fldCharType="begin"/></w:r>
+      <w:r><w:instrText xml:space="preserve"> REF _Ref1 \h </w:instrText></w:r>
+      <w:r><w:fldChar w:fldCharType="separate"/></w:r>
+      <w:r><w:t>Table 1</w:t></w:r>
+      <w:r><w:fldChar w:fldCharType="end"/></w:r>
+      <w:r><w:t xml:space="preserve"> on page </w:t></w:r>
+      <w:fldSimple w:instr=" PAGEREF _Ref1 \h "><w:r><w:t>4</w:t></w:r></w:fldSimple>
+      <w:r><w:t xml:space="preserve"> or </w:t></w:r>
+      <w:r><w:fldChar w:fldCharType="begin"/></w:r>
+      <w:r><w:instrText xml:space="preserve"> HYPERLINK "https://example.com/" </w:instrText></w:r>
+      <w:r><w:fldChar w:fldCharType="separate"/></w:r>
+      <w:r><w:t>the site</w:t></w:r>
+      <w:r><w:fldChar w:fldCharType="end"/></w:r>
+    </w:p>
+    <w:tbl>
+      <w:tr>
+        <w:tc>
+          <w:p>
+            <w:r><w:t xml:space="preserve">Page </w:t></w:r>
+            <w:fldSimple w:instr=" PAGE "><w:r><w:t>2</w:t></w:r></w:fldSimple>
+          </w:p>
+        </w:tc>
+      </w:tr>
+    </w:tbl>
+  </w:body>
+</w:document>"#;
+
+        let mut parser = DocxParser::from_bytes(create_minimal_docx(doc_xml)).unwrap();
+        let doc = parser.parse().unwrap();
+        let fields = doc.fields();
+        let types: Vec<_> = fields.iter().map(|f| f.field_type).collect();
+        assert_eq!(
+            types,
+            [
+                FieldType::Ref,
+                FieldType::PageRef,
+                FieldType::Hyperlink,
+                FieldType::Page
+            ]
+        );
+        assert_eq!(fields[0].instruction, "REF _Ref1 \\h");
+        assert_eq!(fields[0].result_runs, 1);
+
+        let md = crate::render::to_markdown(&doc, &Default::default()).unwrap();
+        assert!(
+            md.starts_with("See Table 1 on page 4 or [the site](https://example.com/)"),
+            "{md}"
+        );
+        assert!(md.contains("| Page 2 |"), "{md}");
+
+        let options =
+            crate::render::RenderOptions::new().with_field_handling(FieldHandling::LinkReferences);
+        let md = crate::render::to_markdown(&doc, &options).unwrap();
+        assert!(
+            md.starts_with("See [Table 1](#_Ref1) on page [4](#_Ref1) or"),
+            "{md}"
+        );
+        let options =
+            crate::render::RenderOptions::new().with_field_handling(FieldHandling::DropPageNumbers);
+        let md = crate::render::to_markdown(&doc, &options).unwrap();
+        assert!(md.starts_with("See Table 1 on page  or"), "{md}");
+        assert!(!md.contains("Page 2"), "{md}");
+    }
+
+    #[test]
+    fn test_bookmarks_are_kept_for_linked_references() {
+        let doc_xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main">
+  <w:body>
+    <w:p>
+      <w:bookmarkStart w:id="0" w:name="_Ref1"/>
+      <w:r><w:t>Figure 2</w:t></w:r>
+      <w:bookmarkEnd w:id="0"/>
+      <w:bookmarkStart w:id="1" w:name="_GoBack"/>
+      <w:bookmarkEnd w:id="1"/>
+    </w:p>
+    <w:p>
+      <w:r><w:t xml:space="preserve">See </w:t></w:r>
+      <w:fldSimple w:instr=" REF _Ref1 \h "><w:r><w:t>Figure 2</w:t></w:r></w:fldSimple>
+      <w:r><w:t xml:space="preserve"> and </w:t></w:r>
+      <w:fldSimple w:instr=" HYPERLINK \l &quot;_Toc3&quot; "><w:r><w:t>the total</w:t></w:r></w:fldSimple>
+      <w:r><w:t>.</w:t></w:r>
+    </w:p>
+    <w:tbl>
+      <w:tr>
+        <w:tc>
+          <w:p><w:bookmarkStart w:id="2" w:name="_Toc3"/><w:r><w:t>Total</w:t></w:r></w:p>
+        </w:tc>
+      </w:tr>
+    </w:tbl>
+  </w:body>
+</w:document>"#;
+
+        let mut parser = DocxParser::from_bytes(create_minimal_docx(doc_xml)).unwrap();
+        let doc = parser.parse().unwrap();
+        let blocks = &doc.sections[0].content;
+        let Block::Paragraph(target) = &blocks[0] else {
+            panic!("expected a paragraph");
+        };
+        assert_eq!(target.bookmarks, ["_Ref1"]);
+        let Block::Table(table) = &blocks[2] else {
+            panic!("expected a table");
+        };
+        assert_eq!(table.rows[0].cells[0].content[0].bookmarks, ["_Toc3"]);
+
+        let md = crate::render::to_markdown(&doc, &Default::default()).unwrap();
+        assert!(!md.contains("<a id="), "{md}");
+        assert!(md.contains("See Figure 2 and the total."), "{md}");
+
+        let options =
+            crate::render::RenderOptions::new().with_field_handling(FieldHandling::LinkReferences);
+        let md = crate::render::to_markdown(&doc, &options).unwrap();
+        assert!(md.starts_with("<a id=\"_Ref1\"></a>Figure 2"), "{md}");
+        assert!(
+            md.contains("See [Figure 2](#_Ref1) and [the total](#_Toc3)."),
+            "{md}"
+        );
+        assert!(md.contains("<a id=\"_Toc3\"></a>Total"), "{md}");
+    }
+
     fn empty_test_parser() -> DocxParser {
         DocxParser::from_bytes(create_minimal_docx(
             r#"<?xml version="1.0" encoding="UTF-8"?>
@@ -2937,7 +3627,7 @@ mod tests {
 
     #[test]
     fn test_docx_nested_table_malformed_entity_preserves_raw_text() {
-        let parser = empty_test_parser();
+        let mut parser = empty_test_parser();
         let xml = r#"<w:tbl xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main">
   <w:tr>
     <w:tc>
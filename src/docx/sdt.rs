@@ -0,0 +1,257 @@
+//! DOCX content controls (`w:sdt`) as form fields.
+//!
+//! A content control wraps runs inside a paragraph, whole paragraphs, table cells or
+//! rows. Its `w:sdtPr` properties say what kind of field it is (plain or rich text,
+//! drop-down, combo box, date, checkbox, picture) and name it with a tag and an alias;
+//! its `w:sdtContent` is the text the document shows, which the parsers read as usual.
+//! Controls that are not fields — building blocks such as a table of contents or a
+//! cover page, citations, bibliographies, groups and repeating sections — are left
+//! out, their content read as if they were not there.
+
+use crate::error::Result;
+use crate::model::{
+    Block, FormField, FormFieldItem, FormFieldType, Paragraph, RevisionType, Table, TextRun,
+};
+
+/// A content control's properties, waiting for its content.
+#[derive(Debug, Clone, Default)]
+pub struct ContentControl {
+    field: FormField,
+    /// `w:showingPlcHdr`: the content is the placeholder, not a value
+    showing_placeholder: bool,
+}
+
+impl ContentControl {
+    /// Parse the content of `w:sdtPr`. `None` when the control is not a form field.
+    pub fn parse(sdt_pr_xml: &str) -> Result<Option<Self>> {
+        let mut control = ContentControl::default();
+        let mut reader = crate::decode::reader_for(sdt_pr_xml);
+        let mut buf = Vec::new();
+
+        loop {
+            match reader.read_event_into(&mut buf) {
+                Ok(
+                    quick_xml::events::Event::Start(ref e) | quick_xml::events::Event::Empty(ref e),
+                ) => {
+                    let value = |key: &[u8]| {
+                        e.attributes()
+                            .flatten()
+                            .find(|attr| attr.key.as_ref() == key)
+                            .map(|attr| crate::decode::attr_value_lossy(&attr))
+                    };
+                    let field = &mut control.field;
+                    match e.name().as_ref() {
+                        b"w:id" => field.id = value(b"w:val"),
+                        b"w:tag" => field.tag = value(b"w:val"),
+                        b"w:alias" => field.alias = value(b"w:val"),
+                        b"w:showingPlcHdr" => {
+                            control.showing_placeholder =
+                                !matches!(value(b"w:val").as_deref(), Some("0" | "false" | "off"));
+                        }
+                        b"w:text" => field.field_type = FormFieldType::Text,
+                        b"w:richText" => field.field_type = FormFieldType::RichText,
+                        b"w:comboBox" => field.field_type = FormFieldType::ComboBox,
+                        b"w:dropDownList" => field.field_type = FormFieldType::DropDown,
+                        b"w:listItem" => {
+                            let stored = value(b"w:value").unwrap_or_default();
+                            field.items.push(FormFieldItem {
+                                display_text: value(b"w:displayText")
+                                    .unwrap_or_else(|| stored.clone()),
+                                value: stored,
+                            });
+                        }
+                        b"w:date" => {
+                            field.field_type = FormFieldType::Date;
+                            field.date = value(b"w:fullDate");
+                        }
+                        b"w14:checkbox" => {
+                            field.field_type = FormFieldType::Checkbox;
+                            field.checked = Some(false);
+                        }
+                        b"w14:checked" => {
+                            field.checked = Some(matches!(
+                                value(b"w14:val").as_deref(),
+                                Some("1" | "true" | "on") | None
+                            ));
+                        }
+                        b"w:picture" => field.field_type = FormFieldType::Picture,
+                        b"w:docPartObj"
+                        | b"w:docPartList"
+                        | b"w:citation"
+                        | b"w:bibliography"
+                        | b"w:equation"
+                        | b"w:group"
+                        | b"w15:repeatingSection"
+                        | b"w15:repeatingSectionItem" => {
+                            return Ok(None);
+                        }
+                        _ => {}
+                    }
+                }
+                Ok(quick_xml::events::Event::Eof) => break,
+                Err(e) => return Err(e.into()),
+                _ => {}
+            }
+            buf.clear();
+        }
+
+        Ok(Some(control))
+    }
+
+    /// Put the field on the runs from `start` on, the content of a control inside a
+    /// paragraph.
+    pub fn attach_to_runs(self, runs: &mut Vec<TextRun>, start: usize) {
+        let start = start.min(runs.len());
+        let text = run_text(&runs[start..]);
+        let field = self.finish(text);
+        place(runs, start, field);
+    }
+
+    /// Put the field on the first of `paragraphs`, the content of a control around
+    /// paragraphs, cells or rows. A control with no paragraph is dropped.
+    pub fn attach_to_paragraphs<'a>(self, paragraphs: impl IntoIterator<Item = &'a mut Paragraph>) {
+        let mut paragraphs: Vec<&mut Paragraph> = paragraphs.into_iter().collect();
+        let text = paragraphs
+            .iter()
+            .map(|para| run_text(&para.runs))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let field = self.finish(text);
+        if let Some(first) = paragraphs.first_mut() {
+            place(&mut first.runs, 0, field);
+        }
+    }
+
+    /// Put the field on the first paragraph of `blocks`, the content of a control around
+    /// body paragraphs and tables.
+    pub fn attach_to_blocks(self, blocks: &mut [Block]) {
+        fn table_paragraphs<'a>(table: &'a mut Table, paragraphs: &mut Vec<&'a mut Paragraph>) {
+            for cell in table.rows.iter_mut().flat_map(|row| row.cells.iter_mut()) {
+                paragraphs.extend(cell.content.iter_mut());
+                for nested in cell.nested_tables.iter_mut() {
+                    table_paragraphs(nested, paragraphs);
+                }
+            }
+        }
+
+        let mut paragraphs = Vec::new();
+        for block in blocks {
+            match block {
+                Block::Paragraph(para) => paragraphs.push(para),
+                Block::Table(table) => table_paragraphs(table, &mut paragraphs),
+                _ => {}
+            }
+        }
+        self.attach_to_paragraphs(paragraphs);
+    }
+
+    fn finish(self, text: String) -> FormField {
+        let mut field = self.field;
+        if self.showing_placeholder {
+            field.placeholder = Some(text).filter(|t| !t.is_empty());
+        } else if !field.is_checkbox() {
+            field.value = Some(text);
+        }
+        field
+    }
+}
+
+/// Where the content of a control inside a table starts.
+#[derive(Debug, Clone, Copy)]
+pub enum TableScope {
+    /// At this run of the paragraph being read
+    Runs(usize),
+    /// At this paragraph of the cell being read
+    Paragraphs(usize),
+    /// At this cell of the row being read
+    Cells(usize),
+    /// At this row of the table
+    Rows(usize),
+}
+
+/// The text of `runs` as the document shows it, deleted text left out.
+fn run_text(runs: &[TextRun]) -> String {
+    runs.iter()
+        .filter(|run| run.revision != RevisionType::Deleted)
+        .map(|run| run.text.as_str())
+        .collect::<String>()
+        .trim()
+        .to_string()
+}
+
+/// Set `field` on the run at `index`, inserting an empty run there when there is none
+/// or when that run already starts a field of its own (a control inside a control).
+pub fn place(runs: &mut Vec<TextRun>, index: usize, field: FormField) {
+    if runs.get(index).is_none_or(|run| run.form_field.is_some()) {
+        runs.insert(index, TextRun::default());
+    }
+    runs[index].form_field = Some(Box::new(field));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn control(xml: &str) -> ContentControl {
+        ContentControl::parse(xml).unwrap().unwrap()
+    }
+
+    #[test]
+    fn test_parse_properties() {
+        let dropdown = control(
+            r#"<w:alias w:val="Country"/><w:tag w:val="country"/><w:id w:val="-12"/><w:dropDownList w:lastValue="KR"><w:listItem w:displayText="Korea" w:value="KR"/><w:listItem w:value="JP"/></w:dropDownList>"#,
+        );
+        assert_eq!(dropdown.field.alias.as_deref(), Some("Country"));
+        assert_eq!(dropdown.field.name(), Some("country"));
+        assert_eq!(dropdown.field.id.as_deref(), Some("-12"));
+        assert_eq!(dropdown.field.field_type, FormFieldType::DropDown);
+        assert_eq!(dropdown.field.items.len(), 2);
+        assert_eq!(dropdown.field.items[0].display_text, "Korea");
+        assert_eq!(dropdown.field.items[1].display_text, "JP");
+
+        let checkbox = control(
+            r#"<w14:checkbox><w14:checked w14:val="1"/><w14:checkedState w14:val="2612"/></w14:checkbox>"#,
+        );
+        assert_eq!(checkbox.field.field_type, FormFieldType::Checkbox);
+        assert_eq!(checkbox.field.checked, Some(true));
+
+        let date = control(
+            r#"<w:date w:fullDate="2024-03-01T00:00:00Z"><w:dateFormat w:val="M/d/yyyy"/></w:date>"#,
+        );
+        assert_eq!(date.field.date.as_deref(), Some("2024-03-01T00:00:00Z"));
+        assert_eq!(control("").field.field_type, FormFieldType::RichText);
+
+        let toc = r#"<w:docPartObj><w:docPartGallery w:val="Table of Contents"/></w:docPartObj>"#;
+        assert!(ContentControl::parse(toc).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_attach_values_and_placeholders() {
+        let mut runs = vec![TextRun::plain("Name: "), TextRun::plain("Ada")];
+        let mut deleted = TextRun::plain("Bob");
+        deleted.revision = RevisionType::Deleted;
+        runs.push(deleted);
+        control(r#"<w:text/>"#).attach_to_runs(&mut runs, 1);
+        let field = runs[1].form_field.as_deref().unwrap();
+        assert_eq!(field.value.as_deref(), Some("Ada"));
+        assert!(runs[0].form_field.is_none());
+
+        let mut runs = vec![TextRun::plain("Click here")];
+        control(r#"<w:showingPlcHdr/>"#).attach_to_runs(&mut runs, 0);
+        let field = runs[0].form_field.as_deref().unwrap();
+        assert_eq!(field.value, None);
+        assert_eq!(field.placeholder.as_deref(), Some("Click here"));
+
+        let mut runs = Vec::new();
+        control(r#"<w:text/>"#).attach_to_runs(&mut runs, 0);
+        assert_eq!(runs.len(), 1);
+        assert!(!runs[0].is_empty());
+
+        let mut paragraphs = vec![Paragraph::with_text("one"), Paragraph::with_text("two")];
+        control("").attach_to_paragraphs(&mut paragraphs);
+        control("").attach_to_paragraphs(&mut paragraphs);
+        assert_eq!(paragraphs[0].runs.len(), 2);
+        let fields: Vec<_> = paragraphs[0].form_fields().collect();
+        assert_eq!(fields[0].value.as_deref(), Some("one\ntwo"));
+    }
+}
@@ -0,0 +1,1585 @@
+//! DOCX writer.
+//!
+//! Serializes a [`Document`] into a WordprocessingML package. The output is
+//! shaped so that [`DocxParser`](super::DocxParser) reads it back to the same
+//! model for everything both sides understand: headings, lists, run formatting,
+//! hyperlinks, tracked changes, tables with merged cells, inline images and
+//! footnotes/endnotes.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::{Cursor, Write};
+
+use crate::error::Result;
+use crate::model::{
+    unique_file_name, Block, Cell, CellAlignment, Document, InlineImage, ListInfo, ListType,
+    Metadata, Paragraph, ResourceType, RevisionType, Table, TextAlignment, TextRun, TextStyle,
+    VerticalAlignment,
+};
+use crate::render::common::{note_definition, xml_chars};
+
+use super::parser::{self, guess_mime_type};
+
+const REL_NS: &str = "http://schemas.openxmlformats.org/officeDocument/2006/relationships";
+
+/// Page size and margins of US Letter with one-inch margins, in twips.
+const SECTION_PROPERTIES: &str = "<w:pgSz w:w=\"12240\" w:h=\"15840\"/>\
+     <w:pgMar w:top=\"1440\" w:right=\"1440\" w:bottom=\"1440\" w:left=\"1440\" \
+     w:header=\"720\" w:footer=\"720\" w:gutter=\"0\"/>";
+
+/// Width of the text column on that page, shared out between columns of
+/// tables that do not carry their own widths.
+const TEXT_WIDTH_TWIPS: u32 = 9360;
+
+/// Fallback image size (6 x 4 inches) when neither the image nor its
+/// resource knows its dimensions.
+const DEFAULT_IMAGE_EMU: (u32, u32) = (5_486_400, 3_657_600);
+
+/// EMUs per pixel at 96 DPI.
+const EMU_PER_PIXEL: u32 = 9525;
+
+/// Writes a [`Document`] out as a .docx file.
+///
+/// # Example
+///
+/// ```no_run
+/// use undoc::docx::DocxWriter;
+///
+/// let doc = undoc::parse_file("report.odt")?;
+/// let bytes = DocxWriter::new().write(&doc)?;
+/// std::fs::write("report.docx", bytes)?;
+/// # Ok::<(), undoc::Error>(())
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DocxWriter;
+
+impl DocxWriter {
+    /// Create a writer.
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Serialize a document into the bytes of a .docx package.
+    pub fn write(&self, doc: &Document) -> Result<Vec<u8>> {
+        let mut package = Package::new(doc);
+        let document_xml = package.document_xml(doc);
+
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let options = zip::write::SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Deflated);
+
+        let mut parts: Vec<(String, Vec<u8>)> = vec![
+            (
+                "[Content_Types].xml".into(),
+                package.content_types(doc).into(),
+            ),
+            ("_rels/.rels".into(), package_rels(doc).into()),
+            ("docProps/core.xml".into(), core_xml(&doc.metadata).into()),
+        ];
+        if let Some(app) = app_xml(&doc.metadata) {
+            parts.push(("docProps/app.xml".into(), app.into()));
+        }
+        parts.push(("word/document.xml".into(), document_xml.into()));
+        parts.push((
+            "word/_rels/document.xml.rels".into(),
+            package.document_rels().into(),
+        ));
+        parts.push(("word/styles.xml".into(), package.styles_xml().into()));
+        if !package.lists.instances.is_empty() {
+            parts.push((
+                "word/numbering.xml".into(),
+                package.lists.numbering_xml().into(),
+            ));
+        }
+        if !package.footnotes.is_empty() {
+            parts.push((
+                "word/footnotes.xml".into(),
+                notes_xml("footnote", &package.footnotes).into(),
+            ));
+        }
+        if !package.endnotes.is_empty() {
+            parts.push((
+                "word/endnotes.xml".into(),
+                notes_xml("endnote", &package.endnotes).into(),
+            ));
+        }
+        for (target, resource_id) in &package.media {
+            parts.push((
+                format!("word/{}", target),
+                doc.resources[resource_id].data.clone(),
+            ));
+        }
+
+        for (path, data) in parts {
+            zip.start_file(path, options)?;
+            zip.write_all(&data)?;
+        }
+        Ok(zip.finish()?.into_inner())
+    }
+
+    /// Serialize a document and write it to a .docx file.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn write_to_file(&self, doc: &Document, path: impl AsRef<std::path::Path>) -> Result<()> {
+        std::fs::write(path, self.write(doc)?)?;
+        Ok(())
+    }
+}
+
+/// A relationship from word/document.xml to another part or an external URL.
+struct Relationship {
+    id: String,
+    kind: &'static str,
+    target: String,
+    external: bool,
+}
+
+/// Which notes part a footnote label lives in, and the `w:id` it gets there.
+#[derive(Clone, Copy)]
+enum NoteRef {
+    Footnote(u32),
+    Endnote(u32),
+}
+
+/// State gathered while the body is written: the relationships, media, list
+/// definitions, notes and styles the other parts have to declare.
+struct Package {
+    relationships: Vec<Relationship>,
+    taken_ids: HashSet<String>,
+    next_rel: u32,
+    /// Resource id → relationship id of its media part, and the size its
+    /// resource gives, in EMUs.
+    image_rels: HashMap<String, (String, Option<(u32, u32)>)>,
+    /// Hyperlink target → relationship id.
+    link_rels: HashMap<String, String>,
+    /// Media part path (relative to word/) → resource id, in package order.
+    media: Vec<(String, String)>,
+    /// Footnote label as it appears in `[^label]` → where the note is written.
+    notes: HashMap<String, NoteRef>,
+    footnotes: BTreeMap<u32, String>,
+    endnotes: BTreeMap<u32, String>,
+    lists: ListNumbering,
+    /// Paragraph styles the document names beyond the built-in ones.
+    custom_styles: BTreeMap<String, String>,
+    next_drawing: u32,
+    next_revision: u32,
+}
+
+impl Package {
+    fn new(doc: &Document) -> Self {
+        let mut package = Self {
+            relationships: Vec::new(),
+            taken_ids: HashSet::new(),
+            next_rel: 0,
+            image_rels: HashMap::new(),
+            link_rels: HashMap::new(),
+            media: Vec::new(),
+            notes: HashMap::new(),
+            footnotes: BTreeMap::new(),
+            endnotes: BTreeMap::new(),
+            lists: ListNumbering::default(),
+            custom_styles: BTreeMap::new(),
+            next_drawing: 0,
+            next_revision: 0,
+        };
+        package.collect_media(doc);
+        package.collect_notes(doc);
+        package.add_part_rel("styles", "styles.xml");
+        package
+    }
+
+    /// Embed every image resource. A resource id that is a valid relationship
+    /// id is kept as one, so the parser hands the same id back.
+    fn collect_media(&mut self, doc: &Document) {
+        let mut ids: Vec<&String> = doc
+            .resources
+            .iter()
+            .filter(|(_, r)| r.resource_type == ResourceType::Image && !r.data.is_empty())
+            .map(|(id, _)| id)
+            .collect();
+        ids.sort();
+
+        for id in &ids {
+            if is_xml_name(id) {
+                self.taken_ids.insert((*id).clone());
+            }
+        }
+
+        let mut file_names = HashSet::new();
+        for id in ids {
+            let rel_id = if is_xml_name(id) {
+                id.clone()
+            } else {
+                self.fresh_rel_id()
+            };
+            let resource = &doc.resources[id];
+            let name = unique_file_name(&resource.suggested_filename(id), &mut file_names);
+            let size = resource.width.zip(resource.height).map(|(w, h)| {
+                (
+                    w.saturating_mul(EMU_PER_PIXEL),
+                    h.saturating_mul(EMU_PER_PIXEL),
+                )
+            });
+            let target = format!("media/{}", name);
+            self.relationships.push(Relationship {
+                id: rel_id.clone(),
+                kind: "image",
+                target: target.clone(),
+                external: false,
+            });
+            self.image_rels.insert(id.clone(), (rel_id, size));
+            self.media.push((target, id.clone()));
+        }
+    }
+
+    /// Find the `[^label]: text` paragraphs the parsers append for notes and
+    /// assign each label a note id: its own number when it has one, the next
+    /// free one otherwise. `e`-prefixed numeric labels are endnotes.
+    fn collect_notes(&mut self, doc: &Document) {
+        let definitions: Vec<(String, String)> = doc
+            .sections
+            .iter()
+            .flat_map(|s| &s.content)
+            .filter_map(|block| match block {
+                Block::Paragraph(para) => note_definition(para),
+                _ => None,
+            })
+            .collect();
+
+        let mut unnumbered = Vec::new();
+        for (label, text) in definitions {
+            if self.notes.contains_key(&label) {
+                continue;
+            }
+            let endnote = label.strip_prefix('e').and_then(|n| n.parse::<u32>().ok());
+            match (label.parse::<u32>().ok(), endnote) {
+                (Some(id), _) if id > 0 && !self.footnotes.contains_key(&id) => {
+                    self.footnotes.insert(id, text);
+                    self.notes.insert(label, NoteRef::Footnote(id));
+                }
+                (_, Some(id)) if id > 0 && !self.endnotes.contains_key(&id) => {
+                    self.endnotes.insert(id, text);
+                    self.notes.insert(label, NoteRef::Endnote(id));
+                }
+                _ => unnumbered.push((label, text)),
+            }
+        }
+        for (label, text) in unnumbered {
+            let id = (1..)
+                .find(|id| !self.footnotes.contains_key(id))
+                .unwrap_or(1);
+            self.footnotes.insert(id, text);
+            self.notes.insert(label, NoteRef::Footnote(id));
+        }
+
+        if !self.footnotes.is_empty() {
+            self.add_part_rel("footnotes", "footnotes.xml");
+        }
+        if !self.endnotes.is_empty() {
+            self.add_part_rel("endnotes", "endnotes.xml");
+        }
+    }
+
+    fn fresh_rel_id(&mut self) -> String {
+        loop {
+            self.next_rel += 1;
+            let id = format!("rId{}", self.next_rel);
+            if self.taken_ids.insert(id.clone()) {
+                return id;
+            }
+        }
+    }
+
+    fn add_part_rel(&mut self, kind: &'static str, target: &str) {
+        let id = self.fresh_rel_id();
+        self.relationships.push(Relationship {
+            id,
+            kind,
+            target: target.to_string(),
+            external: false,
+        });
+    }
+
+    fn link_rel(&mut self, url: &str) -> String {
+        if let Some(id) = self.link_rels.get(url) {
+            return id.clone();
+        }
+        let id = self.fresh_rel_id();
+        self.relationships.push(Relationship {
+            id: id.clone(),
+            kind: "hyperlink",
+            target: url.to_string(),
+            external: true,
+        });
+        self.link_rels.insert(url.to_string(), id.clone());
+        id
+    }
+
+    fn document_xml(&mut self, doc: &Document) -> String {
+        let mut body = String::new();
+        for (idx, section) in doc.sections.iter().enumerate() {
+            if idx > 0 {
+                push_section_break(&mut body);
+            }
+            for block in &section.content {
+                self.write_block(&mut body, block);
+            }
+        }
+        if !self.lists.instances.is_empty() {
+            self.add_part_rel("numbering", "numbering.xml");
+        }
+
+        format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+             <w:document \
+             xmlns:w=\"http://schemas.openxmlformats.org/wordprocessingml/2006/main\" \
+             xmlns:r=\"{REL_NS}\" \
+             xmlns:wp=\"http://schemas.openxmlformats.org/drawingml/2006/wordprocessingDrawing\" \
+             xmlns:a=\"http://schemas.openxmlformats.org/drawingml/2006/main\" \
+             xmlns:pic=\"http://schemas.openxmlformats.org/drawingml/2006/picture\">\
+             <w:body>{body}<w:sectPr>{SECTION_PROPERTIES}</w:sectPr></w:body></w:document>"
+        )
+    }
+
+    fn write_block(&mut self, out: &mut String, block: &Block) {
+        match block {
+            Block::Paragraph(para) => {
+                // Note definitions are written to the notes parts instead.
+                if note_definition(para).is_none_or(|(label, _)| !self.notes.contains_key(&label)) {
+                    self.write_paragraph(out, para, CellAlignment::Left);
+                }
+            }
+            Block::Table(table) => self.write_table(out, table),
+            Block::PageBreak => out.push_str("<w:p><w:r><w:br w:type=\"page\"/></w:r></w:p>"),
+            Block::SectionBreak => push_section_break(out),
+            Block::Image {
+                resource_id,
+                alt_text,
+                width,
+                height,
+            } => {
+                let image = InlineImage {
+                    resource_id: resource_id.clone(),
+                    alt_text: alt_text.clone(),
+                    width: *width,
+                    height: *height,
+                };
+                out.push_str("<w:p>");
+                self.write_image(out, &image);
+                out.push_str("</w:p>");
+            }
+        }
+    }
+
+    /// Write a paragraph. Inside a table cell, `cell_alignment` stands in for
+    /// a paragraph alignment left at its default.
+    fn write_paragraph(
+        &mut self,
+        out: &mut String,
+        para: &Paragraph,
+        cell_alignment: CellAlignment,
+    ) {
+        out.push_str("<w:p>");
+
+        let mut ppr = String::new();
+        if para.heading.is_heading() {
+            ppr.push_str(&format!(
+                "<w:pStyle w:val=\"Heading{}\"/>",
+                para.heading.level()
+            ));
+        } else if let Some(id) = para.style_id.as_deref().filter(|id| !is_builtin_style(id)) {
+            let name = para.style_name.clone().unwrap_or_else(|| id.to_string());
+            self.custom_styles.entry(id.to_string()).or_insert(name);
+            ppr.push_str(&format!("<w:pStyle w:val=\"{}\"/>", escape_xml(id)));
+        }
+        if let Some(num_id) = para
+            .list_info
+            .as_ref()
+            .and_then(|info| self.lists.num_id(info))
+        {
+            let level = para.list_info.as_ref().map_or(0, |info| info.level.min(8));
+            ppr.push_str(&format!(
+                "<w:numPr><w:ilvl w:val=\"{level}\"/><w:numId w:val=\"{num_id}\"/></w:numPr>"
+            ));
+        } else if para.indent_level > 0 {
+            ppr.push_str(&format!(
+                "<w:ind w:left=\"{}\"/>",
+                720 * u32::from(para.indent_level)
+            ));
+        }
+        let jc = match (para.alignment, cell_alignment) {
+            (TextAlignment::Center, _) | (TextAlignment::Left, CellAlignment::Center) => "center",
+            (TextAlignment::Right, _) | (TextAlignment::Left, CellAlignment::Right) => "right",
+            (TextAlignment::Justify, _) => "both",
+            (TextAlignment::Left, CellAlignment::Left) => "",
+        };
+        if !jc.is_empty() {
+            ppr.push_str(&format!("<w:jc w:val=\"{jc}\"/>"));
+        }
+        if !ppr.is_empty() {
+            out.push_str("<w:pPr>");
+            out.push_str(&ppr);
+            out.push_str("</w:pPr>");
+        }
+
+        // Consecutive runs sharing a link go into one w:hyperlink.
+        let mut idx = 0;
+        while idx < para.runs.len() {
+            let link = para.runs[idx].hyperlink.as_deref();
+            let end = para.runs[idx..]
+                .iter()
+                .position(|run| run.hyperlink.as_deref() != link)
+                .map_or(para.runs.len(), |n| idx + n);
+            match link {
+                Some(url) => {
+                    let rel_id = self.link_rel(url);
+                    out.push_str(&format!("<w:hyperlink r:id=\"{rel_id}\" w:history=\"1\">"));
+                    for run in &para.runs[idx..end] {
+                        self.write_run(out, run, true);
+                    }
+                    out.push_str("</w:hyperlink>");
+                }
+                None => {
+                    for run in &para.runs[idx..end] {
+                        self.write_run(out, run, false);
+                    }
+                }
+            }
+            idx = end;
+        }
+
+        for image in &para.images {
+            self.write_image(out, image);
+        }
+        out.push_str("</w:p>");
+    }
+
+    /// Write a text run, splitting out footnote references into runs of
+    /// their own, inside a w:ins/w:del when the run is a tracked change.
+    fn write_run(&mut self, out: &mut String, run: &TextRun, in_link: bool) {
+        let revision = match run.revision {
+            RevisionType::None => None,
+            RevisionType::Inserted => Some("w:ins"),
+            RevisionType::Deleted => Some("w:del"),
+        };
+        if let Some(tag) = revision {
+            self.next_revision += 1;
+            out.push_str(&format!(
+                "<{tag} w:id=\"{}\" w:author=\"undoc\">",
+                self.next_revision
+            ));
+        }
+
+        let rpr = run_properties(&run.style, in_link);
+        let text_tag = if run.revision == RevisionType::Deleted {
+            "w:delText"
+        } else {
+            "w:t"
+        };
+        let pieces = split_note_refs(&run.text, &self.notes);
+        let last = pieces.len().saturating_sub(1);
+        for (idx, piece) in pieces.into_iter().enumerate() {
+            out.push_str("<w:r>");
+            match piece {
+                Piece::Text(text) => {
+                    out.push_str(&rpr);
+                    push_run_text(out, text, text_tag);
+                }
+                Piece::Note(note) => {
+                    out.push_str("<w:rPr><w:vertAlign w:val=\"superscript\"/></w:rPr>");
+                    out.push_str(&match note {
+                        NoteRef::Footnote(id) => format!("<w:footnoteReference w:id=\"{id}\"/>"),
+                        NoteRef::Endnote(id) => format!("<w:endnoteReference w:id=\"{id}\"/>"),
+                    });
+                }
+            }
+            if idx == last {
+                if run.line_break {
+                    out.push_str("<w:br/>");
+                }
+                if run.page_break {
+                    out.push_str("<w:br w:type=\"page\"/>");
+                }
+            }
+            out.push_str("</w:r>");
+        }
+
+        if let Some(tag) = revision {
+            out.push_str(&format!("</{tag}>"));
+        }
+    }
+
+    fn write_image(&mut self, out: &mut String, image: &InlineImage) {
+        let Some((rel_id, resource_size)) = self.image_rels.get(&image.resource_id) else {
+            return;
+        };
+        let (cx, cy) = image
+            .width
+            .zip(image.height)
+            .or(*resource_size)
+            .unwrap_or(DEFAULT_IMAGE_EMU);
+        let rel_id = rel_id.clone();
+        self.next_drawing += 1;
+        let id = self.next_drawing;
+        let descr = image
+            .alt_text
+            .as_deref()
+            .map(|alt| format!(" descr=\"{}\"", escape_xml(alt)))
+            .unwrap_or_default();
+        out.push_str(&format!(
+            "<w:r><w:drawing>\
+             <wp:inline distT=\"0\" distB=\"0\" distL=\"0\" distR=\"0\">\
+             <wp:extent cx=\"{cx}\" cy=\"{cy}\"/>\
+             <wp:docPr id=\"{id}\" name=\"Picture {id}\"{descr}/>\
+             <wp:cNvGraphicFramePr><a:graphicFrameLocks noChangeAspect=\"1\"/></wp:cNvGraphicFramePr>\
+             <a:graphic><a:graphicData uri=\"http://schemas.openxmlformats.org/drawingml/2006/picture\">\
+             <pic:pic><pic:nvPicPr><pic:cNvPr id=\"{id}\" name=\"Picture {id}\"/><pic:cNvPicPr/></pic:nvPicPr>\
+             <pic:blipFill><a:blip r:embed=\"{rel_id}\"/><a:stretch><a:fillRect/></a:stretch></pic:blipFill>\
+             <pic:spPr><a:xfrm><a:off x=\"0\" y=\"0\"/><a:ext cx=\"{cx}\" cy=\"{cy}\"/></a:xfrm>\
+             <a:prstGeom prst=\"rect\"><a:avLst/></a:prstGeom></pic:spPr></pic:pic>\
+             </a:graphicData></a:graphic></wp:inline></w:drawing></w:r>"
+        ));
+    }
+
+    fn write_table(&mut self, out: &mut String, table: &Table) {
+        let rows = grid_rows(table);
+        let col_count = rows
+            .iter()
+            .map(|row| row.iter().map(GridCell::col_span).sum::<u32>())
+            .max()
+            .unwrap_or(0)
+            .max(1);
+
+        out.push_str(
+            "<w:tbl><w:tblPr><w:tblStyle w:val=\"TableGrid\"/><w:tblW w:w=\"0\" w:type=\"auto\"/>",
+        );
+        if let Some(ref caption) = table.caption {
+            out.push_str(&format!(
+                "<w:tblCaption w:val=\"{}\"/>",
+                escape_xml(caption)
+            ));
+        }
+        out.push_str("</w:tblPr><w:tblGrid>");
+        let widths = match table.column_widths {
+            Some(ref widths) if widths.len() == col_count as usize => widths.clone(),
+            _ => vec![TEXT_WIDTH_TWIPS / col_count; col_count as usize],
+        };
+        for width in widths {
+            out.push_str(&format!("<w:gridCol w:w=\"{width}\"/>"));
+        }
+        out.push_str("</w:tblGrid>");
+
+        for (row, cells) in table.rows.iter().zip(rows) {
+            out.push_str("<w:tr>");
+            let header =
+                row.is_header || (!row.cells.is_empty() && row.cells.iter().all(|c| c.is_header));
+            if header || row.height.is_some() {
+                out.push_str("<w:trPr>");
+                if let Some(height) = row.height {
+                    out.push_str(&format!("<w:trHeight w:val=\"{height}\"/>"));
+                }
+                if header {
+                    out.push_str("<w:tblHeader/>");
+                }
+                out.push_str("</w:trPr>");
+            }
+            for cell in cells {
+                self.write_cell(out, cell);
+            }
+            out.push_str("</w:tr>");
+        }
+        out.push_str("</w:tbl>");
+    }
+
+    fn write_cell(&mut self, out: &mut String, slot: GridCell<'_>) {
+        out.push_str("<w:tc><w:tcPr>");
+        let span = slot.col_span();
+        if span > 1 {
+            out.push_str(&format!("<w:gridSpan w:val=\"{span}\"/>"));
+        }
+        let cell = match slot {
+            GridCell::Origin(cell) => cell,
+            GridCell::Continued { .. } => {
+                out.push_str("<w:vMerge/></w:tcPr><w:p/></w:tc>");
+                return;
+            }
+            GridCell::Empty { .. } => {
+                out.push_str("</w:tcPr><w:p/></w:tc>");
+                return;
+            }
+        };
+        if cell.row_span > 1 {
+            out.push_str("<w:vMerge w:val=\"restart\"/>");
+        }
+        if let Some(fill) = cell.background.as_deref().and_then(hex_color) {
+            out.push_str(&format!(
+                "<w:shd w:val=\"clear\" w:color=\"auto\" w:fill=\"{fill}\"/>"
+            ));
+        }
+        match cell.vertical_alignment {
+            VerticalAlignment::Top => {}
+            VerticalAlignment::Middle => out.push_str("<w:vAlign w:val=\"center\"/>"),
+            VerticalAlignment::Bottom => out.push_str("<w:vAlign w:val=\"bottom\"/>"),
+        }
+        out.push_str("</w:tcPr>");
+
+        for para in &cell.content {
+            self.write_paragraph(out, para, cell.alignment);
+        }
+        for nested in &cell.nested_tables {
+            self.write_table(out, nested);
+        }
+        // A cell has to end in a paragraph.
+        if cell.content.is_empty() || !cell.nested_tables.is_empty() {
+            out.push_str("<w:p/>");
+        }
+        out.push_str("</w:tc>");
+    }
+
+    fn content_types(&self, doc: &Document) -> String {
+        let mut xml = String::from(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+             <Types xmlns=\"http://schemas.openxmlformats.org/package/2006/content-types\">\
+             <Default Extension=\"rels\" ContentType=\"application/vnd.openxmlformats-package.relationships+xml\"/>\
+             <Default Extension=\"xml\" ContentType=\"application/xml\"/>",
+        );
+        let mut extensions = BTreeMap::new();
+        for (target, resource_id) in &self.media {
+            let Some((_, ext)) = target.rsplit_once('.') else {
+                continue;
+            };
+            let mime = doc.resources[resource_id]
+                .mime_type
+                .clone()
+                .or_else(|| guess_mime_type(target))
+                .unwrap_or_else(|| "application/octet-stream".to_string());
+            extensions.entry(ext.to_lowercase()).or_insert(mime);
+        }
+        for (ext, mime) in extensions {
+            xml.push_str(&format!(
+                "<Default Extension=\"{}\" ContentType=\"{}\"/>",
+                escape_xml(&ext),
+                escape_xml(&mime)
+            ));
+        }
+
+        let wml = "application/vnd.openxmlformats-officedocument.wordprocessingml";
+        let mut overrides = vec![
+            (
+                "/word/document.xml".to_string(),
+                format!("{wml}.document.main+xml"),
+            ),
+            ("/word/styles.xml".to_string(), format!("{wml}.styles+xml")),
+            (
+                "/docProps/core.xml".to_string(),
+                "application/vnd.openxmlformats-package.core-properties+xml".to_string(),
+            ),
+        ];
+        if app_xml(&doc.metadata).is_some() {
+            overrides.push((
+                "/docProps/app.xml".to_string(),
+                "application/vnd.openxmlformats-officedocument.extended-properties+xml".to_string(),
+            ));
+        }
+        if !self.lists.instances.is_empty() {
+            overrides.push((
+                "/word/numbering.xml".to_string(),
+                format!("{wml}.numbering+xml"),
+            ));
+        }
+        if !self.footnotes.is_empty() {
+            overrides.push((
+                "/word/footnotes.xml".to_string(),
+                format!("{wml}.footnotes+xml"),
+            ));
+        }
+        if !self.endnotes.is_empty() {
+            overrides.push((
+                "/word/endnotes.xml".to_string(),
+                format!("{wml}.endnotes+xml"),
+            ));
+        }
+        for (part, content_type) in overrides {
+            xml.push_str(&format!(
+                "<Override PartName=\"{part}\" ContentType=\"{content_type}\"/>"
+            ));
+        }
+        xml.push_str("</Types>");
+        xml
+    }
+
+    fn document_rels(&self) -> String {
+        let mut xml = String::from(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+             <Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">",
+        );
+        for rel in &self.relationships {
+            xml.push_str(&format!(
+                "<Relationship Id=\"{}\" Type=\"{REL_NS}/{}\" Target=\"{}\"{}/>",
+                rel.id,
+                rel.kind,
+                escape_xml(&rel.target),
+                if rel.external {
+                    " TargetMode=\"External\""
+                } else {
+                    ""
+                }
+            ));
+        }
+        xml.push_str("</Relationships>");
+        xml
+    }
+
+    fn styles_xml(&self) -> String {
+        let mut xml = String::from(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+             <w:styles xmlns:w=\"http://schemas.openxmlformats.org/wordprocessingml/2006/main\">\
+             <w:docDefaults><w:rPrDefault><w:rPr>\
+             <w:rFonts w:ascii=\"Calibri\" w:hAnsi=\"Calibri\" w:eastAsia=\"Calibri\" w:cs=\"Calibri\"/>\
+             <w:sz w:val=\"22\"/><w:szCs w:val=\"22\"/></w:rPr></w:rPrDefault>\
+             <w:pPrDefault><w:pPr><w:spacing w:after=\"160\" w:line=\"259\" w:lineRule=\"auto\"/></w:pPr></w:pPrDefault>\
+             </w:docDefaults>\
+             <w:style w:type=\"paragraph\" w:default=\"1\" w:styleId=\"Normal\"><w:name w:val=\"Normal\"/><w:qFormat/></w:style>",
+        );
+        for (level, size) in (1..=6).zip([32, 28, 26, 24, 22, 22]) {
+            xml.push_str(&format!(
+                "<w:style w:type=\"paragraph\" w:styleId=\"Heading{level}\">\
+                 <w:name w:val=\"heading {level}\"/><w:basedOn w:val=\"Normal\"/><w:next w:val=\"Normal\"/>\
+                 <w:uiPriority w:val=\"9\"/><w:qFormat/>\
+                 <w:pPr><w:keepNext/><w:spacing w:before=\"240\" w:after=\"80\"/><w:outlineLvl w:val=\"{}\"/></w:pPr>\
+                 <w:rPr><w:b/><w:sz w:val=\"{size}\"/></w:rPr></w:style>",
+                level - 1
+            ));
+        }
+        for (id, name) in &self.custom_styles {
+            xml.push_str(&format!(
+                "<w:style w:type=\"paragraph\" w:customStyle=\"1\" w:styleId=\"{}\">\
+                 <w:name w:val=\"{}\"/><w:basedOn w:val=\"Normal\"/></w:style>",
+                escape_xml(id),
+                escape_xml(name)
+            ));
+        }
+        xml.push_str(
+            "<w:style w:type=\"character\" w:styleId=\"Hyperlink\"><w:name w:val=\"Hyperlink\"/>\
+             <w:uiPriority w:val=\"99\"/><w:unhideWhenUsed/>\
+             <w:rPr><w:color w:val=\"0563C1\"/><w:u w:val=\"single\"/></w:rPr></w:style>\
+             <w:style w:type=\"table\" w:default=\"1\" w:styleId=\"TableNormal\"><w:name w:val=\"Normal Table\"/>\
+             <w:tblPr><w:tblInd w:w=\"0\" w:type=\"dxa\"/><w:tblCellMar>\
+             <w:top w:w=\"0\" w:type=\"dxa\"/><w:left w:w=\"108\" w:type=\"dxa\"/>\
+             <w:bottom w:w=\"0\" w:type=\"dxa\"/><w:right w:w=\"108\" w:type=\"dxa\"/>\
+             </w:tblCellMar></w:tblPr></w:style>\
+             <w:style w:type=\"table\" w:styleId=\"TableGrid\"><w:name w:val=\"Table Grid\"/>\
+             <w:basedOn w:val=\"TableNormal\"/><w:tblPr><w:tblBorders>",
+        );
+        for edge in ["top", "left", "bottom", "right", "insideH", "insideV"] {
+            xml.push_str(&format!(
+                "<w:{edge} w:val=\"single\" w:sz=\"4\" w:space=\"0\" w:color=\"auto\"/>"
+            ));
+        }
+        xml.push_str("</w:tblBorders></w:tblPr></w:style></w:styles>");
+        xml
+    }
+}
+
+/// Numbering definitions built up as list paragraphs are written.
+///
+/// Each instance gets its own abstract definition whose levels take the type
+/// and start value of the first item seen at that level. A paragraph joins
+/// the current instance when the number Word would give it there — counted
+/// the way [`NumberingMap`](super::numbering::NumberingMap) counts — is the
+/// number it carries; otherwise it starts a new instance.
+#[derive(Default)]
+struct ListNumbering {
+    instances: Vec<ListInstance>,
+}
+
+#[derive(Default)]
+struct ListInstance {
+    /// Level → list type and start value.
+    levels: BTreeMap<u8, (ListType, u32)>,
+    /// Level → next number; a missing entry means the level's start value.
+    counters: HashMap<u8, u32>,
+    last_level: Option<u8>,
+}
+
+impl ListInstance {
+    /// The number the next item at `level` would get, or `None` when that
+    /// level is already defined with another list type.
+    fn next_number(&self, list_type: ListType, level: u8) -> Option<Option<u32>> {
+        match self.levels.get(&level) {
+            Some(&(defined, _)) if defined != list_type => None,
+            Some(&(_, start)) => Some(Some(self.counters.get(&level).copied().unwrap_or(start))),
+            None => Some(None),
+        }
+    }
+
+    fn advance(&mut self, list_type: ListType, level: u8, number: Option<u32>) {
+        let start = number
+            .filter(|_| list_type == ListType::Numbered)
+            .unwrap_or(1);
+        let (_, start) = *self.levels.entry(level).or_insert((list_type, start));
+        if self.last_level.is_some_and(|last| level <= last) {
+            self.counters.retain(|&l, _| l <= level);
+        }
+        let current = self.counters.get(&level).copied().unwrap_or(start);
+        self.counters.insert(level, current + 1);
+        self.last_level = Some(level);
+    }
+}
+
+impl ListNumbering {
+    /// The `w:numId` for a list paragraph, or `None` for a paragraph that is
+    /// not a list item.
+    fn num_id(&mut self, info: &ListInfo) -> Option<usize> {
+        if info.list_type == ListType::None {
+            return None;
+        }
+        let level = info.level.min(8);
+        let fits = self.instances.last().is_some_and(|instance| {
+            match instance.next_number(info.list_type, level) {
+                None => false,
+                Some(None) => true,
+                Some(Some(expected)) => {
+                    info.list_type != ListType::Numbered
+                        || info.number.is_none_or(|n| n == expected)
+                }
+            }
+        });
+        if !fits {
+            self.instances.push(ListInstance::default());
+        }
+        let instance = self.instances.last_mut()?;
+        instance.advance(info.list_type, level, info.number);
+        Some(self.instances.len())
+    }
+
+    fn numbering_xml(&self) -> String {
+        let mut xml = String::from(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+             <w:numbering xmlns:w=\"http://schemas.openxmlformats.org/wordprocessingml/2006/main\">",
+        );
+        for (idx, instance) in self.instances.iter().enumerate() {
+            xml.push_str(&format!(
+                "<w:abstractNum w:abstractNumId=\"{idx}\"><w:multiLevelType w:val=\"hybridMultilevel\"/>"
+            ));
+            for level in 0..9u8 {
+                let (list_type, start) = instance
+                    .levels
+                    .get(&level)
+                    .copied()
+                    .unwrap_or((ListType::Numbered, 1));
+                let (fmt, text) = match list_type {
+                    ListType::Numbered => ("decimal", format!("%{}.", level + 1)),
+                    _ => ("bullet", "\u{2022}".to_string()),
+                };
+                xml.push_str(&format!(
+                    "<w:lvl w:ilvl=\"{level}\"><w:start w:val=\"{start}\"/><w:numFmt w:val=\"{fmt}\"/>\
+                     <w:lvlText w:val=\"{text}\"/><w:lvlJc w:val=\"left\"/>\
+                     <w:pPr><w:ind w:left=\"{}\" w:hanging=\"360\"/></w:pPr></w:lvl>",
+                    720 * (u32::from(level) + 1)
+                ));
+            }
+            xml.push_str("</w:abstractNum>");
+        }
+        for idx in 0..self.instances.len() {
+            xml.push_str(&format!(
+                "<w:num w:numId=\"{}\"><w:abstractNumId w:val=\"{idx}\"/></w:num>",
+                idx + 1
+            ));
+        }
+        xml.push_str("</w:numbering>");
+        xml
+    }
+}
+
+/// One `w:tc` of a table row: a cell of the model, the continuation of a
+/// vertical merge from a row above, or filler for a gap before one.
+#[derive(Clone, Copy)]
+enum GridCell<'a> {
+    Origin(&'a Cell),
+    Continued { col_span: u32 },
+    Empty { col_span: u32 },
+}
+
+impl GridCell<'_> {
+    fn col_span(&self) -> u32 {
+        match self {
+            GridCell::Origin(cell) => cell.col_span.max(1),
+            GridCell::Continued { col_span } | GridCell::Empty { col_span } => *col_span,
+        }
+    }
+}
+
+/// Lay a table's rows out as the `w:tc` elements WordprocessingML wants: the
+/// model records a vertical merge once, on its first cell, while every row it
+/// covers needs a `w:vMerge` continuation cell in its column.
+fn grid_rows(table: &Table) -> Vec<Vec<GridCell<'_>>> {
+    // Open vertical merges: starting column → (rows still covered, column span).
+    let mut open: BTreeMap<usize, (u32, u32)> = BTreeMap::new();
+    let mut rows = Vec::with_capacity(table.rows.len());
+
+    for row in &table.rows {
+        let mut cells = Vec::new();
+        let mut col = 0usize;
+        for cell in &row.cells {
+            col = continue_merges(&mut open, col, &mut cells, false);
+            let col_span = cell.col_span.max(1);
+            if cell.row_span > 1 {
+                open.insert(col, (cell.row_span - 1, col_span));
+            }
+            cells.push(GridCell::Origin(cell));
+            col += col_span as usize;
+        }
+        continue_merges(&mut open, col, &mut cells, true);
+        rows.push(cells);
+    }
+    rows
+}
+
+/// Emit continuation cells for the merges open at `col`, and with `to_end`
+/// for every one further right as well. Returns the column reached.
+fn continue_merges<'a>(
+    open: &mut BTreeMap<usize, (u32, u32)>,
+    mut col: usize,
+    cells: &mut Vec<GridCell<'a>>,
+    to_end: bool,
+) -> usize {
+    loop {
+        if let Some((rows_left, col_span)) = open.get_mut(&col) {
+            let col_span = *col_span;
+            *rows_left -= 1;
+            if *rows_left == 0 {
+                open.remove(&col);
+            }
+            cells.push(GridCell::Continued { col_span });
+            col += col_span as usize;
+            continue;
+        }
+        match open.range(col..).next() {
+            Some((&next, _)) if to_end => {
+                cells.push(GridCell::Empty {
+                    col_span: (next - col) as u32,
+                });
+                col = next;
+            }
+            _ => return col,
+        }
+    }
+}
+
+/// A piece of run text: plain text, or a `[^label]` footnote reference.
+enum Piece<'a> {
+    Text(&'a str),
+    Note(NoteRef),
+}
+
+/// Split run text around the `[^label]` references to known notes.
+fn split_note_refs<'a>(text: &'a str, notes: &HashMap<String, NoteRef>) -> Vec<Piece<'a>> {
+    let mut pieces = Vec::new();
+    let mut rest = text;
+    let mut scanned = 0;
+    while let Some(start) = rest[scanned..].find("[^").map(|i| scanned + i) {
+        let Some(end) = rest[start..].find(']').map(|i| start + i) else {
+            break;
+        };
+        match notes.get(&rest[start + 2..end]) {
+            Some(&note) => {
+                if start > 0 {
+                    pieces.push(Piece::Text(&rest[..start]));
+                }
+                pieces.push(Piece::Note(note));
+                rest = &rest[end + 1..];
+                scanned = 0;
+            }
+            None => scanned = start + 2,
+        }
+    }
+    if !rest.is_empty() || pieces.is_empty() {
+        pieces.push(Piece::Text(rest));
+    }
+    pieces
+}
+
+/// Escape text for a part, without the characters XML 1.0 does not allow.
+fn escape_xml(text: &str) -> String {
+    parser::escape_xml(&xml_chars(text))
+}
+
+/// Write run text as w:t (or w:delText) pieces, with tabs and line feeds as
+/// the elements Word uses for them and other control characters dropped.
+fn push_run_text(out: &mut String, text: &str, tag: &str) {
+    let mut pending = String::new();
+    let flush = |out: &mut String, pending: &mut String| {
+        if !pending.is_empty() {
+            out.push_str(&format!(
+                "<{tag} xml:space=\"preserve\">{}</{tag}>",
+                escape_xml(pending)
+            ));
+            pending.clear();
+        }
+    };
+    for ch in text.chars() {
+        match ch {
+            '\t' => {
+                flush(out, &mut pending);
+                out.push_str("<w:tab/>");
+            }
+            '\n' => {
+                flush(out, &mut pending);
+                out.push_str("<w:br/>");
+            }
+            c if c.is_control() => {}
+            c => pending.push(c),
+        }
+    }
+    flush(out, &mut pending);
+}
+
+/// The w:rPr for a text style, in schema order. Empty for the default style.
+fn run_properties(style: &TextStyle, in_link: bool) -> String {
+    let mut rpr = String::new();
+    if in_link {
+        rpr.push_str("<w:rStyle w:val=\"Hyperlink\"/>");
+    }
+    let font = style
+        .font
+        .as_deref()
+        .or(style.code.then_some("Courier New"));
+    if let Some(font) = font {
+        let font = escape_xml(font);
+        rpr.push_str(&format!(
+            "<w:rFonts w:ascii=\"{font}\" w:hAnsi=\"{font}\"/>"
+        ));
+    }
+    if style.bold {
+        rpr.push_str("<w:b/>");
+    }
+    if style.italic {
+        rpr.push_str("<w:i/>");
+    }
+    if style.strikethrough {
+        rpr.push_str("<w:strike/>");
+    }
+    if let Some(color) = style.color.as_deref().and_then(hex_color) {
+        rpr.push_str(&format!("<w:color w:val=\"{color}\"/>"));
+    }
+    if let Some(size) = style.size {
+        rpr.push_str(&format!("<w:sz w:val=\"{size}\"/>"));
+    }
+    match style.highlight.as_deref() {
+        Some(name) if HIGHLIGHT_COLORS.contains(&name) => {
+            rpr.push_str(&format!("<w:highlight w:val=\"{name}\"/>"));
+        }
+        _ => {}
+    }
+    if style.underline {
+        rpr.push_str("<w:u w:val=\"single\"/>");
+    }
+    if let Some(fill) = style.highlight.as_deref().and_then(hex_color) {
+        rpr.push_str(&format!(
+            "<w:shd w:val=\"clear\" w:color=\"auto\" w:fill=\"{fill}\"/>"
+        ));
+    }
+    if style.superscript {
+        rpr.push_str("<w:vertAlign w:val=\"superscript\"/>");
+    } else if style.subscript {
+        rpr.push_str("<w:vertAlign w:val=\"subscript\"/>");
+    }
+    if rpr.is_empty() {
+        rpr
+    } else {
+        format!("<w:rPr>{rpr}</w:rPr>")
+    }
+}
+
+/// The named colors `w:highlight` accepts.
+const HIGHLIGHT_COLORS: &[&str] = &[
+    "black",
+    "blue",
+    "cyan",
+    "green",
+    "magenta",
+    "red",
+    "yellow",
+    "white",
+    "darkBlue",
+    "darkCyan",
+    "darkGreen",
+    "darkMagenta",
+    "darkRed",
+    "darkYellow",
+    "darkGray",
+    "lightGray",
+];
+
+/// A six-digit hex color, with any leading `#` removed.
+fn hex_color(value: &str) -> Option<&str> {
+    let hex = value.strip_prefix('#').unwrap_or(value);
+    (hex.len() == 6 && hex.chars().all(|c| c.is_ascii_hexdigit())).then_some(hex)
+}
+
+/// Whether a string can serve as a relationship id (an XML name).
+fn is_xml_name(id: &str) -> bool {
+    let mut chars = id.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || matches!(c, '_' | '-' | '.'))
+}
+
+/// Styles the writer defines itself; a paragraph naming one of them keeps
+/// only what the model says about it (its heading level).
+fn is_builtin_style(id: &str) -> bool {
+    id == "Normal"
+        || id
+            .strip_prefix("Heading")
+            .is_some_and(|n| n.parse::<u8>().is_ok())
+}
+
+fn push_section_break(out: &mut String) {
+    out.push_str("<w:p><w:pPr><w:sectPr>");
+    out.push_str(SECTION_PROPERTIES);
+    out.push_str("</w:sectPr></w:pPr></w:p>");
+}
+
+fn package_rels(doc: &Document) -> String {
+    let app = if app_xml(&doc.metadata).is_some() {
+        format!(
+            "<Relationship Id=\"rId3\" Type=\"{REL_NS}/extended-properties\" Target=\"docProps/app.xml\"/>"
+        )
+    } else {
+        String::new()
+    };
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+         <Relationships xmlns=\"http://schemas.openxmlformats.org/package/2006/relationships\">\
+         <Relationship Id=\"rId1\" Type=\"{REL_NS}/officeDocument\" Target=\"word/document.xml\"/>\
+         <Relationship Id=\"rId2\" \
+         Type=\"http://schemas.openxmlformats.org/package/2006/relationships/metadata/core-properties\" \
+         Target=\"docProps/core.xml\"/>{app}</Relationships>"
+    )
+}
+
+fn core_xml(meta: &Metadata) -> String {
+    let mut props = String::new();
+    let mut element = |tag: &str, value: Option<&str>, attrs: &str| {
+        if let Some(value) = value {
+            props.push_str(&format!("<{tag}{attrs}>{}</{tag}>", escape_xml(value)));
+        }
+    };
+    element("dc:title", meta.title.as_deref(), "");
+    element("dc:subject", meta.subject.as_deref(), "");
+    element("dc:creator", meta.author.as_deref(), "");
+    let keywords = meta.keywords.join(", ");
+    element(
+        "cp:keywords",
+        Some(keywords.as_str()).filter(|k| !k.is_empty()),
+        "",
+    );
+    element("dc:description", meta.description.as_deref(), "");
+    element("cp:lastModifiedBy", meta.last_modified_by.as_deref(), "");
+    let w3cdtf = " xsi:type=\"dcterms:W3CDTF\"";
+    element("dcterms:created", meta.created.as_deref(), w3cdtf);
+    element("dcterms:modified", meta.modified.as_deref(), w3cdtf);
+
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+         <cp:coreProperties \
+         xmlns:cp=\"http://schemas.openxmlformats.org/package/2006/metadata/core-properties\" \
+         xmlns:dc=\"http://purl.org/dc/elements/1.1/\" xmlns:dcterms=\"http://purl.org/dc/terms/\" \
+         xmlns:xsi=\"http://www.w3.org/2001/XMLSchema-instance\">{props}</cp:coreProperties>"
+    )
+}
+
+/// docProps/app.xml, written only when the metadata has something for it.
+fn app_xml(meta: &Metadata) -> Option<String> {
+    let mut props = String::new();
+    if let Some(ref application) = meta.application {
+        props.push_str(&format!(
+            "<Application>{}</Application>",
+            escape_xml(application)
+        ));
+    }
+    if let Some(pages) = meta.page_count {
+        props.push_str(&format!("<Pages>{pages}</Pages>"));
+    }
+    if let Some(words) = meta.word_count {
+        props.push_str(&format!("<Words>{words}</Words>"));
+    }
+    (!props.is_empty()).then(|| {
+        format!(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+             <Properties xmlns=\"http://schemas.openxmlformats.org/officeDocument/2006/extended-properties\">\
+             {props}</Properties>"
+        )
+    })
+}
+
+/// footnotes.xml or endnotes.xml: the two separator notes Word expects,
+/// then one single-paragraph note per entry.
+fn notes_xml(kind: &str, notes: &BTreeMap<u32, String>) -> String {
+    let root = format!("w:{kind}s");
+    let note = format!("w:{kind}");
+    let mut xml = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\" standalone=\"yes\"?>\n\
+         <{root} xmlns:w=\"http://schemas.openxmlformats.org/wordprocessingml/2006/main\">\
+         <{note} w:type=\"separator\" w:id=\"-1\"><w:p><w:r><w:separator/></w:r></w:p></{note}>\
+         <{note} w:type=\"continuationSeparator\" w:id=\"0\"><w:p><w:r><w:continuationSeparator/></w:r></w:p></{note}>"
+    );
+    let reference_mark = if kind == "footnote" {
+        "<w:footnoteRef/>"
+    } else {
+        "<w:endnoteRef/>"
+    };
+    for (id, text) in notes {
+        xml.push_str(&format!(
+            "<{note} w:id=\"{id}\"><w:p>\
+             <w:r><w:rPr><w:vertAlign w:val=\"superscript\"/></w:rPr>{reference_mark}</w:r>"
+        ));
+        xml.push_str("<w:r>");
+        push_run_text(&mut xml, &format!(" {text}"), "w:t");
+        xml.push_str(&format!("</w:r></w:p></{note}>"));
+    }
+    xml.push_str(&format!("</{root}>"));
+    xml
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::docx::DocxParser;
+    use crate::model::{HeadingLevel, Resource, Row, Section};
+    use crate::render::{to_markdown, RenderOptions};
+
+    fn round_trip(doc: &Document) -> Document {
+        let bytes = DocxWriter::new().write(doc).unwrap();
+        DocxParser::from_bytes(bytes).unwrap().parse().unwrap()
+    }
+
+    fn document(content: Vec<Block>) -> Document {
+        let mut doc = Document::new();
+        let mut section = Section::new(0);
+        section.content = content;
+        doc.add_section(section);
+        doc
+    }
+
+    fn paragraphs(doc: &Document) -> Vec<&Paragraph> {
+        doc.sections[0]
+            .content
+            .iter()
+            .filter_map(|block| match block {
+                Block::Paragraph(para) => Some(para),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn list_item(text: &str, list_type: ListType, level: u8, number: Option<u32>) -> Block {
+        let mut para = Paragraph::with_text(text);
+        para.list_info = Some(ListInfo {
+            list_type,
+            level,
+            number,
+        });
+        Block::Paragraph(para)
+    }
+
+    fn assert_same_markdown(original: &Document, reparsed: &Document) {
+        let options = RenderOptions::default();
+        assert_eq!(
+            to_markdown(reparsed, &options).unwrap(),
+            to_markdown(original, &options).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_headings_and_run_formatting_round_trip() {
+        let mut styled = Paragraph::new();
+        styled.add_run(TextRun::plain("Plain, "));
+        styled.add_run(TextRun::styled("bold", TextStyle::bold()));
+        styled.add_run(TextRun::plain(" & "));
+        let mut fancy = TextStyle::italic();
+        fancy.underline = true;
+        fancy.strikethrough = true;
+        fancy.color = Some("FF0000".to_string());
+        fancy.size = Some(28);
+        fancy.font = Some("Georgia".to_string());
+        styled.add_run(TextRun::styled("fancy", fancy.clone()));
+        let sup = TextStyle {
+            superscript: true,
+            ..Default::default()
+        };
+        styled.add_run(TextRun::styled("2", sup));
+        styled.add_run(TextRun::link(" docs", "https://example.com/a?b=1&c=2"));
+        styled.alignment = TextAlignment::Center;
+
+        let mut doc = document(vec![
+            Block::Paragraph(Paragraph::heading(HeadingLevel::H1, "Title <1>")),
+            Block::Paragraph(styled),
+            Block::Paragraph(Paragraph::heading(HeadingLevel::H3, "Deeper")),
+            Block::Paragraph(Paragraph::with_text("tab\there")),
+        ]);
+        doc.metadata.title = Some("Report".to_string());
+        doc.metadata.author = Some("Jane".to_string());
+
+        let reparsed = round_trip(&doc);
+        assert_same_markdown(&doc, &reparsed);
+        assert_eq!(reparsed.metadata.title.as_deref(), Some("Report"));
+        assert_eq!(reparsed.metadata.author.as_deref(), Some("Jane"));
+
+        let paras = paragraphs(&reparsed);
+        assert_eq!(paras[0].heading, HeadingLevel::H1);
+        assert_eq!(paras[0].style_id.as_deref(), Some("Heading1"));
+        assert_eq!(paras[0].style_name.as_deref(), Some("heading 1"));
+        assert_eq!(paras[2].heading, HeadingLevel::H3);
+        assert_eq!(paras[1].alignment, TextAlignment::Center);
+        let fancy_run = paras[1].runs.iter().find(|r| r.text == "fancy").unwrap();
+        assert_eq!(fancy_run.style, fancy);
+        let link = paras[1].runs.iter().find(|r| r.text == " docs").unwrap();
+        assert_eq!(
+            link.hyperlink.as_deref(),
+            Some("https://example.com/a?b=1&c=2")
+        );
+    }
+
+    #[test]
+    fn test_lists_keep_types_levels_and_numbers() {
+        let doc = document(vec![
+            list_item("one", ListType::Numbered, 0, Some(1)),
+            list_item("bullet", ListType::Bullet, 1, None),
+            list_item("two", ListType::Numbered, 0, Some(2)),
+            Block::Paragraph(Paragraph::with_text("Between")),
+            list_item("restart", ListType::Numbered, 0, Some(5)),
+            list_item("six", ListType::Numbered, 0, Some(6)),
+            list_item("sub a", ListType::Numbered, 1, Some(1)),
+            list_item("sub b", ListType::Numbered, 1, Some(2)),
+            list_item("seven", ListType::Numbered, 0, Some(7)),
+            list_item("sub again", ListType::Numbered, 1, Some(1)),
+        ]);
+
+        let reparsed = round_trip(&doc);
+        assert_same_markdown(&doc, &reparsed);
+        let items: Vec<(ListType, u8, Option<u32>)> = paragraphs(&reparsed)
+            .iter()
+            .filter_map(|p| p.list_info.as_ref())
+            .map(|info| (info.list_type, info.level, info.number))
+            .collect();
+        let expected: Vec<(ListType, u8, Option<u32>)> = paragraphs(&doc)
+            .iter()
+            .filter_map(|p| p.list_info.as_ref())
+            .map(|info| (info.list_type, info.level, info.number))
+            .collect();
+        assert_eq!(items, expected);
+    }
+
+    #[test]
+    fn test_tables_keep_spans_headers_and_nesting() {
+        let mut nested = Table::new();
+        nested.add_row(Row {
+            cells: vec![Cell::with_text("inner")],
+            is_header: false,
+            height: None,
+        });
+        let mut wide = Cell::with_text("Wide");
+        wide.col_span = 2;
+        let mut tall = Cell::with_text("Tall");
+        tall.row_span = 3;
+        let mut holder = Cell::with_text("holder");
+        holder.nested_tables.push(nested);
+
+        let mut table = Table::new();
+        table.add_row(Row::header(vec![
+            Cell::header("A"),
+            Cell::header("B"),
+            Cell::header("C"),
+        ]));
+        table.add_row(Row {
+            cells: vec![tall, wide],
+            is_header: false,
+            height: None,
+        });
+        table.add_row(Row {
+            cells: vec![Cell::with_text("b2"), holder],
+            is_header: false,
+            height: None,
+        });
+        table.add_row(Row {
+            cells: vec![Cell::with_text("b3"), Cell::with_text("c3")],
+            is_header: false,
+            height: None,
+        });
+        let doc = document(vec![Block::Table(table)]);
+
+        let reparsed = round_trip(&doc);
+        assert_same_markdown(&doc, &reparsed);
+        let Block::Table(ref table) = reparsed.sections[0].content[0] else {
+            panic!("expected a table");
+        };
+        assert!(table.rows[0].is_header);
+        assert_eq!(table.rows[1].cells[0].row_span, 3);
+        assert_eq!(table.rows[1].cells[1].col_span, 2);
+        assert_eq!(table.rows[2].cells.len(), 2);
+        assert_eq!(table.rows[2].cells[1].nested_tables.len(), 1);
+        assert_eq!(table.rows[3].cells[1].plain_text(), "c3");
+    }
+
+    #[test]
+    fn test_images_keep_resource_ids_and_alt_text() {
+        let png = vec![0x89, b'P', b'N', b'G', 0, 1, 2, 3];
+        let mut para = Paragraph::with_text("Figure:");
+        para.images.push(InlineImage {
+            resource_id: "rId7".to_string(),
+            alt_text: Some("A \"chart\"".to_string()),
+            width: None,
+            height: None,
+        });
+        let mut doc = document(vec![
+            Block::Paragraph(para),
+            Block::Image {
+                resource_id: "logo image".to_string(),
+                alt_text: None,
+                width: None,
+                height: None,
+            },
+        ]);
+        doc.add_resource(
+            "rId7",
+            Resource::image(png.clone(), Some("chart.png".into())),
+        );
+        doc.add_resource(
+            "logo image",
+            Resource::image(vec![0xFF, 0xD8, 0xFF], Some("chart.png".into())),
+        );
+
+        let reparsed = round_trip(&doc);
+        let paras = paragraphs(&reparsed);
+        assert_eq!(paras[0].images[0].resource_id, "rId7");
+        assert_eq!(paras[0].images[0].alt_text.as_deref(), Some("A \"chart\""));
+        assert_eq!(reparsed.resources["rId7"].data, png);
+        // The second image gets a fresh relationship id and its own file name.
+        let logo = &paras[1].images[0].resource_id;
+        assert_ne!(logo, "rId7");
+        assert_eq!(reparsed.resources[logo].data, vec![0xFF, 0xD8, 0xFF]);
+        let mut names: Vec<_> = reparsed
+            .resources
+            .values()
+            .filter_map(|r| r.filename.as_deref())
+            .collect();
+        names.sort();
+        assert_eq!(names, vec!["chart.png", "chart_2.png"]);
+    }
+
+    #[test]
+    fn test_notes_and_revisions_round_trip() {
+        let mut cited = Paragraph::new();
+        cited.add_run(TextRun::plain("Claim[^1] and more[^e2]."));
+        let mut inserted = TextRun::plain("added ");
+        inserted.revision = RevisionType::Inserted;
+        let mut deleted = TextRun::plain("removed ");
+        deleted.revision = RevisionType::Deleted;
+        cited.add_run(inserted);
+        cited.add_run(deleted);
+        let doc = document(vec![
+            Block::Paragraph(cited),
+            Block::Paragraph(Paragraph::with_text("Literal [^x] stays")),
+            Block::Paragraph(Paragraph::with_text("[^1]: A footnote.")),
+            Block::Paragraph(Paragraph::with_text("[^e2]: An endnote.")),
+        ]);
+
+        let reparsed = round_trip(&doc);
+        assert_same_markdown(&doc, &reparsed);
+        let paras = paragraphs(&reparsed);
+        assert_eq!(paras.len(), 4);
+        let revisions: Vec<(&str, RevisionType)> = paras[0]
+            .runs
+            .iter()
+            .filter(|r| r.revision != RevisionType::None)
+            .map(|r| (r.text.as_str(), r.revision))
+            .collect();
+        assert_eq!(
+            revisions,
+            vec![
+                ("added ", RevisionType::Inserted),
+                ("removed ", RevisionType::Deleted)
+            ]
+        );
+    }
+
+    #[test]
+    fn test_unnumbered_note_labels_get_free_ids() {
+        let doc = document(vec![
+            Block::Paragraph(Paragraph::with_text("See[^note] and[^1].")),
+            Block::Paragraph(Paragraph::with_text("[^1]: First.")),
+            Block::Paragraph(Paragraph::with_text("[^note]: Named.")),
+        ]);
+
+        let reparsed = round_trip(&doc);
+        let texts: Vec<String> = paragraphs(&reparsed)
+            .iter()
+            .map(|p| p.plain_text())
+            .collect();
+        assert_eq!(
+            texts,
+            vec!["See[^2] and[^1].", "[^1]: First.", "[^2]: Named."]
+        );
+    }
+
+    #[test]
+    fn test_headings_and_list_items_are_not_note_definitions() {
+        let doc = document(vec![
+            Block::Paragraph(Paragraph::with_text("Claim[^1].")),
+            Block::Paragraph(Paragraph::heading(HeadingLevel::H2, "[^1]: Heading")),
+            list_item("[^1]: Item", ListType::Bullet, 0, None),
+            Block::Paragraph(Paragraph::with_text("[^1]: A footnote.")),
+        ]);
+
+        let reparsed = round_trip(&doc);
+        let paras = paragraphs(&reparsed);
+        let texts: Vec<String> = paras.iter().map(|p| p.plain_text()).collect();
+        assert_eq!(
+            texts,
+            vec![
+                "Claim[^1].",
+                "[^1]: Heading",
+                "[^1]: Item",
+                "[^1]: A footnote."
+            ]
+        );
+        assert_eq!(paras[1].heading, HeadingLevel::H2);
+        assert!(paras[2].list_info.is_some());
+    }
+
+    #[test]
+    fn test_round_trip_drops_characters_xml_does_not_allow() {
+        let mut cited = Paragraph::with_text("Bell\u{7} form\u{C}feed\u{FFFE}.");
+        cited.style_name = Some("Odd\u{FFFF}Style".to_string());
+        let mut doc = document(vec![
+            Block::Paragraph(cited),
+            list_item("one", ListType::Numbered, 0, Some(1)),
+            list_item("nested\u{1}", ListType::Numbered, 1, Some(1)),
+            list_item("again", ListType::Numbered, 0, Some(1)),
+        ]);
+        doc.metadata.title = Some("Title\u{FFFE}".to_string());
+
+        let bytes = DocxWriter::new().write(&doc).unwrap();
+        let mut archive = zip::ZipArchive::new(Cursor::new(&bytes)).unwrap();
+        for i in 0..archive.len() {
+            let mut file = archive.by_index(i).unwrap();
+            let mut part = String::new();
+            if file.name().ends_with(".xml") || file.name().ends_with(".rels") {
+                std::io::Read::read_to_string(&mut file, &mut part).unwrap();
+                assert_eq!(xml_chars(&part), part, "{}", file.name());
+            }
+        }
+
+        let reparsed = DocxParser::from_bytes(bytes).unwrap().parse().unwrap();
+        assert_eq!(reparsed.metadata.title.as_deref(), Some("Title"));
+        let paras = paragraphs(&reparsed);
+        assert_eq!(paras[0].plain_text(), "Bell formfeed.");
+        let items: Vec<(u8, Option<u32>)> = paras
+            .iter()
+            .filter_map(|p| p.list_info.as_ref())
+            .map(|info| (info.level, info.number))
+            .collect();
+        assert_eq!(items, vec![(0, Some(1)), (1, Some(1)), (0, Some(1))]);
+    }
+}
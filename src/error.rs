@@ -60,6 +60,8 @@ pub enum ErrorKind {
     Encrypted = 12,
     /// [`Error::Render`]
     Render = 13,
+    /// [`Error::WrongPassword`]
+    WrongPassword = 14,
 }
 
 /// Errors that can occur during document processing.
@@ -121,6 +123,10 @@ pub enum Error {
     /// Error during rendering.
     #[error("Render error: {0}")]
     Render(String),
+
+    /// The password supplied for an encrypted document is incorrect.
+    #[error("Incorrect password for encrypted document")]
+    WrongPassword,
 }
 
 impl From<zip::result::ZipError> for Error {
@@ -212,6 +218,7 @@ impl Error {
             Error::ResourceNotFound(_) => ErrorKind::ResourceNotFound,
             Error::Encrypted => ErrorKind::Encrypted,
             Error::Render(_) => ErrorKind::Render,
+            Error::WrongPassword => ErrorKind::WrongPassword,
         }
     }
 }
@@ -271,6 +278,7 @@ mod tests {
         );
         assert_eq!(Error::Encrypted.kind(), ErrorKind::Encrypted);
         assert_eq!(Error::Render("bad table".into()).kind(), ErrorKind::Render);
+        assert_eq!(Error::WrongPassword.kind(), ErrorKind::WrongPassword);
     }
 
     /// Both XML variants are the same failure reason, so they must share one number —
@@ -371,5 +379,6 @@ mod tests {
         ResourceNotFound = 11,
         Encrypted = 12,
         Render = 13,
+        WrongPassword = 14,
     }
 }
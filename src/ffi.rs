@@ -86,7 +86,7 @@ uncore::export_last_error_abi!(LAST_ERROR, undoc_last_error, undoc_last_error_ki
 /// `undoc_last_error_kind` value when no error is recorded on this thread.
 pub const UNDOC_ERROR_NONE: c_int = uncore::kind::NONE;
 
-// Values 1..=13 are [`ErrorKind`] discriminants — core failure reasons.
+// Values 1..=14 are [`ErrorKind`] discriminants — core failure reasons.
 // Values 100+ are FFI-boundary reasons with no core `Error` counterpart.
 
 /// An argument was null or not valid UTF-8.
@@ -2,8 +2,10 @@
 //!
 //! High-performance Microsoft Office document extraction to Markdown.
 //!
-//! This library provides tools for parsing DOCX, XLSX, and PPTX files
-//! and converting them to Markdown, plain text, or structured JSON.
+//! This library provides tools for parsing DOCX, XLSX, and PPTX files (and
+//! their legacy 97-2003 binary counterparts: .doc, .xls and .ppt, plus
+//! Excel binary .xlsb and OpenDocument .odt, .ods and .odp) and converting them
+//! to Markdown, plain text, HTML, or structured JSON.
 //!
 //! ## Quick Start
 //!
@@ -48,6 +50,14 @@
 //! - `docx` (default): Word document support
 //! - `xlsx` (default): Excel spreadsheet support
 //! - `pptx` (default): PowerPoint presentation support
+//! - `doc` (default): legacy Word 97-2003 (.doc) support
+//! - `xls` (default): legacy Excel 97-2003 (.xls) support
+//! - `xlsb` (default): Excel binary workbook (.xlsb) support
+//! - `ppt` (default): legacy PowerPoint 97-2003 (.ppt) support
+//! - `odt` (default): OpenDocument text (.odt) support
+//! - `ods` (default): OpenDocument spreadsheet (.ods) support
+//! - `odp` (default): OpenDocument presentation (.odp) support
+//! - `decrypt` (default): password-protected OOXML packages
 //! - `async`: Async I/O support with Tokio
 //! - `ffi`: C-ABI bindings for foreign language integration
 
@@ -56,6 +66,8 @@ pub mod container;
 mod decode;
 pub mod detect;
 pub mod error;
+#[cfg(any(feature = "docx", feature = "pptx"))]
+mod math;
 pub mod model;
 pub mod streaming;
 
@@ -68,6 +80,45 @@ pub mod xlsx;
 #[cfg(feature = "pptx")]
 pub mod pptx;
 
+#[cfg(feature = "doc")]
+pub mod doc;
+
+#[cfg(feature = "xls")]
+pub mod xls;
+
+#[cfg(feature = "xlsb")]
+pub mod xlsb;
+
+#[cfg(feature = "ppt")]
+pub mod ppt;
+
+#[cfg(feature = "odt")]
+pub mod odt;
+
+#[cfg(feature = "ods")]
+pub mod ods;
+
+#[cfg(feature = "odp")]
+pub mod odp;
+
+#[cfg(any(feature = "doc", feature = "xls", feature = "ppt"))]
+mod ole;
+
+#[cfg(any(feature = "odt", feature = "ods", feature = "odp"))]
+mod odf;
+
+#[cfg(any(
+    feature = "docx",
+    feature = "pptx",
+    feature = "odt",
+    feature = "ods",
+    feature = "odp"
+))]
+mod xml_tree;
+
+#[cfg(feature = "decrypt")]
+mod decrypt;
+
 pub mod render;
 
 #[cfg(feature = "ffi")]
@@ -78,7 +129,7 @@ pub use container::{OoxmlContainer, Relationship, Relationships};
 pub use detect::detect_format_from_bytes;
 #[cfg(not(target_arch = "wasm32"))]
 pub use detect::detect_format_from_path;
-pub use detect::FormatType;
+pub use detect::{FormatType, FormatVariant};
 pub use error::{Error, ErrorKind, Result};
 pub use model::{
     Block, Cell, CellAlignment, Document, HeadingLevel, ListInfo, ListType, Metadata, Paragraph,
@@ -125,7 +176,53 @@ pub fn parse_file(path: impl AsRef<Path>) -> Result<Document> {
             let mut parser = pptx::PptxParser::open(path)?;
             parser.parse()
         }
-        #[cfg(not(all(feature = "docx", feature = "xlsx", feature = "pptx")))]
+        #[cfg(feature = "doc")]
+        FormatType::Doc => {
+            let mut parser = doc::DocParser::open(path)?;
+            parser.parse()
+        }
+        #[cfg(feature = "xls")]
+        FormatType::Xls => {
+            let mut parser = xls::XlsParser::open(path)?;
+            parser.parse()
+        }
+        #[cfg(feature = "xlsb")]
+        FormatType::Xlsb => {
+            let mut parser = xlsb::XlsbParser::open(path)?;
+            parser.parse()
+        }
+        #[cfg(feature = "ppt")]
+        FormatType::Ppt => {
+            let mut parser = ppt::PptParser::open(path)?;
+            parser.parse()
+        }
+        #[cfg(feature = "odt")]
+        FormatType::Odt => {
+            let mut parser = odt::OdtParser::open(path)?;
+            parser.parse()
+        }
+        #[cfg(feature = "ods")]
+        FormatType::Ods => {
+            let mut parser = ods::OdsParser::open(path)?;
+            parser.parse()
+        }
+        #[cfg(feature = "odp")]
+        FormatType::Odp => {
+            let mut parser = odp::OdpParser::open(path)?;
+            parser.parse()
+        }
+        #[cfg(not(all(
+            feature = "docx",
+            feature = "xlsx",
+            feature = "pptx",
+            feature = "doc",
+            feature = "xls",
+            feature = "xlsb",
+            feature = "ppt",
+            feature = "odt",
+            feature = "ods",
+            feature = "odp"
+        )))]
         _ => Err(Error::UnsupportedFormat(format!("{:?}", format))),
     }
 }
@@ -160,11 +257,101 @@ pub fn parse_bytes(data: &[u8]) -> Result<Document> {
             let mut parser = pptx::PptxParser::from_bytes(data.to_vec())?;
             parser.parse()
         }
-        #[cfg(not(all(feature = "docx", feature = "xlsx", feature = "pptx")))]
+        #[cfg(feature = "doc")]
+        FormatType::Doc => {
+            let mut parser = doc::DocParser::from_bytes(data.to_vec())?;
+            parser.parse()
+        }
+        #[cfg(feature = "xls")]
+        FormatType::Xls => {
+            let mut parser = xls::XlsParser::from_bytes(data.to_vec())?;
+            parser.parse()
+        }
+        #[cfg(feature = "xlsb")]
+        FormatType::Xlsb => {
+            let mut parser = xlsb::XlsbParser::from_bytes(data.to_vec())?;
+            parser.parse()
+        }
+        #[cfg(feature = "ppt")]
+        FormatType::Ppt => {
+            let mut parser = ppt::PptParser::from_bytes(data.to_vec())?;
+            parser.parse()
+        }
+        #[cfg(feature = "odt")]
+        FormatType::Odt => {
+            let mut parser = odt::OdtParser::from_bytes(data.to_vec())?;
+            parser.parse()
+        }
+        #[cfg(feature = "ods")]
+        FormatType::Ods => {
+            let mut parser = ods::OdsParser::from_bytes(data.to_vec())?;
+            parser.parse()
+        }
+        #[cfg(feature = "odp")]
+        FormatType::Odp => {
+            let mut parser = odp::OdpParser::from_bytes(data.to_vec())?;
+            parser.parse()
+        }
+        #[cfg(not(all(
+            feature = "docx",
+            feature = "xlsx",
+            feature = "pptx",
+            feature = "doc",
+            feature = "xls",
+            feature = "xlsb",
+            feature = "ppt",
+            feature = "odt",
+            feature = "ods",
+            feature = "odp"
+        )))]
         _ => Err(Error::UnsupportedFormat(format!("{:?}", format))),
     }
 }
 
+/// Parse a password-protected document file.
+///
+/// An ECMA-376 encrypted package is decrypted with `password` and then parsed like
+/// any other OOXML file; a file that is not encrypted is parsed as usual and the
+/// password is ignored. A password that does not open the package is
+/// [`Error::WrongPassword`].
+///
+/// # Example
+///
+/// ```no_run
+/// use undoc::parse_file_with_password;
+///
+/// let doc = parse_file_with_password("protected.docx", "secret")?;
+/// println!("Sections: {}", doc.sections.len());
+/// # Ok::<(), undoc::Error>(())
+/// ```
+#[cfg(all(feature = "decrypt", not(target_arch = "wasm32")))]
+pub fn parse_file_with_password(path: impl AsRef<Path>, password: &str) -> Result<Document> {
+    let data = std::fs::read(path)?;
+    parse_bytes_with_password(&data, password)
+}
+
+/// Parse a password-protected document from bytes.
+///
+/// See [`parse_file_with_password`].
+///
+/// # Example
+///
+/// ```no_run
+/// use undoc::parse_bytes_with_password;
+///
+/// let data = std::fs::read("protected.xlsx")?;
+/// let doc = parse_bytes_with_password(&data, "secret")?;
+/// # Ok::<(), undoc::Error>(())
+/// ```
+#[cfg(feature = "decrypt")]
+pub fn parse_bytes_with_password(data: &[u8], password: &str) -> Result<Document> {
+    if matches!(detect_format_from_bytes(data), Err(Error::Encrypted)) {
+        let package = decrypt::decrypt_package(data.to_vec(), password)?;
+        return parse_bytes(&package);
+    }
+    parse_bytes(data)
+}
+
 /// Extract plain text from a document.
 ///
 /// # Example
@@ -238,6 +425,92 @@ pub fn to_text(path: impl AsRef<Path>, options: &render::RenderOptions) -> Resul
     render::to_text(&doc, options)
 }
 
+/// Convert a document to HTML.
+///
+/// # Example
+///
+/// ```no_run
+/// use undoc::{to_html, render::HtmlOptions};
+///
+/// let html = to_html("document.docx", &HtmlOptions::default())?;
+/// std::fs::write("output.html", html)?;
+/// # Ok::<(), undoc::Error>(())
+/// ```
+#[cfg(not(target_arch = "wasm32"))]
+pub fn to_html(path: impl AsRef<Path>, options: &render::HtmlOptions) -> Result<String> {
+    let doc = parse_file(path)?;
+    render::to_html(&doc, options)
+}
+
+/// Convert a document to AsciiDoc.
+///
+/// # Example
+///
+/// ```no_run
+/// use undoc::{to_asciidoc, render::RenderOptions};
+///
+/// let adoc = to_asciidoc("document.docx", &RenderOptions::default())?;
+/// std::fs::write("output.adoc", adoc)?;
+/// # Ok::<(), undoc::Error>(())
+/// ```
+#[cfg(not(target_arch = "wasm32"))]
+pub fn to_asciidoc(path: impl AsRef<Path>, options: &render::RenderOptions) -> Result<String> {
+    let doc = parse_file(path)?;
+    render::to_asciidoc(&doc, options)
+}
+
+/// Convert a document to LaTeX.
+///
+/// # Example
+///
+/// ```no_run
+/// use undoc::{to_latex, render::LatexOptions};
+///
+/// let tex = to_latex("paper.docx", &LatexOptions::default())?;
+/// std::fs::write("paper.tex", tex)?;
+/// # Ok::<(), undoc::Error>(())
+/// ```
+#[cfg(not(target_arch = "wasm32"))]
+pub fn to_latex(path: impl AsRef<Path>, options: &render::LatexOptions) -> Result<String> {
+    let doc = parse_file(path)?;
+    render::to_latex(&doc, options)
+}
+
+/// Convert each section of a document to CSV.
+///
+/// # Example
+///
+/// ```no_run
+/// use undoc::{to_csv, render::CsvOptions};
+///
+/// for (i, csv) in to_csv("data.xlsx", &CsvOptions::default())?.iter().enumerate() {
+///     std::fs::write(format!("sheet{}.csv", i + 1), csv)?;
+/// }
+/// # Ok::<(), undoc::Error>(())
+/// ```
+#[cfg(not(target_arch = "wasm32"))]
+pub fn to_csv(path: impl AsRef<Path>, options: &render::CsvOptions) -> Result<Vec<String>> {
+    let doc = parse_file(path)?;
+    render::to_csv(&doc, options)
+}
+
+/// Convert a document to an EPUB 3 book.
+///
+/// # Example
+///
+/// ```no_run
+/// use undoc::{to_epub, render::EpubOptions};
+///
+/// let epub = to_epub("manual.docx", &EpubOptions::default())?;
+/// std::fs::write("manual.epub", epub)?;
+/// # Ok::<(), undoc::Error>(())
+/// ```
+#[cfg(not(target_arch = "wasm32"))]
+pub fn to_epub(path: impl AsRef<Path>, options: &render::EpubOptions) -> Result<Vec<u8>> {
+    let doc = parse_file(path)?;
+    render::to_epub(&doc, options)
+}
+
 /// Convert a document to JSON.
 ///
 /// # Example
@@ -0,0 +1,755 @@
+//! Office Math (OMML) to LaTeX translation.
+//!
+//! Word stores equations as OMML (`m:oMath`, `m:oMathPara`) inside paragraphs, and
+//! PowerPoint wraps the same markup in `a14:m`. This module turns it into LaTeX math
+//! source, without the surrounding `$` delimiters: fractions, radicals, n-ary
+//! operators, sub- and superscripts, matrices, delimiters, accents, bars, functions,
+//! limits, group characters and equation arrays. Elements it does not know are
+//! replaced by their content.
+
+use crate::error::Result;
+use crate::model::{MathMode, TextRun};
+use crate::xml_tree::{self, Element, Names, Node};
+use quick_xml::events::BytesStart;
+use quick_xml::Reader;
+
+/// Reading an OMML element's properties.
+trait Properties {
+    /// The `m:val` of the `prop` element in this element's `pr` properties.
+    fn prop(&self, pr: &str, prop: &str) -> Option<&str>;
+
+    /// Whether the `prop` element of the `pr` properties is on. OMML writes a bare
+    /// element for on, and `m:val="off"` (or `0`, `false`) for off.
+    fn flag(&self, pr: &str, prop: &str) -> bool;
+}
+
+impl Properties for Element {
+    fn prop(&self, pr: &str, prop: &str) -> Option<&str> {
+        self.child(pr)?.child(prop)?.attr("val")
+    }
+
+    fn flag(&self, pr: &str, prop: &str) -> bool {
+        self.child(pr)
+            .and_then(|p| p.child(prop))
+            .is_some_and(|p| !matches!(p.attr("val"), Some("0" | "off" | "false")))
+    }
+}
+
+/// Translate OMML to LaTeX.
+///
+/// `xml` is the content of an `m:oMath` element, or of an `m:oMathPara`, whose
+/// equations are set one per line.
+pub(crate) fn omml_to_latex(xml: &str) -> Result<String> {
+    let root = xml_tree::parse(xml, "equation", Names::Local)?;
+    let mut translator = Translator::default();
+    let lines: Vec<String> = root
+        .children_named("oMath")
+        .map(|math| translator.children(math))
+        .map(|line| line.trim().to_string())
+        .collect();
+    let latex = match lines.len() {
+        0 => translator.children(&root),
+        1 => lines.into_iter().next().unwrap_or_default(),
+        _ => format!(
+            "\\begin{{gathered}}{}\\end{{gathered}}",
+            lines.join(" \\\\ ")
+        ),
+    };
+    Ok(latex.trim().to_string())
+}
+
+/// Translate the equation starting at `start` (`m:oMathPara` or `m:oMath`) into a math
+/// run, consuming it from `reader`, which reads `xml`.
+pub(crate) fn read_equation(
+    reader: &mut Reader<&[u8]>,
+    xml: &str,
+    start: &BytesStart,
+) -> Result<Option<TextRun>> {
+    let mode = if start.name().local_name().as_ref() == b"oMathPara" {
+        MathMode::Display
+    } else {
+        MathMode::Inline
+    };
+    let span = reader.read_to_end(start.name())?;
+    let omml = xml
+        .get(span.start as usize..span.end as usize)
+        .unwrap_or_default();
+    let latex = omml_to_latex(omml)?;
+    Ok((!latex.is_empty()).then(|| TextRun::math(latex, mode)))
+}
+
+/// Functions LaTeX has an operator for, written `\name`.
+const FUNCTIONS: &[&str] = &[
+    "arccos", "arcsin", "arctan", "arg", "cos", "cosh", "cot", "coth", "csc", "deg", "det", "dim",
+    "exp", "gcd", "hom", "inf", "ker", "lg", "lim", "liminf", "limsup", "ln", "log", "max", "min",
+    "Pr", "sec", "sin", "sinh", "sup", "tan", "tanh",
+];
+
+#[derive(Default)]
+struct Translator {
+    /// Inside an `m:eqArr`, where `&` marks an alignment point rather than text
+    in_eq_arr: bool,
+}
+
+impl Translator {
+    fn children(&mut self, node: &Element) -> String {
+        node.nodes
+            .iter()
+            .map(|child| match child {
+                Node::Element(e) => self.node(e),
+                // Text outside `m:t` is layout whitespace, or the text of elements
+                // nested past the tree's depth limit, which is all there is left.
+                Node::Text(raw) if raw.trim().is_empty() => String::new(),
+                Node::Text(raw) => text(raw, self.in_eq_arr),
+            })
+            .collect()
+    }
+
+    /// The LaTeX of the element `name` below `node`, or nothing.
+    fn part(&mut self, node: &Element, name: &str) -> String {
+        node.child(name)
+            .map(|part| self.children(part))
+            .unwrap_or_default()
+            .trim()
+            .to_string()
+    }
+
+    fn node(&mut self, node: &Element) -> String {
+        match node.name.as_str() {
+            "r" => self.run(node),
+            "t" => text(&node.text(), self.in_eq_arr),
+            "f" => self.fraction(node),
+            "rad" => {
+                let base = self.part(node, "e");
+                let degree = self.part(node, "deg");
+                if degree.is_empty() || node.flag("radPr", "degHide") {
+                    format!("\\sqrt{{{base}}}")
+                } else {
+                    format!("\\sqrt[{degree}]{{{base}}}")
+                }
+            }
+            "nary" => self.nary(node),
+            "sSub" => {
+                let base = self.part(node, "e");
+                format!("{}_{{{}}}", group(&base), self.part(node, "sub"))
+            }
+            "sSup" => {
+                let base = self.part(node, "e");
+                format!("{}^{{{}}}", group(&base), self.part(node, "sup"))
+            }
+            "sSubSup" => {
+                let base = self.part(node, "e");
+                let sub = self.part(node, "sub");
+                format!("{}_{{{sub}}}^{{{}}}", group(&base), self.part(node, "sup"))
+            }
+            "sPre" => {
+                let sub = self.part(node, "sub");
+                let sup = self.part(node, "sup");
+                format!("{{}}_{{{sub}}}^{{{sup}}}{}", group(&self.part(node, "e")))
+            }
+            "m" => format!("\\begin{{matrix}}{}\\end{{matrix}}", self.matrix_rows(node)),
+            "d" => self.delimiter(node),
+            "acc" => {
+                let accent = node.prop("accPr", "chr").unwrap_or("\u{0302}");
+                format!("{}{{{}}}", accent_command(accent), self.part(node, "e"))
+            }
+            "bar" => {
+                let command = match node.prop("barPr", "pos") {
+                    Some("top") => "\\overline",
+                    _ => "\\underline",
+                };
+                format!("{command}{{{}}}", self.part(node, "e"))
+            }
+            "func" => {
+                let name =
+                    function_name(node.child("fName")).unwrap_or_else(|| self.part(node, "fName"));
+                format!("{name}{{{}}}", self.part(node, "e"))
+            }
+            "limLow" | "limUpp" => {
+                let limit = self.part(node, "lim");
+                let (script, stack) = if node.name == "limLow" {
+                    ("_", "\\underset")
+                } else {
+                    ("^", "\\overset")
+                };
+                match function_name(node.child("e")) {
+                    Some(name) => format!("{name}{script}{{{limit}}}"),
+                    None => format!("{stack}{{{limit}}}{{{}}}", self.part(node, "e")),
+                }
+            }
+            "groupChr" => {
+                let chr = node.prop("groupChrPr", "chr").unwrap_or("\u{23DF}");
+                let top = node.prop("groupChrPr", "pos") == Some("top");
+                let base = self.part(node, "e");
+                match (chr, top) {
+                    ("\u{23DE}", _) | ("\u{23DF}", true) => format!("\\overbrace{{{base}}}"),
+                    ("\u{23DF}", false) => format!("\\underbrace{{{base}}}"),
+                    (chr, true) => format!("\\overset{{{}}}{{{base}}}", text(chr, false).trim()),
+                    (chr, false) => {
+                        format!("\\underset{{{}}}{{{base}}}", text(chr, false).trim())
+                    }
+                }
+            }
+            "borderBox" => format!("\\boxed{{{}}}", self.part(node, "e")),
+            "phant" if !node.flag("phantPr", "show") && node.child("phantPr").is_some() => {
+                format!("\\phantom{{{}}}", self.part(node, "e"))
+            }
+            "eqArr" => {
+                let was = std::mem::replace(&mut self.in_eq_arr, true);
+                let rows: Vec<String> = node
+                    .children_named("e")
+                    .map(|row| self.children(row).trim().to_string())
+                    .collect();
+                self.in_eq_arr = was;
+                let environment = if rows.iter().any(|row| row.contains('&')) {
+                    "aligned"
+                } else {
+                    "gathered"
+                };
+                format!(
+                    "\\begin{{{environment}}}{}\\end{{{environment}}}",
+                    rows.join(" \\\\ ")
+                )
+            }
+            // Properties say how to draw; what they say is read where it matters.
+            name if name.ends_with("Pr") => String::new(),
+            _ => self.children(node),
+        }
+    }
+
+    fn run(&mut self, node: &Element) -> String {
+        let raw: String = node.children_named("t").map(Element::text).collect();
+        if raw.is_empty() {
+            return String::new();
+        }
+        if node.flag("rPr", "nor") {
+            return format!("\\text{{{}}}", text_mode(&raw));
+        }
+        let latex = text(&raw, self.in_eq_arr);
+        let font = match (node.prop("rPr", "scr"), node.prop("rPr", "sty")) {
+            (Some("double-struck"), _) => Some("\\mathbb"),
+            (Some("script"), _) => Some("\\mathcal"),
+            (Some("fraktur"), _) => Some("\\mathfrak"),
+            (_, Some("p")) => Some("\\mathrm"),
+            (_, Some("b")) => Some("\\mathbf"),
+            (_, Some("bi")) => Some("\\boldsymbol"),
+            _ => None,
+        };
+        match font {
+            Some(font) if raw.chars().any(char::is_alphabetic) => {
+                format!("{font}{{{}}}", latex.trim())
+            }
+            _ => latex,
+        }
+    }
+
+    fn fraction(&mut self, node: &Element) -> String {
+        let num = self.part(node, "num");
+        let den = self.part(node, "den");
+        match node.prop("fPr", "type") {
+            Some("lin" | "skw") => format!("{}/{}", group(&num), group(&den)),
+            Some("noBar") => format!("\\genfrac{{}}{{}}{{0pt}}{{}}{{{num}}}{{{den}}}"),
+            _ => format!("\\frac{{{num}}}{{{den}}}"),
+        }
+    }
+
+    fn nary(&mut self, node: &Element) -> String {
+        let chr = node.prop("naryPr", "chr").unwrap_or("\u{222B}");
+        let mut latex = nary_command(chr);
+        let integral = matches!(chr, "\u{222B}" | "\u{222C}" | "\u{222D}" | "\u{222E}");
+        if integral && node.prop("naryPr", "limLoc") == Some("undOvr") {
+            latex.push_str("\\limits");
+        }
+        let sub = self.part(node, "sub");
+        if !sub.is_empty() && !node.flag("naryPr", "subHide") {
+            latex.push_str(&format!("_{{{sub}}}"));
+        }
+        let sup = self.part(node, "sup");
+        if !sup.is_empty() && !node.flag("naryPr", "supHide") {
+            latex.push_str(&format!("^{{{sup}}}"));
+        }
+        latex.push_str(&format!("{{{}}}", self.part(node, "e")));
+        latex
+    }
+
+    fn matrix_rows(&mut self, node: &Element) -> String {
+        node.children_named("mr")
+            .map(|row| {
+                row.children_named("e")
+                    .map(|cell| self.children(cell).trim().to_string())
+                    .collect::<Vec<_>>()
+                    .join(" & ")
+            })
+            .collect::<Vec<_>>()
+            .join(" \\\\ ")
+    }
+
+    fn delimiter(&mut self, node: &Element) -> String {
+        let open = node.prop("dPr", "begChr").unwrap_or("(");
+        let close = node.prop("dPr", "endChr").unwrap_or(")");
+        let separator = node.prop("dPr", "sepChr").unwrap_or("|");
+        let parts: Vec<&Element> = node.children_named("e").collect();
+
+        // A matrix in brackets is one of LaTeX's bracketed matrix environments.
+        if let [part] = parts.as_slice() {
+            let children: Vec<&Element> = part.children().collect();
+            let only_matrix = matches!(children.as_slice(), [only] if only.name == "m");
+            let environment = match (open, close) {
+                ("(", ")") => Some("pmatrix"),
+                ("[", "]") => Some("bmatrix"),
+                ("{", "}") => Some("Bmatrix"),
+                ("|", "|") => Some("vmatrix"),
+                ("\u{2016}", "\u{2016}") => Some("Vmatrix"),
+                _ => None,
+            };
+            if let (true, Some(environment)) = (only_matrix, environment) {
+                let rows = self.matrix_rows(children[0]);
+                return format!("\\begin{{{environment}}}{rows}\\end{{{environment}}}");
+            }
+        }
+
+        let inner = parts
+            .iter()
+            .map(|part| self.children(part).trim().to_string())
+            .collect::<Vec<_>>()
+            .join(&format!(" \\middle{} ", delimiter_symbol(separator)));
+        format!(
+            "\\left{} {inner} \\right{}",
+            delimiter_symbol(open),
+            delimiter_symbol(close)
+        )
+    }
+}
+
+/// `base` as the base of a script: bare when it is one symbol, braced otherwise.
+fn group(base: &str) -> String {
+    let base = base.trim();
+    let single = base.chars().count() == 1
+        || (base.starts_with('\\') && base[1..].chars().all(|c| c.is_ascii_alphabetic()));
+    if single && !base.is_empty() {
+        base.to_string()
+    } else {
+        format!("{{{base}}}")
+    }
+}
+
+/// `\name` for an element holding nothing but the name of a known function, such as
+/// `sin` in `m:fName` or `lim` under `m:limLow`; `\operatorname{name}` for another
+/// plain name.
+fn function_name(node: Option<&Element>) -> Option<String> {
+    let node = node?;
+    if !node
+        .children()
+        .all(|c| c.name == "r" || c.name.ends_with("Pr"))
+    {
+        return None;
+    }
+    let name = node.text();
+    let name = name.trim();
+    if FUNCTIONS.contains(&name) {
+        Some(format!("\\{name}"))
+    } else if !name.is_empty() && name.chars().all(|c| c.is_ascii_alphabetic()) && name.len() > 1 {
+        Some(format!("\\operatorname{{{name}}}"))
+    } else {
+        None
+    }
+}
+
+/// Math-mode LaTeX for the text of a run.
+fn text(s: &str, alignment_marks: bool) -> String {
+    let mut latex = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' if alignment_marks => latex.push('&'),
+            '{' | '}' | '#' | '%' | '&' | '$' | '_' => {
+                latex.push('\\');
+                latex.push(c);
+            }
+            '\\' => latex.push_str("\\backslash "),
+            '^' => latex.push_str("\\wedge "),
+            '~' => latex.push_str("\\sim "),
+            '\u{2212}' => latex.push('-'),
+            '\u{2032}' => latex.push('\''),
+            '\u{2033}' => latex.push_str("''"),
+            '\u{00B0}' => latex.push_str("^{\\circ}"),
+            c => match symbol(c) {
+                // A control word ends at the first non-letter, so a space keeps the
+                // next letter out of it.
+                Some(command) => {
+                    latex.push_str(command);
+                    latex.push(' ');
+                }
+                None => latex.push(c),
+            },
+        }
+    }
+    latex
+}
+
+/// Text-mode LaTeX for the text of a normal-text run (`\text{...}`).
+fn text_mode(s: &str) -> String {
+    let mut latex = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '{' | '}' | '#' | '%' | '&' | '$' | '_' => {
+                latex.push('\\');
+                latex.push(c);
+            }
+            '\\' => latex.push_str("\\textbackslash{}"),
+            '^' => latex.push_str("\\textasciicircum{}"),
+            '~' => latex.push_str("\\textasciitilde{}"),
+            c => latex.push(c),
+        }
+    }
+    latex
+}
+
+/// The LaTeX command for a symbol character, if it has one.
+fn symbol(c: char) -> Option<&'static str> {
+    Some(match c {
+        'α' => "\\alpha",
+        'β' => "\\beta",
+        'γ' => "\\gamma",
+        'δ' => "\\delta",
+        'ε' => "\\varepsilon",
+        'ϵ' => "\\epsilon",
+        'ζ' => "\\zeta",
+        'η' => "\\eta",
+        'θ' => "\\theta",
+        'ϑ' => "\\vartheta",
+        'ι' => "\\iota",
+        'κ' => "\\kappa",
+        'λ' => "\\lambda",
+        'μ' => "\\mu",
+        'ν' => "\\nu",
+        'ξ' => "\\xi",
+        'π' => "\\pi",
+        'ϖ' => "\\varpi",
+        'ρ' => "\\rho",
+        'ϱ' => "\\varrho",
+        'σ' => "\\sigma",
+        'ς' => "\\varsigma",
+        'τ' => "\\tau",
+        'υ' => "\\upsilon",
+        'φ' => "\\varphi",
+        'ϕ' => "\\phi",
+        'χ' => "\\chi",
+        'ψ' => "\\psi",
+        'ω' => "\\omega",
+        'Γ' => "\\Gamma",
+        'Δ' => "\\Delta",
+        'Θ' => "\\Theta",
+        'Λ' => "\\Lambda",
+        'Ξ' => "\\Xi",
+        'Π' => "\\Pi",
+        'Σ' => "\\Sigma",
+        'Υ' => "\\Upsilon",
+        'Φ' => "\\Phi",
+        'Ψ' => "\\Psi",
+        'Ω' => "\\Omega",
+        '±' => "\\pm",
+        '∓' => "\\mp",
+        '×' => "\\times",
+        '÷' => "\\div",
+        '·' | '⋅' => "\\cdot",
+        '∗' => "\\ast",
+        '∘' => "\\circ",
+        '•' => "\\bullet",
+        '≤' => "\\leq",
+        '≥' => "\\geq",
+        '≠' => "\\neq",
+        '≈' => "\\approx",
+        '≡' => "\\equiv",
+        '∼' => "\\sim",
+        '≃' => "\\simeq",
+        '≅' => "\\cong",
+        '∝' => "\\propto",
+        '≪' => "\\ll",
+        '≫' => "\\gg",
+        '∈' => "\\in",
+        '∉' => "\\notin",
+        '∋' => "\\ni",
+        '⊂' => "\\subset",
+        '⊃' => "\\supset",
+        '⊆' => "\\subseteq",
+        '⊇' => "\\supseteq",
+        '∪' => "\\cup",
+        '∩' => "\\cap",
+        '∅' => "\\emptyset",
+        '∀' => "\\forall",
+        '∃' => "\\exists",
+        '¬' => "\\neg",
+        '∧' => "\\wedge",
+        '∨' => "\\vee",
+        '→' => "\\rightarrow",
+        '←' => "\\leftarrow",
+        '↔' => "\\leftrightarrow",
+        '⇒' => "\\Rightarrow",
+        '⇐' => "\\Leftarrow",
+        '⇔' => "\\Leftrightarrow",
+        '↦' => "\\mapsto",
+        '∞' => "\\infty",
+        '∂' => "\\partial",
+        '∇' => "\\nabla",
+        'ℏ' => "\\hbar",
+        'ℓ' => "\\ell",
+        '…' => "\\ldots",
+        '⋯' => "\\cdots",
+        '⋮' => "\\vdots",
+        '⋱' => "\\ddots",
+        '∠' => "\\angle",
+        '⊥' => "\\perp",
+        '∥' => "\\parallel",
+        '⊕' => "\\oplus",
+        '⊗' => "\\otimes",
+        'ℝ' => "\\mathbb{R}",
+        'ℕ' => "\\mathbb{N}",
+        'ℤ' => "\\mathbb{Z}",
+        'ℚ' => "\\mathbb{Q}",
+        'ℂ' => "\\mathbb{C}",
+        '√' => "\\surd",
+        _ => return None,
+    })
+}
+
+/// The LaTeX operator for an `m:nary` character.
+fn nary_command(chr: &str) -> String {
+    match chr {
+        "\u{2211}" => "\\sum",
+        "\u{220F}" => "\\prod",
+        "\u{2210}" => "\\coprod",
+        "\u{222B}" => "\\int",
+        "\u{222C}" => "\\iint",
+        "\u{222D}" => "\\iiint",
+        "\u{222E}" => "\\oint",
+        "\u{22C3}" => "\\bigcup",
+        "\u{22C2}" => "\\bigcap",
+        "\u{22C1}" => "\\bigvee",
+        "\u{22C0}" => "\\bigwedge",
+        "\u{2A01}" => "\\bigoplus",
+        "\u{2A02}" => "\\bigotimes",
+        "\u{2A00}" => "\\bigodot",
+        other => return format!("\\mathop{{{}}}", text(other, false).trim()),
+    }
+    .to_string()
+}
+
+/// The LaTeX accent for an `m:acc` combining character.
+fn accent_command(chr: &str) -> &'static str {
+    match chr {
+        "\u{0300}" => "\\grave",
+        "\u{0301}" => "\\acute",
+        "\u{0303}" => "\\tilde",
+        "\u{0304}" => "\\bar",
+        "\u{0305}" | "\u{203E}" => "\\overline",
+        "\u{0306}" => "\\breve",
+        "\u{0307}" => "\\dot",
+        "\u{0308}" => "\\ddot",
+        "\u{030C}" => "\\check",
+        "\u{20D6}" => "\\overleftarrow",
+        "\u{20D7}" => "\\vec",
+        "\u{20DB}" => "\\dddot",
+        "\u{20E1}" => "\\overleftrightarrow",
+        _ => "\\hat",
+    }
+}
+
+/// A delimiter as `\left`/`\right` take it; `.` for none.
+fn delimiter_symbol(chr: &str) -> &str {
+    match chr {
+        "" => ".",
+        "{" => "\\{",
+        "}" => "\\}",
+        "\u{27E8}" | "\u{2329}" => "\\langle",
+        "\u{27E9}" | "\u{232A}" => "\\rangle",
+        "\u{2016}" => "\\|",
+        "\u{230A}" => "\\lfloor",
+        "\u{230B}" => "\\rfloor",
+        "\u{2308}" => "\\lceil",
+        "\u{2309}" => "\\rceil",
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn latex(omml: &str) -> String {
+        omml_to_latex(omml).unwrap()
+    }
+
+    fn r(text: &str) -> String {
+        format!("<m:r><m:t>{text}</m:t></m:r>")
+    }
+
+    #[test]
+    fn test_runs_and_symbols() {
+        assert_eq!(latex(&r("a+b=c")), "a+b=c");
+        assert_eq!(latex(&r("2πr≤∞")), "2\\pi r\\leq \\infty");
+        assert_eq!(latex(&r("{x}_1 &amp; 50%")), "\\{x\\}\\_1 \\& 50\\%");
+        assert_eq!(
+            latex(r#"<m:r><m:rPr><m:sty m:val="p"/></m:rPr><m:t>d</m:t></m:r>"#),
+            "\\mathrm{d}"
+        );
+        assert_eq!(
+            latex(r#"<m:r><m:rPr><m:nor/></m:rPr><m:t>if x_0</m:t></m:r>"#),
+            "\\text{if x\\_0}"
+        );
+    }
+
+    #[test]
+    fn test_fractions_and_radicals() {
+        let frac = format!(
+            "<m:f><m:num>{}</m:num><m:den>{}</m:den></m:f>",
+            r("a"),
+            r("b+1")
+        );
+        assert_eq!(latex(&frac), "\\frac{a}{b+1}");
+        let linear = format!(
+            r#"<m:f><m:fPr><m:type m:val="lin"/></m:fPr><m:num>{}</m:num><m:den>{}</m:den></m:f>"#,
+            r("a"),
+            r("b+1")
+        );
+        assert_eq!(latex(&linear), "a/{b+1}");
+
+        let sqrt = format!(
+            r#"<m:rad><m:radPr><m:degHide m:val="1"/></m:radPr><m:deg/><m:e>{}</m:e></m:rad>"#,
+            r("x")
+        );
+        assert_eq!(latex(&sqrt), "\\sqrt{x}");
+        let cube = format!(
+            "<m:rad><m:deg>{}</m:deg><m:e>{}</m:e></m:rad>",
+            r("3"),
+            r("x")
+        );
+        assert_eq!(latex(&cube), "\\sqrt[3]{x}");
+    }
+
+    #[test]
+    fn test_nary_and_scripts() {
+        let sum = format!(
+            r#"<m:nary><m:naryPr><m:chr m:val="∑"/><m:limLoc m:val="undOvr"/></m:naryPr><m:sub>{}</m:sub><m:sup>{}</m:sup><m:e>{}</m:e></m:nary>"#,
+            r("i=1"),
+            r("n"),
+            r("x")
+        );
+        assert_eq!(latex(&sum), "\\sum_{i=1}^{n}{x}");
+        let integral = format!(
+            r#"<m:nary><m:naryPr><m:subHide m:val="on"/><m:supHide/></m:naryPr><m:sub/><m:sup/><m:e>{}</m:e></m:nary>"#,
+            r("f")
+        );
+        assert_eq!(latex(&integral), "\\int{f}");
+
+        let squared = format!(
+            "<m:sSup><m:e>{}</m:e><m:sup>{}</m:sup></m:sSup>",
+            r("x"),
+            r("2")
+        );
+        assert_eq!(latex(&squared), "x^{2}");
+        let indexed = format!(
+            "<m:sSubSup><m:e>{}</m:e><m:sub>{}</m:sub><m:sup>{}</m:sup></m:sSubSup>",
+            r("ab"),
+            r("i"),
+            r("2")
+        );
+        assert_eq!(latex(&indexed), "{ab}_{i}^{2}");
+        let greek = format!(
+            "<m:sSub><m:e>{}</m:e><m:sub>{}</m:sub></m:sSub>",
+            r("α"),
+            r("0")
+        );
+        assert_eq!(latex(&greek), "\\alpha_{0}");
+    }
+
+    #[test]
+    fn test_matrices_and_delimiters() {
+        let matrix = format!(
+            "<m:m><m:mr><m:e>{}</m:e><m:e>{}</m:e></m:mr><m:mr><m:e>{}</m:e><m:e>{}</m:e></m:mr></m:m>",
+            r("1"),
+            r("0"),
+            r("0"),
+            r("1")
+        );
+        assert_eq!(
+            latex(&format!("<m:d><m:e>{matrix}</m:e></m:d>")),
+            "\\begin{pmatrix}1 & 0 \\\\ 0 & 1\\end{pmatrix}"
+        );
+        assert_eq!(
+            latex(&matrix),
+            "\\begin{matrix}1 & 0 \\\\ 0 & 1\\end{matrix}"
+        );
+
+        let set = format!(
+            r#"<m:d><m:dPr><m:begChr m:val="{{"/><m:endChr m:val=""/><m:sepChr m:val="|"/></m:dPr><m:e>{}</m:e><m:e>{}</m:e></m:d>"#,
+            r("x"),
+            r("x>0")
+        );
+        assert_eq!(latex(&set), "\\left\\{ x \\middle| x>0 \\right.");
+    }
+
+    #[test]
+    fn test_accents_functions_and_limits() {
+        let vector = format!(
+            r#"<m:acc><m:accPr><m:chr m:val="⃗"/></m:accPr><m:e>{}</m:e></m:acc>"#,
+            r("v")
+        );
+        assert_eq!(latex(&vector), "\\vec{v}");
+        let hat = format!("<m:acc><m:e>{}</m:e></m:acc>", r("x"));
+        assert_eq!(latex(&hat), "\\hat{x}");
+        let mean = format!(
+            r#"<m:bar><m:barPr><m:pos m:val="top"/></m:barPr><m:e>{}</m:e></m:bar>"#,
+            r("x")
+        );
+        assert_eq!(latex(&mean), "\\overline{x}");
+
+        let sine = format!(
+            r#"<m:func><m:fName><m:r><m:rPr><m:sty m:val="p"/></m:rPr><m:t>sin</m:t></m:r></m:fName><m:e>{}</m:e></m:func>"#,
+            r("θ")
+        );
+        assert_eq!(latex(&sine), "\\sin{\\theta}");
+        let limit = format!(
+            r#"<m:func><m:fName><m:limLow><m:e><m:r><m:rPr><m:sty m:val="p"/></m:rPr><m:t>lim</m:t></m:r></m:e><m:lim>{}</m:lim></m:limLow></m:fName><m:e>{}</m:e></m:func>"#,
+            r("n→∞"),
+            r("a")
+        );
+        assert_eq!(latex(&limit), "\\lim_{n\\rightarrow \\infty}{a}");
+        let brace = format!("<m:groupChr><m:e>{}</m:e></m:groupChr>", r("a+b"));
+        assert_eq!(latex(&brace), "\\underbrace{a+b}");
+    }
+
+    #[test]
+    fn test_equation_arrays_and_paragraphs() {
+        let system = format!(
+            "<m:eqArr><m:e>{}</m:e><m:e>{}</m:e></m:eqArr>",
+            r("x&amp;=1"),
+            r("y&amp;=2")
+        );
+        assert_eq!(
+            latex(&system),
+            "\\begin{aligned}x&=1 \\\\ y&=2\\end{aligned}"
+        );
+
+        let para = format!(
+            "<m:oMathParaPr/><m:oMath>{}</m:oMath><m:oMath>{}</m:oMath>",
+            r("a=b"),
+            r("c=d")
+        );
+        assert_eq!(latex(&para), "\\begin{gathered}a=b \\\\ c=d\\end{gathered}");
+        assert_eq!(latex(&format!("<m:oMath>{}</m:oMath>", r("e"))), "e");
+    }
+
+    #[test]
+    fn test_deep_nesting_falls_back_to_text() {
+        let depth = 50_000;
+        let omml = format!(
+            "{}{}{}",
+            "<m:d><m:e>".repeat(depth),
+            r("x"),
+            "</m:e></m:d>".repeat(depth)
+        );
+        let latex = latex(&omml);
+        assert!(latex.starts_with("\\left( "));
+        assert!(latex.contains('x'));
+    }
+}
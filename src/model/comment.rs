@@ -0,0 +1,56 @@
+//! Review comment model.
+
+use serde::{Deserialize, Serialize};
+
+/// A review comment (DOCX `word/comments.xml`) and the text it is anchored to.
+///
+/// Each paragraph lists the comments whose range ends in it in
+/// [`Paragraph::comment_ids`](super::Paragraph::comment_ids), which is where a
+/// renderer places them.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
+pub struct Comment {
+    /// Comment ID, unique within the document (`w:id`)
+    pub id: String,
+
+    /// Author name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub author: Option<String>,
+
+    /// Author initials
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub initials: Option<String>,
+
+    /// When the comment was written (ISO 8601)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date: Option<String>,
+
+    /// Comment text, one line per paragraph
+    #[serde(default)]
+    pub text: String,
+
+    /// The document text between the comment's range start and end, with a line
+    /// break between paragraphs. `None` when the comment marks a point rather than
+    /// a range.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub anchor: Option<String>,
+
+    /// ID of the comment this one replies to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent_id: Option<String>,
+
+    /// Whether the thread was marked resolved (done)
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub resolved: bool,
+
+    /// ID that stays the same across saves, from `word/commentsIds.xml`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub durable_id: Option<String>,
+}
+
+impl Comment {
+    /// Check if this comment is a reply in another comment's thread.
+    pub fn is_reply(&self) -> bool {
+        self.parent_id.is_some()
+    }
+}
@@ -1,12 +1,13 @@
 //! Document model structures.
 
-use super::{Paragraph, Resource, Table};
-use crate::detect::FormatType;
+use super::{Comment, Field, FormField, Paragraph, Resource, Table};
+use crate::detect::{FormatType, FormatVariant};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 /// Document metadata extracted from docProps/core.xml and docProps/app.xml.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
 pub struct Metadata {
     /// Document title
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -53,8 +54,63 @@ pub struct Metadata {
     pub word_count: Option<u32>,
 }
 
+/// Format seconds since 1970-01-01 UTC as `YYYY-MM-DDTHH:MM:SSZ`, the form
+/// [`Metadata`] timestamps take.
+pub(crate) fn iso8601_from_unix(unix: i64) -> String {
+    let days = unix.div_euclid(86_400);
+    let secs = unix.rem_euclid(86_400);
+    let (year, month, day) = civil_from_days(days);
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}Z",
+        year,
+        month,
+        day,
+        secs / 3600,
+        (secs % 3600) / 60,
+        secs % 60
+    )
+}
+
+/// Proleptic Gregorian date for a count of days since 1970-01-01.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+/// Days since 1970-01-01 for a proleptic Gregorian date, the inverse of
+/// `civil_from_days`. `None` for a date that does not exist, such as February 31.
+pub(crate) fn days_from_civil(year: i64, month: u32, day: u32) -> Option<i64> {
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let month_days = match month {
+        2 if leap => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        1..=12 => 31,
+        _ => return None,
+    };
+    if day == 0 || day > month_days {
+        return None;
+    }
+    let year = year - i64::from(month <= 2);
+    let era = year.div_euclid(400);
+    let yoe = year.rem_euclid(400);
+    let mp = i64::from(if month > 2 { month - 3 } else { month + 9 });
+    let doy = (153 * mp + 2) / 5 + i64::from(day) - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    Some(era * 146_097 + doe - 719_468)
+}
+
 /// A content block within a section.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
 #[serde(tag = "type")]
 pub enum Block {
     /// A paragraph of text
@@ -83,6 +139,7 @@ pub enum Block {
 
 /// A document section (DOCX) or worksheet (XLSX) or slide (PPTX).
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
 pub struct Section {
     /// Section index (0-based)
     pub index: usize,
@@ -152,12 +209,25 @@ impl Section {
     }
 }
 
+/// Version of the JSON form of a [`Document`], written by
+/// [`render::to_json`](crate::render::to_json) as its `schema_version` field and
+/// described by `schema/document.schema.json`.
+///
+/// It goes up when a change would break a reader of that JSON: a field renamed,
+/// removed or given a new meaning. New optional fields leave it as it is.
+pub const JSON_SCHEMA_VERSION: u32 = 1;
+
 /// A parsed Office document.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
 pub struct Document {
     /// Document format (DOCX, XLSX, PPTX).
     pub format: FormatType,
 
+    /// Variant of the format: template, macro-enabled, slide show.
+    #[serde(default)]
+    pub variant: FormatVariant,
+
     /// Document metadata
     pub metadata: Metadata,
 
@@ -168,6 +238,10 @@ pub struct Document {
     /// Extracted resources (images, media)
     #[serde(skip_serializing_if = "HashMap::is_empty", default)]
     pub resources: HashMap<String, Resource>,
+
+    /// Review comments, in the order the document stores them (DOCX only)
+    #[serde(skip_serializing_if = "Vec::is_empty", default)]
+    pub comments: Vec<Comment>,
 }
 
 impl Document {
@@ -191,6 +265,93 @@ impl Document {
         self.resources.get(id)
     }
 
+    /// Load a document from the JSON [`render::to_json`](crate::render::to_json)
+    /// writes, to render it again without the source file.
+    ///
+    /// JSON without a `schema_version`, written before the field existed, reads as
+    /// version 1. JSON from a newer schema version than [`JSON_SCHEMA_VERSION`] is
+    /// refused rather than read half-right.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// use undoc::Document;
+    /// use undoc::render::{to_markdown, RenderOptions};
+    ///
+    /// let doc = Document::from_json(&std::fs::read_to_string("report.json")?)?;
+    /// let markdown = to_markdown(&doc, &RenderOptions::default())?;
+    /// # Ok::<(), undoc::Error>(())
+    /// ```
+    pub fn from_json(json: &str) -> crate::Result<Self> {
+        #[derive(Deserialize)]
+        struct Versioned {
+            schema_version: Option<u32>,
+            #[serde(flatten)]
+            document: Document,
+        }
+
+        let versioned: Versioned = serde_json::from_str(json)
+            .map_err(|e| crate::Error::InvalidData(format!("document JSON: {e}")))?;
+        match versioned.schema_version {
+            Some(version) if version > JSON_SCHEMA_VERSION => {
+                Err(crate::Error::UnsupportedFormat(format!(
+                    "document JSON schema version {version} (this build reads up to {JSON_SCHEMA_VERSION})"
+                )))
+            }
+            _ => Ok(versioned.document),
+        }
+    }
+
+    /// Get a review comment by ID.
+    pub fn get_comment(&self, id: &str) -> Option<&Comment> {
+        self.comments.iter().find(|c| c.id == id)
+    }
+
+    /// Get every form field, in document order: body, tables included, then the headers
+    /// and footers of each section.
+    pub fn form_fields(&self) -> Vec<&FormField> {
+        self.all_paragraphs()
+            .into_iter()
+            .flat_map(Paragraph::form_fields)
+            .collect()
+    }
+
+    /// Get every field, in the same order as [`Self::form_fields`].
+    pub fn fields(&self) -> Vec<&Field> {
+        self.all_paragraphs()
+            .into_iter()
+            .flat_map(Paragraph::fields)
+            .collect()
+    }
+
+    /// Every paragraph: body, tables included, then the headers and footers of each
+    /// section.
+    fn all_paragraphs(&self) -> Vec<&Paragraph> {
+        fn from_table<'a>(table: &'a Table, paragraphs: &mut Vec<&'a Paragraph>) {
+            for cell in table.rows.iter().flat_map(|row| &row.cells) {
+                paragraphs.extend(&cell.content);
+                for nested in &cell.nested_tables {
+                    from_table(nested, paragraphs);
+                }
+            }
+        }
+
+        let mut paragraphs = Vec::new();
+        for section in &self.sections {
+            for block in &section.content {
+                match block {
+                    Block::Paragraph(para) => paragraphs.push(para),
+                    Block::Table(table) => from_table(table, &mut paragraphs),
+                    _ => {}
+                }
+            }
+            for header_footer in [&section.header, &section.footer].into_iter().flatten() {
+                paragraphs.extend(header_footer);
+            }
+        }
+        paragraphs
+    }
+
     /// Get the total number of content blocks across all sections.
     pub fn total_blocks(&self) -> usize {
         self.sections.iter().map(|s| s.len()).sum()
@@ -281,6 +442,9 @@ mod tests {
                     line_break: false,
                     page_break: false,
                     revision: RevisionType::None,
+                    math: None,
+                    form_field: None,
+                    field: None,
                 },
                 TextRun::plain("!"),
             ],
@@ -322,4 +486,17 @@ mod tests {
         assert_eq!(section.name, Some("Sheet1".to_string()));
         assert_eq!(section.index, 0);
     }
+
+    #[test]
+    fn test_days_from_civil_inverts_civil_from_days() {
+        for days in (-800_000..800_000).step_by(97) {
+            let (year, month, day) = civil_from_days(days);
+            assert_eq!(days_from_civil(year, month, day), Some(days));
+        }
+        assert_eq!(days_from_civil(1970, 1, 1), Some(0));
+        assert_eq!(days_from_civil(2000, 2, 29), Some(11_016));
+        assert_eq!(days_from_civil(1900, 2, 29), None);
+        assert_eq!(days_from_civil(2021, 2, 31), None);
+        assert_eq!(days_from_civil(2021, 13, 1), None);
+    }
 }
@@ -0,0 +1,227 @@
+//! Field model.
+
+use serde::{Deserialize, Serialize};
+
+/// The kind of a field, named by the first word of its instruction.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum FieldType {
+    /// `REF`: the text of a bookmark
+    Ref,
+    /// `PAGEREF`: the page number of a bookmark
+    PageRef,
+    /// `SEQ`: the next number of a sequence, such as a figure or table caption number
+    Seq,
+    /// `DATE`, `TIME`, `CREATEDATE`, `SAVEDATE` or `PRINTDATE`
+    Date,
+    /// `HYPERLINK`: a link to a URL or a bookmark
+    Hyperlink,
+    /// `MERGEFIELD`: a mail merge field
+    MergeField,
+    /// `TOC`: a table of contents
+    Toc,
+    /// `DOCPROPERTY`: a document property
+    DocProperty,
+    /// `PAGE`: the current page number
+    Page,
+    /// `NUMPAGES`: the number of pages
+    NumPages,
+    /// Any other field
+    #[default]
+    Other,
+}
+
+impl FieldType {
+    /// The field type a field keyword names (case-insensitive).
+    pub fn from_keyword(keyword: &str) -> Self {
+        match keyword.to_ascii_uppercase().as_str() {
+            "REF" => Self::Ref,
+            "PAGEREF" => Self::PageRef,
+            "SEQ" => Self::Seq,
+            "DATE" | "TIME" | "CREATEDATE" | "SAVEDATE" | "PRINTDATE" => Self::Date,
+            "HYPERLINK" => Self::Hyperlink,
+            "MERGEFIELD" => Self::MergeField,
+            "TOC" => Self::Toc,
+            "DOCPROPERTY" => Self::DocProperty,
+            "PAGE" => Self::Page,
+            "NUMPAGES" => Self::NumPages,
+            _ => Self::Other,
+        }
+    }
+}
+
+/// A field: text Word computes from an instruction, such as a cross-reference, a page
+/// number or a date.
+///
+/// The field sits on the first run of its result ([`TextRun::field`](super::TextRun::field)),
+/// the text Word last computed for it, which stays in the paragraph as the document
+/// shows it. [`Paragraph::fields`](super::Paragraph::fields) and
+/// [`Document::fields`](super::Document::fields) list them.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
+pub struct Field {
+    /// Kind of field
+    #[serde(default)]
+    pub field_type: FieldType,
+
+    /// The field code, such as `REF _Ref12345 \h`
+    pub instruction: String,
+
+    /// How many runs the result has, this one first. 0 when the field has no result;
+    /// its run is then empty.
+    #[serde(default)]
+    pub result_runs: usize,
+}
+
+impl Field {
+    /// Create a field from its instruction, which names its type.
+    pub fn new(instruction: impl Into<String>) -> Self {
+        let instruction = instruction.into().trim().to_string();
+        let field_type =
+            FieldType::from_keyword(instruction.split_whitespace().next().unwrap_or_default());
+        Self {
+            field_type,
+            instruction,
+            result_runs: 0,
+        }
+    }
+
+    /// The words of the instruction after its keyword, quotes removed. Switches such as
+    /// `\h` are words of their own.
+    pub fn arguments(&self) -> Vec<String> {
+        let mut words = Vec::new();
+        let mut chars = self.instruction.chars().peekable();
+        while let Some(c) = chars.next() {
+            if c.is_whitespace() {
+                continue;
+            }
+            let mut word = String::new();
+            if c == '"' {
+                for c in chars.by_ref() {
+                    if c == '"' {
+                        break;
+                    }
+                    word.push(c);
+                }
+            } else {
+                word.push(c);
+                while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+                    word.push(c);
+                }
+            }
+            words.push(word);
+        }
+        words.into_iter().skip(1).collect()
+    }
+
+    /// Check if the instruction has a switch, such as `\h` (case-insensitive).
+    pub fn has_switch(&self, switch: &str) -> bool {
+        self.arguments()
+            .iter()
+            .any(|word| word.eq_ignore_ascii_case(switch))
+    }
+
+    /// The bookmark of this document a field refers to: the one a `REF` or `PAGEREF`
+    /// field names, or the one a `HYPERLINK` field without a URL leads to (`\l`).
+    pub fn bookmark(&self) -> Option<String> {
+        match self.field_type {
+            FieldType::Ref | FieldType::PageRef => self
+                .arguments()
+                .into_iter()
+                .next()
+                .filter(|word| !word.starts_with('\\')),
+            FieldType::Hyperlink => match self.hyperlink_parts() {
+                (None, location) => location,
+                (Some(_), _) => None,
+            },
+            _ => None,
+        }
+    }
+
+    /// Where a `HYPERLINK` field leads outside this document: its URL, followed by
+    /// `#location` when the field names a location in it (`\l`). A link to a bookmark
+    /// of this document has no URL; [`Self::bookmark`] names its target.
+    pub fn link_target(&self) -> Option<String> {
+        if self.field_type != FieldType::Hyperlink {
+            return None;
+        }
+        match self.hyperlink_parts() {
+            (Some(url), Some(location)) => Some(format!("{url}#{location}")),
+            (url, _) => url,
+        }
+    }
+
+    /// The URL and the location (`\l`) of a `HYPERLINK` instruction, either of which
+    /// may be missing.
+    fn hyperlink_parts(&self) -> (Option<String>, Option<String>) {
+        let mut url = None;
+        let mut location = None;
+        let mut words = self.arguments().into_iter();
+        while let Some(word) = words.next() {
+            if word.eq_ignore_ascii_case("\\l") {
+                location = words.next();
+            } else if word.eq_ignore_ascii_case("\\t") || word.eq_ignore_ascii_case("\\o") {
+                // Switches that take a value: a target frame and a screen tip
+                words.next();
+            } else if !word.starts_with('\\') {
+                url = url.or(Some(word));
+            }
+        }
+        (
+            url.filter(|url| !url.is_empty()),
+            location.filter(|location| !location.is_empty()),
+        )
+    }
+
+    /// Check if the field's result is only a page number, which means nothing outside
+    /// a paginated layout: `PAGE`, `NUMPAGES`, `SECTIONPAGES` and `PAGEREF`.
+    pub fn is_page_number(&self) -> bool {
+        matches!(
+            self.field_type,
+            FieldType::Page | FieldType::NumPages | FieldType::PageRef
+        ) || self
+            .instruction
+            .split_whitespace()
+            .next()
+            .is_some_and(|keyword| keyword.eq_ignore_ascii_case("SECTIONPAGES"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_field_instructions() {
+        let field = Field::new(r#" REF _Ref123 \h "#);
+        assert_eq!(field.field_type, FieldType::Ref);
+        assert_eq!(field.instruction, r#"REF _Ref123 \h"#);
+        assert_eq!(field.bookmark().as_deref(), Some("_Ref123"));
+        assert!(field.has_switch("\\H"));
+        assert!(!field.is_page_number());
+
+        let link = Field::new(r#"HYPERLINK "https://example.com/a b" \o "Tip""#);
+        assert_eq!(link.arguments(), ["https://example.com/a b", "\\o", "Tip"]);
+        assert_eq!(
+            link.link_target().as_deref(),
+            Some("https://example.com/a b")
+        );
+        let located = Field::new(r#"HYPERLINK "guide.docx" \l "_Toc1""#);
+        assert_eq!(located.link_target().as_deref(), Some("guide.docx#_Toc1"));
+        assert_eq!(located.bookmark(), None);
+        let internal = Field::new(r#"HYPERLINK \l "_Toc1""#);
+        assert_eq!(internal.link_target(), None);
+        assert_eq!(internal.bookmark().as_deref(), Some("_Toc1"));
+        let upper = Field::new(r#"HYPERLINK "guide.docx" \L "_Toc2" \O "Tip""#);
+        assert_eq!(upper.link_target().as_deref(), Some("guide.docx#_Toc2"));
+
+        assert_eq!(
+            Field::new("createdate \\@ \"yyyy\"").field_type,
+            FieldType::Date
+        );
+        assert!(Field::new("PAGEREF _Toc1 \\h").is_page_number());
+        assert!(Field::new("SECTIONPAGES").is_page_number());
+        assert_eq!(Field::new("QUOTE x").field_type, FieldType::Other);
+    }
+}
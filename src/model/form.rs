@@ -0,0 +1,105 @@
+//! Form field model.
+
+use serde::{Deserialize, Serialize};
+
+/// The kind of a form field.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum FormFieldType {
+    /// Plain text
+    Text,
+    /// Formatted text, which may span paragraphs
+    #[default]
+    RichText,
+    /// A list of choices that also takes typed text
+    ComboBox,
+    /// A list of choices
+    DropDown,
+    /// A date picker
+    Date,
+    /// A checkbox
+    Checkbox,
+    /// A picture
+    Picture,
+}
+
+/// A choice of a drop-down or combo box field.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
+pub struct FormFieldItem {
+    /// Text shown for the choice
+    pub display_text: String,
+
+    /// Value stored for the choice
+    pub value: String,
+}
+
+/// A form field: a DOCX content control (`w:sdt`) or legacy form field people fill in.
+///
+/// The field sits on the first run of its content
+/// ([`TextRun::form_field`](super::TextRun::form_field)); the content itself stays in
+/// the paragraphs as the document shows it. [`Document::form_fields`](super::Document::form_fields)
+/// lists every field of a document.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
+pub struct FormField {
+    /// Control ID (`w:id`)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+
+    /// Tag, the name a template gives the field for code to find it by
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tag: Option<String>,
+
+    /// Alias, the title shown to the person filling the form in
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+
+    /// Kind of field
+    #[serde(default)]
+    pub field_type: FormFieldType,
+
+    /// Current value: the text entered or the choice made, with a line break between
+    /// paragraphs. `None` while the field shows its placeholder, and for a checkbox.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+
+    /// Placeholder text the field shows while empty
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub placeholder: Option<String>,
+
+    /// Choices of a drop-down or combo box
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub items: Vec<FormFieldItem>,
+
+    /// Whether a checkbox is checked
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub checked: Option<bool>,
+
+    /// The date picked, as stored (ISO 8601)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub date: Option<String>,
+
+    /// Whether this is a legacy form field (`FORMTEXT`, `FORMCHECKBOX`, `FORMDROPDOWN`)
+    /// rather than a content control. Its tag is the field's bookmark name.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub legacy: bool,
+}
+
+impl FormField {
+    /// Check if this field is a checkbox.
+    pub fn is_checkbox(&self) -> bool {
+        self.field_type == FormFieldType::Checkbox
+    }
+
+    /// The state of a checkbox; `None` for other fields.
+    pub fn checkbox_state(&self) -> Option<bool> {
+        self.is_checkbox().then(|| self.checked == Some(true))
+    }
+
+    /// The name to key the field's value by: its tag, or else its alias.
+    pub fn name(&self) -> Option<&str> {
+        self.tag.as_deref().or(self.alias.as_deref())
+    }
+}
@@ -4,12 +4,18 @@
 //! in a format-agnostic way. Parsers convert format-specific XML into these structures,
 //! and renderers convert them to output formats like Markdown.
 
+mod comment;
 mod document;
+mod field;
+mod form;
 mod paragraph;
 mod resource;
 mod table;
 
+pub use comment::*;
 pub use document::*;
+pub use field::*;
+pub use form::*;
 pub use paragraph::*;
 pub use resource::*;
 pub use table::*;
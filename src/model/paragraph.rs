@@ -1,9 +1,11 @@
 //! Paragraph and text run models.
 
+use super::{Field, FormField};
 use serde::{Deserialize, Serialize};
 
 /// Text alignment within a paragraph.
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
 #[serde(rename_all = "lowercase")]
 pub enum TextAlignment {
     #[default]
@@ -15,6 +17,7 @@ pub enum TextAlignment {
 
 /// Heading level (h1-h6 or none).
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
 pub enum HeadingLevel {
     #[default]
     None,
@@ -61,6 +64,7 @@ impl HeadingLevel {
 
 /// List type for paragraphs.
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
 #[serde(rename_all = "lowercase")]
 pub enum ListType {
     #[default]
@@ -73,6 +77,7 @@ pub enum ListType {
 
 /// Revision type for tracked changes support.
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
 #[serde(rename_all = "lowercase")]
 pub enum RevisionType {
     /// Normal text (not a tracked change)
@@ -84,8 +89,20 @@ pub enum RevisionType {
     Deleted,
 }
 
+/// How an equation is set in the text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum MathMode {
+    /// Within the line, like a word (OMML `m:oMath`)
+    Inline,
+    /// On a line of its own (OMML `m:oMathPara`)
+    Display,
+}
+
 /// List information for a paragraph.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
 pub struct ListInfo {
     /// Type of list
     pub list_type: ListType,
@@ -98,6 +115,7 @@ pub struct ListInfo {
 
 /// Text style properties.
 #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
 pub struct TextStyle {
     /// Bold text
     #[serde(default, skip_serializing_if = "std::ops::Not::not")]
@@ -180,6 +198,7 @@ impl TextStyle {
 
 /// A run of text with consistent styling.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
 pub struct TextRun {
     /// The text content
     pub text: String,
@@ -203,6 +222,18 @@ pub struct TextRun {
     /// Revision type for tracked changes (inserted/deleted)
     #[serde(default, skip_serializing_if = "is_default_revision")]
     pub revision: RevisionType,
+
+    /// Set when the run is an equation, whose LaTeX source is then `text`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub math: Option<MathMode>,
+
+    /// The form field whose content starts with this run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub form_field: Option<Box<FormField>>,
+
+    /// The field whose result starts with this run
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub field: Option<Box<Field>>,
 }
 
 fn is_default_style(style: &TextStyle) -> bool {
@@ -223,6 +254,9 @@ impl TextRun {
             line_break: false,
             page_break: false,
             revision: RevisionType::None,
+            math: None,
+            form_field: None,
+            field: None,
         }
     }
 
@@ -235,6 +269,9 @@ impl TextRun {
             line_break: false,
             page_break: false,
             revision: RevisionType::None,
+            math: None,
+            form_field: None,
+            field: None,
         }
     }
 
@@ -247,6 +284,17 @@ impl TextRun {
             line_break: false,
             page_break: false,
             revision: RevisionType::None,
+            math: None,
+            form_field: None,
+            field: None,
+        }
+    }
+
+    /// Create an equation run from its LaTeX source.
+    pub fn math(latex: impl Into<String>, mode: MathMode) -> Self {
+        Self {
+            math: Some(mode),
+            ..Self::plain(latex)
         }
     }
 
@@ -255,14 +303,21 @@ impl TextRun {
         self.hyperlink.is_some()
     }
 
-    /// Check if this run is empty.
+    /// Check if this run is an equation.
+    pub fn is_math(&self) -> bool {
+        self.math.is_some()
+    }
+
+    /// Check if this run is empty. A run holding a form field is not, even when the
+    /// field has no text.
     pub fn is_empty(&self) -> bool {
-        self.text.is_empty()
+        self.text.is_empty() && self.form_field.is_none()
     }
 }
 
 /// An inline image within text.
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
 pub struct InlineImage {
     /// Resource ID for the image
     pub resource_id: String,
@@ -282,6 +337,7 @@ pub struct InlineImage {
 
 /// An element within a paragraph (text run or inline image).
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
 #[serde(untagged)]
 pub enum ParagraphElement {
     Text(TextRun),
@@ -290,6 +346,7 @@ pub enum ParagraphElement {
 
 /// A paragraph of text.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
 pub struct Paragraph {
     /// Text runs in this paragraph
     #[serde(default)]
@@ -322,6 +379,15 @@ pub struct Paragraph {
     /// Indentation level
     #[serde(default, skip_serializing_if = "is_zero")]
     pub indent_level: u8,
+
+    /// IDs of the review comments whose anchored range ends in this paragraph
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub comment_ids: Vec<String>,
+
+    /// Names of the bookmarks that start in this paragraph: the targets of `REF`,
+    /// `PAGEREF` and in-document `HYPERLINK` fields
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub bookmarks: Vec<String>,
 }
 
 fn is_default_alignment(a: &TextAlignment) -> bool {
@@ -391,6 +457,16 @@ impl Paragraph {
         self.images.is_empty() && (self.runs.is_empty() || self.runs.iter().all(|r| r.is_empty()))
     }
 
+    /// Get the form fields that start in this paragraph.
+    pub fn form_fields(&self) -> impl Iterator<Item = &FormField> {
+        self.runs.iter().filter_map(|run| run.form_field.as_deref())
+    }
+
+    /// Get the fields whose result starts in this paragraph.
+    pub fn fields(&self) -> impl Iterator<Item = &Field> {
+        self.runs.iter().filter_map(|run| run.field.as_deref())
+    }
+
     /// Check if this paragraph is a heading.
     pub fn is_heading(&self) -> bool {
         self.heading.is_heading()
@@ -416,17 +492,50 @@ impl Paragraph {
         }
 
         let mut merged: Vec<TextRun> = Vec::with_capacity(self.runs.len());
+        // Fields whose result is being read: where each is in `merged`, and how many
+        // of the runs to come are part of its result
+        let mut open_fields: Vec<(usize, usize)> = Vec::new();
+        // How many of them the last run kept is part of
+        let mut last_fields = 0;
 
         for run in self.runs.drain(..) {
+            open_fields.retain(|&(_, left)| left > 0);
+            // A field's result merges only within itself, and its first run holds the
+            // field, so it stays apart from what comes before.
+            let same_fields = run.field.is_none()
+                && open_fields.len() == last_fields
+                && merged.last().is_some_and(|last| {
+                    last.field.is_none()
+                        || open_fields.last().map(|&(at, _)| at) == Some(merged.len() - 1)
+                });
             // Check if we can merge with the last run
-            let should_merge = merged.last().is_some_and(|last: &TextRun| {
-                // Same style and same hyperlink (both None or both Some with same URL)
-                // Don't merge if the previous run has a line break (preserve the break)
-                last.style == run.style
-                    && last.hyperlink == run.hyperlink
-                    && !last.line_break
-                    && !last.page_break
-            });
+            let should_merge = same_fields
+                && merged.last().is_some_and(|last: &TextRun| {
+                    // Same style, same hyperlink (both None or both Some with same URL) and
+                    // same revision (a tracked change must stay separable from its context)
+                    // Don't merge if the previous run has a line break (preserve the break)
+                    // Equations stay whole: each one is a formula of its own.
+                    // A form field's run marks where the field starts, so it stays apart.
+                    last.style == run.style
+                        && last.hyperlink == run.hyperlink
+                        && last.revision == run.revision
+                        && last.math.is_none()
+                        && run.math.is_none()
+                        && last.form_field.is_none()
+                        && run.form_field.is_none()
+                        && !last.line_break
+                        && !last.page_break
+                });
+
+            for (at, left) in open_fields.iter_mut() {
+                *left -= 1;
+                if should_merge {
+                    if let Some(field) = merged[*at].field.as_mut() {
+                        field.result_runs -= 1;
+                    }
+                }
+            }
+            let result_runs = run.field.as_ref().map_or(0, |field| field.result_runs);
 
             if should_merge {
                 // Merge text with the last run, with smart spacing
@@ -448,7 +557,11 @@ impl Paragraph {
             } else {
                 // Start a new run
                 merged.push(run);
+                if result_runs > 0 {
+                    open_fields.push((merged.len() - 1, result_runs - 1));
+                }
             }
+            last_fields = open_fields.len();
         }
 
         self.runs = merged;
@@ -632,6 +745,47 @@ mod tests {
         assert_eq!(para.runs[0].text, "TCP"); // NOT "T CP"
     }
 
+    #[test]
+    fn test_merge_adjacent_runs_keeps_tracked_changes_apart() {
+        // A deletion merged into the text around it could no longer be hidden.
+        let mut para = Paragraph::new();
+        para.runs.push(TextRun::plain("keep"));
+        let mut deleted = TextRun::plain("old");
+        deleted.revision = RevisionType::Deleted;
+        para.runs.push(deleted);
+        para.merge_adjacent_runs();
+
+        assert_eq!(para.runs.len(), 2);
+        assert_eq!(para.runs[1].revision, RevisionType::Deleted);
+    }
+
+    #[test]
+    fn test_merge_adjacent_runs_keeps_field_results_apart() {
+        let field = |instruction: &str, result_runs: usize, text: &str| TextRun {
+            field: Some(Box::new(Field {
+                result_runs,
+                ..Field::new(instruction)
+            })),
+            ..TextRun::plain(text)
+        };
+        // "See " [HYPERLINK: "Intro" [PAGEREF: "3"] "."] " here"
+        let mut para = Paragraph::new();
+        para.runs = vec![
+            TextRun::plain("See "),
+            field("HYPERLINK \\l _Toc1", 4, "Intro"),
+            TextRun::plain(" on"),
+            field("PAGEREF _Toc1", 1, "3"),
+            TextRun::plain("."),
+            TextRun::plain(" here"),
+        ];
+        para.merge_adjacent_runs();
+
+        let texts: Vec<_> = para.runs.iter().map(|run| run.text.as_str()).collect();
+        assert_eq!(texts, ["See ", "Intro on", "3", ".", " here"]);
+        let counts: Vec<_> = para.fields().map(|field| field.result_runs).collect();
+        assert_eq!(counts, [3, 1]);
+    }
+
     #[test]
     fn test_merge_adjacent_runs_cjk_ascii_no_space() {
         // Same-style runs merge WITHOUT space - even across script boundaries
@@ -1,9 +1,12 @@
 //! Resource (image, media) model structures.
 
-use serde::{Deserialize, Serialize};
+use base64::Engine;
+use serde::{Deserialize, Deserializer, Serialize};
+use std::collections::HashSet;
 
 /// Type of resource.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
 #[serde(rename_all = "lowercase")]
 pub enum ResourceType {
     /// Image (PNG, JPEG, GIF, BMP, TIFF, WMF, EMF)
@@ -54,6 +57,7 @@ impl ResourceType {
 
 /// A binary resource (image, media file, etc.).
 #[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
 pub struct Resource {
     /// Resource type
     pub resource_type: ResourceType,
@@ -66,8 +70,9 @@ pub struct Resource {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub mime_type: Option<String>,
 
-    /// Binary data
-    #[serde(skip)]
+    /// Binary data. Serialization leaves it out; JSON written with
+    /// `JsonResources::Base64` carries it base64-encoded, and that is read back.
+    #[serde(skip_serializing, default, deserialize_with = "from_base64")]
     pub data: Vec<u8>,
 
     /// Size in bytes
@@ -86,6 +91,14 @@ pub struct Resource {
     pub alt_text: Option<String>,
 }
 
+/// Decode the base64 `data` of a resource.
+fn from_base64<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+    let encoded = String::deserialize(deserializer)?;
+    base64::engine::general_purpose::STANDARD
+        .decode(encoded)
+        .map_err(serde::de::Error::custom)
+}
+
 impl Resource {
     /// Create a new resource.
     pub fn new(resource_type: ResourceType, data: Vec<u8>) -> Self {
@@ -214,6 +227,44 @@ impl Resource {
     }
 }
 
+/// A file name for a resource made safe for a package part name and unique among
+/// `taken`, which compares case-insensitively.
+pub(crate) fn unique_file_name(suggested: &str, taken: &mut HashSet<String>) -> String {
+    let base = std::path::Path::new(suggested)
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let base: String = base
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '.' | '-' | '_') {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    let (stem, ext) = match base.rsplit_once('.') {
+        Some((stem, ext)) if !stem.is_empty() => (stem.to_string(), format!(".{ext}")),
+        _ => (
+            if base.is_empty() {
+                "image".into()
+            } else {
+                base
+            },
+            String::new(),
+        ),
+    };
+
+    let mut name = format!("{stem}{ext}");
+    let mut n = 1;
+    while !taken.insert(name.to_lowercase()) {
+        n += 1;
+        name = format!("{stem}_{n}{ext}");
+    }
+    name
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
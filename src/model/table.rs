@@ -5,6 +5,7 @@ use serde::{Deserialize, Serialize};
 
 /// Horizontal alignment for table cells.
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
 #[serde(rename_all = "lowercase")]
 pub enum CellAlignment {
     #[default]
@@ -15,6 +16,7 @@ pub enum CellAlignment {
 
 /// Vertical alignment for table cells.
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
 #[serde(rename_all = "lowercase")]
 pub enum VerticalAlignment {
     #[default]
@@ -25,6 +27,7 @@ pub enum VerticalAlignment {
 
 /// A cell in a table.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
 pub struct Cell {
     /// Cell content (paragraphs)
     #[serde(default)]
@@ -57,6 +60,11 @@ pub struct Cell {
     /// Background color (hex)
     #[serde(skip_serializing_if = "Option::is_none")]
     pub background: Option<String>,
+
+    /// The value a spreadsheet cell stores, when it is displayed differently —
+    /// the serial number behind a date-formatted cell, for example.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
 }
 
 fn default_span() -> u32 {
@@ -138,6 +146,7 @@ impl Cell {
 
 /// A row in a table.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
 pub struct Row {
     /// Cells in this row
     #[serde(default)]
@@ -190,6 +199,7 @@ impl Row {
 
 /// A table.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(test, derive(schemars::JsonSchema))]
 pub struct Table {
     /// Rows in this table
     #[serde(default)]
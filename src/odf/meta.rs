@@ -0,0 +1,88 @@
+//! Document metadata from `meta.xml`.
+
+use crate::model::Metadata;
+
+use super::xml::Element;
+
+/// Read the `office:meta` element of `meta.xml` into `Metadata`.
+///
+/// ODF records both the original author (`meta:initial-creator`) and the last person to
+/// save the document (`dc:creator`); the first maps to `author`, the second to
+/// `last_modified_by`, and `author` falls back to `dc:creator` when only it is present.
+pub(crate) fn read_metadata(root: &Element) -> Metadata {
+    let mut meta = Metadata::default();
+    let Some(office_meta) = root.child("office:meta") else {
+        return meta;
+    };
+
+    let text_of = |name: &str| {
+        office_meta
+            .child(name)
+            .map(|e| e.text().trim().to_string())
+            .filter(|text| !text.is_empty())
+    };
+
+    meta.title = text_of("dc:title");
+    meta.subject = text_of("dc:subject");
+    meta.description = text_of("dc:description");
+    meta.last_modified_by = text_of("dc:creator");
+    meta.author = text_of("meta:initial-creator").or_else(|| meta.last_modified_by.clone());
+    meta.created = text_of("meta:creation-date");
+    meta.modified = text_of("dc:date");
+    meta.application = text_of("meta:generator");
+    meta.keywords = office_meta
+        .children()
+        .filter(|e| e.name == "meta:keyword")
+        .flat_map(|e| {
+            e.text()
+                .split([',', ';'])
+                .map(|k| k.trim().to_string())
+                .collect::<Vec<_>>()
+        })
+        .filter(|k| !k.is_empty())
+        .collect();
+
+    if let Some(stats) = office_meta.child("meta:document-statistic") {
+        let count = |name: &str| stats.attr(name).and_then(|v| v.parse().ok());
+        meta.page_count = count("meta:page-count");
+        meta.word_count = count("meta:word-count");
+    }
+    meta
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::odf::xml;
+
+    #[test]
+    fn test_meta_fields() {
+        let xml = r#"<office:document-meta
+            xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0"
+            xmlns:meta="urn:oasis:names:tc:opendocument:xmlns:meta:1.0"
+            xmlns:dc="http://purl.org/dc/elements/1.1/">
+          <office:meta>
+            <meta:generator>LibreOffice/7.6</meta:generator>
+            <dc:title>Quarterly report</dc:title>
+            <meta:initial-creator>Ada</meta:initial-creator>
+            <dc:creator>Grace</dc:creator>
+            <meta:creation-date>2024-01-02T03:04:05</meta:creation-date>
+            <dc:date>2024-02-03T04:05:06</dc:date>
+            <meta:keyword>finance, q1</meta:keyword>
+            <meta:keyword>draft</meta:keyword>
+            <meta:document-statistic meta:page-count="3" meta:word-count="512"/>
+          </office:meta>
+        </office:document-meta>"#;
+        let meta = read_metadata(&xml::parse(xml, "meta.xml").unwrap());
+
+        assert_eq!(meta.title.as_deref(), Some("Quarterly report"));
+        assert_eq!(meta.author.as_deref(), Some("Ada"));
+        assert_eq!(meta.last_modified_by.as_deref(), Some("Grace"));
+        assert_eq!(meta.created.as_deref(), Some("2024-01-02T03:04:05"));
+        assert_eq!(meta.modified.as_deref(), Some("2024-02-03T04:05:06"));
+        assert_eq!(meta.application.as_deref(), Some("LibreOffice/7.6"));
+        assert_eq!(meta.keywords, vec!["finance", "q1", "draft"]);
+        assert_eq!(meta.page_count, Some(3));
+        assert_eq!(meta.word_count, Some(512));
+    }
+}
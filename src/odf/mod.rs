@@ -0,0 +1,87 @@
+//! OpenDocument (ODF) package plumbing shared by the ODT, ODS and ODP parsers.
+//!
+//! An OpenDocument file is a ZIP package like an OOXML one, with a fixed layout in place
+//! of relationships: `content.xml` holds the body and its automatic styles, `styles.xml`
+//! the named styles and page masters, `meta.xml` the metadata, and pictures sit under
+//! `Pictures/`. The package is read through [`OoxmlContainer`], which knows nothing
+//! OOXML-specific about plain entries.
+
+mod meta;
+mod styles;
+mod text;
+mod xml;
+
+pub(crate) use styles::StyleSheet;
+#[cfg(feature = "odp")]
+pub(crate) use text::length_to_emu;
+pub(crate) use text::TextReader;
+pub(crate) use xml::Element;
+
+use crate::container::OoxmlContainer;
+use crate::error::{Error, Result};
+use crate::model::{Document, Metadata, Resource};
+
+/// The parsed parts of an OpenDocument package.
+pub(crate) struct Package {
+    container: OoxmlContainer,
+    /// Root of `content.xml`.
+    pub content: Element,
+    /// Root of `styles.xml`, empty when the part is absent.
+    #[cfg_attr(not(feature = "odt"), allow(dead_code))]
+    pub styles_part: Element,
+    /// Styles of `styles.xml` overlaid with the automatic styles of `content.xml`.
+    pub styles: StyleSheet,
+    pub metadata: Metadata,
+}
+
+impl Package {
+    /// Read the parts every OpenDocument parser needs.
+    pub fn read(container: OoxmlContainer) -> Result<Self> {
+        // Encrypted entries are still listed under their plain names; reading them as
+        // XML would report a damaged part instead of the password it needs.
+        if let Some(manifest) = container.read_xml_optional("META-INF/manifest.xml")? {
+            if manifest.contains("encryption-data") {
+                return Err(Error::Encrypted);
+            }
+        }
+
+        let content = xml::parse(&container.read_xml("content.xml")?, "content.xml")?;
+        let styles_part = match container.read_xml_optional("styles.xml")? {
+            Some(part) => xml::parse(&part, "styles.xml")?,
+            None => Element::default(),
+        };
+        let metadata = match container.read_xml_optional("meta.xml")? {
+            Some(part) => meta::read_metadata(&xml::parse(&part, "meta.xml")?),
+            None => Metadata::default(),
+        };
+
+        let mut styles = StyleSheet::default();
+        styles.add_part(&styles_part);
+        styles.add_part(&content);
+
+        Ok(Self {
+            container,
+            content,
+            styles_part,
+            styles,
+            metadata,
+        })
+    }
+
+    /// The element under `office:body` that holds the document, such as `office:text`.
+    pub fn body(&self, name: &str) -> Result<&Element> {
+        self.content
+            .path(&["office:body", name])
+            .ok_or_else(|| Error::MissingComponent(format!("content.xml {}", name)))
+    }
+
+    /// Add the pictures a reader referenced to the document, keyed by resource id.
+    /// A picture missing from the package is left out rather than failing the parse.
+    pub fn add_images(&self, images: &[(String, String)], doc: &mut Document) {
+        for (id, path) in images {
+            if let Ok(data) = self.container.read_binary(path) {
+                doc.add_resource(id.clone(), Resource::image(data, Some(id.clone())));
+            }
+        }
+    }
+}
@@ -0,0 +1,364 @@
+//! OpenDocument styles: paragraph and text formatting, outline levels, list styles.
+//!
+//! Formatting in ODF lives almost entirely in styles. A paragraph or span names a style
+//! (usually an automatic one in `content.xml`), which names a parent (a common style in
+//! `styles.xml`), and so on up to the family's default style; a property is whatever
+//! the nearest style in that chain sets.
+
+use std::collections::HashMap;
+
+use crate::model::{ListType, TextAlignment, TextStyle};
+
+use super::xml::Element;
+
+/// Guard against a parent chain that loops.
+const MAX_INHERITANCE_DEPTH: usize = 32;
+
+/// The properties of one style that the model can carry. `None` is "inherited".
+#[derive(Debug, Clone, Default)]
+struct Style {
+    parent: Option<String>,
+    display_name: Option<String>,
+    /// Defined in `office:automatic-styles`: direct formatting rather than a named style.
+    automatic: bool,
+    outline_level: Option<u8>,
+    alignment: Option<TextAlignment>,
+    bold: Option<bool>,
+    italic: Option<bool>,
+    underline: Option<bool>,
+    strikethrough: Option<bool>,
+    /// `Some(true)` for superscript, `Some(false)` for subscript.
+    raised: Option<Option<bool>>,
+    font: Option<String>,
+    /// Half-points, as `TextStyle.size` is for DOCX.
+    size: Option<u32>,
+    color: Option<String>,
+}
+
+/// How one level of a list style marks its items.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct ListLevel {
+    pub list_type: ListType,
+    pub start: u32,
+}
+
+/// The styles of a document, keyed by family and name.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct StyleSheet {
+    styles: HashMap<(String, String), Style>,
+    defaults: HashMap<String, Style>,
+    lists: HashMap<String, HashMap<u8, ListLevel>>,
+}
+
+impl StyleSheet {
+    /// Add the styles of a part: its `office:styles`, `office:automatic-styles` and
+    /// `office:master-styles` children. A later part overrides a same-named style.
+    pub fn add_part(&mut self, root: &Element) {
+        for group in root.children() {
+            let automatic = match group.name.as_str() {
+                "office:styles" => false,
+                "office:automatic-styles" => true,
+                _ => continue,
+            };
+            for e in group.children() {
+                self.add_style(e, automatic);
+            }
+        }
+    }
+
+    fn add_style(&mut self, e: &Element, automatic: bool) {
+        match e.name.as_str() {
+            "style:style" => {
+                let (Some(family), Some(name)) = (e.attr("style:family"), e.attr("style:name"))
+                else {
+                    return;
+                };
+                let style = Style {
+                    automatic,
+                    ..read_style(e)
+                };
+                self.styles
+                    .insert((family.to_string(), name.to_string()), style);
+            }
+            "style:default-style" => {
+                if let Some(family) = e.attr("style:family") {
+                    self.defaults.insert(family.to_string(), read_style(e));
+                }
+            }
+            "text:list-style" => {
+                if let Some(name) = e.attr("style:name") {
+                    self.lists.insert(name.to_string(), read_list_style(e));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// The chain from the named style up through its parents, then the default style.
+    fn chain<'a>(&'a self, family: &str, name: Option<&str>) -> Vec<&'a Style> {
+        let mut chain = Vec::new();
+        let mut next = name.map(str::to_string);
+        while let Some(name) = next.take() {
+            if chain.len() >= MAX_INHERITANCE_DEPTH {
+                break;
+            }
+            if let Some(style) = self.styles.get(&(family.to_string(), name)) {
+                chain.push(style);
+                next = style.parent.clone();
+            }
+        }
+        chain.extend(self.defaults.get(family));
+        chain
+    }
+
+    fn resolve<T: Clone>(
+        &self,
+        family: &str,
+        name: Option<&str>,
+        get: impl Fn(&Style) -> Option<T>,
+    ) -> Option<T> {
+        self.chain(family, name).into_iter().find_map(get)
+    }
+
+    /// Character formatting of a text or paragraph style, applied on top of `base`.
+    pub fn text_style(&self, family: &str, name: Option<&str>, base: &TextStyle) -> TextStyle {
+        let chain = self.chain(family, name);
+        let pick = |get: &dyn Fn(&Style) -> Option<bool>, inherited: bool| {
+            chain.iter().find_map(|s| get(s)).unwrap_or(inherited)
+        };
+        let raised = chain.iter().find_map(|s| s.raised);
+        TextStyle {
+            bold: pick(&|s| s.bold, base.bold),
+            italic: pick(&|s| s.italic, base.italic),
+            underline: pick(&|s| s.underline, base.underline),
+            strikethrough: pick(&|s| s.strikethrough, base.strikethrough),
+            superscript: raised.map_or(base.superscript, |r| r == Some(true)),
+            subscript: raised.map_or(base.subscript, |r| r == Some(false)),
+            code: base.code,
+            font: chain
+                .iter()
+                .find_map(|s| s.font.clone())
+                .or_else(|| base.font.clone()),
+            size: chain.iter().find_map(|s| s.size).or(base.size),
+            color: chain
+                .iter()
+                .find_map(|s| s.color.clone())
+                .or_else(|| base.color.clone()),
+            highlight: base.highlight.clone(),
+        }
+    }
+
+    /// Paragraph alignment of a paragraph style.
+    pub fn alignment(&self, name: Option<&str>) -> TextAlignment {
+        self.resolve("paragraph", name, |s| s.alignment)
+            .unwrap_or_default()
+    }
+
+    /// Outline level a paragraph style gives its paragraphs, making them headings.
+    pub fn outline_level(&self, name: Option<&str>) -> Option<u8> {
+        self.resolve("paragraph", name, |s| s.outline_level)
+    }
+
+    /// The named style behind a style and its display name, for `Paragraph.style_id`
+    /// and `style_name`. Automatic styles are skipped in favour of their parent.
+    pub fn named_style(&self, family: &str, name: &str) -> Option<(String, String)> {
+        let mut name = name.to_string();
+        for _ in 0..MAX_INHERITANCE_DEPTH {
+            let Some(style) = self.styles.get(&(family.to_string(), name.clone())) else {
+                break;
+            };
+            if !style.automatic {
+                let display = style
+                    .display_name
+                    .clone()
+                    .unwrap_or_else(|| name.replace("_20_", " "));
+                return Some((name, display));
+            }
+            name = style.parent.clone()?;
+        }
+        let display = name.replace("_20_", " ");
+        Some((name, display))
+    }
+
+    /// Marker of a list level; bullets when the list style is unknown.
+    pub fn list_level(&self, list_style: Option<&str>, level: u8) -> ListLevel {
+        list_style
+            .and_then(|name| self.lists.get(name))
+            .and_then(|levels| levels.get(&(level + 1)))
+            .copied()
+            .unwrap_or(ListLevel {
+                list_type: ListType::Bullet,
+                start: 1,
+            })
+    }
+}
+
+fn read_style(e: &Element) -> Style {
+    let mut style = Style {
+        parent: e.attr("style:parent-style-name").map(str::to_string),
+        display_name: e.attr("style:display-name").map(str::to_string),
+        outline_level: e
+            .attr("style:default-outline-level")
+            .and_then(|v| v.parse().ok())
+            .filter(|&level| level > 0),
+        ..Style::default()
+    };
+
+    if let Some(props) = e.child("style:paragraph-properties") {
+        style.alignment = props.attr("fo:text-align").and_then(|align| match align {
+            "start" | "left" => Some(TextAlignment::Left),
+            "center" => Some(TextAlignment::Center),
+            "end" | "right" => Some(TextAlignment::Right),
+            "justify" => Some(TextAlignment::Justify),
+            _ => None,
+        });
+    }
+
+    if let Some(props) = e.child("style:text-properties") {
+        style.bold = props.attr("fo:font-weight").map(|weight| match weight {
+            "bold" | "bolder" => true,
+            other => other.parse::<u32>().is_ok_and(|w| w >= 600),
+        });
+        style.italic = props
+            .attr("fo:font-style")
+            .map(|s| matches!(s, "italic" | "oblique"));
+        style.underline = props
+            .attr("style:text-underline-style")
+            .map(|s| s != "none");
+        style.strikethrough = props
+            .attr("style:text-line-through-style")
+            .map(|s| s != "none");
+        style.raised = props.attr("style:text-position").map(text_position);
+        style.font = props
+            .attr("style:font-name")
+            .or_else(|| props.attr("fo:font-family"))
+            .map(|f| f.trim_matches(['\'', '"']).to_string());
+        style.size = props.attr("fo:font-size").and_then(half_points);
+        style.color = props
+            .attr("fo:color")
+            .map(|c| c.trim_start_matches('#').to_ascii_uppercase());
+    }
+    style
+}
+
+/// `style:text-position`: `super`/`sub`, or a signed percentage, then a font size.
+fn text_position(value: &str) -> Option<bool> {
+    let offset = value.split_whitespace().next()?;
+    match offset {
+        "super" => Some(true),
+        "sub" => Some(false),
+        _ => {
+            let percent: f64 = offset.trim_end_matches('%').parse().ok()?;
+            (percent != 0.0).then_some(percent > 0.0)
+        }
+    }
+}
+
+/// Font size in half-points, from a point size; relative sizes are not resolved.
+fn half_points(value: &str) -> Option<u32> {
+    let points: f64 = value.strip_suffix("pt")?.trim().parse().ok()?;
+    Some((points * 2.0).round() as u32)
+}
+
+fn read_list_style(e: &Element) -> HashMap<u8, ListLevel> {
+    e.children()
+        .filter_map(|level| {
+            let number: u8 = level.attr("text:level")?.parse().ok()?;
+            let list_type = match level.name.as_str() {
+                // An empty format is a numbered level that shows no number.
+                "text:list-level-style-number"
+                    if level
+                        .attr("style:num-format")
+                        .is_some_and(|f| !f.is_empty()) =>
+                {
+                    ListType::Numbered
+                }
+                _ => ListType::Bullet,
+            };
+            let start = level
+                .attr("text:start-value")
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(1);
+            Some((number, ListLevel { list_type, start }))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::odf::xml;
+
+    fn sheet(styles: &str) -> StyleSheet {
+        let xml = format!(
+            r#"<office:document-styles
+                xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0"
+                xmlns:style="urn:oasis:names:tc:opendocument:xmlns:style:1.0"
+                xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0"
+                xmlns:fo="urn:oasis:names:tc:opendocument:xmlns:xsl-fo-compatible:1.0">
+                <office:styles>{styles}</office:styles>
+            </office:document-styles>"#
+        );
+        let mut sheet = StyleSheet::default();
+        sheet.add_part(&xml::parse(&xml, "styles.xml").unwrap());
+        sheet
+    }
+
+    #[test]
+    fn test_properties_are_inherited_from_parents() {
+        let sheet = sheet(
+            r#"<style:style style:name="Heading" style:family="paragraph">
+                 <style:text-properties fo:font-weight="bold" fo:font-size="14pt"/>
+               </style:style>
+               <style:style style:name="Heading_20_2" style:display-name="Heading 2"
+                   style:family="paragraph" style:parent-style-name="Heading"
+                   style:default-outline-level="2">
+                 <style:paragraph-properties fo:text-align="center"/>
+                 <style:text-properties fo:font-style="italic"/>
+               </style:style>"#,
+        );
+
+        let style = sheet.text_style("paragraph", Some("Heading_20_2"), &TextStyle::default());
+        assert!(style.bold && style.italic);
+        assert_eq!(style.size, Some(28));
+        assert_eq!(sheet.outline_level(Some("Heading_20_2")), Some(2));
+        assert_eq!(sheet.alignment(Some("Heading_20_2")), TextAlignment::Center);
+        assert_eq!(
+            sheet.named_style("paragraph", "Heading_20_2"),
+            Some(("Heading_20_2".to_string(), "Heading 2".to_string()))
+        );
+    }
+
+    #[test]
+    fn test_a_style_can_switch_inherited_formatting_off() {
+        let sheet = sheet(
+            r#"<style:style style:name="T1" style:family="text">
+                 <style:text-properties fo:font-weight="normal" style:text-position="-33% 58%"/>
+               </style:style>"#,
+        );
+        let style = sheet.text_style("text", Some("T1"), &TextStyle::bold());
+        assert!(!style.bold);
+        assert!(style.subscript && !style.superscript);
+    }
+
+    #[test]
+    fn test_list_levels() {
+        let sheet = sheet(
+            r#"<text:list-style style:name="L1">
+                 <text:list-level-style-bullet text:level="1"/>
+                 <text:list-level-style-number text:level="2" style:num-format="1" text:start-value="3"/>
+                 <text:list-level-style-number text:level="3" style:num-format=""/>
+               </text:list-style>"#,
+        );
+        assert_eq!(sheet.list_level(Some("L1"), 0).list_type, ListType::Bullet);
+        assert_eq!(
+            sheet.list_level(Some("L1"), 1),
+            ListLevel {
+                list_type: ListType::Numbered,
+                start: 3
+            }
+        );
+        assert_eq!(sheet.list_level(Some("L1"), 2).list_type, ListType::Bullet);
+        assert_eq!(sheet.list_level(None, 0).list_type, ListType::Bullet);
+    }
+}
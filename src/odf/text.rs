@@ -0,0 +1,685 @@
+//! Text content: paragraphs, headings, lists, tables, notes and frames.
+//!
+//! The same elements make up the body of a text document, the cells of a spreadsheet
+//! and the text boxes of a presentation, so one reader serves all three.
+
+use std::collections::HashMap;
+
+use crate::model::{
+    Block, Cell, HeadingLevel, InlineImage, ListInfo, ListType, Paragraph, Row, Table, TextRun,
+    TextStyle,
+};
+
+use super::styles::StyleSheet;
+use super::xml::{Element, Node};
+
+/// Converts ODF text elements into model blocks.
+pub(crate) struct TextReader<'a> {
+    styles: &'a StyleSheet,
+    /// Footnote and endnote definitions in citation order, as `(label, text)`.
+    notes: Vec<(String, String)>,
+    footnote_count: usize,
+    endnote_count: usize,
+    /// Pictures referenced so far, as `(resource id, path in the package)`.
+    images: Vec<(String, String)>,
+    /// Last item number of each list style, for lists that continue numbering.
+    list_numbers: HashMap<String, u32>,
+}
+
+impl<'a> TextReader<'a> {
+    pub fn new(styles: &'a StyleSheet) -> Self {
+        Self {
+            styles,
+            notes: Vec::new(),
+            footnote_count: 0,
+            endnote_count: 0,
+            images: Vec::new(),
+            list_numbers: HashMap::new(),
+        }
+    }
+
+    /// Note definitions collected so far, as `(label, text)`.
+    #[cfg_attr(not(feature = "odt"), allow(dead_code))]
+    pub fn notes(&self) -> &[(String, String)] {
+        &self.notes
+    }
+
+    /// Pictures referenced so far, as `(resource id, path in the package)`.
+    pub fn images(&self) -> &[(String, String)] {
+        &self.images
+    }
+
+    /// Convert the block-level children of an element.
+    pub fn blocks(&mut self, parent: &Element) -> Vec<Block> {
+        let mut out = Vec::new();
+        for e in parent.children() {
+            self.block(e, &mut out);
+        }
+        out
+    }
+
+    /// The paragraphs among the blocks of an element, for headers, footers and notes.
+    pub fn paragraphs(&mut self, parent: &Element) -> Vec<Paragraph> {
+        self.blocks(parent)
+            .into_iter()
+            .filter_map(|block| match block {
+                Block::Paragraph(p) => Some(p),
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn block(&mut self, e: &Element, out: &mut Vec<Block>) {
+        match e.name.as_str() {
+            "text:p" | "text:h" => {
+                let mut extra = Vec::new();
+                let para = self.paragraph(e, &mut extra);
+                if !para.is_empty() || !para.images.is_empty() {
+                    out.push(Block::Paragraph(para));
+                }
+                out.extend(extra);
+            }
+            "text:list" => self.list(e, 0, None, out),
+            "table:table" => out.push(Block::Table(self.table(e))),
+            "text:soft-page-break" => {}
+            // Deleted text of tracked changes lives here; skipping it yields the
+            // document as it would read with all changes accepted.
+            "text:tracked-changes"
+            | "office:forms"
+            | "text:sequence-decls"
+            | "text:variable-decls"
+            | "text:user-field-decls"
+            | "table:table-source"
+            | "office:annotation" => {}
+            "draw:frame" => {
+                let mut para = Paragraph::new();
+                self.frame(e, &mut para, out);
+                if !para.images.is_empty() {
+                    out.push(Block::Paragraph(para));
+                }
+            }
+            // Sections, indexes, shapes and other containers: their content counts.
+            _ => {
+                for child in e.children() {
+                    self.block(child, out);
+                }
+            }
+        }
+    }
+
+    /// Convert a `text:p` or `text:h`. Text boxes anchored in it go to `extra`.
+    fn paragraph(&mut self, e: &Element, extra: &mut Vec<Block>) -> Paragraph {
+        let style_name = e.attr("text:style-name");
+        let mut para = Paragraph::new();
+        para.alignment = self.styles.alignment(style_name);
+        if let Some((id, name)) = style_name.and_then(|s| self.styles.named_style("paragraph", s)) {
+            para.style_id = Some(id);
+            para.style_name = Some(name);
+        }
+
+        let base = if e.name == "text:h" {
+            let level = e
+                .attr("text:outline-level")
+                .and_then(|v| v.parse().ok())
+                .or_else(|| self.styles.outline_level(style_name))
+                .unwrap_or(1);
+            para.heading = HeadingLevel::from_number(level);
+            // A heading style's own emphasis is how the level looks, not content.
+            TextStyle::default()
+        } else {
+            self.styles
+                .text_style("paragraph", style_name, &TextStyle::default())
+        };
+
+        let mut runs = Runs::new(para);
+        self.inline(&e.nodes, &base, None, &mut runs, extra);
+        runs.finish()
+    }
+
+    fn inline(
+        &mut self,
+        nodes: &[Node],
+        style: &TextStyle,
+        link: Option<&str>,
+        runs: &mut Runs,
+        extra: &mut Vec<Block>,
+    ) {
+        for node in nodes {
+            let e = match node {
+                Node::Text(text) => {
+                    runs.text(text, style, link);
+                    continue;
+                }
+                Node::Element(e) => e,
+            };
+            match e.name.as_str() {
+                "text:span" => {
+                    let style = self
+                        .styles
+                        .text_style("text", e.attr("text:style-name"), style);
+                    self.inline(&e.nodes, &style, link, runs, extra);
+                }
+                "text:a" => {
+                    let href = e.attr("xlink:href").filter(|h| !h.is_empty()).or(link);
+                    self.inline(&e.nodes, style, href, runs, extra);
+                }
+                "text:s" => {
+                    let count = e
+                        .attr("text:c")
+                        .and_then(|c| c.parse::<usize>().ok())
+                        .unwrap_or(1);
+                    runs.literal(&" ".repeat(count.min(1024)), style, link);
+                }
+                "text:tab" => runs.literal("\t", style, link),
+                "text:line-break" => runs.line_break(style),
+                "text:note" => {
+                    let label = self.note(e);
+                    runs.literal(&format!("[^{}]", label), style, None);
+                }
+                "draw:frame" => self.frame(e, &mut runs.para, extra),
+                // Comments, bookmarks, change marks and ruby annotations carry no body text.
+                "office:annotation"
+                | "office:annotation-end"
+                | "text:bookmark"
+                | "text:bookmark-start"
+                | "text:bookmark-end"
+                | "text:reference-mark"
+                | "text:reference-mark-start"
+                | "text:reference-mark-end"
+                | "text:change"
+                | "text:change-start"
+                | "text:change-end"
+                | "text:soft-page-break"
+                | "text:ruby-text" => {}
+                name if name.starts_with("draw:") && name != "draw:a" => {
+                    // Shapes hold their text as paragraphs of their own.
+                    extra.extend(self.blocks(e));
+                }
+                // Fields, `draw:a`, `text:ruby-base` and the like: keep their text.
+                _ => self.inline(&e.nodes, style, link, runs, extra),
+            }
+        }
+    }
+
+    /// Record a footnote or endnote and return its label: `1`, `2`, ... for footnotes
+    /// and `e1`, `e2`, ... for endnotes, the markers the Word parsers emit.
+    fn note(&mut self, e: &Element) -> String {
+        let label = if e.attr("text:note-class") == Some("endnote") {
+            self.endnote_count += 1;
+            format!("e{}", self.endnote_count)
+        } else {
+            self.footnote_count += 1;
+            self.footnote_count.to_string()
+        };
+        let text = match e.child("text:note-body") {
+            Some(body) => self
+                .paragraphs(body)
+                .iter()
+                .map(Paragraph::plain_text)
+                .collect::<Vec<_>>()
+                .join(" "),
+            None => String::new(),
+        };
+        self.notes.push((label.clone(), text.trim().to_string()));
+        label
+    }
+
+    /// Convert a `draw:frame`: a picture becomes an inline image of `para`, a text
+    /// box becomes blocks in `extra`.
+    fn frame(&mut self, e: &Element, para: &mut Paragraph, extra: &mut Vec<Block>) {
+        para.images.extend(self.frame_image(e));
+        if let Some(text_box) = e.child("draw:text-box") {
+            extra.extend(self.blocks(text_box));
+        }
+    }
+
+    /// The picture of a `draw:frame`, registered as a resource to carry.
+    ///
+    /// A frame may list several `draw:image` alternatives; the first is the preferred one.
+    pub fn frame_image(&mut self, e: &Element) -> Option<InlineImage> {
+        let href = e.child("draw:image")?.attr("xlink:href")?;
+        // Linked pictures live outside the package; there is nothing to carry.
+        if href.is_empty() || href.contains("://") || href.starts_with("../") {
+            return None;
+        }
+        let path = href.trim_start_matches("./").to_string();
+        let id = path.rsplit('/').next().unwrap_or(&path).to_string();
+        if !self.images.iter().any(|(known, _)| *known == id) {
+            self.images.push((id.clone(), path));
+        }
+        let alt_text = ["svg:title", "svg:desc"]
+            .iter()
+            .filter_map(|name| e.child(name))
+            .map(|t| t.text().trim().to_string())
+            .find(|t| !t.is_empty())
+            .or_else(|| e.attr("draw:name").map(str::to_string));
+        Some(InlineImage {
+            resource_id: id,
+            alt_text,
+            width: e.attr("svg:width").and_then(length_to_emu),
+            height: e.attr("svg:height").and_then(length_to_emu),
+        })
+    }
+
+    /// Convert a `text:list`, flattening nested lists into paragraphs with levels.
+    fn list(&mut self, e: &Element, level: u8, inherited: Option<&str>, out: &mut Vec<Block>) {
+        let style = e.attr("text:style-name").or(inherited).map(str::to_string);
+        let marker = self.styles.list_level(style.as_deref(), level);
+        let key = format!("{}#{}", style.as_deref().unwrap_or_default(), level);
+        let continues = e.attr("text:continue-numbering") == Some("true")
+            || e.attr("text:continue-list").is_some();
+        let mut number = match self.list_numbers.get(&key) {
+            Some(&last) if continues => last + 1,
+            _ => marker.start,
+        };
+
+        for item in e.children() {
+            let is_item = match item.name.as_str() {
+                "text:list-item" => true,
+                "text:list-header" => false,
+                _ => continue,
+            };
+            if let Some(start) = item.attr("text:start-value").and_then(|v| v.parse().ok()) {
+                number = start;
+            }
+            let mut first = is_item;
+            for child in item.children() {
+                match child.name.as_str() {
+                    "text:list" => self.list(child, level + 1, style.as_deref(), out),
+                    "text:p" | "text:h" => {
+                        let mut extra = Vec::new();
+                        let mut para = self.paragraph(child, &mut extra);
+                        if first {
+                            para.list_info = Some(ListInfo {
+                                list_type: marker.list_type,
+                                level,
+                                number: (marker.list_type == ListType::Numbered).then_some(number),
+                            });
+                            first = false;
+                        }
+                        if para.list_info.is_some() || !para.is_empty() {
+                            out.push(Block::Paragraph(para));
+                        }
+                        out.extend(extra);
+                    }
+                    _ => self.block(child, out),
+                }
+            }
+            if is_item {
+                self.list_numbers.insert(key.clone(), number);
+                number += 1;
+            }
+        }
+    }
+
+    /// Convert a `table:table`.
+    ///
+    /// Covered cells are left out, as merged cells are in the model, and runs of empty
+    /// rows and cells at the end of the table are dropped: producers often repeat them
+    /// out to the edge of the sheet.
+    pub fn table(&mut self, e: &Element) -> Table {
+        let mut rows: Vec<(Row, usize)> = Vec::new();
+        self.table_rows(e, false, &mut rows);
+
+        while rows.last().is_some_and(|(row, _)| row_is_empty(row)) {
+            rows.pop();
+        }
+        let mut table = Table::new();
+        for (row, repeat) in rows {
+            for _ in 1..repeat.min(MAX_REPEAT) {
+                table.add_row(row.clone());
+            }
+            table.add_row(row);
+        }
+        table.caption = e
+            .child("table:title")
+            .map(|t| t.text().trim().to_string())
+            .filter(|t| !t.is_empty());
+        table
+    }
+
+    fn table_rows(&mut self, e: &Element, header: bool, rows: &mut Vec<(Row, usize)>) {
+        for child in e.children() {
+            match child.name.as_str() {
+                "table:table-row" => {
+                    let repeat = repeat_count(child, "table:number-rows-repeated");
+                    let row = self.table_row(child, header);
+                    rows.push((row, repeat));
+                }
+                "table:table-header-rows" => self.table_rows(child, true, rows),
+                "table:table-rows" | "table:table-row-group" => {
+                    self.table_rows(child, header, rows)
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn table_row(&mut self, e: &Element, header: bool) -> Row {
+        let mut cells: Vec<(Cell, usize)> = Vec::new();
+        for child in e.children() {
+            if child.name != "table:table-cell" {
+                continue;
+            }
+            let repeat = repeat_count(child, "table:number-columns-repeated");
+            let mut cell = self.table_cell(child);
+            cell.is_header = header;
+            cells.push((cell, repeat));
+        }
+        while cells.last().is_some_and(|(cell, _)| cell_is_empty(cell)) {
+            cells.pop();
+        }
+
+        let mut row = Row::new();
+        row.is_header = header;
+        for (cell, repeat) in cells {
+            for _ in 1..repeat.min(MAX_REPEAT) {
+                row.add_cell(cell.clone());
+            }
+            row.add_cell(cell);
+        }
+        row
+    }
+
+    /// Convert a `table:table-cell`: its spans and content.
+    pub fn table_cell(&mut self, e: &Element) -> Cell {
+        let mut cell = Cell::new();
+        let span = |name: &str| {
+            e.attr(name)
+                .and_then(|v| v.parse::<u32>().ok())
+                .unwrap_or(1)
+                .max(1)
+        };
+        cell.col_span = span("table:number-columns-spanned");
+        cell.row_span = span("table:number-rows-spanned");
+        for block in self.blocks(e) {
+            match block {
+                Block::Paragraph(p) => cell.content.push(p),
+                Block::Table(t) => cell.nested_tables.push(t),
+                _ => {}
+            }
+        }
+        cell
+    }
+}
+
+/// Upper bound on how often one repeated row or cell is written out.
+const MAX_REPEAT: usize = 1024;
+
+fn repeat_count(e: &Element, name: &str) -> usize {
+    e.attr(name)
+        .and_then(|v| v.parse::<usize>().ok())
+        .unwrap_or(1)
+        .max(1)
+}
+
+fn cell_is_empty(cell: &Cell) -> bool {
+    cell.col_span == 1
+        && cell.row_span == 1
+        && cell.nested_tables.is_empty()
+        && cell
+            .content
+            .iter()
+            .all(|p| p.plain_text().trim().is_empty() && p.images.is_empty())
+}
+
+fn row_is_empty(row: &Row) -> bool {
+    row.cells.iter().all(cell_is_empty)
+}
+
+/// Convert an ODF length (`2.5cm`, `1in`, `12pt`, ...) to EMUs.
+pub(crate) fn length_to_emu(value: &str) -> Option<u32> {
+    let value = value.trim();
+    let split = value
+        .find(|c: char| c.is_ascii_alphabetic())
+        .unwrap_or(value.len());
+    let number: f64 = value[..split].parse().ok()?;
+    let per_unit = match &value[split..] {
+        "in" => 914_400.0,
+        "cm" => 360_000.0,
+        "mm" => 36_000.0,
+        "pt" => 12_700.0,
+        "pc" => 152_400.0,
+        "px" => 9_525.0,
+        _ => return None,
+    };
+    let emu = number * per_unit;
+    (emu.is_finite() && emu >= 0.0 && emu <= u32::MAX as f64).then(|| emu.round() as u32)
+}
+
+/// Builds the runs of a paragraph, collapsing whitespace the way ODF specifies.
+///
+/// Runs of spaces, tabs and newlines in the XML count as one space, and whitespace at
+/// the start and end of a paragraph counts as none; spaces that matter are written as
+/// `text:s`, tabs as `text:tab`.
+struct Runs {
+    para: Paragraph,
+    /// Collapsed whitespace not yet written, because it may turn out to be trailing.
+    pending_space: bool,
+    /// At the start of the paragraph or of a line, where whitespace is dropped.
+    at_line_start: bool,
+}
+
+impl Runs {
+    fn new(para: Paragraph) -> Self {
+        Self {
+            para,
+            pending_space: false,
+            at_line_start: true,
+        }
+    }
+
+    fn text(&mut self, text: &str, style: &TextStyle, link: Option<&str>) {
+        let mut out = String::with_capacity(text.len());
+        for ch in text.chars() {
+            if matches!(ch, ' ' | '\t' | '\r' | '\n') {
+                self.pending_space = true;
+                continue;
+            }
+            if out.is_empty() {
+                self.flush_space(&mut out, style, link);
+            } else if std::mem::take(&mut self.pending_space) {
+                out.push(' ');
+            }
+            self.at_line_start = false;
+            out.push(ch);
+        }
+        self.push(out, style, link);
+    }
+
+    fn literal(&mut self, text: &str, style: &TextStyle, link: Option<&str>) {
+        let mut out = String::new();
+        self.flush_space(&mut out, style, link);
+        out.push_str(text);
+        self.at_line_start = false;
+        self.push(out, style, link);
+    }
+
+    /// Write collapsed whitespace that turned out not to be trailing, either at the
+    /// start of `out` or at the end of the run before it: whichever side is plain text,
+    /// so that emphasis and links do not begin or end with a space.
+    fn flush_space(&mut self, out: &mut String, style: &TextStyle, link: Option<&str>) {
+        if !std::mem::take(&mut self.pending_space) || self.at_line_start {
+            return;
+        }
+        match self.para.runs.last_mut() {
+            Some(last)
+                if !(last.style.has_formatting() || last.hyperlink.is_some())
+                    || style.has_formatting()
+                    || link.is_some() =>
+            {
+                last.text.push(' ')
+            }
+            _ => out.push(' '),
+        }
+    }
+
+    fn line_break(&mut self, style: &TextStyle) {
+        self.pending_space = false;
+        self.at_line_start = true;
+        match self.para.runs.last_mut() {
+            Some(last) if !last.line_break => last.line_break = true,
+            _ => self.para.runs.push(TextRun {
+                line_break: true,
+                ..TextRun::styled("", style.clone())
+            }),
+        }
+    }
+
+    fn push(&mut self, text: String, style: &TextStyle, link: Option<&str>) {
+        if text.is_empty() {
+            return;
+        }
+        if let Some(last) = self.para.runs.last_mut() {
+            if !last.line_break && last.style == *style && last.hyperlink.as_deref() == link {
+                last.text.push_str(&text);
+                return;
+            }
+        }
+        let mut run = TextRun::styled(text, style.clone());
+        run.hyperlink = link.map(str::to_string);
+        self.para.runs.push(run);
+    }
+
+    fn finish(self) -> Paragraph {
+        self.para
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::odf::xml;
+
+    const NS: &str = r#"xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0"
+        xmlns:style="urn:oasis:names:tc:opendocument:xmlns:style:1.0"
+        xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0"
+        xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0"
+        xmlns:draw="urn:oasis:names:tc:opendocument:xmlns:drawing:1.0"
+        xmlns:fo="urn:oasis:names:tc:opendocument:xmlns:xsl-fo-compatible:1.0"
+        xmlns:svg="urn:oasis:names:tc:opendocument:xmlns:svg-compatible:1.0"
+        xmlns:xlink="http://www.w3.org/1999/xlink""#;
+
+    fn read(body: &str) -> (Vec<Block>, Vec<(String, String)>) {
+        let xml = format!("<office:text {NS}>{body}</office:text>");
+        let root = xml::parse(&xml, "content.xml").unwrap();
+        let styles = StyleSheet::default();
+        let mut reader = TextReader::new(&styles);
+        let blocks = reader.blocks(&root);
+        (blocks, reader.notes().to_vec())
+    }
+
+    fn paragraph(block: &Block) -> &Paragraph {
+        match block {
+            Block::Paragraph(p) => p,
+            other => panic!("expected a paragraph, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_whitespace_collapses_except_explicit_spaces() {
+        let (blocks, _) = read(
+            "<text:p>\n  Hello   <text:span>big</text:span>\n world<text:s text:c=\"2\"/>!<text:tab/>x  </text:p>",
+        );
+        assert_eq!(paragraph(&blocks[0]).plain_text(), "Hello big world  !\tx");
+    }
+
+    #[test]
+    fn test_nested_list_levels_and_numbers() {
+        let (blocks, _) = read(
+            r#"<text:list>
+                 <text:list-item><text:p>one</text:p>
+                   <text:list><text:list-item><text:p>inner</text:p></text:list-item></text:list>
+                 </text:list-item>
+                 <text:list-item text:start-value="5"><text:p>two</text:p></text:list-item>
+               </text:list>"#,
+        );
+        let info: Vec<_> = blocks
+            .iter()
+            .map(|b| {
+                let info = paragraph(b).list_info.clone().unwrap();
+                (info.level, paragraph(b).plain_text())
+            })
+            .collect();
+        assert_eq!(
+            info,
+            vec![
+                (0, "one".to_string()),
+                (1, "inner".to_string()),
+                (0, "two".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_covered_cells_are_omitted_and_trailing_repeats_dropped() {
+        let (blocks, _) = read(
+            r#"<table:table>
+                 <table:table-column table:number-columns-repeated="3"/>
+                 <table:table-header-rows><table:table-row>
+                   <table:table-cell table:number-columns-spanned="2"><text:p>A</text:p></table:table-cell>
+                   <table:covered-table-cell/>
+                   <table:table-cell><text:p>B</text:p></table:table-cell>
+                 </table:table-row></table:table-header-rows>
+                 <table:table-row>
+                   <table:table-cell table:number-columns-repeated="2"><text:p>x</text:p></table:table-cell>
+                   <table:table-cell table:number-columns-repeated="1000"/>
+                 </table:table-row>
+                 <table:table-row table:number-rows-repeated="100000"><table:table-cell/></table:table-row>
+               </table:table>"#,
+        );
+        let Block::Table(table) = &blocks[0] else {
+            panic!("expected a table");
+        };
+        assert_eq!(table.rows.len(), 2);
+        assert!(table.rows[0].is_header);
+        assert_eq!(table.rows[0].cells.len(), 2);
+        assert_eq!(table.rows[0].cells[0].col_span, 2);
+        assert_eq!(table.rows[1].cells.len(), 2);
+        assert_eq!(table.rows[1].cells[1].plain_text(), "x");
+    }
+
+    #[test]
+    fn test_notes_become_markers_and_definitions() {
+        let (blocks, notes) = read(
+            r#"<text:p>Claim<text:note text:note-class="footnote"><text:note-citation>1</text:note-citation>
+                 <text:note-body><text:p>Source.</text:p></text:note-body></text:note> and
+                 <text:note text:note-class="endnote"><text:note-citation>i</text:note-citation>
+                 <text:note-body><text:p>Later.</text:p></text:note-body></text:note></text:p>"#,
+        );
+        assert_eq!(paragraph(&blocks[0]).plain_text(), "Claim[^1] and [^e1]");
+        assert_eq!(
+            notes,
+            vec![
+                ("1".to_string(), "Source.".to_string()),
+                ("e1".to_string(), "Later.".to_string())
+            ]
+        );
+    }
+
+    #[test]
+    fn test_frames_give_images_and_text_boxes() {
+        let xml = format!(
+            r#"<office:text {NS}><text:p>See
+                 <draw:frame draw:name="Logo" svg:width="2.54cm" svg:height="1in">
+                   <draw:image xlink:href="Pictures/logo.png"/>
+                 </draw:frame>
+                 <draw:frame><draw:text-box><text:p>Boxed</text:p></draw:text-box></draw:frame>
+               </text:p></office:text>"#
+        );
+        let root = xml::parse(&xml, "content.xml").unwrap();
+        let styles = StyleSheet::default();
+        let mut reader = TextReader::new(&styles);
+        let blocks = reader.blocks(&root);
+
+        let image = &paragraph(&blocks[0]).images[0];
+        assert_eq!(image.resource_id, "logo.png");
+        assert_eq!(image.alt_text.as_deref(), Some("Logo"));
+        assert_eq!((image.width, image.height), (Some(914_400), Some(914_400)));
+        assert_eq!(paragraph(&blocks[1]).plain_text(), "Boxed");
+        assert_eq!(
+            reader.images(),
+            &[("logo.png".to_string(), "Pictures/logo.png".to_string())]
+        );
+    }
+}
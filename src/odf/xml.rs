@@ -0,0 +1,107 @@
+//! A small element tree over the OpenDocument parts.
+//!
+//! ODF content nests freely — lists inside table cells inside text boxes inside list
+//! items — so the parsers walk a tree rather than keeping a state machine per
+//! construct. Element and attribute names are rewritten to the conventional prefix of
+//! their namespace (`text:p`, `table:table-cell`, ...), so a producer that binds the
+//! namespaces to other prefixes is read the same way. The tree is [`crate::xml_tree`],
+//! which bounds how deep it nests.
+
+use crate::error::{Error, Result};
+use crate::xml_tree::{self, Names};
+
+pub(crate) use crate::xml_tree::{Element, Node};
+
+/// Namespace URI → the prefix names are matched by.
+const NAMESPACES: &[(&str, &str)] = &[
+    ("urn:oasis:names:tc:opendocument:xmlns:office:1.0", "office"),
+    ("urn:oasis:names:tc:opendocument:xmlns:style:1.0", "style"),
+    ("urn:oasis:names:tc:opendocument:xmlns:text:1.0", "text"),
+    ("urn:oasis:names:tc:opendocument:xmlns:table:1.0", "table"),
+    ("urn:oasis:names:tc:opendocument:xmlns:drawing:1.0", "draw"),
+    (
+        "urn:oasis:names:tc:opendocument:xmlns:xsl-fo-compatible:1.0",
+        "fo",
+    ),
+    (
+        "urn:oasis:names:tc:opendocument:xmlns:svg-compatible:1.0",
+        "svg",
+    ),
+    (
+        "urn:oasis:names:tc:opendocument:xmlns:presentation:1.0",
+        "presentation",
+    ),
+    ("urn:oasis:names:tc:opendocument:xmlns:meta:1.0", "meta"),
+    (
+        "urn:oasis:names:tc:opendocument:xmlns:datastyle:1.0",
+        "number",
+    ),
+    (
+        "urn:oasis:names:tc:opendocument:xmlns:manifest:1.0",
+        "manifest",
+    ),
+    ("http://www.w3.org/1999/xlink", "xlink"),
+    ("http://purl.org/dc/elements/1.1/", "dc"),
+];
+
+/// Parse a part into its root element.
+pub(crate) fn parse(xml: &str, location: &str) -> Result<Element> {
+    xml_tree::parse(xml, location, Names::Canonical(NAMESPACES))?
+        .nodes
+        .into_iter()
+        .find_map(|node| match node {
+            Node::Element(e) => Some(e),
+            Node::Text(_) => None,
+        })
+        .ok_or_else(|| Error::xml_parse_with_context("no root element", location))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_names_are_matched_by_namespace_not_prefix() {
+        let xml = r#"<doc:document-content
+            xmlns:doc="urn:oasis:names:tc:opendocument:xmlns:office:1.0"
+            xmlns:t="urn:oasis:names:tc:opendocument:xmlns:text:1.0">
+            <doc:body><t:p t:style-name="P1">A &amp; B</t:p></doc:body>
+        </doc:document-content>"#;
+        let root = parse(xml, "content.xml").unwrap();
+
+        assert_eq!(root.name, "office:document-content");
+        let p = root.path(&["office:body", "text:p"]).unwrap();
+        assert_eq!(p.attr("text:style-name"), Some("P1"));
+        assert_eq!(p.text(), "A & B");
+    }
+
+    #[test]
+    fn test_default_namespace_applies_to_elements_only() {
+        let xml = r#"<p xmlns="urn:oasis:names:tc:opendocument:xmlns:text:1.0" id="x"/>"#;
+        let root = parse(xml, "content.xml").unwrap();
+        assert_eq!(root.name, "text:p");
+        assert_eq!(root.attr("id"), Some("x"));
+    }
+
+    #[test]
+    fn test_truncated_part_keeps_what_was_read() {
+        let xml = r#"<a:x xmlns:a="urn:oasis:names:tc:opendocument:xmlns:text:1.0"><a:p>kept"#;
+        let root = parse(xml, "content.xml").unwrap();
+        assert_eq!(
+            root.child("text:p").map(Element::text).as_deref(),
+            Some("kept")
+        );
+    }
+
+    #[test]
+    fn test_deeply_nested_spans_are_read_as_text() {
+        let depth = 100_000;
+        let xml = format!(
+            r#"<t:p xmlns:t="urn:oasis:names:tc:opendocument:xmlns:text:1.0">{}deep{}</t:p>"#,
+            "<t:span>".repeat(depth),
+            "</t:span>".repeat(depth)
+        );
+        let root = parse(&xml, "content.xml").unwrap();
+        assert_eq!(root.text(), "deep");
+    }
+}
@@ -0,0 +1,24 @@
+//! ODP (OpenDocument Presentation) parser.
+//!
+//! This module provides parsing for OpenDocument presentations, as written by
+//! LibreOffice Impress: a ZIP package whose `content.xml` holds one `draw:page`
+//! per slide, each with its frames and shapes and a `presentation:notes` page
+//! for the speaker notes.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use undoc::odp::OdpParser;
+//!
+//! let mut parser = OdpParser::open("presentation.odp")?;
+//! let doc = parser.parse()?;
+//!
+//! for section in &doc.sections {
+//!     println!("{}: {} blocks", section.name.as_deref().unwrap_or(""), section.content.len());
+//! }
+//! # Ok::<(), undoc::Error>(())
+//! ```
+
+mod parser;
+
+pub use parser::OdpParser;
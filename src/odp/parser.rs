@@ -0,0 +1,260 @@
+//! ODP parser implementation.
+
+use crate::container::OoxmlContainer;
+use crate::error::Result;
+use crate::model::{Block, Document, HeadingLevel, Paragraph, Section};
+use crate::odf::{length_to_emu, Element, Package, TextReader};
+
+/// Parser for OpenDocument presentation (.odp) documents.
+pub struct OdpParser {
+    package: Package,
+}
+
+impl OdpParser {
+    /// Open an ODP file for parsing.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn open(path: impl AsRef<std::path::Path>) -> Result<Self> {
+        let data = std::fs::read(path)?;
+        Self::from_bytes(data)
+    }
+
+    /// Create a parser from bytes.
+    pub fn from_bytes(data: Vec<u8>) -> Result<Self> {
+        let container = OoxmlContainer::from_bytes(data)?;
+        Ok(Self {
+            package: Package::read(container)?,
+        })
+    }
+
+    /// Parse the presentation and return a Document model.
+    pub fn parse(&mut self) -> Result<Document> {
+        let mut doc = Document::new();
+        doc.format = crate::detect::FormatType::Odp;
+        doc.metadata = self.package.metadata.clone();
+
+        let pages = self.pages()?;
+        doc.metadata.page_count = Some(pages.len() as u32);
+
+        let mut reader = TextReader::new(&self.package.styles);
+        for (idx, page) in pages.into_iter().enumerate() {
+            doc.add_section(parse_page_as_section(&mut reader, idx, page));
+        }
+
+        self.package.add_images(reader.images(), &mut doc);
+        Ok(doc)
+    }
+
+    /// Get the number of slides.
+    pub fn slide_count(&self) -> usize {
+        self.pages().map_or(0, |pages| pages.len())
+    }
+
+    fn pages(&self) -> Result<Vec<&Element>> {
+        let body = self.package.body("office:presentation")?;
+        Ok(body.children().filter(|e| e.name == "draw:page").collect())
+    }
+}
+
+/// Convert a `draw:page` into a section named after the page, with its speaker notes.
+fn parse_page_as_section(reader: &mut TextReader<'_>, idx: usize, page: &Element) -> Section {
+    let name = page
+        .attr("draw:name")
+        .filter(|n| !n.is_empty())
+        .map_or_else(|| format!("Slide {}", idx + 1), str::to_string);
+    let mut section = Section::with_name(idx, name);
+    section.content = shapes(reader, page);
+
+    if let Some(notes) = page.child("presentation:notes") {
+        let paragraphs: Vec<Paragraph> = shapes(reader, notes)
+            .into_iter()
+            .filter_map(|block| match block {
+                Block::Paragraph(p) => Some(p),
+                _ => None,
+            })
+            .collect();
+        if !paragraphs.is_empty() {
+            section.notes = Some(paragraphs);
+        }
+    }
+    section
+}
+
+/// Convert the shapes among the children of a page or group, in reading order:
+/// top to bottom, then left to right. Shapes without a position keep their place in
+/// the drawing order relative to each other.
+fn shapes(reader: &mut TextReader<'_>, parent: &Element) -> Vec<Block> {
+    let mut shapes: Vec<&Element> = parent
+        .children()
+        .filter(|e| e.name.starts_with("draw:") && e.name != "draw:page-thumbnail")
+        .collect();
+    shapes.sort_by_key(|e| position(e));
+
+    let mut out = Vec::new();
+    for shape in shapes {
+        match shape.name.as_str() {
+            "draw:frame" => frame(reader, shape, &mut out),
+            "draw:g" => out.extend(self::shapes(reader, shape)),
+            // Connectors, lines and custom shapes hold their text as paragraphs.
+            _ => out.extend(reader.blocks(shape)),
+        }
+    }
+    out
+}
+
+/// Top-left corner of a shape in EMUs, for ordering.
+fn position(e: &Element) -> (u32, u32) {
+    let coordinate = |name: &str| e.attr(name).and_then(length_to_emu).unwrap_or(0);
+    (coordinate("svg:y"), coordinate("svg:x"))
+}
+
+/// Convert a `draw:frame`: a title, subtitle or text box, a picture, or a table.
+fn frame(reader: &mut TextReader<'_>, e: &Element, out: &mut Vec<Block>) {
+    // Title placeholders become headings, as in the PPTX parser.
+    let heading = match e.attr("presentation:class") {
+        Some("title") => HeadingLevel::H1,
+        Some("subtitle") => HeadingLevel::H2,
+        _ => HeadingLevel::None,
+    };
+
+    if let Some(text_box) = e.child("draw:text-box") {
+        for mut block in reader.blocks(text_box) {
+            if let Block::Paragraph(p) = &mut block {
+                if heading != HeadingLevel::None && p.list_info.is_none() {
+                    p.heading = heading;
+                }
+            }
+            out.push(block);
+        }
+    } else if let Some(image) = reader.frame_image(e) {
+        out.push(Block::Image {
+            resource_id: image.resource_id,
+            alt_text: image.alt_text,
+            width: image.width,
+            height: image.height,
+        });
+    } else if let Some(table) = e.child("table:table") {
+        out.push(Block::Table(reader.table(table)));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::{to_markdown, RenderOptions, SectionMarkerStyle};
+    use std::io::{Cursor, Write};
+
+    const NS: &str = r#"xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0"
+        xmlns:style="urn:oasis:names:tc:opendocument:xmlns:style:1.0"
+        xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0"
+        xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0"
+        xmlns:draw="urn:oasis:names:tc:opendocument:xmlns:drawing:1.0"
+        xmlns:presentation="urn:oasis:names:tc:opendocument:xmlns:presentation:1.0"
+        xmlns:svg="urn:oasis:names:tc:opendocument:xmlns:svg-compatible:1.0"
+        xmlns:xlink="http://www.w3.org/1999/xlink""#;
+
+    const PNG: &[u8] = b"\x89PNG\r\n\x1a\nfake";
+
+    /// Build an ODP package around the `draw:page` elements of a presentation body.
+    fn odp(pages: &str) -> Vec<u8> {
+        let content = format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <office:document-content {NS} office:version="1.3">
+              <office:body><office:presentation>{pages}</office:presentation></office:body>
+            </office:document-content>"#
+        );
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let options = zip::write::SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Stored);
+        let parts: [(&str, &[u8]); 3] = [
+            (
+                "mimetype",
+                b"application/vnd.oasis.opendocument.presentation",
+            ),
+            ("content.xml", content.as_bytes()),
+            ("Pictures/photo.png", PNG),
+        ];
+        for (path, data) in parts {
+            zip.start_file(path, options).unwrap();
+            zip.write_all(data).unwrap();
+        }
+        zip.finish().unwrap().into_inner()
+    }
+
+    fn parse(pages: &str) -> Document {
+        OdpParser::from_bytes(odp(pages)).unwrap().parse().unwrap()
+    }
+
+    const DECK: &str = r#"
+        <draw:page draw:name="Intro">
+          <draw:frame presentation:class="outline" svg:x="1cm" svg:y="5cm">
+            <draw:text-box><text:list><text:list-item><text:p>Point</text:p></text:list-item></text:list></draw:text-box>
+          </draw:frame>
+          <draw:frame presentation:class="title" svg:x="1cm" svg:y="1cm">
+            <draw:text-box><text:p>Welcome</text:p></draw:text-box>
+          </draw:frame>
+          <draw:frame svg:x="10cm" svg:y="5cm" svg:width="1in" svg:height="1in">
+            <draw:image xlink:href="Pictures/photo.png"/><svg:title>Photo</svg:title>
+          </draw:frame>
+          <presentation:notes>
+            <draw:page-thumbnail draw:page-number="1"/>
+            <draw:frame presentation:class="notes"><draw:text-box><text:p>Say hello</text:p></draw:text-box></draw:frame>
+          </presentation:notes>
+        </draw:page>
+        <draw:page draw:name="Numbers">
+          <draw:frame svg:x="1cm" svg:y="3cm">
+            <table:table>
+              <table:table-row><table:table-cell><text:p>A</text:p></table:table-cell>
+                <table:table-cell><text:p>B</text:p></table:table-cell></table:table-row>
+            </table:table>
+          </draw:frame>
+          <draw:custom-shape svg:x="1cm" svg:y="1cm"><text:p>Shape text</text:p></draw:custom-shape>
+        </draw:page>"#;
+
+    #[test]
+    fn test_one_section_per_page_in_reading_order() {
+        let doc = parse(DECK);
+        assert_eq!(doc.format, crate::detect::FormatType::Odp);
+        assert_eq!(doc.metadata.page_count, Some(2));
+        assert_eq!(doc.sections.len(), 2);
+
+        let intro = &doc.sections[0];
+        assert_eq!(intro.name.as_deref(), Some("Intro"));
+        let Block::Paragraph(title) = &intro.content[0] else {
+            panic!("expected the title first");
+        };
+        assert_eq!(title.plain_text(), "Welcome");
+        assert_eq!(title.heading, HeadingLevel::H1);
+        let Block::Paragraph(point) = &intro.content[1] else {
+            panic!("expected the outline next");
+        };
+        assert!(point.list_info.is_some());
+        assert!(matches!(
+            &intro.content[2],
+            Block::Image { resource_id, alt_text, width, .. }
+                if resource_id == "photo.png" && alt_text.as_deref() == Some("Photo")
+                    && *width == Some(914_400)
+        ));
+        assert_eq!(doc.resources["photo.png"].data, PNG);
+
+        let notes = intro.notes.as_ref().unwrap();
+        assert_eq!(notes.len(), 1);
+        assert_eq!(notes[0].plain_text(), "Say hello");
+
+        let numbers = &doc.sections[1];
+        assert_eq!(numbers.name.as_deref(), Some("Numbers"));
+        assert!(
+            matches!(&numbers.content[0], Block::Paragraph(p) if p.plain_text() == "Shape text")
+        );
+        assert!(matches!(&numbers.content[1], Block::Table(t) if t.rows[0].cells.len() == 2));
+        assert!(numbers.notes.is_none());
+    }
+
+    #[test]
+    fn test_slide_markers_match_pptx() {
+        let doc = parse(DECK);
+        let options = RenderOptions::default().with_section_markers(SectionMarkerStyle::Comment);
+        let markdown = to_markdown(&doc, &options).unwrap();
+        assert!(markdown.contains("<!-- slide 1: Intro -->"), "{markdown}");
+        assert!(markdown.contains("<!-- slide 2: Numbers -->"), "{markdown}");
+    }
+}
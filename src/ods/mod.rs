@@ -0,0 +1,24 @@
+//! ODS (OpenDocument Spreadsheet) workbook parser.
+//!
+//! This module provides parsing for OpenDocument spreadsheets, as written by
+//! LibreOffice Calc and Google Sheets: a ZIP package whose `content.xml` holds
+//! one `table:table` per sheet, with runs of identical rows and cells stored
+//! once with a repeat count.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use undoc::ods::OdsParser;
+//!
+//! let mut parser = OdsParser::open("spreadsheet.ods")?;
+//! let doc = parser.parse()?;
+//!
+//! for section in &doc.sections {
+//!     println!("Sheet: {}", section.name.as_deref().unwrap_or("Unnamed"));
+//! }
+//! # Ok::<(), undoc::Error>(())
+//! ```
+
+mod parser;
+
+pub use parser::OdsParser;
@@ -0,0 +1,470 @@
+//! ODS parser implementation.
+
+use std::collections::BTreeMap;
+
+use crate::container::OoxmlContainer;
+use crate::error::Result;
+use crate::model::{Block, Cell, Document, Paragraph, Row, Section, Table, TextRun, TextStyle};
+use crate::odf::{Element, Package, TextReader};
+
+/// Rows and columns of the largest sheet ODF applications write (LibreOffice's limits).
+/// Repeats are clipped to this grid, so a count in the file cannot ask for more.
+const MAX_ROWS: u64 = 1 << 20;
+const MAX_COLUMNS: u64 = 1 << 14;
+/// Cells a sheet is read into at most. Repeats can ask for billions in a few bytes;
+/// a sheet past this is cut short, with a note after its table saying so.
+const MAX_CELLS: usize = 1 << 18;
+
+/// Parser for OpenDocument spreadsheet (.ods) documents.
+pub struct OdsParser {
+    package: Package,
+}
+
+impl OdsParser {
+    /// Open an ODS file for parsing.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn open(path: impl AsRef<std::path::Path>) -> Result<Self> {
+        let data = std::fs::read(path)?;
+        Self::from_bytes(data)
+    }
+
+    /// Create a parser from bytes.
+    pub fn from_bytes(data: Vec<u8>) -> Result<Self> {
+        let container = OoxmlContainer::from_bytes(data)?;
+        Ok(Self {
+            package: Package::read(container)?,
+        })
+    }
+
+    /// Parse the workbook and return a Document model.
+    pub fn parse(&mut self) -> Result<Document> {
+        let mut doc = Document::new();
+        doc.format = crate::detect::FormatType::Ods;
+        doc.metadata = self.package.metadata.clone();
+
+        let sheets = self.sheets()?;
+        doc.metadata.page_count = Some(sheets.len() as u32);
+
+        let mut reader = TextReader::new(&self.package.styles);
+        for (idx, sheet) in sheets.into_iter().enumerate() {
+            let mut section = Section::with_name(idx, sheet.attr("table:name").unwrap_or_default());
+            let (table, truncated) = read_sheet(&mut reader, sheet);
+            if !table.is_empty() {
+                section.add_block(Block::Table(table));
+            }
+            if truncated {
+                section.add_block(Block::Paragraph(Paragraph {
+                    runs: vec![TextRun::styled(
+                        format!("Sheet truncated to its first {MAX_CELLS} cells."),
+                        TextStyle::italic(),
+                    )],
+                    ..Default::default()
+                }));
+            }
+            doc.add_section(section);
+        }
+
+        // Pictures anchored in a cell sit in its paragraphs.
+        self.package.add_images(reader.images(), &mut doc);
+        Ok(doc)
+    }
+
+    /// Get the number of sheets in the workbook.
+    pub fn sheet_count(&self) -> usize {
+        self.sheets().map_or(0, |sheets| sheets.len())
+    }
+
+    /// Get sheet names.
+    pub fn sheet_names(&self) -> Vec<&str> {
+        self.sheets()
+            .map(|sheets| {
+                sheets
+                    .into_iter()
+                    .map(|s| s.attr("table:name").unwrap_or_default())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    fn sheets(&self) -> Result<Vec<&Element>> {
+        let body = self.package.body("office:spreadsheet")?;
+        Ok(body
+            .children()
+            .filter(|e| e.name == "table:table")
+            .collect())
+    }
+}
+
+/// A cell with content, or the anchor of a merge.
+struct PlacedCell {
+    cell: Cell,
+    has_content: bool,
+}
+
+/// Read a sheet into a table, and whether it was cut short at [`MAX_CELLS`].
+///
+/// Like the XLSX parser, rows are the rows holding a value, without the empty rows
+/// between them, and columns run from A to the last column holding one, so
+/// formatting-only cells repeated out to the edge of the sheet never widen the table.
+/// Cells are placed on a sparse grid as the repeats are walked: an empty repeated cell
+/// or row only advances the position, so its count costs nothing however large it is.
+/// Cells a merge covers are left out; the merge's first cell carries the span.
+fn read_sheet(reader: &mut TextReader<'_>, sheet: &Element) -> (Table, bool) {
+    let mut grid: BTreeMap<(u64, u64), PlacedCell> = BTreeMap::new();
+    let mut row_idx = 0;
+    let mut truncated = false;
+    for_each_row(sheet, &mut |row, repeat| {
+        let repeat = repeat.min(MAX_ROWS.saturating_sub(row_idx));
+        if truncated {
+            return;
+        }
+        let cells = read_row(reader, row);
+        // A row of empty merges repeats formatting; only its first copy can matter.
+        let copies = if cells.iter().any(|(_, cell)| cell.has_content) {
+            repeat
+        } else {
+            repeat.min(1)
+        };
+        'rows: for r in row_idx..row_idx + copies {
+            for (col, cell) in &cells {
+                if grid.len() >= MAX_CELLS {
+                    truncated = true;
+                    break 'rows;
+                }
+                grid.insert(
+                    (r, *col),
+                    PlacedCell {
+                        cell: cell.cell.clone(),
+                        has_content: cell.has_content,
+                    },
+                );
+            }
+        }
+        row_idx += repeat;
+    });
+    let (table, clipped) = build_table(grid);
+    (table, truncated || clipped)
+}
+
+/// Call `f` with each row of a sheet and its repeat count, through header rows and groups.
+fn for_each_row(parent: &Element, f: &mut impl FnMut(&Element, u64)) {
+    for child in parent.children() {
+        match child.name.as_str() {
+            "table:table-row" => f(child, repeat_count(child, "table:number-rows-repeated")),
+            "table:table-header-rows" | "table:table-rows" | "table:table-row-group" => {
+                for_each_row(child, f)
+            }
+            _ => {}
+        }
+    }
+}
+
+/// The cells of a row that hold content or anchor a merge, by column. An empty merge
+/// anchor is placed once however often it repeats.
+fn read_row(reader: &mut TextReader<'_>, row: &Element) -> Vec<(u64, PlacedCell)> {
+    let mut placed = Vec::new();
+    let mut col = 0;
+    for e in row.children() {
+        let repeat = match e.name.as_str() {
+            "table:table-cell" | "table:covered-table-cell" => {
+                repeat_count(e, "table:number-columns-repeated")
+                    .min(MAX_COLUMNS.saturating_sub(col))
+            }
+            _ => continue,
+        };
+        if e.name == "table:table-cell" {
+            let mut cell = reader.table_cell(e);
+            if cell.content.is_empty() {
+                if let Some(text) = value_text(e) {
+                    cell.content.push(Paragraph {
+                        runs: vec![TextRun::plain(text)],
+                        ..Default::default()
+                    });
+                }
+            }
+            cell.value = stored_value(e).filter(|v| *v != cell.plain_text());
+            let has_content = !cell.plain_text().trim().is_empty()
+                || !cell.nested_tables.is_empty()
+                || cell.content.iter().any(|p| !p.images.is_empty());
+            let copies = match (has_content, cell.col_span > 1 || cell.row_span > 1) {
+                (true, _) => repeat,
+                (false, true) => repeat.min(1),
+                (false, false) => 0,
+            };
+            for offset in 0..copies {
+                placed.push((
+                    col + offset,
+                    PlacedCell {
+                        cell: cell.clone(),
+                        has_content,
+                    },
+                ));
+            }
+        }
+        col += repeat;
+    }
+    placed
+}
+
+/// Lay the placed cells out as rows, applying merges, and whether the rows were cut
+/// short to keep the table within [`MAX_CELLS`].
+fn build_table(mut grid: BTreeMap<(u64, u64), PlacedCell>) -> (Table, bool) {
+    let mut table = Table::new();
+    let content = || grid.iter().filter(|(_, c)| c.has_content).map(|(&k, _)| k);
+    let (Some(first_row), Some(last_row), Some(last_col)) = (
+        content().map(|(r, _)| r).min(),
+        content().map(|(r, _)| r).max(),
+        content().map(|(_, c)| c).max(),
+    ) else {
+        return (table, false);
+    };
+
+    // Only rows holding a placed cell are laid out, each padded to the full width.
+    let width = last_col + 1;
+    let mut rows: Vec<u64> = grid
+        .keys()
+        .map(|&(r, _)| r)
+        .filter(|r| (first_row..=last_row).contains(r))
+        .collect();
+    rows.dedup();
+    let max_rows = (MAX_CELLS as u64 / width).max(1) as usize;
+    let truncated = rows.len() > max_rows;
+    rows.truncate(max_rows);
+
+    // The merge covering each column, as (first row, last row, anchor column). Merges
+    // are clipped to the laid-out rows and columns, so a span never points past them.
+    let mut cover: Vec<Option<(u64, u64, u64)>> = vec![None; width as usize];
+    for (i, &r) in rows.iter().enumerate() {
+        let is_header = i == 0;
+        let mut row = Row {
+            is_header,
+            ..Row::new()
+        };
+        for c in 0..width {
+            if let Some((start, end, anchor)) = cover[c as usize] {
+                if (start..=end).contains(&r) && (r, c) != (start, anchor) {
+                    continue;
+                }
+            }
+            let mut cell = grid.remove(&(r, c)).map(|p| p.cell).unwrap_or_default();
+            if cell.row_span > 1 || cell.col_span > 1 {
+                let r2 = (r + cell.row_span as u64 - 1).min(last_row);
+                let c2 = (c + cell.col_span as u64 - 1).min(last_col);
+                cell.row_span = rows[i..].partition_point(|&rr| rr <= r2) as u32;
+                cell.col_span = (c2 - c + 1) as u32;
+                for slot in &mut cover[c as usize..=c2 as usize] {
+                    *slot = Some((r, r2, c));
+                }
+            }
+            cell.is_header = is_header;
+            row.cells.push(cell);
+        }
+        table.add_row(row);
+    }
+    (table, truncated)
+}
+
+fn repeat_count(e: &Element, name: &str) -> u64 {
+    e.attr(name)
+        .and_then(|v| v.parse::<u64>().ok())
+        .unwrap_or(1)
+        .max(1)
+}
+
+/// Text of a cell that has a value but no paragraph showing it.
+fn value_text(e: &Element) -> Option<String> {
+    let attr = match e.attr("office:value-type")? {
+        "string" => "office:string-value",
+        "date" => "office:date-value",
+        "time" => "office:time-value",
+        "boolean" => {
+            let value = e.attr("office:boolean-value")?;
+            return Some(if value == "true" { "TRUE" } else { "FALSE" }.to_string());
+        }
+        _ => "office:value",
+    };
+    e.attr(attr).map(str::to_string)
+}
+
+/// The value a typed cell stores, as opposed to the text it displays.
+fn stored_value(e: &Element) -> Option<String> {
+    let attr = match e.attr("office:value-type")? {
+        "date" => "office:date-value",
+        "time" => "office:time-value",
+        "boolean" => "office:boolean-value",
+        "float" | "percentage" | "currency" => "office:value",
+        _ => return None,
+    };
+    e.attr(attr).map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{Cursor, Write};
+
+    const NS: &str = r#"xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0"
+        xmlns:style="urn:oasis:names:tc:opendocument:xmlns:style:1.0"
+        xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0"
+        xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0"
+        xmlns:meta="urn:oasis:names:tc:opendocument:xmlns:meta:1.0"
+        xmlns:dc="http://purl.org/dc/elements/1.1/""#;
+
+    /// Build an ODS package around the `table:table` elements of a spreadsheet body.
+    fn ods(tables: &str) -> Vec<u8> {
+        let content = format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <office:document-content {NS} office:version="1.3">
+              <office:body><office:spreadsheet>{tables}</office:spreadsheet></office:body>
+            </office:document-content>"#
+        );
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let options = zip::write::SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Stored);
+        let parts: [(&str, &[u8]); 2] = [
+            (
+                "mimetype",
+                b"application/vnd.oasis.opendocument.spreadsheet",
+            ),
+            ("content.xml", content.as_bytes()),
+        ];
+        for (path, data) in parts {
+            zip.start_file(path, options).unwrap();
+            zip.write_all(data).unwrap();
+        }
+        zip.finish().unwrap().into_inner()
+    }
+
+    fn parse(tables: &str) -> Document {
+        OdsParser::from_bytes(ods(tables)).unwrap().parse().unwrap()
+    }
+
+    fn table(section: &Section) -> &Table {
+        match &section.content[0] {
+            Block::Table(t) => t,
+            other => panic!("expected a table, got {other:?}"),
+        }
+    }
+
+    fn texts(table: &Table) -> Vec<Vec<String>> {
+        table
+            .rows
+            .iter()
+            .map(|r| r.cells.iter().map(|c| c.plain_text()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_one_section_per_sheet() {
+        let doc = parse(
+            r#"<table:table table:name="Revenue">
+                 <table:table-row><table:table-cell office:value-type="string">
+                   <text:p>Q1</text:p></table:table-cell></table:table-row>
+               </table:table>
+               <table:table table:name="Empty"><table:table-row><table:table-cell/></table:table-row></table:table>"#,
+        );
+        assert_eq!(doc.format, crate::detect::FormatType::Ods);
+        assert_eq!(doc.sections.len(), 2);
+        assert_eq!(doc.sections[0].name.as_deref(), Some("Revenue"));
+        assert_eq!(doc.sections[1].name.as_deref(), Some("Empty"));
+        assert!(doc.sections[1].content.is_empty());
+        assert_eq!(doc.metadata.page_count, Some(2));
+    }
+
+    #[test]
+    fn test_repeats_expand_content_and_skip_empty_runs() {
+        let doc = parse(
+            r#"<table:table table:name="S">
+                 <table:table-column table:number-columns-repeated="16384"/>
+                 <table:table-row table:number-rows-repeated="2">
+                   <table:table-cell table:number-columns-repeated="3"/>
+                 </table:table-row>
+                 <table:table-row>
+                   <table:table-cell office:value-type="float" office:value="7" table:number-columns-repeated="2">
+                     <text:p>7</text:p></table:table-cell>
+                   <table:table-cell table:number-columns-repeated="16382"/>
+                 </table:table-row>
+                 <table:table-row table:number-rows-repeated="3">
+                   <table:table-cell table:number-columns-repeated="16384"/>
+                 </table:table-row>
+                 <table:table-row>
+                   <table:table-cell table:number-columns-repeated="2"/>
+                   <table:table-cell office:value-type="boolean" office:boolean-value="true"/>
+                 </table:table-row>
+                 <table:table-row table:number-rows-repeated="1048570">
+                   <table:table-cell table:number-columns-repeated="16384"/>
+                 </table:table-row>
+               </table:table>"#,
+        );
+        let table = table(&doc.sections[0]);
+        assert_eq!(texts(table), vec![vec!["7", "7", ""], vec!["", "", "TRUE"]]);
+        assert!(table.rows[0].is_header);
+    }
+
+    #[test]
+    fn test_repeated_content_is_cut_short() {
+        let doc = parse(
+            r#"<table:table table:name="S">
+                 <table:table-row table:number-rows-repeated="1048576">
+                   <table:table-cell table:number-columns-repeated="16384"
+                     table:number-columns-spanned="2" table:number-rows-spanned="1048576">
+                     <text:p>x</text:p></table:table-cell>
+                 </table:table-row>
+               </table:table>"#,
+        );
+        let section = &doc.sections[0];
+        let cells: usize = table(section).rows.iter().map(|r| r.cells.len()).sum();
+        assert!(cells <= MAX_CELLS);
+        match &section.content[1] {
+            Block::Paragraph(note) => assert!(note.plain_text().contains("truncated")),
+            other => panic!("expected a note, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_typed_cells_keep_their_stored_value() {
+        let doc = parse(
+            r#"<table:table table:name="S"><table:table-row>
+                 <table:table-cell office:value-type="date" office:date-value="2021-01-01">
+                   <text:p>01/01/21</text:p></table:table-cell>
+                 <table:table-cell office:value-type="float" office:value="7">
+                   <text:p>7</text:p></table:table-cell>
+                 <table:table-cell office:value-type="string"><text:p>x</text:p></table:table-cell>
+               </table:table-row></table:table>"#,
+        );
+        let cells = &table(&doc.sections[0]).rows[0].cells;
+        assert_eq!(cells[0].value.as_deref(), Some("2021-01-01"));
+        assert_eq!(cells[1].value, None);
+        assert_eq!(cells[2].value, None);
+    }
+
+    #[test]
+    fn test_spans_map_to_cell_spans_and_covered_cells_are_omitted() {
+        let doc = parse(
+            r#"<table:table table:name="S">
+                 <table:table-row>
+                   <table:table-cell table:number-columns-spanned="2" table:number-rows-spanned="2">
+                     <text:p>Header</text:p></table:table-cell>
+                   <table:covered-table-cell/>
+                   <table:table-cell><text:p>C</text:p></table:table-cell>
+                 </table:table-row>
+                 <table:table-row>
+                   <table:covered-table-cell table:number-columns-repeated="2"/>
+                   <table:table-cell><text:p>c2</text:p></table:table-cell>
+                 </table:table-row>
+                 <table:table-row>
+                   <table:table-cell><text:p>a</text:p></table:table-cell>
+                   <table:table-cell><text:p>b</text:p></table:table-cell>
+                   <table:table-cell><text:p>c</text:p></table:table-cell>
+                 </table:table-row>
+               </table:table>"#,
+        );
+        let table = table(&doc.sections[0]);
+        let header = &table.rows[0].cells[0];
+        assert_eq!((header.col_span, header.row_span), (2, 2));
+        assert_eq!(
+            texts(table),
+            vec![vec!["Header", "C"], vec!["c2"], vec!["a", "b", "c"]]
+        );
+    }
+}
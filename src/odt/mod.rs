@@ -0,0 +1,23 @@
+//! ODT (OpenDocument Text) document parser.
+//!
+//! This module provides parsing for OpenDocument text documents, as written by
+//! LibreOffice, OpenOffice and Google Docs: a ZIP package whose `content.xml`
+//! holds the body, `styles.xml` the named styles and page headers and footers,
+//! and `meta.xml` the metadata.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use undoc::odt::OdtParser;
+//!
+//! let mut parser = OdtParser::open("document.odt")?;
+//! let doc = parser.parse()?;
+//!
+//! println!("Title: {:?}", doc.metadata.title);
+//! println!("Content: {}", doc.plain_text());
+//! # Ok::<(), undoc::Error>(())
+//! ```
+
+mod parser;
+
+pub use parser::OdtParser;
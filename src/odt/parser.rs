@@ -0,0 +1,320 @@
+//! ODT parser implementation.
+
+use crate::container::OoxmlContainer;
+use crate::error::Result;
+use crate::model::{Block, Document, Paragraph, Section};
+use crate::odf::{Element, Package, TextReader};
+
+/// Parser for OpenDocument text (.odt) documents.
+pub struct OdtParser {
+    package: Package,
+}
+
+impl OdtParser {
+    /// Open an ODT file for parsing.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn open(path: impl AsRef<std::path::Path>) -> Result<Self> {
+        let data = std::fs::read(path)?;
+        Self::from_bytes(data)
+    }
+
+    /// Create a parser from bytes.
+    pub fn from_bytes(data: Vec<u8>) -> Result<Self> {
+        let container = OoxmlContainer::from_bytes(data)?;
+        Ok(Self {
+            package: Package::read(container)?,
+        })
+    }
+
+    /// Parse the document and return a Document model.
+    pub fn parse(&mut self) -> Result<Document> {
+        let mut doc = Document::new();
+        doc.format = crate::detect::FormatType::Odt;
+        doc.metadata = self.package.metadata.clone();
+
+        let body = self.package.body("office:text")?;
+        let mut reader = TextReader::new(&self.package.styles);
+        let mut section = Section::new(0);
+        section.content = reader.blocks(body);
+
+        if let Some(master) = self.master_page() {
+            section.header = page_region(&mut reader, master, "style:header");
+            section.footer = page_region(&mut reader, master, "style:footer");
+        }
+
+        // Append note definitions at end of section, as the DOCX parser does.
+        for (label, text) in reader.notes() {
+            section.add_block(Block::Paragraph(Paragraph::with_text(format!(
+                "[^{}]: {}",
+                label, text
+            ))));
+        }
+
+        self.package.add_images(reader.images(), &mut doc);
+        doc.add_section(section);
+        Ok(doc)
+    }
+
+    /// The page master the body starts on: `Standard` when it exists, else the first.
+    fn master_page(&self) -> Option<&Element> {
+        let masters = self.package.styles_part.child("office:master-styles")?;
+        masters
+            .children()
+            .find(|m| m.name == "style:master-page" && m.attr("style:name") == Some("Standard"))
+            .or_else(|| masters.child("style:master-page"))
+    }
+}
+
+fn page_region(
+    reader: &mut TextReader<'_>,
+    master: &Element,
+    name: &str,
+) -> Option<Vec<Paragraph>> {
+    // `style:display="false"` keeps a switched-off header's content in the file.
+    let region = master
+        .child(name)
+        .filter(|r| r.attr("style:display") != Some("false"))?;
+    let paragraphs = reader.paragraphs(region);
+    (!paragraphs.is_empty()).then_some(paragraphs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{HeadingLevel, ListType, TextAlignment};
+    use std::io::{Cursor, Write};
+
+    const NS: &str = r#"xmlns:office="urn:oasis:names:tc:opendocument:xmlns:office:1.0"
+        xmlns:style="urn:oasis:names:tc:opendocument:xmlns:style:1.0"
+        xmlns:text="urn:oasis:names:tc:opendocument:xmlns:text:1.0"
+        xmlns:table="urn:oasis:names:tc:opendocument:xmlns:table:1.0"
+        xmlns:draw="urn:oasis:names:tc:opendocument:xmlns:drawing:1.0"
+        xmlns:fo="urn:oasis:names:tc:opendocument:xmlns:xsl-fo-compatible:1.0"
+        xmlns:svg="urn:oasis:names:tc:opendocument:xmlns:svg-compatible:1.0"
+        xmlns:meta="urn:oasis:names:tc:opendocument:xmlns:meta:1.0"
+        xmlns:dc="http://purl.org/dc/elements/1.1/"
+        xmlns:xlink="http://www.w3.org/1999/xlink""#;
+
+    const PNG: &[u8] = b"\x89PNG\r\n\x1a\nfake";
+
+    /// Build an ODT package around a body, automatic styles and `styles.xml` content.
+    fn odt(automatic_styles: &str, body: &str, styles: &str) -> Vec<u8> {
+        let content = format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <office:document-content {NS} office:version="1.3">
+              <office:automatic-styles>{automatic_styles}</office:automatic-styles>
+              <office:body><office:text>{body}</office:text></office:body>
+            </office:document-content>"#
+        );
+        let styles = format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <office:document-styles {NS} office:version="1.3">{styles}</office:document-styles>"#
+        );
+        let meta = format!(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <office:document-meta {NS} office:version="1.3"><office:meta>
+              <dc:title>Fixture</dc:title><meta:initial-creator>Ada</meta:initial-creator>
+            </office:meta></office:document-meta>"#
+        );
+
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let options = zip::write::SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Stored);
+        let parts: [(&str, &[u8]); 5] = [
+            ("mimetype", b"application/vnd.oasis.opendocument.text"),
+            ("content.xml", content.as_bytes()),
+            ("styles.xml", styles.as_bytes()),
+            ("meta.xml", meta.as_bytes()),
+            ("Pictures/chart.png", PNG),
+        ];
+        for (path, data) in parts {
+            zip.start_file(path, options).unwrap();
+            zip.write_all(data).unwrap();
+        }
+        zip.finish().unwrap().into_inner()
+    }
+
+    fn parse(data: Vec<u8>) -> Document {
+        OdtParser::from_bytes(data).unwrap().parse().unwrap()
+    }
+
+    fn paragraphs(doc: &Document) -> Vec<&Paragraph> {
+        doc.sections[0]
+            .content
+            .iter()
+            .filter_map(|b| match b {
+                Block::Paragraph(p) => Some(p),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_headings_and_character_formatting() {
+        let doc = parse(odt(
+            r#"<style:style style:name="P1" style:family="paragraph" style:parent-style-name="Standard">
+                 <style:paragraph-properties fo:text-align="center"/>
+               </style:style>
+               <style:style style:name="T1" style:family="text">
+                 <style:text-properties fo:font-weight="bold"/>
+               </style:style>
+               <style:style style:name="T2" style:family="text">
+                 <style:text-properties fo:font-style="italic" style:text-underline-style="solid"/>
+               </style:style>"#,
+            r#"<text:h text:style-name="Heading_20_1" text:outline-level="1">Title</text:h>
+               <text:h text:style-name="Heading_20_2">Sub</text:h>
+               <text:p text:style-name="P1">Plain <text:span text:style-name="T1">bold</text:span>
+                 <text:span text:style-name="T2">under</text:span>
+                 <text:a xlink:href="https://example.com">link</text:a></text:p>"#,
+            r#"<office:styles>
+                 <style:style style:name="Standard" style:family="paragraph"/>
+                 <style:style style:name="Heading_20_1" style:display-name="Heading 1" style:family="paragraph"
+                     style:default-outline-level="1">
+                   <style:text-properties fo:font-weight="bold"/>
+                 </style:style>
+                 <style:style style:name="Heading_20_2" style:display-name="Heading 2" style:family="paragraph"
+                     style:default-outline-level="2"/>
+               </office:styles>"#,
+        ));
+
+        assert_eq!(doc.format, crate::detect::FormatType::Odt);
+        let paras = paragraphs(&doc);
+        assert_eq!(paras[0].heading, HeadingLevel::H1);
+        assert_eq!(paras[0].style_name.as_deref(), Some("Heading 1"));
+        assert!(
+            !paras[0].runs[0].style.bold,
+            "heading style emphasis is not content"
+        );
+        assert_eq!(paras[1].heading, HeadingLevel::H2);
+
+        let body = paras[2];
+        assert_eq!(body.alignment, TextAlignment::Center);
+        assert_eq!(body.style_id.as_deref(), Some("Standard"));
+        assert_eq!(body.plain_text(), "Plain bold under link");
+        let bold = body.runs.iter().find(|r| r.text.trim() == "bold").unwrap();
+        assert!(bold.style.bold);
+        let under = body.runs.iter().find(|r| r.text.trim() == "under").unwrap();
+        assert!(under.style.italic && under.style.underline);
+        let link = body.runs.iter().find(|r| r.text.trim() == "link").unwrap();
+        assert_eq!(link.hyperlink.as_deref(), Some("https://example.com"));
+    }
+
+    #[test]
+    fn test_numbered_lists_follow_their_list_style() {
+        let doc = parse(odt(
+            r#"<text:list-style style:name="L1">
+                 <text:list-level-style-number text:level="1" style:num-format="1"/>
+                 <text:list-level-style-bullet text:level="2" text:bullet-char="•"/>
+               </text:list-style>"#,
+            r#"<text:list text:style-name="L1">
+                 <text:list-item><text:p>first</text:p>
+                   <text:list><text:list-item><text:p>nested</text:p></text:list-item></text:list>
+                 </text:list-item>
+                 <text:list-item><text:p>second</text:p></text:list-item>
+               </text:list>
+               <text:p>between</text:p>
+               <text:list text:style-name="L1" text:continue-numbering="true">
+                 <text:list-item><text:p>third</text:p></text:list-item>
+               </text:list>"#,
+            "",
+        ));
+
+        let items: Vec<_> = paragraphs(&doc)
+            .into_iter()
+            .filter_map(|p| {
+                let info = p.list_info.as_ref()?;
+                Some((info.list_type, info.level, info.number, p.plain_text()))
+            })
+            .collect();
+        assert_eq!(
+            items,
+            vec![
+                (ListType::Numbered, 0, Some(1), "first".to_string()),
+                (ListType::Bullet, 1, None, "nested".to_string()),
+                (ListType::Numbered, 0, Some(2), "second".to_string()),
+                (ListType::Numbered, 0, Some(3), "third".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tables_with_spans() {
+        let doc = parse(odt(
+            "",
+            r#"<table:table table:name="T">
+                 <table:table-column table:number-columns-repeated="2"/>
+                 <table:table-row>
+                   <table:table-cell table:number-rows-spanned="2"><text:p>tall</text:p></table:table-cell>
+                   <table:table-cell><text:p>a</text:p></table:table-cell>
+                 </table:table-row>
+                 <table:table-row>
+                   <table:covered-table-cell/>
+                   <table:table-cell><text:p>b</text:p></table:table-cell>
+                 </table:table-row>
+               </table:table>"#,
+            "",
+        ));
+
+        let Block::Table(table) = &doc.sections[0].content[0] else {
+            panic!("expected a table");
+        };
+        assert_eq!(table.rows[0].cells[0].row_span, 2);
+        assert_eq!(table.rows[1].cells.len(), 1);
+        assert_eq!(table.rows[1].cells[0].plain_text(), "b");
+    }
+
+    #[test]
+    fn test_notes_images_metadata_and_page_regions() {
+        let doc = parse(odt(
+            "",
+            r#"<text:p>Fact<text:note text:id="ftn1" text:note-class="footnote">
+                 <text:note-citation>1</text:note-citation>
+                 <text:note-body><text:p>Cited.</text:p></text:note-body></text:note></text:p>
+               <text:p><draw:frame svg:width="1in" svg:height="0.5in">
+                 <draw:image xlink:href="Pictures/chart.png"/><svg:title>Chart</svg:title>
+               </draw:frame></text:p>"#,
+            r#"<office:master-styles>
+                 <style:master-page style:name="Standard">
+                   <style:header><text:p>Running head</text:p></style:header>
+                   <style:footer><text:p>Page <text:page-number>1</text:page-number></text:p></style:footer>
+                 </style:master-page>
+               </office:master-styles>"#,
+        ));
+
+        assert_eq!(doc.metadata.title.as_deref(), Some("Fixture"));
+        assert_eq!(doc.metadata.author.as_deref(), Some("Ada"));
+
+        let section = &doc.sections[0];
+        let paras = paragraphs(&doc);
+        assert_eq!(paras[0].plain_text(), "Fact[^1]");
+        assert_eq!(paras.last().unwrap().plain_text(), "[^1]: Cited.");
+
+        let image = &paras[1].images[0];
+        assert_eq!(image.resource_id, "chart.png");
+        assert_eq!(image.alt_text.as_deref(), Some("Chart"));
+        assert_eq!(image.width, Some(914_400));
+        assert_eq!(doc.resources["chart.png"].data, PNG);
+
+        assert_eq!(
+            section.header.as_ref().unwrap()[0].plain_text(),
+            "Running head"
+        );
+        assert_eq!(section.footer.as_ref().unwrap()[0].plain_text(), "Page 1");
+    }
+
+    #[test]
+    fn test_missing_body_is_a_missing_component() {
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let options = zip::write::SimpleFileOptions::default();
+        zip.start_file("content.xml", options).unwrap();
+        zip.write_all(format!("<office:document-content {NS}/>").as_bytes())
+            .unwrap();
+        let data = zip.finish().unwrap().into_inner();
+
+        let err = OdtParser::from_bytes(data).unwrap().parse().unwrap_err();
+        assert!(
+            matches!(err, crate::Error::MissingComponent(_)),
+            "got: {err}"
+        );
+    }
+}
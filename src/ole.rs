@@ -0,0 +1,365 @@
+//! Shared plumbing for the legacy binary Office formats.
+//!
+//! Word, Excel and PowerPoint 97-2003 files are all OLE2 compound files (CFB) holding a
+//! handful of well-known streams. The record layouts differ per format, but opening the
+//! container, pulling a stream out of it, decoding 8-bit text and reading the
+//! `\u{5}SummaryInformation` property set are the same job in every parser — so they
+//! live here rather than being copied three times.
+//!
+//! Every reader in this module is bounds-checked and returns `None` (or skips) on short
+//! input instead of panicking: these files are frequently truncated or written by
+//! third-party tools, and a damaged property set must not cost the caller the body text.
+
+use std::io::{Cursor, Read};
+
+use crate::error::{Error, Result};
+use crate::model::Metadata;
+
+/// An in-memory compound file.
+pub(crate) type CompoundFile = cfb::CompoundFile<Cursor<Vec<u8>>>;
+
+/// Stream holding the standard document properties (title, author, dates, counts).
+const SUMMARY_INFORMATION_STREAM: &str = "/\u{5}SummaryInformation";
+
+/// Open a compound file from bytes.
+///
+/// Detection has already seen the CFB header by the time a parser gets here, so a
+/// directory that will not parse is damaged data, not an unknown format.
+pub(crate) fn open_compound(data: Vec<u8>) -> Result<CompoundFile> {
+    cfb::CompoundFile::open(Cursor::new(data))
+        .map_err(|e| Error::InvalidData(format!("OLE/CFB container: {e}")))
+}
+
+/// Read a whole stream, reporting its absence as a missing component.
+pub(crate) fn read_stream(container: &mut CompoundFile, name: &str) -> Result<Vec<u8>> {
+    read_stream_optional(container, name)?
+        .ok_or_else(|| Error::MissingComponent(name.trim_start_matches('/').to_string()))
+}
+
+/// Read a whole stream, or `None` if the container does not hold it.
+pub(crate) fn read_stream_optional(
+    container: &mut CompoundFile,
+    name: &str,
+) -> Result<Option<Vec<u8>>> {
+    if !container.is_stream(name) {
+        return Ok(None);
+    }
+    let mut stream = container.open_stream(name)?;
+    let mut data = Vec::new();
+    stream.read_to_end(&mut data)?;
+    Ok(Some(data))
+}
+
+/// Little-endian `u16` at `offset`, or `None` past the end.
+pub(crate) fn u16_at(data: &[u8], offset: usize) -> Option<u16> {
+    let bytes = data.get(offset..offset.checked_add(2)?)?;
+    Some(u16::from_le_bytes([bytes[0], bytes[1]]))
+}
+
+/// Little-endian `i16` at `offset`, or `None` past the end.
+pub(crate) fn i16_at(data: &[u8], offset: usize) -> Option<i16> {
+    u16_at(data, offset).map(|v| v as i16)
+}
+
+/// Little-endian `u32` at `offset`, or `None` past the end.
+pub(crate) fn u32_at(data: &[u8], offset: usize) -> Option<u32> {
+    let bytes = data.get(offset..offset.checked_add(4)?)?;
+    Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+/// Little-endian `i32` at `offset`, or `None` past the end.
+pub(crate) fn i32_at(data: &[u8], offset: usize) -> Option<i32> {
+    u32_at(data, offset).map(|v| v as i32)
+}
+
+/// Decode UTF-16LE code units, replacing unpaired surrogates.
+pub(crate) fn decode_utf16le(bytes: &[u8]) -> String {
+    let units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .collect();
+    String::from_utf16_lossy(&units)
+}
+
+/// Decode Windows-1252 text, the code page of every "compressed" 8-bit string the
+/// binary formats store.
+///
+/// Latin-1 agrees everywhere except `0x80..=0x9F`, which is exactly where the curly
+/// quotes, dashes and the euro sign live — the characters a Latin-1 shortcut would turn
+/// into invisible C1 controls.
+pub(crate) fn decode_cp1252(bytes: &[u8]) -> String {
+    bytes.iter().map(|&b| cp1252_char(b)).collect()
+}
+
+/// Map one Windows-1252 byte to its character.
+pub(crate) fn cp1252_char(byte: u8) -> char {
+    const HIGH: [char; 32] = [
+        '\u{20AC}', '\u{81}', '\u{201A}', '\u{0192}', '\u{201E}', '\u{2026}', '\u{2020}',
+        '\u{2021}', '\u{02C6}', '\u{2030}', '\u{0160}', '\u{2039}', '\u{0152}', '\u{8D}',
+        '\u{017D}', '\u{8F}', '\u{90}', '\u{2018}', '\u{2019}', '\u{201C}', '\u{201D}', '\u{2022}',
+        '\u{2013}', '\u{2014}', '\u{02DC}', '\u{2122}', '\u{0161}', '\u{203A}', '\u{0153}',
+        '\u{9D}', '\u{017E}', '\u{0178}',
+    ];
+    match byte {
+        0x80..=0x9F => HIGH[(byte - 0x80) as usize],
+        _ => byte as char,
+    }
+}
+
+/// Read the standard document properties from `\u{5}SummaryInformation`.
+///
+/// Best-effort by design: the property set is optional, and a damaged one yields
+/// whatever properties could be read rather than an error.
+pub(crate) fn parse_summary_information(container: &mut CompoundFile) -> Metadata {
+    match read_stream_optional(container, SUMMARY_INFORMATION_STREAM) {
+        Ok(Some(data)) => parse_property_set(&data),
+        _ => Metadata::default(),
+    }
+}
+
+/// Property identifiers of the SummaryInformation property set (MS-OLEPS 2.25.1).
+mod pid {
+    pub const CODEPAGE: u32 = 0x01;
+    pub const TITLE: u32 = 0x02;
+    pub const SUBJECT: u32 = 0x03;
+    pub const AUTHOR: u32 = 0x04;
+    pub const KEYWORDS: u32 = 0x05;
+    pub const COMMENTS: u32 = 0x06;
+    pub const LAST_AUTHOR: u32 = 0x08;
+    pub const CREATE_DTM: u32 = 0x0C;
+    pub const LAST_SAVE_DTM: u32 = 0x0D;
+    pub const PAGE_COUNT: u32 = 0x0E;
+    pub const WORD_COUNT: u32 = 0x0F;
+    pub const APP_NAME: u32 = 0x12;
+}
+
+/// Property value types we understand (MS-OLEPS 2.15).
+const VT_I2: u32 = 0x0002;
+const VT_I4: u32 = 0x0003;
+const VT_LPSTR: u32 = 0x001E;
+const VT_LPWSTR: u32 = 0x001F;
+const VT_FILETIME: u32 = 0x0040;
+
+/// A decoded property value.
+enum PropertyValue {
+    Int(i64),
+    Text(String),
+    Time(u64),
+}
+
+/// Parse the first section of a property set stream into [`Metadata`].
+fn parse_property_set(data: &[u8]) -> Metadata {
+    let mut meta = Metadata::default();
+
+    // PropertySetStream header: 28 bytes, then (FMTID, offset) pairs.
+    let Some(section) = u32_at(data, 44).map(|v| v as usize) else {
+        return meta;
+    };
+    let Some(count) = u32_at(data, section + 4) else {
+        return meta;
+    };
+
+    // The code page governs every VT_LPSTR in the section, so find it first.
+    let entries: Vec<(u32, usize)> = (0..count.min(1024) as usize)
+        .filter_map(|i| {
+            let at = section + 8 + i * 8;
+            Some((u32_at(data, at)?, section + u32_at(data, at + 4)? as usize))
+        })
+        .collect();
+    let codepage = entries
+        .iter()
+        .find(|(id, _)| *id == pid::CODEPAGE)
+        .and_then(|&(_, offset)| match read_property(data, offset, 1252) {
+            Some(PropertyValue::Int(cp)) => Some(cp as u16),
+            _ => None,
+        })
+        .unwrap_or(1252);
+
+    for (id, offset) in entries {
+        let Some(value) = read_property(data, offset, codepage) else {
+            continue;
+        };
+        match (id, value) {
+            (pid::TITLE, PropertyValue::Text(s)) => meta.title = non_empty(s),
+            (pid::SUBJECT, PropertyValue::Text(s)) => meta.subject = non_empty(s),
+            (pid::AUTHOR, PropertyValue::Text(s)) => meta.author = non_empty(s),
+            (pid::COMMENTS, PropertyValue::Text(s)) => meta.description = non_empty(s),
+            (pid::LAST_AUTHOR, PropertyValue::Text(s)) => meta.last_modified_by = non_empty(s),
+            (pid::APP_NAME, PropertyValue::Text(s)) => meta.application = non_empty(s),
+            (pid::KEYWORDS, PropertyValue::Text(s)) => {
+                meta.keywords = s
+                    .split([',', ';'])
+                    .map(|k| k.trim().to_string())
+                    .filter(|k| !k.is_empty())
+                    .collect();
+            }
+            (pid::CREATE_DTM, PropertyValue::Time(t)) => meta.created = filetime_to_iso8601(t),
+            (pid::LAST_SAVE_DTM, PropertyValue::Time(t)) => meta.modified = filetime_to_iso8601(t),
+            (pid::PAGE_COUNT, PropertyValue::Int(n)) if n > 0 => meta.page_count = Some(n as u32),
+            (pid::WORD_COUNT, PropertyValue::Int(n)) if n > 0 => meta.word_count = Some(n as u32),
+            _ => {}
+        }
+    }
+
+    meta
+}
+
+/// Read one typed property value at `offset`.
+fn read_property(data: &[u8], offset: usize, codepage: u16) -> Option<PropertyValue> {
+    let vt = u32_at(data, offset)? & 0xFFFF;
+    let body = offset + 4;
+    match vt {
+        VT_I2 => Some(PropertyValue::Int(i16_at(data, body)? as i64)),
+        VT_I4 => Some(PropertyValue::Int(i32_at(data, body)? as i64)),
+        VT_FILETIME => {
+            let low = u32_at(data, body)? as u64;
+            let high = u32_at(data, body + 4)? as u64;
+            Some(PropertyValue::Time((high << 32) | low))
+        }
+        VT_LPSTR => {
+            let len = u32_at(data, body)? as usize;
+            let bytes = data.get(body + 4..(body + 4).checked_add(len)?)?;
+            let text = match codepage {
+                1200 => decode_utf16le(bytes),
+                65001 => String::from_utf8_lossy(bytes).into_owned(),
+                _ => decode_cp1252(bytes),
+            };
+            Some(PropertyValue::Text(text.trim_end_matches('\0').to_string()))
+        }
+        VT_LPWSTR => {
+            let chars = u32_at(data, body)? as usize;
+            let bytes = data.get(body + 4..(body + 4).checked_add(chars.checked_mul(2)?)?)?;
+            Some(PropertyValue::Text(
+                decode_utf16le(bytes).trim_end_matches('\0').to_string(),
+            ))
+        }
+        _ => None,
+    }
+}
+
+fn non_empty(s: String) -> Option<String> {
+    let trimmed = s.trim();
+    (!trimmed.is_empty()).then(|| trimmed.to_string())
+}
+
+/// Convert a Windows FILETIME (100 ns ticks since 1601-01-01 UTC) to the
+/// `YYYY-MM-DDTHH:MM:SSZ` form OOXML core properties use, so metadata reads the same
+/// whichever generation of file it came from.
+///
+/// A zero FILETIME means "not set" and yields `None`.
+pub(crate) fn filetime_to_iso8601(filetime: u64) -> Option<String> {
+    const TICKS_PER_SECOND: u64 = 10_000_000;
+    const SECONDS_1601_TO_1970: i64 = 11_644_473_600;
+
+    if filetime == 0 {
+        return None;
+    }
+    let unix = (filetime / TICKS_PER_SECOND) as i64 - SECONDS_1601_TO_1970;
+    Some(crate::model::iso8601_from_unix(unix))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cp1252_maps_the_c1_range() {
+        assert_eq!(decode_cp1252(b"caf\xe9"), "café");
+        assert_eq!(
+            decode_cp1252(b"\x93quoted\x94 \x96 \x80"),
+            "\u{201C}quoted\u{201D} \u{2013} €"
+        );
+    }
+
+    #[test]
+    fn test_filetime_to_iso8601() {
+        // 2024-03-15T12:34:56Z
+        let unix: u64 = 1_710_506_096;
+        let filetime = (unix + 11_644_473_600) * 10_000_000;
+        assert_eq!(
+            filetime_to_iso8601(filetime).as_deref(),
+            Some("2024-03-15T12:34:56Z")
+        );
+        assert_eq!(filetime_to_iso8601(0), None);
+    }
+
+    #[test]
+    fn test_readers_are_bounds_checked() {
+        assert_eq!(u16_at(&[1], 0), None);
+        assert_eq!(u32_at(&[1, 2, 3, 4], 1), None);
+        assert_eq!(u32_at(&[1, 0, 0, 0], 0), Some(1));
+        assert_eq!(u16_at(&[], usize::MAX), None);
+    }
+
+    /// Build a one-section SummaryInformation stream from `(pid, vt, body)` triples.
+    fn summary_information(props: &[(u32, u32, Vec<u8>)]) -> Vec<u8> {
+        let mut values = Vec::new();
+        let mut index = Vec::new();
+        let header_len = 8 + props.len() * 8;
+        for (id, vt, body) in props {
+            index.push((*id, header_len + values.len()));
+            values.extend_from_slice(&vt.to_le_bytes());
+            values.extend_from_slice(body);
+            while values.len() % 4 != 0 {
+                values.push(0);
+            }
+        }
+
+        let mut section = Vec::new();
+        section.extend_from_slice(&((header_len + values.len()) as u32).to_le_bytes());
+        section.extend_from_slice(&(props.len() as u32).to_le_bytes());
+        for (id, offset) in index {
+            section.extend_from_slice(&id.to_le_bytes());
+            section.extend_from_slice(&(offset as u32).to_le_bytes());
+        }
+        section.extend_from_slice(&values);
+
+        let mut stream = vec![0xFE, 0xFF, 0, 0, 0, 0, 0, 0];
+        stream.extend_from_slice(&[0u8; 16]); // CLSID
+        stream.extend_from_slice(&1u32.to_le_bytes());
+        stream.extend_from_slice(&[0u8; 16]); // FMTID
+        stream.extend_from_slice(&48u32.to_le_bytes());
+        stream.extend_from_slice(&section);
+        stream
+    }
+
+    fn lpstr(s: &str) -> Vec<u8> {
+        let mut body = ((s.len() + 1) as u32).to_le_bytes().to_vec();
+        body.extend_from_slice(s.as_bytes());
+        body.push(0);
+        body
+    }
+
+    #[test]
+    fn test_property_set_fields() {
+        let created = (1_710_506_096u64 + 11_644_473_600) * 10_000_000;
+        let mut filetime = (created as u32).to_le_bytes().to_vec();
+        filetime.extend_from_slice(&((created >> 32) as u32).to_le_bytes());
+
+        let data = summary_information(&[
+            (pid::CODEPAGE, VT_I2, 1252u16.to_le_bytes().to_vec()),
+            (pid::TITLE, VT_LPSTR, lpstr("Quarterly report")),
+            (pid::AUTHOR, VT_LPSTR, lpstr("Kim")),
+            (pid::KEYWORDS, VT_LPSTR, lpstr("alpha; beta")),
+            (pid::CREATE_DTM, VT_FILETIME, filetime),
+            (pid::PAGE_COUNT, VT_I4, 3i32.to_le_bytes().to_vec()),
+        ]);
+
+        let meta = parse_property_set(&data);
+        assert_eq!(meta.author.as_deref(), Some("Kim"));
+        assert_eq!(meta.keywords, vec!["alpha", "beta"]);
+        assert_eq!(meta.created.as_deref(), Some("2024-03-15T12:34:56Z"));
+        assert_eq!(meta.page_count, Some(3));
+        assert_eq!(meta.title.as_deref(), Some("Quarterly report"));
+    }
+
+    #[test]
+    fn test_damaged_property_set_yields_defaults() {
+        let meta = parse_property_set(&[0xFE, 0xFF, 0, 0]);
+        assert!(meta.title.is_none());
+        let mut truncated = summary_information(&[(pid::TITLE, VT_LPSTR, lpstr("Title"))]);
+        truncated.truncate(70);
+        let meta = parse_property_set(&truncated);
+        assert!(meta.title.is_none());
+    }
+}
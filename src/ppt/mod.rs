@@ -0,0 +1,26 @@
+//! PPT (PowerPoint 97-2003) presentation parser.
+//!
+//! This module provides parsing for legacy Microsoft PowerPoint presentations
+//! in the binary (.ppt) format: an OLE2 compound file whose `PowerPoint
+//! Document` stream holds a tree of records — the slide list with its
+//! placeholder text, one container per slide and notes page with its
+//! OfficeArt drawing — and whose `Pictures` stream holds the images.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use undoc::ppt::PptParser;
+//!
+//! let mut parser = PptParser::open("presentation.ppt")?;
+//! let doc = parser.parse()?;
+//!
+//! for section in &doc.sections {
+//!     println!("{}: {} blocks", section.name.as_deref().unwrap_or(""), section.content.len());
+//! }
+//! # Ok::<(), undoc::Error>(())
+//! ```
+
+mod parser;
+mod records;
+
+pub use parser::PptParser;
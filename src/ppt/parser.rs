@@ -0,0 +1,989 @@
+//! PPT parser implementation.
+
+use std::collections::{HashMap, HashSet};
+
+use crate::error::{Error, Result};
+use crate::model::{
+    Block, Document, HeadingLevel, Metadata, Paragraph, Resource, Section, TextRun,
+};
+use crate::ole::{self, i16_at, i32_at, u16_at, u32_at, CompoundFile};
+
+use super::records::{rt, Children, Record, HEADER_LEN};
+
+/// `CurrentUserAtom.headerToken` of a file whose document stream is encrypted.
+const ENCRYPTED_HEADER_TOKEN: u32 = 0xF3D1_C4DF;
+
+/// `UserEditAtom` bodies this long carry `encryptSessionPersistIdRef`.
+const ENCRYPTED_USER_EDIT_LEN: usize = 32;
+
+/// `SlideListWithTextContainer` instances.
+const SLIDE_LIST: u16 = 0;
+const NOTES_LIST: u16 = 2;
+
+/// `TextHeaderAtom.textType` values (MS-PPT 2.13.33).
+mod text_type {
+    pub const TITLE: u32 = 0;
+    pub const NOTES: u32 = 2;
+    pub const CENTER_BODY: u32 = 5;
+    pub const CENTER_TITLE: u32 = 6;
+}
+
+/// OfficeArt shape property ids (MS-ODRAW 2.3).
+mod pid {
+    pub const PIB: u16 = 0x0104;
+    pub const WZ_NAME: u16 = 0x0380;
+    pub const WZ_DESCRIPTION: u16 = 0x0381;
+}
+
+/// EMUs per PowerPoint master unit (576 per inch).
+const EMU_PER_MASTER_UNIT_X2: u64 = 3175;
+
+/// A block of text and the placeholder kind it was typed as.
+#[derive(Debug, Clone, Default)]
+struct TextBlock {
+    kind: u32,
+    text: String,
+}
+
+/// A slide or notes page listed in a `SlideListWithTextContainer`.
+#[derive(Debug, Clone, Default)]
+struct SlideEntry {
+    persist_id: u32,
+    /// `slideId` for slides, `notesId` for notes pages.
+    id: u32,
+    /// Placeholder text stored with the list, referenced from the drawing by index.
+    outline: Vec<TextBlock>,
+}
+
+/// Parser for legacy PowerPoint 97-2003 (.ppt) presentations.
+pub struct PptParser {
+    container: CompoundFile,
+    stream: Vec<u8>,
+    /// Persist object id → stream offset, newest edit winning.
+    persist: HashMap<u32, u32>,
+    slides: Vec<SlideEntry>,
+    notes: Vec<SlideEntry>,
+    /// Picture of each 1-based BStore index, as `(resource id, data)`.
+    pictures: Vec<Option<(String, Vec<u8>)>>,
+}
+
+impl PptParser {
+    /// Open a PPT file for parsing.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn open(path: impl AsRef<std::path::Path>) -> Result<Self> {
+        let data = std::fs::read(path)?;
+        Self::from_bytes(data)
+    }
+
+    /// Create a parser from bytes.
+    pub fn from_bytes(data: Vec<u8>) -> Result<Self> {
+        let mut container = ole::open_compound(data)?;
+        let stream = ole::read_stream(&mut container, "/PowerPoint Document")?;
+        let current_user = ole::read_stream_optional(&mut container, "/Current User")?;
+        let pictures_stream = ole::read_stream_optional(&mut container, "/Pictures")?;
+
+        let last_edit = match current_user.as_deref().and_then(current_edit_offset) {
+            Some(Ok(offset)) => offset,
+            Some(Err(e)) => return Err(e),
+            // No usable `Current User`: the last edit is still the last one written.
+            None => last_user_edit(&stream)
+                .ok_or_else(|| Error::MissingComponent("PowerPoint UserEditAtom".into()))?,
+        };
+
+        let (persist, doc_ref) = read_persist_directory(&stream, last_edit)?;
+        let document = persist
+            .get(&doc_ref)
+            .and_then(|&offset| Record::expect(&stream, offset as usize, rt::DOCUMENT))
+            .ok_or_else(|| Error::MissingComponent("PowerPoint DocumentContainer".into()))?;
+
+        let mut slides = Vec::new();
+        let mut notes = Vec::new();
+        let mut pictures = Vec::new();
+        for child in document.children() {
+            match (child.kind, child.instance) {
+                (rt::SLIDE_LIST_WITH_TEXT, SLIDE_LIST) => slides = read_slide_list(child),
+                (rt::SLIDE_LIST_WITH_TEXT, NOTES_LIST) => notes = read_slide_list(child),
+                (rt::PP_DRAWING_GROUP, _) => {
+                    pictures = read_pictures(child, pictures_stream.as_deref().unwrap_or(&[]))
+                }
+                _ => {}
+            }
+        }
+
+        Ok(Self {
+            container,
+            stream,
+            persist,
+            slides,
+            notes,
+            pictures,
+        })
+    }
+
+    /// Parse the presentation and return a Document model.
+    pub fn parse(&mut self) -> Result<Document> {
+        let mut doc = Document::new();
+        doc.format = crate::detect::FormatType::Ppt;
+        doc.metadata = self.parse_metadata();
+
+        for (id, data) in self.pictures.iter().flatten() {
+            doc.add_resource(id.clone(), Resource::image(data.clone(), Some(id.clone())));
+        }
+
+        for (idx, slide) in self.slides.iter().enumerate() {
+            doc.add_section(self.parse_slide_as_section(idx, slide));
+        }
+
+        Ok(doc)
+    }
+
+    /// Get the number of slides.
+    pub fn slide_count(&self) -> usize {
+        self.slides.len()
+    }
+
+    fn parse_metadata(&mut self) -> Metadata {
+        let mut meta = ole::parse_summary_information(&mut self.container);
+        meta.page_count = Some(self.slides.len() as u32);
+        meta
+    }
+
+    /// Parse a single slide into a Section.
+    fn parse_slide_as_section(&self, idx: usize, slide: &SlideEntry) -> Section {
+        let mut section = Section::with_name(idx, format!("Slide {}", idx + 1));
+
+        let Some(record) = self.persist_record(slide.persist_id, rt::SLIDE) else {
+            return section;
+        };
+        for block in self.drawing_blocks(record, &slide.outline, None) {
+            section.add_block(block);
+        }
+
+        let notes_id = record
+            .child(rt::SLIDE_ATOM)
+            .and_then(|atom| u32_at(atom.body, 16))
+            .filter(|&id| id != 0);
+        if let Some(entry) = notes_id.and_then(|id| self.notes.iter().find(|n| n.id == id)) {
+            if let Some(notes) = self.persist_record(entry.persist_id, rt::NOTES) {
+                let paragraphs: Vec<Paragraph> = self
+                    .drawing_blocks(notes, &entry.outline, Some(text_type::NOTES))
+                    .into_iter()
+                    .filter_map(|block| match block {
+                        Block::Paragraph(p) => Some(p),
+                        _ => None,
+                    })
+                    .collect();
+                if !paragraphs.is_empty() {
+                    section.notes = Some(paragraphs);
+                }
+            }
+        }
+
+        section
+    }
+
+    fn persist_record(&self, persist_id: u32, kind: u16) -> Option<Record<'_>> {
+        let offset = *self.persist.get(&persist_id)?;
+        Record::expect(&self.stream, offset as usize, kind)
+    }
+
+    /// Turn the shapes of a slide or notes drawing into blocks, in drawing order.
+    ///
+    /// Placeholder text lives in the slide list and is pulled in where the drawing
+    /// refers to it; any the drawing never refers to follows at the end. With
+    /// `only_kind`, text of other kinds and pictures are left out — the notes page
+    /// repeats the slide image and its header and footer, none of which are notes.
+    fn drawing_blocks(
+        &self,
+        slide: Record<'_>,
+        outline: &[TextBlock],
+        only_kind: Option<u32>,
+    ) -> Vec<Block> {
+        let mut shapes = Vec::new();
+        if let Some(group) = slide
+            .child(rt::PP_DRAWING)
+            .and_then(|d| d.child(rt::DG_CONTAINER))
+            .and_then(|dg| dg.child(rt::SPGR_CONTAINER))
+        {
+            collect_shapes(group, &mut shapes);
+        }
+
+        let wanted = |kind: u32| only_kind.is_none_or(|k| k == kind);
+        let mut used = HashSet::new();
+        let mut blocks = Vec::new();
+        for shape in shapes {
+            for text in shape_text(shape, outline, &mut used) {
+                if wanted(text.kind) {
+                    blocks.extend(text_paragraphs(&text).into_iter().map(Block::Paragraph));
+                }
+            }
+            if only_kind.is_none() {
+                blocks.extend(self.shape_picture(shape));
+            }
+        }
+        for (i, text) in outline.iter().enumerate() {
+            if !used.contains(&i) && wanted(text.kind) {
+                blocks.extend(text_paragraphs(text).into_iter().map(Block::Paragraph));
+            }
+        }
+        blocks
+    }
+
+    /// The picture a shape fills itself with, if its blip could be read.
+    fn shape_picture(&self, shape: Record<'_>) -> Option<Block> {
+        let props = shape.child(rt::FOPT).map(read_properties)?;
+        let pib = props.iter().find(|p| p.id == pid::PIB)?.value;
+        let (resource_id, _) = self
+            .pictures
+            .get((pib as usize).checked_sub(1)?)?
+            .as_ref()?;
+
+        let text_prop = |id| {
+            props
+                .iter()
+                .find(|p| p.id == id)
+                .map(|p| {
+                    ole::decode_utf16le(p.complex)
+                        .trim_end_matches('\0')
+                        .to_string()
+                })
+                .filter(|s| !s.is_empty())
+        };
+        let (width, height) = shape
+            .child(rt::CLIENT_ANCHOR)
+            .and_then(|anchor| anchor_size(anchor.body))
+            .unzip();
+
+        Some(Block::Image {
+            resource_id: resource_id.clone(),
+            alt_text: text_prop(pid::WZ_DESCRIPTION).or_else(|| text_prop(pid::WZ_NAME)),
+            width,
+            height,
+        })
+    }
+}
+
+/// Read `CurrentUserAtom.offsetToCurrentEdit`, or report the stream as encrypted.
+fn current_edit_offset(current_user: &[u8]) -> Option<Result<u32>> {
+    let token = u32_at(current_user, HEADER_LEN + 4)?;
+    if token == ENCRYPTED_HEADER_TOKEN {
+        return Some(Err(Error::Encrypted));
+    }
+    u32_at(current_user, HEADER_LEN + 8).map(Ok)
+}
+
+/// Offset of the last top-level `UserEditAtom` in the stream.
+fn last_user_edit(stream: &[u8]) -> Option<u32> {
+    let mut records = Children::of(stream);
+    let mut last = None;
+    loop {
+        let at = records.offset();
+        let Some(record) = records.next() else {
+            return last;
+        };
+        if record.kind == rt::USER_EDIT_ATOM {
+            last = Some(at as u32);
+        }
+    }
+}
+
+/// Follow the chain of `UserEditAtom`s from the newest, merging their persist
+/// directories so later edits override earlier ones.
+///
+/// Returns the directory and the persist id of the `DocumentContainer`.
+fn read_persist_directory(stream: &[u8], last_edit: u32) -> Result<(HashMap<u32, u32>, u32)> {
+    let mut persist = HashMap::new();
+    let mut doc_ref = None;
+    let mut visited = HashSet::new();
+    let mut offset = last_edit;
+
+    while visited.insert(offset) {
+        let Some(edit) = Record::expect(stream, offset as usize, rt::USER_EDIT_ATOM) else {
+            break;
+        };
+        if edit.body.len() >= ENCRYPTED_USER_EDIT_LEN {
+            return Err(Error::Encrypted);
+        }
+        doc_ref.get_or_insert(u32_at(edit.body, 16).unwrap_or(1));
+
+        if let Some(dir) = u32_at(edit.body, 12)
+            .and_then(|at| Record::expect(stream, at as usize, rt::PERSIST_DIRECTORY_ATOM))
+        {
+            let mut pos = 0;
+            while let Some(entry) = u32_at(dir.body, pos) {
+                let (first, count) = (entry & 0x000F_FFFF, entry >> 20);
+                for i in 0..count {
+                    let Some(at) = u32_at(dir.body, pos + 4 + i as usize * 4) else {
+                        break;
+                    };
+                    persist.entry(first + i).or_insert(at);
+                }
+                pos += 4 + count as usize * 4;
+            }
+        }
+
+        match u32_at(edit.body, 8) {
+            Some(previous) if previous != 0 => offset = previous,
+            _ => break,
+        }
+    }
+
+    match doc_ref {
+        Some(doc_ref) => Ok((persist, doc_ref)),
+        None => Err(Error::InvalidData(format!(
+            "no UserEditAtom at offset {last_edit} of the PowerPoint Document stream"
+        ))),
+    }
+}
+
+/// Read the slides (or notes pages) of a `SlideListWithTextContainer` with the
+/// placeholder text stored after each one.
+fn read_slide_list(list: Record<'_>) -> Vec<SlideEntry> {
+    let mut entries: Vec<SlideEntry> = Vec::new();
+    for child in list.children() {
+        match child.kind {
+            rt::SLIDE_PERSIST_ATOM => entries.push(SlideEntry {
+                persist_id: u32_at(child.body, 0).unwrap_or(0),
+                id: u32_at(child.body, 12).unwrap_or(0),
+                outline: Vec::new(),
+            }),
+            rt::TEXT_HEADER_ATOM => {
+                if let Some(entry) = entries.last_mut() {
+                    entry.outline.push(TextBlock {
+                        kind: u32_at(child.body, 0).unwrap_or(0),
+                        text: String::new(),
+                    });
+                }
+            }
+            rt::TEXT_CHARS_ATOM | rt::TEXT_BYTES_ATOM => {
+                if let Some(block) = entries.last_mut().and_then(|e| e.outline.last_mut()) {
+                    block.text.push_str(&decode_text(child));
+                }
+            }
+            _ => {}
+        }
+    }
+    entries
+}
+
+/// Gather the shape containers of a group, descending into nested groups.
+///
+/// Groups nest as deep as the file says, so they are walked with a stack of the
+/// groups being read rather than by recursion.
+fn collect_shapes<'a>(group: Record<'a>, shapes: &mut Vec<Record<'a>>) {
+    let mut groups = vec![group.children()];
+    while let Some(children) = groups.last_mut() {
+        match children.next() {
+            Some(child) if child.kind == rt::SP_CONTAINER => shapes.push(child),
+            Some(child) if child.kind == rt::SPGR_CONTAINER => groups.push(child.children()),
+            Some(_) => {}
+            None => {
+                groups.pop();
+            }
+        }
+    }
+}
+
+/// The text of one shape: its own text box, or the outline text it refers to.
+fn shape_text(
+    shape: Record<'_>,
+    outline: &[TextBlock],
+    used: &mut HashSet<usize>,
+) -> Vec<TextBlock> {
+    let mut blocks: Vec<TextBlock> = Vec::new();
+    for holder in shape
+        .children()
+        .filter(|r| r.kind == rt::CLIENT_TEXTBOX || r.kind == rt::CLIENT_DATA)
+    {
+        for child in holder.children() {
+            match child.kind {
+                rt::OUTLINE_TEXT_REF_ATOM => {
+                    let index = u32_at(child.body, 0).unwrap_or(0) as usize;
+                    if let Some(text) = outline.get(index) {
+                        if used.insert(index) {
+                            blocks.push(text.clone());
+                        }
+                    }
+                }
+                rt::TEXT_HEADER_ATOM => blocks.push(TextBlock {
+                    kind: u32_at(child.body, 0).unwrap_or(0),
+                    text: String::new(),
+                }),
+                rt::TEXT_CHARS_ATOM | rt::TEXT_BYTES_ATOM => {
+                    if let Some(block) = blocks.last_mut() {
+                        block.text.push_str(&decode_text(child));
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    blocks
+}
+
+/// Decode a `TextCharsAtom` (UTF-16) or `TextBytesAtom` (the low byte of each
+/// UTF-16 unit, so Latin-1 rather than a Windows code page).
+fn decode_text(atom: Record<'_>) -> String {
+    if atom.kind == rt::TEXT_CHARS_ATOM {
+        ole::decode_utf16le(atom.body)
+    } else {
+        atom.body.iter().map(|&b| b as char).collect()
+    }
+}
+
+/// Split text into paragraphs at `\r`; a vertical tab is a line break within one.
+///
+/// Titles become first-level headings and the title slide's subtitle a second-level
+/// one, as the PPTX parser maps `title` and `subTitle` placeholders.
+fn text_paragraphs(block: &TextBlock) -> Vec<Paragraph> {
+    let heading = match block.kind {
+        text_type::TITLE | text_type::CENTER_TITLE => HeadingLevel::H1,
+        text_type::CENTER_BODY => HeadingLevel::H2,
+        _ => HeadingLevel::None,
+    };
+
+    block
+        .text
+        .split('\r')
+        .filter_map(|line| {
+            let lines: Vec<&str> = line.split('\u{0B}').collect();
+            let runs = lines
+                .iter()
+                .enumerate()
+                .map(|(i, part)| {
+                    let text: String = part.chars().filter(|&c| c == '\t' || c >= ' ').collect();
+                    let mut run = TextRun::plain(text);
+                    run.line_break = i + 1 < lines.len();
+                    run
+                })
+                .collect();
+            let para = Paragraph {
+                runs,
+                heading,
+                ..Default::default()
+            };
+            (!para.is_empty()).then_some(para)
+        })
+        .collect()
+}
+
+/// One entry of an `OfficeArtFOPT` property table.
+struct Property<'a> {
+    id: u16,
+    value: u32,
+    /// The property's data when it is complex (a string, an array), else empty.
+    complex: &'a [u8],
+}
+
+/// Read an `OfficeArtFOPT`: `instance` fixed entries, then the complex data of the
+/// complex ones in the same order.
+fn read_properties(fopt: Record<'_>) -> Vec<Property<'_>> {
+    let count = fopt.instance as usize;
+    let mut complex_at = count * 6;
+    let mut props = Vec::with_capacity(count);
+    for i in 0..count {
+        let (Some(opid), Some(value)) = (u16_at(fopt.body, i * 6), u32_at(fopt.body, i * 6 + 2))
+        else {
+            break;
+        };
+        let mut complex: &[u8] = &[];
+        if opid & 0x8000 != 0 {
+            let end = complex_at
+                .saturating_add(value as usize)
+                .min(fopt.body.len());
+            complex = fopt.body.get(complex_at..end).unwrap_or_default();
+            complex_at = end;
+        }
+        props.push(Property {
+            id: opid & 0x3FFF,
+            value,
+            complex,
+        });
+    }
+    props
+}
+
+/// Width and height in EMUs of a `ClientAnchor`, which is either four `i16`s or four
+/// `i32`s in master units: top, left, right, bottom.
+fn anchor_size(body: &[u8]) -> Option<(u32, u32)> {
+    let (top, left, right, bottom) = if body.len() >= 16 {
+        (
+            i32_at(body, 0)?,
+            i32_at(body, 4)?,
+            i32_at(body, 8)?,
+            i32_at(body, 12)?,
+        )
+    } else {
+        (
+            i16_at(body, 0)? as i32,
+            i16_at(body, 2)? as i32,
+            i16_at(body, 4)? as i32,
+            i16_at(body, 6)? as i32,
+        )
+    };
+    let emu = |units: i32| (units.max(0) as u64 * EMU_PER_MASTER_UNIT_X2 / 2) as u32;
+    Some((emu(right - left), emu(bottom - top)))
+}
+
+/// Read every picture of the BStore, from the `Pictures` stream or embedded in its
+/// `OfficeArtFBSE` record.
+///
+/// Index `i` holds BStore entry `i + 1`, the number shapes refer to it by. Entries whose
+/// blip cannot be extracted stay `None` so the numbering holds — this includes
+/// compressed metafiles, which would need a DEFLATE decoder to be of any use.
+fn read_pictures(group: Record<'_>, pictures: &[u8]) -> Vec<Option<(String, Vec<u8>)>> {
+    let Some(store) = group
+        .child(rt::DGG_CONTAINER)
+        .and_then(|dgg| dgg.child(rt::BSTORE_CONTAINER))
+    else {
+        return Vec::new();
+    };
+
+    store
+        .children()
+        .filter(|r| r.kind == rt::FBSE)
+        .enumerate()
+        .map(|(i, fbse)| {
+            let name_len = fbse.body.get(33).copied().unwrap_or(0) as usize;
+            let blip = Record::at(fbse.body, 36 + name_len).or_else(|| {
+                let offset = u32_at(fbse.body, 28)?;
+                Record::at(pictures, offset as usize)
+            })?;
+            let (extension, data) = decode_blip(blip)?;
+            Some((format!("image{}.{}", i + 1, extension), data))
+        })
+        .collect()
+}
+
+/// Extract a blip's image data and the extension it should be saved under.
+fn decode_blip(blip: Record<'_>) -> Option<(&'static str, Vec<u8>)> {
+    // An odd instance means a second 16-byte UID follows the first.
+    let uids = if blip.instance & 1 == 1 { 32 } else { 16 };
+    let bitmap = || blip.body.get(uids + 1..);
+    let metafile = || {
+        // Metafile header: size, bounds, size in EMUs, saved size, compression, filter.
+        const NOT_COMPRESSED: u8 = 0xFE;
+        (*blip.body.get(uids + 32)? == NOT_COMPRESSED).then(|| blip.body.get(uids + 34..))?
+    };
+    let (extension, data) = match blip.kind {
+        rt::BLIP_JPEG | rt::BLIP_JPEG_CMYK => ("jpg", bitmap()?.to_vec()),
+        rt::BLIP_PNG => ("png", bitmap()?.to_vec()),
+        rt::BLIP_TIFF => ("tiff", bitmap()?.to_vec()),
+        rt::BLIP_DIB => ("bmp", dib_to_bmp(bitmap()?)?),
+        rt::BLIP_EMF => ("emf", metafile()?.to_vec()),
+        rt::BLIP_WMF => ("wmf", metafile()?.to_vec()),
+        rt::BLIP_PICT => ("pict", metafile()?.to_vec()),
+        _ => return None,
+    };
+    (!data.is_empty()).then_some((extension, data))
+}
+
+/// Prefix a packed DIB with the `BITMAPFILEHEADER` that makes it a `.bmp` file.
+fn dib_to_bmp(dib: &[u8]) -> Option<Vec<u8>> {
+    const FILE_HEADER_LEN: u32 = 14;
+    const BI_BITFIELDS: u32 = 3;
+
+    let header_len = u32_at(dib, 0)?;
+    let bit_count = u16_at(dib, 14)? as u32;
+    let compression = u32_at(dib, 16).unwrap_or(0);
+    let colors_used = u32_at(dib, 32).unwrap_or(0);
+    let palette = match colors_used {
+        0 if bit_count <= 8 => 1 << bit_count,
+        n => n,
+    };
+    let masks = if header_len == 40 && compression == BI_BITFIELDS {
+        12
+    } else {
+        0
+    };
+    let pixels_at = FILE_HEADER_LEN + header_len + masks + palette * 4;
+
+    let mut bmp = Vec::with_capacity(dib.len() + FILE_HEADER_LEN as usize);
+    bmp.extend_from_slice(b"BM");
+    bmp.extend_from_slice(&(dib.len() as u32 + FILE_HEADER_LEN).to_le_bytes());
+    bmp.extend_from_slice(&[0; 4]);
+    bmp.extend_from_slice(&pixels_at.to_le_bytes());
+    bmp.extend_from_slice(dib);
+    Some(bmp)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::{to_markdown, RenderOptions};
+
+    const PNG: &[u8] = b"\x89PNG\r\n\x1a\nfake";
+
+    fn atom(kind: u16, instance: u16, body: &[u8]) -> Vec<u8> {
+        let mut out = (instance << 4).to_le_bytes().to_vec();
+        out.extend_from_slice(&kind.to_le_bytes());
+        out.extend_from_slice(&(body.len() as u32).to_le_bytes());
+        out.extend_from_slice(body);
+        out
+    }
+
+    fn container(kind: u16, instance: u16, children: &[Vec<u8>]) -> Vec<u8> {
+        let body = children.concat();
+        let mut out = ((instance << 4) | 0x0F).to_le_bytes().to_vec();
+        out.extend_from_slice(&kind.to_le_bytes());
+        out.extend_from_slice(&(body.len() as u32).to_le_bytes());
+        out.extend(body);
+        out
+    }
+
+    fn text_header(kind: u32) -> Vec<u8> {
+        atom(rt::TEXT_HEADER_ATOM, 0, &kind.to_le_bytes())
+    }
+
+    fn chars(text: &str) -> Vec<u8> {
+        let body: Vec<u8> = text.encode_utf16().flat_map(|u| u.to_le_bytes()).collect();
+        atom(rt::TEXT_CHARS_ATOM, 0, &body)
+    }
+
+    fn persist_atom(persist_id: u32, id: u32) -> Vec<u8> {
+        let body = [persist_id, 0, 0, id, 0]
+            .iter()
+            .flat_map(|v| v.to_le_bytes())
+            .collect::<Vec<_>>();
+        atom(rt::SLIDE_PERSIST_ATOM, 0, &body)
+    }
+
+    fn drawing(shapes: Vec<Vec<u8>>) -> Vec<u8> {
+        let mut group = vec![container(rt::SP_CONTAINER, 0, &[])];
+        group.extend(shapes);
+        container(
+            rt::PP_DRAWING,
+            0,
+            &[container(
+                rt::DG_CONTAINER,
+                0,
+                &[container(rt::SPGR_CONTAINER, 0, &group)],
+            )],
+        )
+    }
+
+    /// `inner` inside `depth` nested groups, built without copying it once per level.
+    fn nested_groups(depth: usize, inner: Vec<u8>) -> Vec<u8> {
+        let mut out = Vec::with_capacity(depth * HEADER_LEN + inner.len());
+        for level in 0..depth {
+            let len = (depth - level - 1) * HEADER_LEN + inner.len();
+            out.extend_from_slice(&0x0Fu16.to_le_bytes());
+            out.extend_from_slice(&rt::SPGR_CONTAINER.to_le_bytes());
+            out.extend_from_slice(&(len as u32).to_le_bytes());
+        }
+        out.extend(inner);
+        out
+    }
+
+    fn text_shape(records: Vec<Vec<u8>>) -> Vec<u8> {
+        container(
+            rt::SP_CONTAINER,
+            0,
+            &[container(rt::CLIENT_TEXTBOX, 0, &records)],
+        )
+    }
+
+    fn picture_shape(pib: u32, description: &str) -> Vec<u8> {
+        let name: Vec<u8> = description
+            .encode_utf16()
+            .chain([0])
+            .flat_map(|u| u.to_le_bytes())
+            .collect();
+        let mut fopt = pid::PIB.to_le_bytes().to_vec();
+        fopt.extend_from_slice(&pib.to_le_bytes());
+        fopt.extend_from_slice(&(pid::WZ_DESCRIPTION | 0x8000).to_le_bytes());
+        fopt.extend_from_slice(&(name.len() as u32).to_le_bytes());
+        fopt.extend_from_slice(&name);
+        let anchor: Vec<u8> = [0i16, 0, 576, 288]
+            .iter()
+            .flat_map(|v| v.to_le_bytes())
+            .collect();
+        container(
+            rt::SP_CONTAINER,
+            0,
+            &[
+                atom(rt::FOPT, 2, &fopt),
+                atom(rt::CLIENT_ANCHOR, 0, &anchor),
+            ],
+        )
+    }
+
+    /// A presentation with one titled slide, a text box, a picture and notes.
+    struct PptFixture {
+        encrypted: bool,
+        current_user: bool,
+        /// Groups the text box is nested in.
+        text_box_groups: usize,
+    }
+
+    impl Default for PptFixture {
+        fn default() -> Self {
+            Self {
+                encrypted: false,
+                current_user: true,
+                text_box_groups: 0,
+            }
+        }
+    }
+
+    impl PptFixture {
+        fn build(&self) -> Vec<u8> {
+            // Pictures stream: one PNG blip.
+            let mut png_body = vec![0u8; 17];
+            png_body.extend_from_slice(PNG);
+            let pictures = atom(rt::BLIP_PNG, 0x6E0, &png_body);
+
+            let mut fbse = vec![0u8; 36];
+            fbse[20..24].copy_from_slice(&(pictures.len() as u32).to_le_bytes());
+            let drawing_group = container(
+                rt::PP_DRAWING_GROUP,
+                0,
+                &[container(
+                    rt::DGG_CONTAINER,
+                    0,
+                    &[container(
+                        rt::BSTORE_CONTAINER,
+                        1,
+                        &[atom(rt::FBSE, 0, &fbse)],
+                    )],
+                )],
+            );
+
+            let document = container(
+                rt::DOCUMENT,
+                0,
+                &[
+                    container(
+                        rt::SLIDE_LIST_WITH_TEXT,
+                        SLIDE_LIST,
+                        &[
+                            persist_atom(2, 256),
+                            text_header(text_type::TITLE),
+                            chars("Quarterly Review"),
+                            text_header(1),
+                            chars("First point\rSecond\u{0B}line"),
+                        ],
+                    ),
+                    container(
+                        rt::SLIDE_LIST_WITH_TEXT,
+                        NOTES_LIST,
+                        &[persist_atom(3, 257)],
+                    ),
+                    drawing_group,
+                ],
+            );
+
+            let mut slide_atom = vec![0u8; 24];
+            slide_atom[16..20].copy_from_slice(&257u32.to_le_bytes());
+            let slide = container(
+                rt::SLIDE,
+                0,
+                &[
+                    atom(rt::SLIDE_ATOM, 2, &slide_atom),
+                    drawing(vec![
+                        text_shape(vec![atom(
+                            rt::OUTLINE_TEXT_REF_ATOM,
+                            0,
+                            &0u32.to_le_bytes(),
+                        )]),
+                        nested_groups(
+                            self.text_box_groups,
+                            text_shape(vec![
+                                text_header(4),
+                                atom(rt::TEXT_BYTES_ATOM, 0, b"Caf\xe9 box"),
+                            ]),
+                        ),
+                        picture_shape(1, "Chart image"),
+                    ]),
+                ],
+            );
+            let notes = container(
+                rt::NOTES,
+                0,
+                &[drawing(vec![
+                    text_shape(vec![text_header(4), chars("Header text")]),
+                    text_shape(vec![text_header(text_type::NOTES), chars("Speak slowly")]),
+                ])],
+            );
+
+            let mut stream = Vec::new();
+            let mut offsets = Vec::new();
+            for record in [document, slide, notes] {
+                offsets.push(stream.len() as u32);
+                stream.extend(record);
+            }
+            let mut dir = (1u32 | (3 << 20)).to_le_bytes().to_vec();
+            for offset in &offsets {
+                dir.extend_from_slice(&offset.to_le_bytes());
+            }
+            let dir_offset = stream.len() as u32;
+            stream.extend(atom(rt::PERSIST_DIRECTORY_ATOM, 0, &dir));
+
+            let edit_offset = stream.len() as u32;
+            let mut edit = vec![0u8; 28];
+            edit[12..16].copy_from_slice(&dir_offset.to_le_bytes());
+            edit[16..20].copy_from_slice(&1u32.to_le_bytes());
+            stream.extend(atom(rt::USER_EDIT_ATOM, 0, &edit));
+
+            let token = if self.encrypted {
+                ENCRYPTED_HEADER_TOKEN
+            } else {
+                0xE391_C05F
+            };
+            let mut current_user = vec![0u8; 8];
+            current_user.extend_from_slice(&20u32.to_le_bytes());
+            current_user.extend_from_slice(&token.to_le_bytes());
+            current_user.extend_from_slice(&edit_offset.to_le_bytes());
+
+            let mut cfb = cfb::CompoundFile::create(std::io::Cursor::new(Vec::new()))
+                .expect("create CFB container");
+            use std::io::Write;
+            let mut streams = vec![("/PowerPoint Document", stream), ("/Pictures", pictures)];
+            if self.current_user {
+                streams.push(("/Current User", current_user));
+            }
+            for (name, data) in streams {
+                cfb.create_stream(name).unwrap().write_all(&data).unwrap();
+            }
+            cfb.flush().unwrap();
+            cfb.into_inner().into_inner()
+        }
+    }
+
+    fn parse(fixture: &PptFixture) -> Document {
+        PptParser::from_bytes(fixture.build())
+            .unwrap()
+            .parse()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_slide_text_in_drawing_order() {
+        let doc = parse(&PptFixture::default());
+        assert_eq!(doc.format, crate::detect::FormatType::Ppt);
+        assert_eq!(doc.sections.len(), 1);
+        assert_eq!(doc.metadata.page_count, Some(1));
+
+        let section = &doc.sections[0];
+        assert_eq!(section.name.as_deref(), Some("Slide 1"));
+        let texts: Vec<String> = section
+            .content
+            .iter()
+            .filter_map(|b| match b {
+                Block::Paragraph(p) => Some(p.plain_text()),
+                _ => None,
+            })
+            .collect();
+        // The title is referenced from the drawing; the body text is not, so it follows.
+        assert_eq!(
+            texts,
+            vec![
+                "Quarterly Review",
+                "Caf\u{e9} box",
+                "First point",
+                "Second\nline"
+            ]
+        );
+        let Block::Paragraph(title) = &section.content[0] else {
+            panic!("expected the title first");
+        };
+        assert_eq!(title.heading, HeadingLevel::H1);
+    }
+
+    #[test]
+    fn test_deeply_nested_groups_are_read() {
+        let fixture = PptFixture {
+            text_box_groups: 100_000,
+            ..PptFixture::default()
+        };
+        let doc = parse(&fixture);
+        let texts: Vec<String> = doc.sections[0]
+            .content
+            .iter()
+            .filter_map(|b| match b {
+                Block::Paragraph(p) => Some(p.plain_text()),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(texts[1], "Caf\u{e9} box");
+    }
+
+    #[test]
+    fn test_notes_keep_only_notes_text() {
+        let doc = parse(&PptFixture::default());
+        let notes = doc.sections[0].notes.as_ref().expect("notes");
+        assert_eq!(notes.len(), 1);
+        assert_eq!(notes[0].plain_text(), "Speak slowly");
+    }
+
+    #[test]
+    fn test_picture_from_pictures_stream() {
+        let doc = parse(&PptFixture::default());
+        let resource = doc.get_resource("image1.png").expect("picture resource");
+        assert_eq!(resource.data, PNG);
+        assert_eq!(resource.mime_type.as_deref(), Some("image/png"));
+
+        let image = doc.sections[0]
+            .content
+            .iter()
+            .find_map(|b| match b {
+                Block::Image {
+                    resource_id,
+                    alt_text,
+                    width,
+                    height,
+                } => Some((resource_id, alt_text, width, height)),
+                _ => None,
+            })
+            .expect("image block");
+        assert_eq!(image.0, "image1.png");
+        assert_eq!(image.1.as_deref(), Some("Chart image"));
+        assert_eq!((*image.2, *image.3), (Some(914_400), Some(457_200)));
+    }
+
+    #[test]
+    fn test_markdown_uses_slide_markers() {
+        let doc = parse(&PptFixture::default());
+        let opts = RenderOptions::new().with_section_markers(crate::SectionMarkerStyle::Comment);
+        let md = to_markdown(&doc, &opts).unwrap();
+        assert!(md.contains("<!-- slide 1: Slide 1 -->"), "{md}");
+        assert!(md.contains("# Quarterly Review"), "{md}");
+    }
+
+    #[test]
+    fn test_missing_current_user_falls_back_to_last_edit() {
+        let doc = parse(&PptFixture {
+            current_user: false,
+            ..Default::default()
+        });
+        assert_eq!(doc.sections.len(), 1);
+    }
+
+    #[test]
+    fn test_encrypted_presentation_reports_encrypted() {
+        let fixture = PptFixture {
+            encrypted: true,
+            ..Default::default()
+        };
+        let err = PptParser::from_bytes(fixture.build()).err().unwrap();
+        assert!(matches!(err, Error::Encrypted), "got: {err}");
+    }
+
+    #[test]
+    fn test_parse_bytes_dispatches_to_ppt_parser() {
+        let doc = crate::parse_bytes(&PptFixture::default().build()).unwrap();
+        assert!(doc.plain_text().contains("Quarterly Review"));
+    }
+
+    #[test]
+    fn test_dib_gets_a_bitmap_file_header() {
+        let mut dib = vec![0u8; 40];
+        dib[0..4].copy_from_slice(&40u32.to_le_bytes());
+        dib[14..16].copy_from_slice(&24u16.to_le_bytes());
+        dib.extend_from_slice(&[1, 2, 3]);
+        let bmp = dib_to_bmp(&dib).unwrap();
+        assert_eq!(&bmp[..2], b"BM");
+        assert_eq!(u32_at(&bmp, 2), Some(57));
+        assert_eq!(u32_at(&bmp, 10), Some(54));
+    }
+}
@@ -0,0 +1,164 @@
+//! PowerPoint binary record framing.
+//!
+//! The `PowerPoint Document` stream, and the OfficeArt drawings inside it, are trees of
+//! records: an 8-byte header (version and instance packed into one `u16`, then the
+//! type and the body length) followed by the body. A record whose version is `0xF` is a
+//! container and its body is more records.
+
+use crate::ole::{u16_at, u32_at};
+
+/// Record types (MS-PPT 2.13.24 and MS-ODRAW 2.2).
+pub mod rt {
+    pub const DOCUMENT: u16 = 0x03E8;
+    pub const SLIDE: u16 = 0x03EE;
+    pub const SLIDE_ATOM: u16 = 0x03EF;
+    pub const NOTES: u16 = 0x03F0;
+    pub const SLIDE_PERSIST_ATOM: u16 = 0x03F3;
+    pub const PP_DRAWING_GROUP: u16 = 0x040B;
+    pub const PP_DRAWING: u16 = 0x040C;
+    pub const OUTLINE_TEXT_REF_ATOM: u16 = 0x0F9E;
+    pub const TEXT_HEADER_ATOM: u16 = 0x0F9F;
+    pub const TEXT_CHARS_ATOM: u16 = 0x0FA0;
+    pub const TEXT_BYTES_ATOM: u16 = 0x0FA8;
+    pub const SLIDE_LIST_WITH_TEXT: u16 = 0x0FF0;
+    pub const USER_EDIT_ATOM: u16 = 0x0FF5;
+    pub const PERSIST_DIRECTORY_ATOM: u16 = 0x1772;
+
+    pub const DGG_CONTAINER: u16 = 0xF000;
+    pub const BSTORE_CONTAINER: u16 = 0xF001;
+    pub const DG_CONTAINER: u16 = 0xF002;
+    pub const SPGR_CONTAINER: u16 = 0xF003;
+    pub const SP_CONTAINER: u16 = 0xF004;
+    pub const FBSE: u16 = 0xF007;
+    pub const FOPT: u16 = 0xF00B;
+    pub const CLIENT_TEXTBOX: u16 = 0xF00D;
+    pub const CLIENT_ANCHOR: u16 = 0xF010;
+    pub const CLIENT_DATA: u16 = 0xF011;
+    pub const BLIP_EMF: u16 = 0xF01A;
+    pub const BLIP_WMF: u16 = 0xF01B;
+    pub const BLIP_PICT: u16 = 0xF01C;
+    pub const BLIP_JPEG: u16 = 0xF01D;
+    pub const BLIP_PNG: u16 = 0xF01E;
+    pub const BLIP_DIB: u16 = 0xF01F;
+    pub const BLIP_TIFF: u16 = 0xF029;
+    pub const BLIP_JPEG_CMYK: u16 = 0xF02A;
+}
+
+/// Record header size.
+pub const HEADER_LEN: usize = 8;
+
+/// One record, its body borrowed from the stream.
+#[derive(Debug, Clone, Copy)]
+pub struct Record<'a> {
+    pub kind: u16,
+    pub instance: u16,
+    version: u8,
+    pub body: &'a [u8],
+}
+
+impl<'a> Record<'a> {
+    /// Read the record at `offset`.
+    ///
+    /// A body that runs past the end of `data` is cut short rather than rejected, so a
+    /// truncated file still yields the records it holds.
+    pub fn at(data: &'a [u8], offset: usize) -> Option<Self> {
+        let packed = u16_at(data, offset)?;
+        let kind = u16_at(data, offset + 2)?;
+        let len = u32_at(data, offset + 4)? as usize;
+        let start = offset + HEADER_LEN;
+        let end = start.saturating_add(len).min(data.len());
+        Some(Self {
+            kind,
+            instance: packed >> 4,
+            version: (packed & 0x0F) as u8,
+            body: data.get(start..end)?,
+        })
+    }
+
+    /// Read the record at `offset`, if it has the expected type.
+    pub fn expect(data: &'a [u8], offset: usize, kind: u16) -> Option<Self> {
+        Self::at(data, offset).filter(|r| r.kind == kind)
+    }
+
+    pub fn is_container(&self) -> bool {
+        self.version == 0x0F
+    }
+
+    /// The records of a container's body; none for an atom.
+    pub fn children(&self) -> Children<'a> {
+        Children {
+            data: if self.is_container() { self.body } else { &[] },
+            pos: 0,
+        }
+    }
+
+    /// The first child of the given type.
+    pub fn child(&self, kind: u16) -> Option<Record<'a>> {
+        self.children().find(|r| r.kind == kind)
+    }
+}
+
+/// Iterator over the records of a container body.
+pub struct Children<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Children<'a> {
+    /// Iterate over a flat run of records, such as a whole stream.
+    pub fn of(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    /// Offset of the next record within the iterated data.
+    pub fn offset(&self) -> usize {
+        self.pos
+    }
+}
+
+impl<'a> Iterator for Children<'a> {
+    type Item = Record<'a>;
+
+    fn next(&mut self) -> Option<Record<'a>> {
+        let record = Record::at(self.data, self.pos)?;
+        self.pos += HEADER_LEN + record.body.len();
+        Some(record)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn header(version: u8, instance: u16, kind: u16, len: u32) -> Vec<u8> {
+        let mut out = ((instance << 4) | version as u16).to_le_bytes().to_vec();
+        out.extend_from_slice(&kind.to_le_bytes());
+        out.extend_from_slice(&len.to_le_bytes());
+        out
+    }
+
+    #[test]
+    fn test_container_children_and_instance() {
+        let mut atom = header(0, 2, rt::TEXT_HEADER_ATOM, 4);
+        atom.extend_from_slice(&1u32.to_le_bytes());
+        let mut data = header(0x0F, 0, rt::SLIDE_LIST_WITH_TEXT, atom.len() as u32);
+        data.extend_from_slice(&atom);
+
+        let record = Record::at(&data, 0).unwrap();
+        assert!(record.is_container());
+        let child = record.child(rt::TEXT_HEADER_ATOM).unwrap();
+        assert_eq!(child.instance, 2);
+        assert_eq!(child.body, &1u32.to_le_bytes());
+        assert_eq!(child.children().count(), 0);
+    }
+
+    #[test]
+    fn test_truncated_body_is_cut_short() {
+        let mut data = header(0, 0, rt::TEXT_CHARS_ATOM, 100);
+        data.extend_from_slice(&[b'a', 0]);
+
+        let record = Record::at(&data, 0).unwrap();
+        assert_eq!(record.body.len(), 2);
+        assert!(Record::at(&data, 9).is_none());
+    }
+}
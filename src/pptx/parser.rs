@@ -112,6 +112,7 @@ impl PptxParser {
     pub fn parse(&mut self) -> Result<Document> {
         let mut doc = Document::new();
         doc.format = crate::detect::FormatType::Pptx;
+        doc.variant = self.container.format_variant();
 
         // Parse metadata
         doc.metadata = self.parse_metadata()?;
@@ -651,6 +652,15 @@ impl PptxParser {
                         b"t" if in_run => {
                             in_text = true;
                         }
+                        // a14:m - equation; mc:Fallback holds a picture of it
+                        b"oMathPara" | b"oMath" if in_paragraph => {
+                            if let Some(run) = crate::math::read_equation(&mut reader, xml, e)? {
+                                current_runs.push(run);
+                            }
+                        }
+                        b"Fallback" => {
+                            reader.read_to_end(e.name())?;
+                        }
                         // a:rPr - run properties
                         b"rPr" if in_run => {
                             in_rpr = true;
@@ -739,6 +749,9 @@ impl PptxParser {
                                     line_break: false,
                                     page_break: false,
                                     revision: RevisionType::None,
+                                    math: None,
+                                    form_field: None,
+                                    field: None,
                                 });
                             }
                             in_run = false;
@@ -866,6 +879,15 @@ impl PptxParser {
                         b"t" if in_run && !in_table => {
                             in_text = true;
                         }
+                        // a14:m - equation; mc:Fallback holds a picture of it
+                        b"oMathPara" | b"oMath" if in_paragraph && !in_table => {
+                            if let Some(run) = crate::math::read_equation(&mut reader, xml, e)? {
+                                current_runs.push(run);
+                            }
+                        }
+                        b"Fallback" => {
+                            reader.read_to_end(e.name())?;
+                        }
                         // a:rPr - run properties
                         b"rPr" if in_run && !in_table => {
                             in_rpr = true;
@@ -1031,6 +1053,9 @@ impl PptxParser {
                                     line_break: false,
                                     page_break: false,
                                     revision: RevisionType::None,
+                                    math: None,
+                                    form_field: None,
+                                    field: None,
                                 });
                             }
                             in_run = false;
@@ -1125,6 +1150,15 @@ impl PptxParser {
                         b"t" if in_run => {
                             in_text = true;
                         }
+                        // a14:m - equation; mc:Fallback holds a picture of it
+                        b"oMathPara" | b"oMath" if in_paragraph => {
+                            if let Some(run) = crate::math::read_equation(&mut reader, xml, e)? {
+                                current_runs.push(run);
+                            }
+                        }
+                        b"Fallback" => {
+                            reader.read_to_end(e.name())?;
+                        }
                         // a:rPr - run properties
                         b"rPr" if in_run => {
                             in_rpr = true;
@@ -1232,6 +1266,9 @@ impl PptxParser {
                                     line_break: false,
                                     page_break: false,
                                     revision: RevisionType::None,
+                                    math: None,
+                                    form_field: None,
+                                    field: None,
                                 });
                             }
                             in_run = false;
@@ -1491,6 +1528,9 @@ fn parse_placeholder_texts_from_xml(xml: &str) -> HashMap<String, Vec<Paragraph>
                                 line_break: false,
                                 page_break: false,
                                 revision: RevisionType::None,
+                                math: None,
+                                form_field: None,
+                                field: None,
                             });
                         }
                         in_run = false;
@@ -1556,6 +1596,7 @@ fn guess_mime_type(path: &str) -> Option<String> {
 mod tests {
     use super::*;
     use crate::error::Error;
+    use crate::model::MathMode;
 
     #[test]
     fn test_open_pptx() {
@@ -2492,6 +2533,47 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_parse_equations() {
+        let slide_xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+<p:sld xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main"
+       xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main"
+       xmlns:a14="http://schemas.microsoft.com/office/drawing/2010/main"
+       xmlns:m="http://schemas.openxmlformats.org/officeDocument/2006/math"
+       xmlns:mc="http://schemas.openxmlformats.org/markup-compatibility/2006">
+  <p:cSld>
+    <p:spTree>
+      <mc:AlternateContent>
+        <mc:Choice Requires="a14">
+          <p:sp>
+            <p:txBody>
+              <a:p><a:r><a:t xml:space="preserve">Root: </a:t></a:r><a14:m><m:oMath><m:rad><m:radPr><m:degHide m:val="1"/></m:radPr><m:deg/><m:e><m:r><m:t>x</m:t></m:r></m:e></m:rad></m:oMath></a14:m></a:p>
+            </p:txBody>
+          </p:sp>
+        </mc:Choice>
+        <mc:Fallback>
+          <p:sp>
+            <p:txBody>
+              <a:p><a:r><a:t>Root: picture</a:t></a:r></a:p>
+            </p:txBody>
+          </p:sp>
+        </mc:Fallback>
+      </mc:AlternateContent>
+    </p:spTree>
+  </p:cSld>
+</p:sld>"#;
+
+        let mut parser = PptxParser::from_bytes(create_minimal_pptx(slide_xml)).unwrap();
+        let doc = parser.parse().unwrap();
+        let Block::Paragraph(para) = &doc.sections[0].content[0] else {
+            panic!("expected a paragraph");
+        };
+        assert_eq!(para.runs.len(), 2);
+        assert_eq!(para.runs[1].text, "\\sqrt{x}");
+        assert_eq!(para.runs[1].math, Some(MathMode::Inline));
+        assert!(!doc.plain_text().contains("picture"));
+    }
+
     #[test]
     fn test_parse_nested_grouped_shapes() {
         let slide_xml = r#"<?xml version="1.0" encoding="UTF-8"?>
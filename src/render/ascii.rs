@@ -0,0 +1,460 @@
+//! Box-drawn table rendering.
+//!
+//! Works from the flat grid of [`super::grid::lay_out`], but unlike the pipe table it
+//! does not flatten merges: a merged cell is drawn as one box over every column and row
+//! it covers, with the borders inside it left out. Widths are measured in terminal
+//! columns, so CJK text stays aligned, and a cell keeps its line breaks and paragraphs
+//! as separate lines.
+//!
+//! The same drawing in `+`, `-`, `=` and `|` is a Pandoc grid table.
+
+use unicode_width::UnicodeWidthStr;
+
+use crate::model::{Cell, CellAlignment, Table};
+
+/// Minimum content width of a column, for readability.
+const MIN_COLUMN_WIDTH: usize = 3;
+
+/// A cell placed on the grid: the rectangle it covers and the lines it holds.
+struct Placed {
+    row: usize,
+    col: usize,
+    rows: usize,
+    cols: usize,
+    lines: Vec<String>,
+    alignment: CellAlignment,
+}
+
+/// The characters a table is drawn with.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Strokes {
+    /// Unicode box drawing.
+    Box,
+    /// Pandoc grid table syntax.
+    Grid,
+}
+
+/// Render a table as a box-drawn text table.
+///
+/// A border line runs under the leading header rows in double strokes. Cells are
+/// top-aligned; horizontally they follow the cell's alignment.
+pub(super) fn render_table_ascii(table: &Table) -> String {
+    draw(table, Strokes::Box, cell_lines)
+}
+
+/// Render a table as a Pandoc grid table, with `lines` giving each cell's Markdown.
+///
+/// Cell text is always left-aligned: Pandoc reads each cell as Markdown blocks, and
+/// padding in front of the text would turn into indentation.
+pub(super) fn render_table_grid(table: &Table, lines: impl Fn(&Cell) -> Vec<String>) -> String {
+    draw(table, Strokes::Grid, lines)
+}
+
+fn draw(table: &Table, strokes: Strokes, lines: impl Fn(&Cell) -> Vec<String>) -> String {
+    let grid = super::grid::lay_out(table);
+    let row_count = grid.len();
+    let col_count = grid.first().map_or(0, Vec::len);
+    if row_count == 0 || col_count == 0 {
+        return String::new();
+    }
+
+    // Which placed cell owns each slot. A slot no cell covers — a row ending short of
+    // the table's width — gets an empty cell of its own.
+    let mut cells: Vec<Placed> = Vec::new();
+    let mut owner: Vec<Vec<Option<usize>>> = vec![vec![None; col_count]; row_count];
+    for (r, slots) in grid.iter().enumerate() {
+        for (c, slot) in slots.iter().enumerate() {
+            let Some(cell) = slot else { continue };
+            let rows = (cell.row_span.max(1) as usize).min(row_count - r);
+            let cols = (cell.col_span.max(1) as usize).min(col_count - c);
+            let index = cells.len();
+            for owned in &mut owner[r..r + rows] {
+                for slot in owned[c..c + cols].iter_mut().filter(|s| s.is_none()) {
+                    *slot = Some(index);
+                }
+            }
+            cells.push(Placed {
+                row: r,
+                col: c,
+                rows,
+                cols,
+                lines: lines(cell),
+                alignment: match strokes {
+                    Strokes::Box => super::markdown::effective_cell_alignment(cell),
+                    Strokes::Grid => CellAlignment::Left,
+                },
+            });
+        }
+    }
+    let owner: Vec<Vec<usize>> = owner
+        .into_iter()
+        .enumerate()
+        .map(|(r, slots)| {
+            slots
+                .into_iter()
+                .enumerate()
+                .map(|(c, slot)| {
+                    slot.unwrap_or_else(|| {
+                        cells.push(Placed {
+                            row: r,
+                            col: c,
+                            rows: 1,
+                            cols: 1,
+                            lines: Vec::new(),
+                            alignment: CellAlignment::Left,
+                        });
+                        cells.len() - 1
+                    })
+                })
+                .collect()
+        })
+        .collect();
+
+    let widths = column_widths(&cells, col_count);
+    let heights = row_heights(&cells, row_count);
+    let header_rows = table.rows.iter().take_while(|r| r.is_header).count();
+
+    let layout = Layout {
+        cells: &cells,
+        owner: &owner,
+        widths: &widths,
+        heights: &heights,
+        strokes,
+    };
+
+    let mut output = String::new();
+    for boundary in 0..=row_count {
+        let double = header_rows > 0 && boundary == header_rows && boundary < row_count;
+        output.push_str(&layout.border(boundary, double));
+        output.push('\n');
+        if let Some(&height) = heights.get(boundary) {
+            for line in 0..height {
+                output.push_str(&layout.content(boundary, line));
+                output.push('\n');
+            }
+        }
+    }
+    output.pop();
+    output
+}
+
+/// A cell's text as display lines: one per paragraph, split at its line breaks.
+fn cell_lines(cell: &Cell) -> Vec<String> {
+    let mut lines: Vec<String> = cell
+        .content
+        .iter()
+        .flat_map(|p| {
+            p.plain_text()
+                .replace("\r\n", "\n")
+                .replace('\r', "\n")
+                .split('\n')
+                .map(|line| line.replace('\t', " ").trim_end().to_string())
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    while lines.last().is_some_and(String::is_empty) {
+        lines.pop();
+    }
+    lines
+}
+
+/// Column widths wide enough for every cell. A merged cell that does not fit in the
+/// columns it covers widens them evenly; cells are sized narrowest span first, so a
+/// merge only adds what the columns under it still lack.
+fn column_widths(cells: &[Placed], col_count: usize) -> Vec<usize> {
+    let mut widths = vec![MIN_COLUMN_WIDTH; col_count];
+    let mut order: Vec<&Placed> = cells.iter().collect();
+    order.sort_by_key(|cell| cell.cols);
+    for cell in order {
+        let needed = cell.lines.iter().map(|l| l.width()).max().unwrap_or(0);
+        let span = &mut widths[cell.col..cell.col + cell.cols];
+        // Inside a merge the separators between columns are content too: " │ ".
+        let available = span.iter().sum::<usize>() + 3 * (cell.cols - 1);
+        if needed > available {
+            let extra = needed - available;
+            let count = span.len();
+            for (i, width) in span.iter_mut().enumerate() {
+                *width += extra / count + usize::from(i < extra % count);
+            }
+        }
+    }
+    widths
+}
+
+/// Row heights in lines, sized like [`column_widths`]: a cell spanning rows may use the
+/// border lines between them, and the last row it covers absorbs any shortfall.
+fn row_heights(cells: &[Placed], row_count: usize) -> Vec<usize> {
+    let mut heights = vec![1; row_count];
+    let mut order: Vec<&Placed> = cells.iter().collect();
+    order.sort_by_key(|cell| cell.rows);
+    for cell in order {
+        let span = &mut heights[cell.row..cell.row + cell.rows];
+        let available = span.iter().sum::<usize>() + (cell.rows - 1);
+        if cell.lines.len() > available {
+            if let Some(last) = span.last_mut() {
+                *last += cell.lines.len() - available;
+            }
+        }
+    }
+    heights
+}
+
+struct Layout<'a> {
+    cells: &'a [Placed],
+    owner: &'a [Vec<usize>],
+    widths: &'a [usize],
+    heights: &'a [usize],
+    strokes: Strokes,
+}
+
+impl Layout<'_> {
+    /// Whether a vertical border runs at column boundary `x` of `row`.
+    fn vertical(&self, row: usize, x: usize) -> bool {
+        x == 0 || x == self.widths.len() || self.owner[row][x - 1] != self.owner[row][x]
+    }
+
+    /// Whether the border line at row boundary `boundary` is drawn over column `col`,
+    /// rather than being a line of a cell that spans it.
+    fn horizontal(&self, boundary: usize, col: usize) -> bool {
+        boundary == 0
+            || boundary == self.heights.len()
+            || self.owner[boundary - 1][col] != self.owner[boundary][col]
+    }
+
+    /// The border line above row `boundary` (or below the last row).
+    fn border(&self, boundary: usize, double: bool) -> String {
+        let above = boundary.checked_sub(1);
+        let below = Some(boundary).filter(|&r| r < self.heights.len());
+        let cols = self.widths.len();
+
+        let mut line = String::new();
+        let mut x = 0;
+        loop {
+            let left = x > 0 && self.horizontal(boundary, x - 1);
+            let right = x < cols && self.horizontal(boundary, x);
+            let up = above.is_some_and(|r| self.vertical(r, x));
+            let down = below.is_some_and(|r| self.vertical(r, x));
+            line.push(match self.strokes {
+                Strokes::Box => junction(up, down, left, right, double),
+                Strokes::Grid => grid_junction(up || down, left || right, double),
+            });
+            if x == cols {
+                break;
+            }
+            if right {
+                let stroke = match (self.strokes, double) {
+                    (Strokes::Box, false) => "─",
+                    (Strokes::Box, true) => "═",
+                    (Strokes::Grid, false) => "-",
+                    (Strokes::Grid, true) => "=",
+                };
+                line.push_str(&stroke.repeat(self.widths[x] + 2));
+                x += 1;
+            } else {
+                // A cell spanning this boundary: the border line is one of its lines.
+                let index = self.owner[boundary][x];
+                let cell = &self.cells[index];
+                let offset = self.line_offset(cell, boundary - 1) + self.heights[boundary - 1];
+                line.push_str(&self.text(cell, offset));
+                x = cell.col + cell.cols;
+            }
+        }
+        line
+    }
+
+    /// Line `line` of row `row`.
+    fn content(&self, row: usize, line: usize) -> String {
+        let vertical = match self.strokes {
+            Strokes::Box => '│',
+            Strokes::Grid => '|',
+        };
+        let mut output = String::from(vertical);
+        let mut x = 0;
+        while x < self.widths.len() {
+            let cell = &self.cells[self.owner[row][x]];
+            output.push_str(&self.text(cell, self.line_offset(cell, row) + line));
+            output.push(vertical);
+            x = cell.col + cell.cols;
+        }
+        output
+    }
+
+    /// Index of the cell's first line in `row`: its lines flow down through every row
+    /// it spans and the border lines between them.
+    fn line_offset(&self, cell: &Placed, row: usize) -> usize {
+        self.heights[cell.row..row].iter().map(|h| h + 1).sum()
+    }
+
+    /// Line `index` of a cell, padded to the cell's full width with a space either side.
+    fn text(&self, cell: &Placed, index: usize) -> String {
+        let width = self.widths[cell.col..cell.col + cell.cols]
+            .iter()
+            .sum::<usize>()
+            + 3 * (cell.cols - 1);
+        let text = cell.lines.get(index).map_or("", String::as_str);
+        let padding = width.saturating_sub(text.width());
+        let (before, after) = match cell.alignment {
+            CellAlignment::Left => (0, padding),
+            CellAlignment::Center => (padding / 2, padding - padding / 2),
+            CellAlignment::Right => (padding, 0),
+        };
+        format!(" {}{}{} ", " ".repeat(before), text, " ".repeat(after))
+    }
+}
+
+/// The box-drawing character joining the given arms, with double horizontal strokes
+/// under a header.
+fn junction(up: bool, down: bool, left: bool, right: bool, double: bool) -> char {
+    match (up, down, left, right, double) {
+        (true, true, true, true, false) => '┼',
+        (true, true, true, false, false) => '┤',
+        (true, true, false, true, false) => '├',
+        (true, false, true, true, false) => '┴',
+        (false, true, true, true, false) => '┬',
+        (false, true, false, true, false) => '┌',
+        (false, true, true, false, false) => '┐',
+        (true, false, false, true, false) => '└',
+        (true, false, true, false, false) => '┘',
+        (true, true, true, true, true) => '╪',
+        (true, true, true, false, true) => '╡',
+        (true, true, false, true, true) => '╞',
+        (true, false, true, true, true) => '╧',
+        (false, true, true, true, true) => '╤',
+        (false, true, false, true, true) => '╒',
+        (false, true, true, false, true) => '╕',
+        (true, false, false, true, true) => '╘',
+        (true, false, true, false, true) => '╛',
+        (_, _, true, true, false) => '─',
+        (_, _, true, true, true) => '═',
+        (true, true, false, false, _) => '│',
+        _ => ' ',
+    }
+}
+
+/// The grid table character where vertical and horizontal borders meet or pass.
+fn grid_junction(vertical: bool, horizontal: bool, double: bool) -> char {
+    match (vertical, horizontal) {
+        (true, true) => '+',
+        (true, false) => '|',
+        (false, true) if double => '=',
+        (false, true) => '-',
+        (false, false) => ' ',
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{Cell, Paragraph, Row};
+
+    fn row(cells: Vec<Cell>) -> Row {
+        Row {
+            cells,
+            is_header: false,
+            height: None,
+        }
+    }
+
+    fn cell(text: &str, col_span: u32, row_span: u32) -> Cell {
+        Cell {
+            col_span,
+            row_span,
+            ..Cell::with_text(text)
+        }
+    }
+
+    #[test]
+    fn test_plain_table_with_header() {
+        let mut table = Table::new();
+        table.add_row(Row::header(vec![Cell::header("Name"), Cell::header("Qty")]));
+        let mut qty = Cell::with_text("12");
+        qty.alignment = CellAlignment::Right;
+        table.add_row(row(vec![Cell::with_text("Apples"), qty]));
+
+        assert_eq!(
+            render_table_ascii(&table),
+            "┌────────┬─────┐\n\
+             │ Name   │ Qty │\n\
+             ╞════════╪═════╡\n\
+             │ Apples │  12 │\n\
+             └────────┴─────┘"
+        );
+    }
+
+    #[test]
+    fn test_merged_cells_span_visibly() {
+        let mut table = Table::new();
+        table.add_row(row(vec![cell("Group", 2, 1), cell("Side", 1, 2)]));
+        table.add_row(row(vec![Cell::with_text("a"), Cell::with_text("b")]));
+
+        assert_eq!(
+            render_table_ascii(&table),
+            "┌───────────┬──────┐\n\
+             │ Group     │ Side │\n\
+             ├─────┬─────┤      │\n\
+             │ a   │ b   │      │\n\
+             └─────┴─────┴──────┘"
+        );
+    }
+
+    #[test]
+    fn test_cjk_width_and_multi_line_cells() {
+        let mut two_lines = Cell::with_text("first");
+        two_lines.content.push(Paragraph::with_text("second"));
+        let mut table = Table::new();
+        table.add_row(row(vec![Cell::with_text("한국어"), two_lines]));
+
+        assert_eq!(
+            render_table_ascii(&table),
+            "┌────────┬────────┐\n\
+             │ 한국어 │ first  │\n\
+             │        │ second │\n\
+             └────────┴────────┘"
+        );
+    }
+
+    #[test]
+    fn test_tall_merged_cell_uses_the_border_lines() {
+        let mut tall = Cell::with_text("one");
+        tall.row_span = 2;
+        for text in ["two", "three", "four"] {
+            tall.content.push(Paragraph::with_text(text));
+        }
+        let mut table = Table::new();
+        table.add_row(row(vec![tall, Cell::with_text("x")]));
+        table.add_row(row(vec![Cell::with_text("y")]));
+
+        assert_eq!(
+            render_table_ascii(&table),
+            "┌───────┬─────┐\n\
+             │ one   │ x   │\n\
+             │ two   ├─────┤\n\
+             │ three │ y   │\n\
+             │ four  │     │\n\
+             └───────┴─────┘"
+        );
+    }
+
+    #[test]
+    fn test_grid_table_spans() {
+        let mut table = Table::new();
+        table.add_row(Row::header(vec![Cell::header("Name"), Cell::header("Qty")]));
+        table.add_row(row(vec![cell("Tall", 1, 2), Cell::with_text("1")]));
+        table.add_row(row(vec![Cell::with_text("2")]));
+        table.add_row(row(vec![cell("Both", 2, 1)]));
+
+        let lines = |cell: &Cell| vec![cell.plain_text()];
+        assert_eq!(
+            render_table_grid(&table, lines),
+            "+------+-----+\n\
+             | Name | Qty |\n\
+             +======+=====+\n\
+             | Tall | 1   |\n\
+             |      +-----+\n\
+             |      | 2   |\n\
+             +------+-----+\n\
+             | Both       |\n\
+             +------------+"
+        );
+    }
+}
@@ -0,0 +1,778 @@
+//! AsciiDoc renderer implementation.
+//!
+//! Emits AsciiDoc as Asciidoctor (and so Antora) reads it. Unlike pipe Markdown,
+//! AsciiDoc tables can span: a merged cell keeps its `2+|` column span and `.2+|` row
+//! span instead of being flattened into empty neighbours.
+
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+
+use crate::error::Result;
+use crate::model::{
+    Block, Cell, CellAlignment, Document, ListType, Paragraph, RevisionType, Section, Table,
+    TextRun,
+};
+
+use super::common::{note_definition, note_id, RunSpacing};
+use super::grid;
+use super::markdown::{self, ResourceMap};
+use super::options::{FieldHandling, RenderOptions, RevisionHandling, SectionMarkerStyle};
+
+/// Deepest section level AsciiDoc has: `======`, one `=` more than the level.
+const MAX_SECTION_LEVEL: u8 = 5;
+
+/// Convert a Document to AsciiDoc.
+///
+/// Footnote and endnote definitions — the `[^label]: text` paragraphs the parsers append
+/// to a section — are folded into `footnote:` macros where their markers appear, so
+/// Asciidoctor numbers and places them itself. A 1×1 callout table becomes a `[NOTE]`
+/// admonition when [`RenderOptions::callout_blockquote`] is set, and
+/// [`RenderOptions::include_frontmatter`] writes the metadata as the document header.
+pub fn to_asciidoc(doc: &Document, options: &RenderOptions) -> Result<String> {
+    let stripped;
+    let doc = if options.include_notes {
+        doc
+    } else {
+        stripped = super::filter::without_notes(doc);
+        &stripped
+    };
+    let fielded;
+    let doc = if options.field_handling == FieldHandling::ShowResults {
+        doc
+    } else {
+        fielded = super::filter::with_fields(doc, options.field_handling);
+        &fielded
+    };
+
+    let renderer = AsciidocRenderer::new(doc, options);
+
+    let mut output = String::new();
+    if options.include_frontmatter {
+        output.push_str(&document_header(doc));
+    }
+    for (i, section) in doc.sections.iter().enumerate() {
+        renderer.render_section(doc, i, section, &mut output);
+    }
+
+    let trimmed = output.trim();
+    if trimmed.is_empty() {
+        return Ok(String::new());
+    }
+    Ok(format!("{trimmed}\n"))
+}
+
+/// The document title and metadata attribute entries; empty without metadata.
+fn document_header(doc: &Document) -> String {
+    let mut header = String::new();
+    if let Some(ref title) = doc.metadata.title {
+        header.push_str(&format!("= {}\n", single_line(title)));
+    }
+    let attributes = [
+        ("author", doc.metadata.author.clone()),
+        ("description", doc.metadata.description.clone()),
+        (
+            "keywords",
+            Some(doc.metadata.keywords.join(", ")).filter(|k| !k.is_empty()),
+        ),
+    ];
+    for (name, value) in attributes {
+        if let Some(value) = value {
+            header.push_str(&format!(":{}: {}\n", name, single_line(&value)));
+        }
+    }
+    if !header.is_empty() {
+        header.push('\n');
+    }
+    header
+}
+
+struct AsciidocRenderer<'a> {
+    options: &'a RenderOptions,
+    resource_map: ResourceMap,
+    /// Label → text of the notes defined anywhere in the document
+    notes: HashMap<String, String>,
+    /// Labels whose `footnote:` macro has been emitted; later markers reference it
+    cited: RefCell<HashSet<String>>,
+}
+
+impl<'a> AsciidocRenderer<'a> {
+    fn new(doc: &Document, options: &'a RenderOptions) -> Self {
+        let notes = doc
+            .sections
+            .iter()
+            .flat_map(|s| &s.content)
+            .filter_map(|block| match block {
+                Block::Paragraph(p) => note_definition(p),
+                _ => None,
+            })
+            .collect();
+        Self {
+            options,
+            resource_map: markdown::build_resource_map(doc),
+            notes,
+            cited: RefCell::new(HashSet::new()),
+        }
+    }
+
+    fn render_section(&self, doc: &Document, index: usize, section: &Section, out: &mut String) {
+        if self.options.section_markers == SectionMarkerStyle::Comment {
+            if let Some(label) = markdown::section_label(doc.format, index, section.name.as_deref())
+            {
+                out.push_str(&format!("// {}\n\n", single_line(&label)));
+            }
+        }
+
+        if let Some(ref name) = section.name {
+            out.push_str(&format!("== {}\n\n", escape_text(&single_line(name))));
+        }
+
+        if self.options.include_headers_footers {
+            if let Some(ref header) = section.header {
+                self.render_region("header", header, out);
+            }
+        }
+
+        self.render_blocks(&section.content, out);
+
+        if self.options.include_headers_footers {
+            if let Some(ref footer) = section.footer {
+                self.render_region("footer", footer, out);
+            }
+        }
+
+        if let Some(ref notes) = section.notes {
+            let paragraphs: Vec<String> = notes
+                .iter()
+                .map(|p| self.inline(p, false))
+                .filter(|t| !t.is_empty())
+                .collect();
+            if !paragraphs.is_empty() {
+                out.push_str(".Notes\n****\n");
+                out.push_str(&paragraphs.join("\n\n"));
+                out.push_str("\n****\n\n");
+            }
+        }
+    }
+
+    /// Render a header or footer as an open block carrying its role.
+    fn render_region(&self, role: &str, paragraphs: &[Paragraph], out: &mut String) {
+        let texts: Vec<String> = paragraphs
+            .iter()
+            .map(|p| self.inline(p, false))
+            .filter(|t| !t.is_empty())
+            .collect();
+        if !texts.is_empty() {
+            out.push_str(&format!("[.{role}]\n--\n"));
+            out.push_str(&texts.join("\n\n"));
+            out.push_str("\n--\n\n");
+        }
+    }
+
+    fn render_blocks(&self, blocks: &[Block], out: &mut String) {
+        let mut i = 0;
+        while i < blocks.len() {
+            match &blocks[i] {
+                Block::Paragraph(p) if p.list_info.is_some() => {
+                    // A list is every consecutive list paragraph, whatever its level.
+                    let items: Vec<&Paragraph> = blocks[i..]
+                        .iter()
+                        .map_while(|b| match b {
+                            Block::Paragraph(p) if p.list_info.is_some() => Some(p),
+                            _ => None,
+                        })
+                        .collect();
+                    i += items.len();
+                    self.render_list(&items, out);
+                    continue;
+                }
+                Block::Paragraph(p) => self.render_paragraph(p, out),
+                Block::Table(table) => self.render_table_block(table, out),
+                Block::PageBreak => {
+                    if self.options.emit_page_breaks {
+                        out.push_str("<<<\n\n");
+                    }
+                }
+                Block::SectionBreak => out.push_str("'''\n\n"),
+                Block::Image {
+                    resource_id,
+                    alt_text,
+                    ..
+                } => {
+                    out.push_str(&format!(
+                        "image::{}\n\n",
+                        self.image_target(resource_id, alt_text.as_deref())
+                    ));
+                }
+            }
+            i += 1;
+        }
+    }
+
+    fn render_paragraph(&self, para: &Paragraph, out: &mut String) {
+        // A definition whose marker became a `footnote:` macro has nothing left to say.
+        if let Some((label, _)) = note_definition(para) {
+            if self.cited.borrow().contains(&label) {
+                return;
+            }
+        }
+
+        let text = self.inline(para, false);
+        if text.is_empty() {
+            return;
+        }
+        let level = para
+            .heading
+            .level()
+            .min(self.options.max_heading_level)
+            .min(MAX_SECTION_LEVEL);
+        if level > 0 {
+            out.push_str(&format!(
+                "{} {}\n\n",
+                "=".repeat(level as usize + 1),
+                single_line(&text)
+            ));
+        } else {
+            out.push_str(&protect_line_start(&text));
+            out.push_str("\n\n");
+        }
+    }
+
+    /// Render consecutive list paragraphs as one list.
+    ///
+    /// AsciiDoc nests by marker: `*`/`**` for bullets and `.`/`..` for numbers, one
+    /// character per level. Asciidoctor reads a new marker as a deeper level, so a
+    /// top-level switch between bullets and numbers ends the list with a `//-` comment
+    /// rather than nesting the second list inside the first.
+    fn render_list(&self, items: &[&Paragraph], out: &mut String) {
+        // The list type open at each depth, to tell a new list from a continued one.
+        let mut open: Vec<ListType> = Vec::new();
+
+        for item in items {
+            let Some(ref info) = item.list_info else {
+                continue;
+            };
+            let list_type = match info.list_type {
+                ListType::Numbered => ListType::Numbered,
+                ListType::Bullet | ListType::None => ListType::Bullet,
+            };
+            let depth = info.level as usize;
+
+            let continues = open.get(depth) == Some(&list_type);
+            if depth == 0 && !open.is_empty() && !continues {
+                out.push_str("\n//-\n\n");
+            }
+            if !continues {
+                if let (ListType::Numbered, Some(start)) = (list_type, info.number) {
+                    if start > 1 {
+                        out.push_str(&format!("[start={start}]\n"));
+                    }
+                }
+            }
+            open.truncate(depth);
+            open.resize(depth, ListType::Bullet);
+            open.push(list_type);
+
+            let marker = if list_type == ListType::Numbered {
+                "."
+            } else {
+                "*"
+            };
+            out.push_str(&marker.repeat(depth + 1));
+            out.push(' ');
+            out.push_str(&self.inline(item, false));
+            out.push('\n');
+        }
+        out.push('\n');
+    }
+
+    /// Render a table, or the admonition a callout table stands for.
+    fn render_table_block(&self, table: &Table, out: &mut String) {
+        if self.options.callout_blockquote {
+            if let Some(cell) = markdown::callout_cell(table) {
+                let paragraphs: Vec<String> = cell
+                    .content
+                    .iter()
+                    .map(|p| self.inline(p, true))
+                    .filter(|t| !t.is_empty())
+                    .collect();
+                out.push_str("[NOTE]\n====\n");
+                out.push_str(&paragraphs.join("\n\n"));
+                out.push_str("\n====\n\n");
+                return;
+            }
+        }
+        out.push_str(&self.render_table(table, '|'));
+        out.push('\n');
+    }
+
+    /// Render a table delimited by `separator`: `|` at the top level and `!` for a table
+    /// nested in an `a|` cell. AsciiDoc nests only that one level, so tables deeper
+    /// still follow their parent instead.
+    fn render_table(&self, table: &Table, separator: char) -> String {
+        if table.is_empty() {
+            return String::new();
+        }
+        let columns = grid::lay_out(table).first().map_or(0, Vec::len);
+
+        let mut out = String::new();
+        if let Some(ref caption) = table.caption {
+            out.push_str(&format!(".{}\n", escape_text(&single_line(caption))));
+        }
+        let cols = match table.column_widths {
+            Some(ref widths) if widths.len() == columns && widths.iter().all(|&w| w > 0) => widths
+                .iter()
+                .map(u32::to_string)
+                .collect::<Vec<_>>()
+                .join(","),
+            _ => format!("{columns}*"),
+        };
+        let header = table.rows.first().is_some_and(|r| r.is_header);
+        out.push_str(&format!(
+            "[{}cols=\"{}\"]\n{}===\n",
+            if header { "%header," } else { "" },
+            cols,
+            separator
+        ));
+
+        let mut trailing = String::new();
+        for (i, row) in table.rows.iter().enumerate() {
+            let cells: Vec<String> = row
+                .cells
+                .iter()
+                .map(|cell| self.render_cell(cell, separator, &mut trailing))
+                .collect();
+            out.push_str(&cells.join(" "));
+            out.push('\n');
+            if i == 0 && header && table.rows.len() > 1 {
+                out.push('\n');
+            }
+        }
+        out.push_str(&format!("{separator}===\n"));
+        out.push_str(&trailing);
+        out
+    }
+
+    /// Render a cell with its span and alignment specifier. Nested tables go in the cell
+    /// when it can hold them, or into `trailing` to follow the table when it cannot.
+    fn render_cell(&self, cell: &Cell, separator: char, trailing: &mut String) -> String {
+        let mut spec = String::new();
+        match (cell.col_span > 1, cell.row_span > 1) {
+            (true, true) => spec.push_str(&format!("{}.{}+", cell.col_span, cell.row_span)),
+            (true, false) => spec.push_str(&format!("{}+", cell.col_span)),
+            (false, true) => spec.push_str(&format!(".{}+", cell.row_span)),
+            (false, false) => {}
+        }
+        match markdown::effective_cell_alignment(cell) {
+            CellAlignment::Center => spec.push('^'),
+            CellAlignment::Right => spec.push('>'),
+            CellAlignment::Left => {}
+        }
+
+        let mut blocks: Vec<String> = cell
+            .content
+            .iter()
+            .map(|p| protect_line_start(&self.inline(p, false)))
+            .filter(|t| !t.is_empty())
+            .collect();
+        let mut nests = false;
+        for nested in &cell.nested_tables {
+            if separator == '|' {
+                blocks.push(self.render_table(nested, '!').trim_end().to_string());
+                nests = true;
+            } else {
+                trailing.push('\n');
+                trailing.push_str(&self.render_table(nested, '|'));
+            }
+        }
+        if nests {
+            // An AsciiDoc cell holds blocks, tables included, where a default cell
+            // only holds paragraphs.
+            spec.push('a');
+        }
+
+        let content = blocks
+            .join("\n\n")
+            .replace(separator, &format!("\\{separator}"));
+        if content.is_empty() {
+            format!("{spec}{separator}")
+        } else {
+            format!("{spec}{separator}{content}")
+        }
+    }
+
+    /// Render a paragraph's runs and inline images, without the enclosing block.
+    /// `plain` drops bold, for content whose emphasis the enclosing block stands for.
+    fn inline(&self, para: &Paragraph, plain: bool) -> String {
+        let merged = para.with_merged_runs();
+        let mut output = String::new();
+        if self.options.field_handling == FieldHandling::LinkReferences {
+            for name in &para.bookmarks {
+                output.push_str(&format!("[[{name}]]"));
+            }
+        }
+        let mut spacing = RunSpacing::default();
+
+        for run in &merged.runs {
+            let Some(text) = self.render_run(run, plain) else {
+                continue;
+            };
+            if spacing.space_before(&run.text) {
+                output.push(' ');
+            }
+            output.push_str(&text);
+        }
+
+        for image in &para.images {
+            if !output.is_empty() && !output.ends_with(char::is_whitespace) {
+                output.push(' ');
+            }
+            output.push_str(&format!(
+                "image:{}",
+                self.image_target(&image.resource_id, image.alt_text.as_deref())
+            ));
+        }
+        output.trim().to_string()
+    }
+
+    /// Render a text run, or `None` when tracked-change handling hides it.
+    fn render_run(&self, run: &TextRun, plain: bool) -> Option<String> {
+        let hidden = matches!(
+            (run.revision, self.options.revision_handling),
+            (RevisionType::Deleted, RevisionHandling::AcceptAll)
+                | (RevisionType::Inserted, RevisionHandling::RejectAll)
+        );
+        let break_marker = if run.page_break && self.options.emit_page_breaks {
+            "\n\n<<<\n\n"
+        } else if run.line_break && self.options.preserve_line_breaks {
+            " +\n"
+        } else {
+            ""
+        };
+        if hidden {
+            return Some(break_marker.to_string());
+        }
+
+        // Markup goes around the trimmed text, with the run's own whitespace outside it.
+        let core = run.text.trim();
+        if core.is_empty() {
+            return Some(format!("{}{}", run.text, break_marker));
+        }
+        let leading_len = run.text.len() - run.text.trim_start().len();
+        let leading = &run.text[..leading_len];
+        let trailing = &run.text[leading_len + core.len()..];
+
+        // Unconstrained marks throughout: constrained ones only apply at word
+        // boundaries, and a run can start or end mid-word.
+        let mut text = match run.math {
+            Some(_) => format!("latexmath:[{}]", core.replace(']', "\\]")),
+            None => self.text_with_note_refs(core),
+        };
+        let style = &run.style;
+        let wrappers = [
+            (style.code, "``", "``"),
+            (style.superscript, "^", "^"),
+            (style.subscript, "~", "~"),
+            (style.underline, "[.underline]##", "##"),
+            (style.strikethrough, "[.line-through]##", "##"),
+            (style.italic, "__", "__"),
+            (style.bold && !plain, "**", "**"),
+        ];
+        // An equation is styled by its own markup
+        for (_, open, close) in wrappers
+            .iter()
+            .filter(|(on, _, _)| *on && run.math.is_none())
+        {
+            text = format!("{open}{text}{close}");
+        }
+        if let Some(ref url) = run.hyperlink {
+            text = format!("{}[{}]", link_target(url), text);
+        }
+        if self.options.revision_handling == RevisionHandling::ShowMarkup {
+            match run.revision {
+                RevisionType::Inserted => text = format!("[.underline]##{text}##"),
+                RevisionType::Deleted => text = format!("[.line-through]##{text}##"),
+                RevisionType::None => {}
+            }
+        }
+        Some(format!("{leading}{text}{trailing}{break_marker}"))
+    }
+
+    /// Escape `text`, turning each `[^label]` marker of a defined note into a `footnote:`
+    /// macro: the first carries the note's text, later ones refer back to it.
+    fn text_with_note_refs(&self, text: &str) -> String {
+        let mut output = String::new();
+        let mut rest = text;
+        while let Some(start) = rest.find("[^") {
+            let Some(len) = rest[start + 2..].find(']') else {
+                break;
+            };
+            let label = &rest[start + 2..start + 2 + len];
+            let Some(note) = self.notes.get(label) else {
+                output.push_str(&escape_text(&rest[..start + 2]));
+                rest = &rest[start + 2..];
+                continue;
+            };
+            output.push_str(&escape_text(rest[..start].trim_end()));
+            let id = format!("fn-{}", note_id(label));
+            if self.cited.borrow_mut().insert(label.to_string()) {
+                // A passthrough in the text is lifted out before the macro is read, so
+                // its escaped brackets need no second escape.
+                output.push_str(&format!("footnote:{id}[{}]", escape_text(note.trim())));
+            } else {
+                output.push_str(&format!("footnote:{id}[]"));
+            }
+            rest = &rest[start + 2 + len + 1..];
+        }
+        output.push_str(&escape_text(rest));
+        output
+    }
+
+    /// An image macro's target and attribute list, e.g. `media/a.png[Chart]`.
+    fn image_target(&self, resource_id: &str, alt_text: Option<&str>) -> String {
+        let path = markdown::image_path(
+            resource_id,
+            &self.resource_map,
+            &self.options.image_path_prefix,
+        );
+        let alt = alt_text.unwrap_or("");
+        let alt = if alt.contains([',', '=', '"']) {
+            format!("\"{}\"", alt.replace('"', "\\\""))
+        } else {
+            alt.to_string()
+        };
+        format!(
+            "{}[{}]",
+            path.replace(' ', "%20"),
+            single_line(&alt).replace(']', "\\]")
+        )
+    }
+}
+
+/// A `link:` macro target; the `++` passthrough keeps spaces and brackets in the URL.
+/// A `#bookmark` in the document is an `xref:` to its anchor instead.
+fn link_target(url: &str) -> String {
+    if let Some(anchor) = url.strip_prefix('#') {
+        format!("xref:{anchor}")
+    } else if url.contains(|c: char| c.is_whitespace() || matches!(c, '[' | ']')) {
+        format!("link:++{url}++")
+    } else {
+        format!("link:{url}")
+    }
+}
+
+/// Escape literal text.
+///
+/// AsciiDoc has no reliable backslash escape for its inline marks, so text holding any
+/// of them goes in a `pass:c[...]` passthrough: its characters are kept as they are and
+/// only made safe for the output format.
+fn escape_text(text: &str) -> String {
+    let special = text.contains(|c: char| {
+        matches!(
+            c,
+            '*' | '_' | '`' | '#' | '^' | '~' | '+' | '[' | ']' | '{' | '}' | '<' | '>' | '\\'
+        )
+    }) || text.contains("::")
+        || text.contains(";;");
+    if special {
+        format!("pass:c[{}]", text.replace(']', "\\]"))
+    } else {
+        text.to_string()
+    }
+}
+
+/// Keep a paragraph from being read as a block: text starting with a list marker, block
+/// title, delimiter, comment, admonition label or attribute entry is led by `{empty}`.
+fn protect_line_start(text: &str) -> String {
+    let first_word = text.split_whitespace().next().unwrap_or("");
+    let numbered = first_word.len() > 1
+        && first_word.ends_with('.')
+        && first_word[..first_word.len() - 1]
+            .chars()
+            .all(|c| c.is_ascii_digit());
+    let admonition = matches!(
+        first_word,
+        "NOTE:" | "TIP:" | "IMPORTANT:" | "WARNING:" | "CAUTION:"
+    );
+    let marker = text.starts_with(['-', '.', '=', '/', ':', '\'', '|', '!']);
+    if numbered || admonition || marker {
+        format!("{{empty}}{text}")
+    } else {
+        text.to_string()
+    }
+}
+
+/// Collapse line breaks, for the places AsciiDoc allows only one line.
+fn single_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{HeadingLevel, ListInfo, Resource, Row, TextStyle};
+
+    fn list_item(text: &str, list_type: ListType, level: u8, number: Option<u32>) -> Block {
+        let mut p = Paragraph::with_text(text);
+        p.list_info = Some(ListInfo {
+            list_type,
+            level,
+            number,
+        });
+        Block::Paragraph(p)
+    }
+
+    fn docx(blocks: Vec<Block>) -> Document {
+        let mut doc = Document::new();
+        let mut section = Section::new(0);
+        section.content = blocks;
+        doc.add_section(section);
+        doc
+    }
+
+    fn render(doc: &Document) -> String {
+        to_asciidoc(doc, &RenderOptions::default()).unwrap()
+    }
+
+    #[test]
+    fn test_headings_paragraphs_and_inline_styles() {
+        let mut para = Paragraph::new();
+        para.add_run(TextRun::plain("Plain"));
+        para.add_run(TextRun::styled("bold", TextStyle::bold()));
+        para.add_run(TextRun::styled("a*b", TextStyle::italic()));
+        let mut link = TextRun::plain("site");
+        link.hyperlink = Some("https://example.com/a b".to_string());
+        para.add_run(link);
+
+        let doc = docx(vec![
+            Block::Paragraph(Paragraph::heading(HeadingLevel::H1, "Title")),
+            Block::Paragraph(Paragraph::heading(HeadingLevel::H2, "Part")),
+            Block::Paragraph(para),
+            Block::Paragraph(Paragraph::with_text("- not a list")),
+        ]);
+        let out = render(&doc);
+        assert_eq!(
+            out,
+            "== Title\n\n=== Part\n\nPlain **bold** __pass:c[a*b]__ link:++https://example.com/a b++[site]\n\n{empty}- not a list\n"
+        );
+    }
+
+    #[test]
+    fn test_lists_nest_by_marker() {
+        let doc = docx(vec![
+            list_item("One", ListType::Bullet, 0, None),
+            list_item("Inner", ListType::Numbered, 1, Some(1)),
+            list_item("Two", ListType::Bullet, 0, None),
+            list_item("Third", ListType::Numbered, 0, Some(3)),
+        ]);
+        assert_eq!(
+            render(&doc),
+            "* One\n.. Inner\n* Two\n\n//-\n\n[start=3]\n. Third\n"
+        );
+    }
+
+    #[test]
+    fn test_tables_keep_merges() {
+        let mut head = Row::header(vec![Cell::header("Group"), Cell::header("C")]);
+        head.cells[0].col_span = 2;
+        let mut tall = Cell::with_text("Tall");
+        tall.row_span = 2;
+        let mut right = Cell::with_text("a|b");
+        right.alignment = CellAlignment::Right;
+        let body = Row {
+            cells: vec![tall, Cell::with_text("x"), right],
+            is_header: false,
+            height: None,
+        };
+        let last = Row {
+            cells: vec![Cell::with_text("y"), Cell::new()],
+            is_header: false,
+            height: None,
+        };
+        let mut table = Table::new();
+        table.rows = vec![head, body, last];
+        let out = render(&docx(vec![Block::Table(table)]));
+        assert_eq!(
+            out,
+            "[%header,cols=\"3*\"]\n|===\n2+|Group |C\n\n.2+|Tall |x >|a\\|b\n|y |\n|===\n"
+        );
+    }
+
+    #[test]
+    fn test_callout_becomes_admonition() {
+        let mut cell = Cell::new();
+        let mut p = Paragraph::new();
+        p.add_run(TextRun::styled("Mind the gap", TextStyle::bold()));
+        cell.content.push(p);
+        let mut table = Table::new();
+        table.add_row(Row {
+            cells: vec![cell],
+            is_header: false,
+            height: None,
+        });
+        let doc = docx(vec![Block::Table(table)]);
+
+        let options = RenderOptions::default().with_callout_blockquote(true);
+        assert_eq!(
+            to_asciidoc(&doc, &options).unwrap(),
+            "[NOTE]\n====\nMind the gap\n====\n"
+        );
+        assert!(render(&doc).starts_with("[cols=\"1*\"]\n|===\n|**Mind the gap**"));
+    }
+
+    #[test]
+    fn test_footnotes_fold_into_macros() {
+        let mut para = Paragraph::new();
+        para.add_run(TextRun::plain("Claim"));
+        para.add_run(TextRun::plain("[^1]"));
+        para.add_run(TextRun::plain(" and again"));
+        para.add_run(TextRun::plain("[^1]"));
+        let doc = docx(vec![
+            Block::Paragraph(para),
+            Block::Paragraph(Paragraph::with_text("[^1]: Source [p. 2]")),
+        ]);
+        assert_eq!(
+            render(&doc),
+            "Claimfootnote:fn-1[pass:c[Source [p. 2\\]]] and againfootnote:fn-1[]\n"
+        );
+    }
+
+    #[test]
+    fn test_images_resolve_like_markdown() {
+        let mut doc = docx(vec![Block::Image {
+            resource_id: "rId5".to_string(),
+            alt_text: Some("Chart, 2024".to_string()),
+            width: None,
+            height: None,
+        }]);
+        doc.add_resource(
+            "rId5",
+            Resource::image(b"\x89PNG".to_vec(), Some("my chart.png".to_string())),
+        );
+        let options = RenderOptions::default().with_image_prefix("assets/");
+        assert_eq!(
+            to_asciidoc(&doc, &options).unwrap(),
+            "image::assets/my%20chart.png[\"Chart, 2024\"]\n"
+        );
+    }
+
+    #[test]
+    fn test_slide_sections_and_header() {
+        let mut doc = Document::new();
+        doc.format = crate::detect::FormatType::Pptx;
+        doc.metadata.title = Some("Deck".to_string());
+        doc.metadata.author = Some("Ana".to_string());
+        let mut section = Section::new(0);
+        section.name = Some("Intro".to_string());
+        section.content = vec![Block::Paragraph(Paragraph::with_text("Hello"))];
+        section.notes = Some(vec![Paragraph::with_text("Say hi")]);
+        doc.add_section(section);
+
+        let options = RenderOptions::default()
+            .with_frontmatter(true)
+            .with_section_markers(SectionMarkerStyle::Comment);
+        assert_eq!(
+            to_asciidoc(&doc, &options).unwrap(),
+            "= Deck\n:author: Ana\n\n// slide 1: Intro\n\n== Intro\n\nHello\n\n.Notes\n****\nSay hi\n****\n"
+        );
+    }
+}
@@ -52,6 +52,40 @@ pub fn clean_text(text: &str, options: &CleanupOptions) -> String {
     }
 }
 
+/// The character-level stages of [`clean_text`], for text that is only part of a line,
+/// such as one run of a paragraph.
+///
+/// Runs of whitespace are collapsed but not trimmed: the space between two runs often
+/// sits at the edge of one of them.
+pub(super) fn clean_fragment(text: &str, options: &CleanupOptions) -> String {
+    let mut result = text.to_string();
+    if options.normalize_strings {
+        result = normalize_unicode(&result);
+    }
+    if options.remove_pua {
+        result = remove_private_use_area(&result);
+    }
+    if options.final_normalize {
+        let mut collapsed = String::with_capacity(result.len());
+        let mut prev_space = false;
+        for c in result.chars() {
+            let space = c.is_whitespace();
+            if !(space && prev_space) {
+                collapsed.push(if space { ' ' } else { c });
+            }
+            prev_space = space;
+        }
+        result = collapsed;
+    }
+    result
+}
+
+/// Whether the line-level stages of [`clean_text`] would remove `line`.
+pub(super) fn drops_line(line: &str, options: &CleanupOptions) -> bool {
+    (options.clean_lines && should_skip_line(line))
+        || (options.filter_structure && is_orphan_mark(line))
+}
+
 /// Split a leading YAML frontmatter block off the text, if there is one.
 ///
 /// Returns the block without its trailing newline and the remaining body. A document
@@ -277,22 +311,23 @@ pub(crate) fn collapse_blank_lines(text: &str) -> String {
 fn filter_structure(text: &str) -> String {
     let stripped: String = text
         .lines()
-        .filter(|line| {
-            let trimmed = line.trim();
-            if trimmed.len() != 1 {
-                return true;
-            }
-            !trimmed
-                .chars()
-                .next()
-                .is_some_and(|c| matches!(c, '|' | '-' | '_' | '=' | '*' | '#' | '~'))
-        })
+        .filter(|line| !is_orphan_mark(line))
         .collect::<Vec<_>>()
         .join("\n");
 
     collapse_blank_lines(&stripped)
 }
 
+/// Check if a line is a lone structural character left behind by a table or rule.
+fn is_orphan_mark(line: &str) -> bool {
+    let trimmed = line.trim();
+    trimmed.len() == 1
+        && trimmed
+            .chars()
+            .next()
+            .is_some_and(|c| matches!(c, '|' | '-' | '_' | '=' | '*' | '#' | '~'))
+}
+
 /// Final whitespace normalization.
 ///
 /// Leading whitespace is preserved verbatim: in CommonMark it is the only way a document
@@ -0,0 +1,102 @@
+//! Helpers shared by the renderers.
+
+use crate::model::{FormField, Paragraph, TextRun};
+
+/// Split a note definition paragraph, `[^label]: text`, into its label and text.
+/// List items and headings are never note definitions.
+pub(crate) fn note_definition(para: &Paragraph) -> Option<(String, String)> {
+    if para.list_info.is_some() || para.heading.is_heading() {
+        return None;
+    }
+    let text = para.plain_text();
+    let rest = text.strip_prefix("[^")?;
+    let (label, body) = rest.split_once("]: ")?;
+    if label.is_empty() || label.contains(char::is_whitespace) {
+        return None;
+    }
+    Some((label.to_string(), body.to_string()))
+}
+
+/// An ID-safe spelling of a note label: ASCII letters and digits, `-` for the rest.
+pub(crate) fn note_id(label: &str) -> String {
+    label
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect()
+}
+
+/// Split a checkbox run into its state and the rest of the run: the label that shares
+/// the run, without the box glyph, which the renderer replaces with a mark of its own.
+pub(crate) fn checkbox(run: &TextRun) -> Option<(bool, TextRun)> {
+    let checked = run
+        .form_field
+        .as_deref()
+        .and_then(FormField::checkbox_state)?;
+    let text = run.text.trim_start();
+    let label = text
+        .strip_prefix(['☐', '☑', '☒'])
+        .unwrap_or(&run.text)
+        .to_string();
+    Some((
+        checked,
+        TextRun {
+            text: label,
+            form_field: None,
+            ..run.clone()
+        },
+    ))
+}
+
+/// Escape text for HTML and XML content.
+pub(crate) fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Escape text for a double-quoted HTML or XML attribute value.
+pub(crate) fn escape_attribute(text: &str) -> String {
+    escape_html(text).replace('"', "&quot;")
+}
+
+/// Drop the characters XML 1.0 does not allow, such as the control characters
+/// legacy binary formats leave in their text.
+pub(crate) fn xml_chars(text: &str) -> String {
+    text.chars()
+        .filter(|&c| {
+            matches!(c, '\t' | '\n' | '\r') || (c >= ' ' && c != '\u{FFFE}' && c != '\u{FFFF}')
+        })
+        .collect()
+}
+
+/// Check if a character should NOT have a space before it.
+pub(crate) fn is_no_space_before(c: char) -> bool {
+    matches!(
+        c,
+        '.' | ',' | ':' | ';' | '!' | '?' | ')' | ']' | '}' | '"' | '\'' | '…'
+    )
+}
+
+/// Where a space goes between the runs of a paragraph: the one the Markdown and text
+/// renderers insert between runs whose text would otherwise run together.
+#[derive(Debug, Default)]
+pub(crate) struct RunSpacing {
+    /// Last character of the runs rendered so far.
+    last: Option<char>,
+}
+
+impl RunSpacing {
+    /// Check if a space goes before a run with this text, and move past the run.
+    pub(crate) fn space_before(&mut self, text: &str) -> bool {
+        let space = match (self.last, text.chars().next()) {
+            (Some(last), Some(first)) => {
+                !last.is_whitespace() && !first.is_whitespace() && !is_no_space_before(first)
+            }
+            _ => false,
+        };
+        if let Some(c) = text.chars().last() {
+            self.last = Some(c);
+        }
+        space
+    }
+}
@@ -0,0 +1,193 @@
+//! CSV renderer implementation.
+//!
+//! Writes the tables of each section as delimited text, one output per section — for
+//! a workbook, one per sheet. Cells are placed on the grid of
+//! [`super::grid::lay_out`], so a merged cell keeps its columns and the cells it covers
+//! come out empty or repeating its value, as [`MergedCells`] chooses.
+
+use crate::error::Result;
+use crate::model::{Block, Cell, Document, Section, Table};
+
+use super::options::{CsvOptions, CsvQuoting, MergedCells};
+
+/// Convert a Document to CSV, one string per section in `doc.sections` order.
+///
+/// A section's tables follow one another, separated by an empty record; its other
+/// content is left out. A section without tables yields an empty string, so the
+/// output still lines up with the sections.
+pub fn to_csv(doc: &Document, options: &CsvOptions) -> Result<Vec<String>> {
+    Ok(doc
+        .sections
+        .iter()
+        .map(|section| render_section(section, options))
+        .collect())
+}
+
+fn render_section(section: &Section, options: &CsvOptions) -> String {
+    let tables: Vec<String> = section
+        .content
+        .iter()
+        .filter_map(|block| match block {
+            Block::Table(table) if !table.is_empty() => Some(render_table(table, options)),
+            _ => None,
+        })
+        .collect();
+    tables.join("\n")
+}
+
+fn render_table(table: &Table, options: &CsvOptions) -> String {
+    let grid = super::grid::lay_out(table);
+    let col_count = grid.first().map_or(0, Vec::len);
+
+    // The text each slot holds: its own cell's, or the covering merge's when repeated.
+    let mut fields: Vec<Vec<Option<String>>> = vec![vec![None; col_count]; grid.len()];
+    for (r, slots) in grid.iter().enumerate() {
+        for (c, slot) in slots.iter().enumerate() {
+            let Some(cell) = slot else { continue };
+            let text = cell_text(cell, options);
+            if options.merged_cells == MergedCells::Repeat {
+                let rows = (cell.row_span.max(1) as usize).min(grid.len() - r);
+                let cols = (cell.col_span.max(1) as usize).min(col_count - c);
+                for covered in &mut fields[r..r + rows] {
+                    for field in covered[c..c + cols].iter_mut().filter(|f| f.is_none()) {
+                        *field = Some(text.clone());
+                    }
+                }
+            }
+            fields[r][c] = Some(text);
+        }
+    }
+
+    let delimiter = options.delimiter.to_string();
+    let mut out = String::new();
+    for row in fields {
+        let record: Vec<String> = row
+            .iter()
+            .map(|field| quote(field.as_deref().unwrap_or(""), options))
+            .collect();
+        out.push_str(&record.join(&delimiter));
+        out.push('\n');
+    }
+    out
+}
+
+fn cell_text(cell: &Cell, options: &CsvOptions) -> String {
+    match cell.value {
+        Some(ref value) if options.raw_values => value.clone(),
+        _ => cell.plain_text(),
+    }
+}
+
+/// Quote a field as the options ask, doubling the quotes inside it.
+fn quote(field: &str, options: &CsvOptions) -> String {
+    let needed = match options.quoting {
+        CsvQuoting::Always => true,
+        CsvQuoting::Never => false,
+        CsvQuoting::Necessary => field.contains([options.delimiter, '"', '\n', '\r']),
+        CsvQuoting::NonNumeric => field.trim().parse::<f64>().is_err(),
+    };
+    if needed {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::Row;
+
+    fn row(cells: Vec<Cell>) -> Row {
+        Row {
+            cells,
+            is_header: false,
+            height: None,
+        }
+    }
+
+    fn sheet(tables: Vec<Table>) -> Document {
+        let mut doc = Document::new();
+        let mut section = Section::new(0);
+        section.name = Some("Data".to_string());
+        section.content = tables.into_iter().map(Block::Table).collect();
+        doc.add_section(section);
+        doc
+    }
+
+    fn merged_table() -> Table {
+        let mut group = Cell::with_text("Group");
+        group.col_span = 2;
+        let mut tall = Cell::with_text("Tall");
+        tall.row_span = 2;
+        let mut table = Table::new();
+        table.add_row(row(vec![group, Cell::with_text("C")]));
+        table.add_row(row(vec![
+            tall,
+            Cell::with_text("a, b"),
+            Cell::with_text("say \"hi\""),
+        ]));
+        table.add_row(row(vec![Cell::with_text("1.5"), Cell::new()]));
+        table
+    }
+
+    #[test]
+    fn test_merges_stay_blank_by_default() {
+        let csv = to_csv(&sheet(vec![merged_table()]), &CsvOptions::default()).unwrap();
+        assert_eq!(
+            csv,
+            vec!["Group,,C\nTall,\"a, b\",\"say \"\"hi\"\"\"\n,1.5,\n"]
+        );
+    }
+
+    #[test]
+    fn test_merges_repeat_when_asked() {
+        let options = CsvOptions::new()
+            .with_merged_cells(MergedCells::Repeat)
+            .with_delimiter('\t')
+            .with_quoting(CsvQuoting::Never);
+        let csv = to_csv(&sheet(vec![merged_table()]), &options).unwrap();
+        assert_eq!(
+            csv,
+            vec!["Group\tGroup\tC\nTall\ta, b\tsay \"hi\"\nTall\t1.5\t\n"]
+        );
+    }
+
+    #[test]
+    fn test_quoting_modes() {
+        let mut table = Table::new();
+        table.add_row(row(vec![Cell::with_text("x"), Cell::with_text("42")]));
+        let doc = sheet(vec![table]);
+
+        let always = CsvOptions::new().with_quoting(CsvQuoting::Always);
+        assert_eq!(to_csv(&doc, &always).unwrap(), vec!["\"x\",\"42\"\n"]);
+        let non_numeric = CsvOptions::new().with_quoting(CsvQuoting::NonNumeric);
+        assert_eq!(to_csv(&doc, &non_numeric).unwrap(), vec!["\"x\",42\n"]);
+    }
+
+    #[test]
+    fn test_raw_values_replace_formatted_dates() {
+        let mut date = Cell::with_text("2021-01-01");
+        date.value = Some("44197".to_string());
+        let mut table = Table::new();
+        table.add_row(row(vec![date, Cell::with_text("plain")]));
+        let doc = sheet(vec![table]);
+
+        assert_eq!(
+            to_csv(&doc, &CsvOptions::default()).unwrap(),
+            vec!["2021-01-01,plain\n"]
+        );
+        let raw = CsvOptions::new().with_raw_values(true);
+        assert_eq!(to_csv(&doc, &raw).unwrap(), vec!["44197,plain\n"]);
+    }
+
+    #[test]
+    fn test_one_output_per_section() {
+        let mut doc = sheet(vec![merged_table(), merged_table()]);
+        doc.add_section(Section::new(1));
+        let csv = to_csv(&doc, &CsvOptions::default()).unwrap();
+        assert_eq!(csv.len(), 2);
+        assert!(csv[0].contains("\n\nGroup,,C\n"));
+        assert_eq!(csv[1], "");
+    }
+}
@@ -0,0 +1,750 @@
+//! EPUB renderer implementation.
+//!
+//! Packages a document as an EPUB 3 book. The content is cut into chapters at
+//! section boundaries and at top-level headings, and each chapter becomes an XHTML
+//! content document written by the HTML renderer. Resources are added to the
+//! manifest, the metadata fills the package's Dublin Core elements, and the
+//! headings make up the navigation document.
+
+use std::collections::HashSet;
+use std::io::{Cursor, Write};
+
+use crate::error::Result;
+use crate::model::{unique_file_name, Block, Document, HeadingLevel, Paragraph, Resource};
+
+use super::common::{escape_attribute, escape_html, note_definition, note_id, xml_chars};
+use super::html::HtmlRenderer;
+use super::options::{EpubOptions, FieldHandling, HtmlOptions};
+
+/// Directory of the package document and everything it lists.
+const CONTENT_DIR: &str = "OEBPS";
+
+/// Directory of the resources, relative to the package document.
+const MEDIA_DIR: &str = "media";
+
+const CONTAINER_XML: &str = "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+<container version=\"1.0\" xmlns=\"urn:oasis:names:tc:opendocument:xmlns:container\">\n\
+<rootfiles>\n\
+<rootfile full-path=\"OEBPS/package.opf\" media-type=\"application/oebps-package+xml\"/>\n\
+</rootfiles>\n\
+</container>\n";
+
+const STYLESHEET: &str = "body { line-height: 1.4; }\n\
+table { border-collapse: collapse; margin: 1em 0; }\n\
+th, td { border: 1px solid #999; padding: 0.2em 0.4em; vertical-align: top; }\n\
+figure { margin: 1em 0; text-align: center; }\n\
+img { max-width: 100%; }\n\
+aside.footnote { font-size: 0.9em; }\n\
+aside.notes { border-left: 3px solid #ccc; padding-left: 1em; font-style: italic; }\n";
+
+/// Convert a Document to an EPUB 3 book, returning the bytes of the .epub file.
+///
+/// Every section starts a chapter, and so does every heading up to
+/// [`EpubOptions::chapter_level`]. A slide or sheet name becomes the title of its
+/// chapter. Footnote and endnote definitions move to the end of each chapter that
+/// refers to them, so the links between a note and its reference stay inside one
+/// content document.
+pub fn to_epub(doc: &Document, options: &EpubOptions) -> Result<Vec<u8>> {
+    let media = media_files(doc);
+    let html_options = HtmlOptions {
+        standalone: false,
+        image_path_prefix: format!("{MEDIA_DIR}/"),
+        revision_handling: options.revision_handling,
+        include_headers_footers: false,
+        field_handling: FieldHandling::ShowResults,
+    };
+    let renderer = HtmlRenderer::xhtml(doc, &html_options, media.iter().cloned().collect());
+
+    let (chapters, definitions) = split_chapters(doc, options.chapter_level);
+    let mut rendered: Vec<RenderedChapter> = chapters
+        .iter()
+        .map(|chapter| {
+            let mut body = String::new();
+            renderer.render_blocks(&chapter.blocks, &mut body);
+            if let Some(notes) = chapter.notes {
+                renderer.render_speaker_notes(notes, &mut body);
+            }
+            RenderedChapter {
+                body,
+                outline: renderer.take_outline(),
+            }
+        })
+        .collect();
+
+    // A definition goes to every chapter that links to it; one nothing links to
+    // stays in the chapter it came from.
+    for (label, para, origin) in definitions {
+        let link = format!("href=\"#fn-{}\"", note_id(&label));
+        let mut targets: Vec<usize> = (0..rendered.len())
+            .filter(|&i| rendered[i].body.contains(&link))
+            .collect();
+        if targets.is_empty() {
+            targets.push(origin);
+        }
+        let mut aside = String::new();
+        renderer.render_blocks(&[Block::Paragraph(para)], &mut aside);
+        for i in targets {
+            rendered[i].body.push_str(&aside);
+        }
+    }
+
+    rendered.retain(|chapter| !chapter.body.trim().is_empty());
+    if rendered.is_empty() {
+        rendered.push(RenderedChapter {
+            body: String::new(),
+            outline: Vec::new(),
+        });
+    }
+
+    let book_title = doc
+        .metadata
+        .title
+        .clone()
+        .filter(|t| !t.trim().is_empty())
+        .or_else(|| {
+            rendered
+                .iter()
+                .flat_map(|c| &c.outline)
+                .map(|entry| entry.text.clone())
+                .find(|text| !text.is_empty())
+        })
+        .unwrap_or_else(|| "Untitled".to_string());
+
+    let chapter_files: Vec<String> = (1..=rendered.len())
+        .map(|n| format!("chapter-{n:03}.xhtml"))
+        .collect();
+    let chapter_titles: Vec<String> = rendered
+        .iter()
+        .enumerate()
+        .map(|(i, chapter)| {
+            chapter
+                .outline
+                .iter()
+                .map(|entry| entry.text.clone())
+                .find(|text| !text.is_empty())
+                .unwrap_or_else(|| {
+                    if i == 0 {
+                        book_title.clone()
+                    } else {
+                        format!("Chapter {}", i + 1)
+                    }
+                })
+        })
+        .collect();
+
+    let identifier = options
+        .identifier
+        .clone()
+        .unwrap_or_else(|| derived_identifier(&book_title, &rendered));
+
+    let mut parts: Vec<(String, Vec<u8>)> = vec![
+        ("META-INF/container.xml".into(), CONTAINER_XML.into()),
+        (
+            format!("{CONTENT_DIR}/package.opf"),
+            package_document(
+                doc,
+                options,
+                &identifier,
+                &book_title,
+                &chapter_files,
+                &media,
+            )
+            .into(),
+        ),
+        (
+            format!("{CONTENT_DIR}/nav.xhtml"),
+            navigation_document(
+                &book_title,
+                &options.language,
+                &rendered,
+                &chapter_files,
+                &chapter_titles,
+            )
+            .into(),
+        ),
+        (format!("{CONTENT_DIR}/style.css"), STYLESHEET.into()),
+    ];
+    for (i, chapter) in rendered.iter().enumerate() {
+        parts.push((
+            format!("{CONTENT_DIR}/{}", chapter_files[i]),
+            xhtml_document(&chapter_titles[i], &options.language, &chapter.body).into(),
+        ));
+    }
+    for (id, name) in &media {
+        parts.push((
+            format!("{CONTENT_DIR}/{MEDIA_DIR}/{name}"),
+            doc.resources[id].data.clone(),
+        ));
+    }
+
+    let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    // The mimetype entry comes first and uncompressed, so a reader can identify
+    // the file from its leading bytes.
+    zip.start_file(
+        "mimetype",
+        zip::write::SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored),
+    )?;
+    zip.write_all(b"application/epub+zip")?;
+    let deflated = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated);
+    for (path, data) in parts {
+        zip.start_file(path, deflated)?;
+        zip.write_all(&data)?;
+    }
+    Ok(zip.finish()?.into_inner())
+}
+
+/// The blocks of one content document, before rendering.
+#[derive(Default)]
+struct Chapter<'a> {
+    blocks: Vec<Block>,
+    /// Whether any block of the section is in it yet; a chapter holding only the
+    /// section's title does not make way for the heading that follows.
+    has_content: bool,
+    /// Speaker notes of the slide the chapter ends
+    notes: Option<&'a [Paragraph]>,
+}
+
+struct RenderedChapter {
+    body: String,
+    outline: Vec<super::html::OutlineEntry>,
+}
+
+/// Cut the document into chapters, taking out the note definitions as
+/// (label, paragraph, index of the chapter they were in).
+fn split_chapters(
+    doc: &Document,
+    chapter_level: u8,
+) -> (Vec<Chapter<'_>>, Vec<(String, Paragraph, usize)>) {
+    let mut chapters: Vec<Chapter> = Vec::new();
+    let mut definitions = Vec::new();
+
+    for section in &doc.sections {
+        let mut chapter = Chapter::default();
+        if let Some(name) = section.name.as_deref().filter(|n| !n.trim().is_empty()) {
+            chapter
+                .blocks
+                .push(Block::Paragraph(Paragraph::heading(HeadingLevel::H1, name)));
+        }
+        for block in &section.content {
+            if let Block::Paragraph(para) = block {
+                if let Some((label, _)) = note_definition(para) {
+                    definitions.push((label, para.clone(), chapters.len()));
+                    continue;
+                }
+                let level = para.heading.level();
+                let starts_chapter =
+                    level > 0 && level <= chapter_level && para.list_info.is_none();
+                if starts_chapter && chapter.has_content {
+                    chapters.push(std::mem::take(&mut chapter));
+                }
+            }
+            chapter.blocks.push(block.clone());
+            chapter.has_content = true;
+        }
+        chapter.notes = section.notes.as_deref();
+        chapters.push(chapter);
+    }
+    (chapters, definitions)
+}
+
+/// File names under [`MEDIA_DIR`] for every resource, by resource ID.
+fn media_files(doc: &Document) -> Vec<(String, String)> {
+    let mut ids: Vec<&String> = doc.resources.keys().collect();
+    ids.sort();
+    let mut taken = HashSet::new();
+    ids.into_iter()
+        .map(|id| {
+            let name = unique_file_name(&doc.resources[id].suggested_filename(id), &mut taken);
+            (id.clone(), name)
+        })
+        .collect()
+}
+
+fn media_type(resource: &Resource, file_name: &str) -> String {
+    resource
+        .mime_type
+        .clone()
+        .or_else(|| Resource::mime_from_filename(file_name))
+        .unwrap_or_else(|| "application/octet-stream".to_string())
+}
+
+fn package_document(
+    doc: &Document,
+    options: &EpubOptions,
+    identifier: &str,
+    title: &str,
+    chapter_files: &[String],
+    media: &[(String, String)],
+) -> String {
+    let meta = &doc.metadata;
+    let mut out = format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <package xmlns=\"http://www.idpf.org/2007/opf\" version=\"3.0\" \
+         unique-identifier=\"book-id\" xml:lang=\"{}\">\n\
+         <metadata xmlns:dc=\"http://purl.org/dc/elements/1.1/\">\n\
+         <dc:identifier id=\"book-id\">{}</dc:identifier>\n\
+         <dc:title>{}</dc:title>\n\
+         <dc:language>{}</dc:language>\n",
+        escape_attribute(&options.language),
+        xml_text(identifier),
+        xml_text(title),
+        xml_text(&options.language),
+    );
+    let mut elements: Vec<(&str, &str)> = Vec::new();
+    if let Some(ref author) = meta.author {
+        elements.push(("creator", author));
+    }
+    if let Some(ref subject) = meta.subject {
+        elements.push(("subject", subject));
+    }
+    for keyword in &meta.keywords {
+        elements.push(("subject", keyword));
+    }
+    if let Some(ref description) = meta.description {
+        elements.push(("description", description));
+    }
+    for (name, value) in elements.into_iter().filter(|(_, v)| !v.trim().is_empty()) {
+        out.push_str(&format!("<dc:{name}>{}</dc:{name}>\n", xml_text(value)));
+    }
+    if let Some(created) = meta.created.as_deref().and_then(epub_timestamp) {
+        out.push_str(&format!("<dc:date>{created}</dc:date>\n"));
+    }
+    let modified = meta
+        .modified
+        .as_deref()
+        .or(meta.created.as_deref())
+        .and_then(epub_timestamp)
+        .unwrap_or_else(now_timestamp);
+    out.push_str(&format!(
+        "<meta property=\"dcterms:modified\">{modified}</meta>\n</metadata>\n"
+    ));
+
+    out.push_str(
+        "<manifest>\n\
+         <item id=\"nav\" href=\"nav.xhtml\" media-type=\"application/xhtml+xml\" properties=\"nav\"/>\n\
+         <item id=\"css\" href=\"style.css\" media-type=\"text/css\"/>\n",
+    );
+    for (i, file) in chapter_files.iter().enumerate() {
+        out.push_str(&format!(
+            "<item id=\"chapter-{}\" href=\"{file}\" media-type=\"application/xhtml+xml\"/>\n",
+            i + 1
+        ));
+    }
+    for (i, (id, name)) in media.iter().enumerate() {
+        out.push_str(&format!(
+            "<item id=\"media-{}\" href=\"{MEDIA_DIR}/{}\" media-type=\"{}\"/>\n",
+            i + 1,
+            escape_attribute(name),
+            escape_attribute(&media_type(&doc.resources[id], name))
+        ));
+    }
+    out.push_str("</manifest>\n<spine>\n");
+    for i in 1..=chapter_files.len() {
+        out.push_str(&format!("<itemref idref=\"chapter-{i}\"/>\n"));
+    }
+    out.push_str("</spine>\n</package>\n");
+    out
+}
+
+/// A heading of the table of contents and the ones nested under it.
+struct TocNode {
+    level: u8,
+    label: String,
+    href: String,
+    children: Vec<TocNode>,
+}
+
+impl TocNode {
+    fn insert(nodes: &mut Vec<TocNode>, node: TocNode) {
+        match nodes.last_mut() {
+            Some(last) if last.level < node.level => Self::insert(&mut last.children, node),
+            _ => nodes.push(node),
+        }
+    }
+
+    fn render(nodes: &[TocNode], out: &mut String) {
+        out.push_str("<ol>\n");
+        for node in nodes {
+            out.push_str(&format!(
+                "<li><a href=\"{}\">{}</a>",
+                escape_attribute(&node.href),
+                escape_html(&node.label)
+            ));
+            if !node.children.is_empty() {
+                out.push('\n');
+                Self::render(&node.children, out);
+            }
+            out.push_str("</li>\n");
+        }
+        out.push_str("</ol>\n");
+    }
+}
+
+/// The navigation document: the headings of every chapter as nested lists. A
+/// chapter without headings is listed under its title.
+fn navigation_document(
+    title: &str,
+    language: &str,
+    chapters: &[RenderedChapter],
+    files: &[String],
+    titles: &[String],
+) -> String {
+    let mut toc: Vec<TocNode> = Vec::new();
+    for (i, chapter) in chapters.iter().enumerate() {
+        if chapter.outline.is_empty() {
+            TocNode::insert(
+                &mut toc,
+                TocNode {
+                    level: 1,
+                    label: titles[i].clone(),
+                    href: files[i].clone(),
+                    children: Vec::new(),
+                },
+            );
+        }
+        for entry in &chapter.outline {
+            TocNode::insert(
+                &mut toc,
+                TocNode {
+                    level: entry.level,
+                    label: entry.text.clone(),
+                    href: format!("{}#{}", files[i], entry.id),
+                    children: Vec::new(),
+                },
+            );
+        }
+    }
+
+    let mut body = String::from("<nav epub:type=\"toc\" id=\"toc\">\n<h1>Contents</h1>\n");
+    TocNode::render(&toc, &mut body);
+    body.push_str("</nav>\n");
+    xhtml_document(title, language, &body)
+}
+
+fn xhtml_document(title: &str, language: &str, body: &str) -> String {
+    let language = escape_attribute(language);
+    format!(
+        "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n\
+         <!DOCTYPE html>\n\
+         <html xmlns=\"http://www.w3.org/1999/xhtml\" xmlns:epub=\"http://www.idpf.org/2007/ops\" \
+         lang=\"{language}\" xml:lang=\"{language}\">\n\
+         <head>\n\
+         <meta charset=\"utf-8\"/>\n\
+         <title>{}</title>\n\
+         <link rel=\"stylesheet\" type=\"text/css\" href=\"style.css\"/>\n\
+         </head>\n\
+         <body>\n{}</body>\n\
+         </html>\n",
+        xml_text(title),
+        xml_chars(body)
+    )
+}
+
+/// Escape metadata text for the package document or a `<title>`, without the
+/// characters XML 1.0 does not allow.
+fn xml_text(text: &str) -> String {
+    escape_html(&xml_chars(text))
+}
+
+/// The `CCYY-MM-DDThh:mm:ssZ` form of an ISO 8601 date or date-time, as
+/// `dcterms:modified` requires: moved to UTC by its offset, fractions of a second
+/// dropped.
+fn epub_timestamp(value: &str) -> Option<String> {
+    fn digits_with(s: &str, separator: u8, at: &[usize]) -> bool {
+        s.bytes().enumerate().all(|(i, b)| {
+            if at.contains(&i) {
+                b == separator
+            } else {
+                b.is_ascii_digit()
+            }
+        })
+    }
+    let number = |s: &str| s.parse::<i64>().ok();
+
+    let value = value.trim();
+    let date = value.get(..10).filter(|d| digits_with(d, b'-', &[4, 7]))?;
+    let days = crate::model::days_from_civil(
+        number(&date[..4])?,
+        date[5..7].parse().ok()?,
+        date[8..].parse().ok()?,
+    )?;
+    let Some(time) = value
+        .get(10..19)
+        .filter(|t| t.starts_with(['T', ' ']) && digits_with(&t[1..], b':', &[2, 5]))
+    else {
+        return Some(crate::model::iso8601_from_unix(days * 86_400));
+    };
+    let seconds = number(&time[1..3])? * 3600 + number(&time[4..6])? * 60 + number(&time[7..])?;
+
+    // A fraction of a second, then the offset from UTC: `Z`, `±hh:mm`, `±hhmm` or `±hh`.
+    let rest = value[19..].strip_prefix('.').map_or(&value[19..], |r| {
+        r.trim_start_matches(|c: char| c.is_ascii_digit())
+    });
+    let offset = match rest.split_at_checked(1) {
+        Some((sign @ ("+" | "-"), zone)) => {
+            let zone = zone.replacen(':', "", 1);
+            let minutes = match zone.len() {
+                2 => number(&zone)? * 60,
+                4 => number(&zone[..2])? * 60 + number(&zone[2..])?,
+                _ => return None,
+            };
+            if sign == "-" {
+                -minutes
+            } else {
+                minutes
+            }
+        }
+        _ => 0,
+    };
+    Some(crate::model::iso8601_from_unix(
+        days * 86_400 + seconds - offset * 60,
+    ))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn now_timestamp() -> String {
+    let secs = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| d.as_secs() as i64);
+    crate::model::iso8601_from_unix(secs)
+}
+
+/// WebAssembly has no clock to ask; a book without dates gets the epoch.
+#[cfg(target_arch = "wasm32")]
+fn now_timestamp() -> String {
+    crate::model::iso8601_from_unix(0)
+}
+
+/// An identifier that follows from the title and content (FNV-1a), so converting
+/// the same document twice gives the same book.
+fn derived_identifier(title: &str, chapters: &[RenderedChapter]) -> String {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    let text = std::iter::once(title).chain(chapters.iter().map(|c| c.body.as_str()));
+    for bytes in text.map(str::as_bytes) {
+        for &b in bytes {
+            hash ^= u64::from(b);
+            hash = hash.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+    format!("urn:undoc:{hash:016x}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::detect::FormatType;
+    use crate::model::{Section, TextRun};
+    use std::collections::HashMap;
+    use std::io::Read;
+
+    fn unzip(bytes: &[u8]) -> HashMap<String, String> {
+        let mut archive = zip::ZipArchive::new(Cursor::new(bytes)).unwrap();
+        let mut files = HashMap::new();
+        for i in 0..archive.len() {
+            let mut file = archive.by_index(i).unwrap();
+            let mut data = Vec::new();
+            file.read_to_end(&mut data).unwrap();
+            files.insert(
+                file.name().to_string(),
+                String::from_utf8_lossy(&data).to_string(),
+            );
+        }
+        files
+    }
+
+    fn manual() -> Document {
+        let mut doc = Document::new();
+        doc.metadata.title = Some("Pump Manual".to_string());
+        doc.metadata.author = Some("Field Ops".to_string());
+        doc.metadata.keywords = vec!["pumps".to_string()];
+        doc.metadata.modified = Some("2024-03-05T10:20:30.5+02:00".to_string());
+
+        let mut claim = Paragraph::new();
+        claim.add_run(TextRun::plain("Check the seal"));
+        claim.add_run(TextRun::plain("[^1]"));
+
+        let mut section = Section::new(0);
+        section.content = vec![
+            Block::Paragraph(Paragraph::with_text("Read this first.")),
+            Block::Paragraph(Paragraph::heading(HeadingLevel::H1, "Install")),
+            Block::Paragraph(Paragraph::heading(HeadingLevel::H2, "Mounting")),
+            Block::Paragraph(claim),
+            Block::Paragraph(Paragraph::heading(HeadingLevel::H1, "Service")),
+            Block::Image {
+                resource_id: "rId7".to_string(),
+                alt_text: Some("Exploded view".to_string()),
+                width: None,
+                height: None,
+            },
+            Block::Paragraph(Paragraph::with_text("[^1]: Torque to 5 Nm")),
+        ];
+        doc.add_section(section);
+        doc.add_resource(
+            "rId7",
+            Resource::image(b"\x89PNG".to_vec(), Some("view.png".to_string())),
+        );
+        doc
+    }
+
+    #[test]
+    fn test_mimetype_comes_first_uncompressed() {
+        let bytes = to_epub(&manual(), &EpubOptions::default()).unwrap();
+        assert_eq!(&bytes[30..38], b"mimetype");
+        assert_eq!(&bytes[38..58], b"application/epub+zip");
+        let mut archive = zip::ZipArchive::new(Cursor::new(&bytes)).unwrap();
+        let first = archive.by_index(0).unwrap();
+        assert_eq!(first.compression(), zip::CompressionMethod::Stored);
+    }
+
+    #[test]
+    fn test_chapters_split_at_top_level_headings() {
+        let files = unzip(&to_epub(&manual(), &EpubOptions::default()).unwrap());
+        let preface = &files["OEBPS/chapter-001.xhtml"];
+        assert!(preface.contains("<title>Pump Manual</title>"), "{preface}");
+        assert!(preface.contains("<p>Read this first.</p>"), "{preface}");
+        let install = &files["OEBPS/chapter-002.xhtml"];
+        assert!(
+            install.contains("<h1 id=\"toc-1\">Install</h1>"),
+            "{install}"
+        );
+        assert!(
+            install.contains("<h2 id=\"toc-2\">Mounting</h2>"),
+            "{install}"
+        );
+        let service = &files["OEBPS/chapter-003.xhtml"];
+        assert!(
+            service.contains("<img src=\"media/view.png\" alt=\"Exploded view\"/>"),
+            "{service}"
+        );
+        assert!(!files.contains_key("OEBPS/chapter-004.xhtml"));
+
+        let whole = to_epub(&manual(), &EpubOptions::new().with_chapter_level(0)).unwrap();
+        let files = unzip(&whole);
+        assert!(!files.contains_key("OEBPS/chapter-002.xhtml"));
+    }
+
+    #[test]
+    fn test_footnotes_follow_their_references() {
+        let files = unzip(&to_epub(&manual(), &EpubOptions::default()).unwrap());
+        let install = &files["OEBPS/chapter-002.xhtml"];
+        assert!(
+            install.contains("<a href=\"#fn-1\" id=\"fnref-1\" epub:type=\"noteref\">1</a>"),
+            "{install}"
+        );
+        assert!(
+            install.contains("<aside id=\"fn-1\" class=\"footnote\" role=\"doc-footnote\" epub:type=\"footnote\">"),
+            "{install}"
+        );
+        assert!(!files["OEBPS/chapter-003.xhtml"].contains("fn-1"));
+    }
+
+    #[test]
+    fn test_nav_follows_heading_hierarchy() {
+        let files = unzip(&to_epub(&manual(), &EpubOptions::default()).unwrap());
+        let nav = &files["OEBPS/nav.xhtml"];
+        assert!(
+            nav.contains(
+                "<ol>\n<li><a href=\"chapter-001.xhtml\">Pump Manual</a></li>\n\
+                 <li><a href=\"chapter-002.xhtml#toc-1\">Install</a>\n\
+                 <ol>\n<li><a href=\"chapter-002.xhtml#toc-2\">Mounting</a></li>\n</ol>\n</li>\n\
+                 <li><a href=\"chapter-003.xhtml#toc-1\">Service</a></li>\n</ol>\n"
+            ),
+            "{nav}"
+        );
+    }
+
+    #[test]
+    fn test_package_metadata_and_manifest() {
+        let options = EpubOptions::new()
+            .with_language("de")
+            .with_identifier("urn:isbn:9780000000000");
+        let files = unzip(&to_epub(&manual(), &options).unwrap());
+        let opf = &files["OEBPS/package.opf"];
+        for expected in [
+            "<dc:identifier id=\"book-id\">urn:isbn:9780000000000</dc:identifier>",
+            "<dc:title>Pump Manual</dc:title>",
+            "<dc:language>de</dc:language>",
+            "<dc:creator>Field Ops</dc:creator>",
+            "<dc:subject>pumps</dc:subject>",
+            "<meta property=\"dcterms:modified\">2024-03-05T08:20:30Z</meta>",
+            "properties=\"nav\"",
+            "<item id=\"media-1\" href=\"media/view.png\" media-type=\"image/png\"/>",
+            "<itemref idref=\"chapter-3\"/>",
+        ] {
+            assert!(opf.contains(expected), "{expected}\n{opf}");
+        }
+        assert_eq!(files["OEBPS/media/view.png"], "\u{FFFD}PNG");
+        assert_eq!(
+            files["META-INF/container.xml"], CONTAINER_XML,
+            "container points at the package document"
+        );
+    }
+
+    #[test]
+    fn test_metadata_drops_characters_xml_does_not_allow() {
+        let mut doc = manual();
+        doc.metadata.title = Some("Pump\u{1} Manual".to_string());
+        doc.metadata.description = Some("Spare\u{0B}parts".to_string());
+        let files = unzip(&to_epub(&doc, &EpubOptions::default()).unwrap());
+        let opf = &files["OEBPS/package.opf"];
+        assert!(opf.contains("<dc:title>Pump Manual</dc:title>"), "{opf}");
+        assert!(
+            opf.contains("<dc:description>Spareparts</dc:description>"),
+            "{opf}"
+        );
+        let preface = &files["OEBPS/chapter-001.xhtml"];
+        assert!(preface.contains("<title>Pump Manual</title>"), "{preface}");
+    }
+
+    #[test]
+    fn test_slides_become_titled_chapters() {
+        let mut doc = Document::new();
+        doc.format = FormatType::Pptx;
+        for (i, name) in ["Intro", "Plan"].into_iter().enumerate() {
+            let mut slide = Section::with_name(i, name);
+            slide.add_paragraph(Paragraph::heading(HeadingLevel::H1, "Slide title"));
+            slide.add_paragraph(Paragraph::with_text("Body\u{0B}text"));
+            slide.notes = Some(vec![Paragraph::with_text("Say hi")]);
+            doc.add_section(slide);
+        }
+        let files = unzip(&to_epub(&doc, &EpubOptions::default()).unwrap());
+        let intro = &files["OEBPS/chapter-001.xhtml"];
+        assert!(
+            intro.contains(
+                "<h1 id=\"toc-1\">Intro</h1>\n<h1 id=\"toc-2\">Slide title</h1>\n<p>Bodytext</p>\n\
+                 <aside class=\"notes\">\n<p>Say hi</p>\n</aside>\n"
+            ),
+            "{intro}"
+        );
+        assert!(files.contains_key("OEBPS/chapter-002.xhtml"));
+        assert!(!files.contains_key("OEBPS/chapter-003.xhtml"));
+        assert!(files["OEBPS/package.opf"].contains("<dc:title>Intro</dc:title>"));
+    }
+
+    #[test]
+    fn test_timestamps() {
+        assert_eq!(
+            epub_timestamp("2024-01-15").as_deref(),
+            Some("2024-01-15T00:00:00Z")
+        );
+        assert_eq!(
+            epub_timestamp("2024-01-15 08:09:10").as_deref(),
+            Some("2024-01-15T08:09:10Z")
+        );
+        assert_eq!(
+            epub_timestamp("2024-03-05T10:20:30.5+02:00").as_deref(),
+            Some("2024-03-05T08:20:30Z")
+        );
+        assert_eq!(
+            epub_timestamp("2024-01-01T01:00:00-0530").as_deref(),
+            Some("2024-01-01T06:30:00Z")
+        );
+        assert_eq!(
+            epub_timestamp("2024-01-01T01:00:00+02").as_deref(),
+            Some("2023-12-31T23:00:00Z")
+        );
+        assert_eq!(epub_timestamp("15/01/2024"), None);
+        assert_eq!(epub_timestamp("2021-02-31"), None);
+    }
+}
@@ -0,0 +1,593 @@
+//! Content decisions of [`RenderOptions`] applied to the model itself.
+//!
+//! The Markdown renderer takes these decisions while it writes. JSON output *is* the
+//! model, so it takes them here first: tracked changes resolved, headers, footers and
+//! notes left out, run text cleaned. The text renderers that have no other use for a
+//! note take only that decision from here. Review comments are placed here too, as
+//! runs the Markdown renderer writes like any other, and field results are dropped or
+//! linked as [`FieldHandling`] says.
+
+use crate::model::{
+    Block, Comment, Document, Paragraph, RevisionType, Section, Table, TextRun, TextStyle,
+};
+
+use super::options::{
+    CleanupOptions, CommentStyle, FieldHandling, RenderOptions, RevisionHandling,
+};
+
+/// What to keep and how to clean it.
+struct Decisions<'a> {
+    revisions: RevisionHandling,
+    headers_footers: bool,
+    notes: bool,
+    cleanup: Option<&'a CleanupOptions>,
+    fields: FieldHandling,
+}
+
+/// The sections of `doc` with every content decision of `options` applied.
+pub(super) fn sections(doc: &Document, options: &RenderOptions) -> Vec<Section> {
+    let decisions = Decisions {
+        revisions: options.revision_handling,
+        headers_footers: options.include_headers_footers,
+        notes: options.include_notes,
+        cleanup: options.cleanup.as_ref(),
+        fields: options.field_handling,
+    };
+    doc.sections
+        .iter()
+        .map(|section| decisions.section(section))
+        .collect()
+}
+
+/// A copy of `doc` without its footnotes, endnotes and speaker notes.
+pub(super) fn without_notes(doc: &Document) -> Document {
+    Document {
+        sections: doc.sections.iter().map(section_without_notes).collect(),
+        ..doc.clone()
+    }
+}
+
+/// A copy of `section` without its footnotes, endnotes and speaker notes.
+pub(super) fn section_without_notes(section: &Section) -> Section {
+    let decisions = Decisions {
+        revisions: RevisionHandling::ShowMarkup,
+        headers_footers: true,
+        notes: false,
+        cleanup: None,
+        fields: FieldHandling::ShowResults,
+    };
+    decisions.section(section)
+}
+
+/// A copy of `doc` with each review comment written where its range ends, in `style`.
+///
+/// Replies follow the comment they answer, so only the comments that start a thread
+/// are placed. As footnotes, the definitions are added to the end of the last section.
+pub(super) fn with_comments(doc: &Document, style: CommentStyle) -> Document {
+    let mut doc = doc.clone();
+    if style == CommentStyle::Omit || doc.comments.is_empty() {
+        return doc;
+    }
+    let comments = doc.comments.clone();
+    let threads: Vec<(&Comment, Vec<&Comment>)> = comments
+        .iter()
+        .filter(|c| !c.is_reply())
+        .map(|c| {
+            let replies = comments
+                .iter()
+                .filter(|r| r.parent_id.as_deref() == Some(c.id.as_str()))
+                .collect();
+            (c, replies)
+        })
+        .collect();
+
+    let mut placed: Vec<&str> = Vec::new();
+    let mut place = |para: &mut Paragraph| {
+        for id in &para.comment_ids {
+            let Some((comment, replies)) = threads.iter().find(|(c, _)| c.id == *id) else {
+                continue;
+            };
+            match style {
+                CommentStyle::Footnotes => {
+                    para.runs
+                        .push(TextRun::plain(format!("[^c{}]", comment.id)));
+                }
+                _ => {
+                    para.runs
+                        .push(TextRun::plain(html_comment("Comment", comment)));
+                    for reply in replies {
+                        para.runs.push(TextRun::plain(html_comment("Reply", reply)));
+                    }
+                }
+            }
+            placed.push(comment.id.as_str());
+        }
+    };
+    for section in &mut doc.sections {
+        for block in &mut section.content {
+            match block {
+                Block::Paragraph(para) => place(para),
+                Block::Table(table) => table_paragraphs(table, &mut place),
+                _ => {}
+            }
+        }
+    }
+
+    if style == CommentStyle::Footnotes && !placed.is_empty() {
+        if doc.sections.is_empty() {
+            doc.sections.push(Section::new(0));
+        }
+        let last = doc.sections.last_mut().expect("a section was just ensured");
+        for (comment, replies) in threads
+            .iter()
+            .filter(|(c, _)| placed.contains(&c.id.as_str()))
+        {
+            let mut para = Paragraph::new();
+            para.runs
+                .push(TextRun::plain(format!("[^c{}]: ", comment.id)));
+            comment_runs(comment, &mut para.runs);
+            for reply in replies {
+                para.runs.push(TextRun::plain(" \u{2014} "));
+                comment_runs(reply, &mut para.runs);
+            }
+            last.add_paragraph(para);
+        }
+    }
+    doc
+}
+
+/// A copy of `doc` with its field results shown as `handling` says.
+pub(super) fn with_fields(doc: &Document, handling: FieldHandling) -> Document {
+    let mut doc = doc.clone();
+    for section in &mut doc.sections {
+        section_fields(section, handling);
+    }
+    doc
+}
+
+/// Show the field results of `section` as `handling` says.
+pub(super) fn section_fields(section: &mut Section, handling: FieldHandling) {
+    let mut apply = |para: &mut Paragraph| {
+        let runs = para.runs.drain(..).map(Some).collect();
+        para.runs = kept_runs(field_results(runs, handling));
+    };
+    for block in &mut section.content {
+        match block {
+            Block::Paragraph(para) => apply(para),
+            Block::Table(table) => table_paragraphs(table, &mut apply),
+            _ => {}
+        }
+    }
+    for paragraphs in [&mut section.header, &mut section.footer, &mut section.notes]
+        .into_iter()
+        .flatten()
+    {
+        paragraphs.iter_mut().for_each(&mut apply);
+    }
+}
+
+/// `runs` with the field results among them dropped or linked as `handling` says. A
+/// dropped field stays, on an empty run.
+fn field_results(mut runs: Vec<Option<TextRun>>, handling: FieldHandling) -> Vec<Option<TextRun>> {
+    for i in 0..runs.len() {
+        let Some(field) = runs[i].as_ref().and_then(|run| run.field.clone()) else {
+            continue;
+        };
+        let end = (i + field.result_runs).min(runs.len());
+        match handling {
+            FieldHandling::DropPageNumbers if field.is_page_number() => {
+                if let Some(run) = runs[i].as_mut() {
+                    run.text.clear();
+                }
+                runs[i + 1..end].fill(None);
+            }
+            FieldHandling::LinkReferences => {
+                if let Some(bookmark) = field.bookmark() {
+                    let target = format!("#{bookmark}");
+                    for run in runs[i..end].iter_mut().flatten() {
+                        run.hyperlink.get_or_insert_with(|| target.clone());
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+    runs
+}
+
+/// The runs left of `runs`, each field's result counting only its runs that are left.
+fn kept_runs(mut runs: Vec<Option<TextRun>>) -> Vec<TextRun> {
+    for i in 0..runs.len() {
+        let Some(count) = runs[i]
+            .as_ref()
+            .and_then(|run| run.field.as_deref())
+            .map(|field| {
+                let end = (i + field.result_runs).min(runs.len());
+                runs[i..end].iter().flatten().count()
+            })
+        else {
+            continue;
+        };
+        if let Some(field) = runs[i].as_mut().and_then(|run| run.field.as_mut()) {
+            field.result_runs = count;
+        }
+    }
+    runs.into_iter().flatten().collect()
+}
+
+/// Apply `f` to every paragraph of a table, nested tables included.
+fn table_paragraphs(table: &mut Table, f: &mut impl FnMut(&mut Paragraph)) {
+    for cell in table.rows.iter_mut().flat_map(|row| row.cells.iter_mut()) {
+        cell.content.iter_mut().for_each(&mut *f);
+        for nested in &mut cell.nested_tables {
+            table_paragraphs(nested, f);
+        }
+    }
+}
+
+/// The day a comment was written, `YYYY-MM-DD`.
+fn comment_day(comment: &Comment) -> Option<&str> {
+    comment
+        .date
+        .as_deref()
+        .map(|d| d.split('T').next().unwrap_or(d))
+}
+
+/// Who wrote a comment and when, as `Author, YYYY-MM-DD`.
+fn byline(comment: &Comment) -> String {
+    [comment.author.as_deref(), comment_day(comment)]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// The text of a comment on one line, marked when its thread is resolved.
+fn comment_text(comment: &Comment) -> String {
+    let text = comment.text.split('\n').collect::<Vec<_>>().join(" ");
+    if comment.resolved {
+        format!("{text} (resolved)")
+    } else {
+        text
+    }
+}
+
+/// A comment as the runs of a footnote definition: the author in bold, then the
+/// date and the text.
+fn comment_runs(comment: &Comment, runs: &mut Vec<TextRun>) {
+    if let Some(author) = comment.author.as_deref() {
+        runs.push(TextRun::styled(author, TextStyle::bold()));
+    }
+    let lead = match (comment.author.is_some(), comment_day(comment)) {
+        (true, Some(date)) => format!(", {date}: "),
+        (true, None) => ": ".to_string(),
+        (false, Some(date)) => format!("{date}: "),
+        (false, None) => String::new(),
+    };
+    runs.push(TextRun::plain(lead + &comment_text(comment)));
+}
+
+/// A comment as an HTML comment, `<!-- Comment from Author, date: text -->`.
+///
+/// `--` may not appear inside an HTML comment, so it is spaced apart.
+fn html_comment(kind: &str, comment: &Comment) -> String {
+    let byline = byline(comment);
+    let mut text = if byline.is_empty() {
+        format!("{kind}: {}", comment_text(comment))
+    } else {
+        format!("{kind} from {byline}: {}", comment_text(comment))
+    };
+    while text.contains("--") {
+        text = text.replace("--", "- -");
+    }
+    format!("<!-- {text} -->")
+}
+
+/// Check if a run is an HTML comment placed by [`with_comments`], written as it is.
+pub(super) fn is_html_comment(text: &str) -> bool {
+    text.len() >= 7 && text.starts_with("<!--") && text.ends_with("-->")
+}
+
+impl Decisions<'_> {
+    fn section(&self, section: &Section) -> Section {
+        let paragraphs = |paragraphs: &Option<Vec<Paragraph>>| {
+            paragraphs.as_ref().map(|paragraphs| {
+                paragraphs
+                    .iter()
+                    .filter_map(|p| self.block_paragraph(p))
+                    .collect()
+            })
+        };
+        Section {
+            index: section.index,
+            name: section.name.clone(),
+            content: section
+                .content
+                .iter()
+                .filter_map(|block| match block {
+                    Block::Paragraph(para) => self.block_paragraph(para).map(Block::Paragraph),
+                    Block::Table(table) => Some(Block::Table(self.table(table))),
+                    other => Some(other.clone()),
+                })
+                .collect(),
+            header: paragraphs(&section.header).filter(|_| self.headers_footers),
+            footer: paragraphs(&section.footer).filter(|_| self.headers_footers),
+            notes: paragraphs(&section.notes).filter(|_| self.notes),
+        }
+    }
+
+    /// A paragraph that stands as a block of its own, or `None` when it is left out:
+    /// a note definition without notes, or a line cleanup would remove.
+    fn block_paragraph(&self, para: &Paragraph) -> Option<Paragraph> {
+        if !self.notes && super::common::note_definition(para).is_some() {
+            return None;
+        }
+        let para = self.paragraph(para);
+        let dropped = self.cleanup.is_some_and(|cleanup| {
+            para.list_info.is_none()
+                && !para.heading.is_heading()
+                && super::cleanup::drops_line(&para.plain_text(), cleanup)
+        });
+        (!dropped).then_some(para)
+    }
+
+    fn paragraph(&self, para: &Paragraph) -> Paragraph {
+        let runs = field_results(
+            para.runs.iter().map(|run| self.run(run)).collect(),
+            self.fields,
+        );
+        Paragraph {
+            runs: kept_runs(runs),
+            ..para.clone()
+        }
+    }
+
+    fn run(&self, run: &TextRun) -> Option<TextRun> {
+        let rejected = match self.revisions {
+            RevisionHandling::AcceptAll => run.revision == RevisionType::Deleted,
+            RevisionHandling::RejectAll => run.revision == RevisionType::Inserted,
+            RevisionHandling::ShowMarkup => false,
+        };
+        if rejected || (!self.notes && is_note_reference(&run.text)) {
+            return None;
+        }
+        let mut run = run.clone();
+        if self.revisions != RevisionHandling::ShowMarkup {
+            // Accepted or rejected, what is left is no longer a change.
+            run.revision = RevisionType::None;
+        }
+        // An equation's text is LaTeX, which cleanup would only damage.
+        if let (Some(cleanup), None) = (self.cleanup, run.math) {
+            run.text = super::cleanup::clean_fragment(&run.text, cleanup);
+        }
+        Some(run)
+    }
+
+    fn table(&self, table: &Table) -> Table {
+        let mut table = table.clone();
+        for cell in table.rows.iter_mut().flat_map(|row| row.cells.iter_mut()) {
+            cell.content = cell.content.iter().map(|p| self.paragraph(p)).collect();
+            cell.nested_tables = cell.nested_tables.iter().map(|t| self.table(t)).collect();
+        }
+        table
+    }
+}
+
+/// Check if a run is the `[^label]` marker of a footnote or endnote reference.
+pub(super) fn is_note_reference(text: &str) -> bool {
+    text.strip_prefix("[^")
+        .and_then(|rest| rest.strip_suffix(']'))
+        .is_some_and(|label| {
+            !label.is_empty() && !label.contains(|c: char| c.is_whitespace() || c == ']')
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{Field, HeadingLevel, TextStyle};
+
+    fn run(text: &str, revision: RevisionType) -> TextRun {
+        TextRun {
+            text: text.to_string(),
+            style: TextStyle::default(),
+            revision,
+            ..TextRun::plain("")
+        }
+    }
+
+    fn doc() -> Document {
+        let mut body = Paragraph::new();
+        body.runs = vec![
+            run("kept ", RevisionType::None),
+            run("old ", RevisionType::Deleted),
+            run("new", RevisionType::Inserted),
+            run("[^1]", RevisionType::None),
+        ];
+        let mut section = Section::new(0);
+        section.add_paragraph(Paragraph::heading(HeadingLevel::H1, "Contents"));
+        section.add_paragraph(body);
+        section.add_paragraph(Paragraph::with_text("Page 3"));
+        section.add_paragraph(Paragraph::with_text("[^1]: The note."));
+        section.header = Some(vec![Paragraph::with_text("Header")]);
+        section.notes = Some(vec![Paragraph::with_text("Speaker")]);
+        let mut doc = Document::new();
+        doc.add_section(section);
+        doc
+    }
+
+    fn texts(section: &Section) -> Vec<String> {
+        section
+            .content
+            .iter()
+            .filter_map(|block| match block {
+                Block::Paragraph(para) => Some(para.plain_text()),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_revisions_resolved() {
+        let accepted = sections(&doc(), &RenderOptions::default());
+        assert_eq!(texts(&accepted[0])[1], "kept new[^1]");
+        let Block::Paragraph(ref body) = accepted[0].content[1] else {
+            panic!("expected a paragraph");
+        };
+        assert!(body.runs.iter().all(|r| r.revision == RevisionType::None));
+
+        let options = RenderOptions::new().with_revision_handling(RevisionHandling::RejectAll);
+        assert_eq!(texts(&sections(&doc(), &options)[0])[1], "kept old [^1]");
+
+        let options = RenderOptions::new().with_show_revisions();
+        assert_eq!(texts(&sections(&doc(), &options)[0])[1], "kept old new[^1]");
+    }
+
+    #[test]
+    fn test_headers_footers_and_notes_left_out() {
+        let kept = sections(&doc(), &RenderOptions::lossless());
+        assert!(kept[0].header.is_some());
+        assert!(kept[0].notes.is_some());
+
+        let options = RenderOptions::new().with_include_notes(false);
+        let section = &sections(&doc(), &options)[0];
+        assert!(section.header.is_none());
+        assert!(section.notes.is_none());
+        assert_eq!(texts(section), ["Contents", "kept new", "Page 3"]);
+    }
+
+    #[test]
+    fn test_cleanup_cleans_runs_and_drops_lines() {
+        let mut doc = doc();
+        let Block::Paragraph(ref mut body) = doc.sections[0].content[1] else {
+            panic!("expected a paragraph");
+        };
+        body.runs[0].text = "\u{201C}kept\u{201D}   ".to_string();
+
+        let options = RenderOptions::new().with_cleanup();
+        let section = &sections(&doc, &options)[0];
+        // The heading survives although its text reads like a TOC marker.
+        assert_eq!(
+            texts(section),
+            ["Contents", "\"kept\" new[^1]", "[^1]: The note."]
+        );
+    }
+
+    #[test]
+    fn test_without_notes_keeps_everything_else() {
+        let stripped = without_notes(&doc());
+        let section = &stripped.sections[0];
+        assert!(section.header.is_some());
+        assert!(section.notes.is_none());
+        assert_eq!(texts(section), ["Contents", "kept old new", "Page 3"]);
+    }
+
+    fn commented_doc() -> Document {
+        let mut para = Paragraph::with_text("The claim");
+        para.comment_ids = vec!["0".to_string(), "1".to_string()];
+        let mut section = Section::new(0);
+        section.add_paragraph(para);
+        let mut doc = Document::new();
+        doc.add_section(section);
+        doc.comments = vec![
+            Comment {
+                id: "0".to_string(),
+                author: Some("Alice".to_string()),
+                date: Some("2024-03-01T09:00:00Z".to_string()),
+                text: "Source?\nPage 3 -- maybe.".to_string(),
+                resolved: true,
+                ..Comment::default()
+            },
+            Comment {
+                id: "1".to_string(),
+                author: Some("Bob".to_string()),
+                text: "Added.".to_string(),
+                parent_id: Some("0".to_string()),
+                ..Comment::default()
+            },
+        ];
+        doc
+    }
+
+    #[test]
+    fn test_comments_as_footnotes() {
+        let doc = with_comments(&commented_doc(), CommentStyle::Footnotes);
+        assert_eq!(
+            texts(&doc.sections[0]),
+            [
+                "The claim[^c0]",
+                "[^c0]: Alice, 2024-03-01: Source? Page 3 -- maybe. (resolved) \u{2014} Bob: Added."
+            ]
+        );
+        let Block::Paragraph(ref definition) = doc.sections[0].content[1] else {
+            panic!("expected a paragraph");
+        };
+        assert!(definition.runs[1].style.bold);
+    }
+
+    #[test]
+    fn test_comments_as_html_comments() {
+        let doc = with_comments(&commented_doc(), CommentStyle::Html);
+        assert_eq!(
+            texts(&doc.sections[0]),
+            [
+                "The claim<!-- Comment from Alice, 2024-03-01: Source? Page 3 - - maybe. \
+                 (resolved) --><!-- Reply from Bob: Added. -->"
+            ]
+        );
+        assert!(is_html_comment("<!-- Reply from Bob: Added. -->"));
+        assert!(!is_html_comment("<!-->"));
+
+        let omitted = with_comments(&commented_doc(), CommentStyle::Omit);
+        assert_eq!(texts(&omitted.sections[0]), ["The claim"]);
+    }
+
+    #[test]
+    fn test_field_results_count_the_runs_kept() {
+        let mut reference = run("Figure ", RevisionType::None);
+        reference.field = Some(Box::new(Field {
+            result_runs: 3,
+            ..Field::new("REF _Ref7 \\h")
+        }));
+        let mut page = run("7", RevisionType::None);
+        page.field = Some(Box::new(Field {
+            result_runs: 1,
+            ..Field::new("PAGE")
+        }));
+        let mut para = Paragraph::new();
+        para.runs = vec![
+            reference,
+            run("1", RevisionType::Deleted),
+            run("2", RevisionType::Inserted),
+            run(", page ", RevisionType::None),
+            page,
+        ];
+        let mut section = Section::new(0);
+        section.add_paragraph(para);
+        let mut doc = Document::new();
+        doc.add_section(section);
+
+        let options = RenderOptions::new().with_field_handling(FieldHandling::LinkReferences);
+        let section = &sections(&doc, &options)[0];
+        let Block::Paragraph(ref para) = section.content[0] else {
+            panic!("expected a paragraph");
+        };
+        assert_eq!(para.plain_text(), "Figure 2, page 7");
+        assert_eq!(para.runs[0].field.as_ref().unwrap().result_runs, 2);
+        assert_eq!(para.runs[1].hyperlink.as_deref(), Some("#_Ref7"));
+        assert_eq!(para.runs[2].hyperlink, None);
+
+        let dropped = with_fields(&doc, FieldHandling::DropPageNumbers);
+        assert_eq!(texts(&dropped.sections[0]), ["Figure 12, page "]);
+        assert_eq!(dropped.fields().len(), 2);
+    }
+
+    #[test]
+    fn test_note_reference() {
+        assert!(is_note_reference("[^1]"));
+        assert!(is_note_reference("[^e12]"));
+        assert!(!is_note_reference("[^]"));
+        assert!(!is_note_reference("[^a b]"));
+        assert!(!is_note_reference("see [^1]"));
+    }
+}
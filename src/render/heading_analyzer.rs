@@ -656,6 +656,9 @@ mod tests {
                 line_break: false,
                 page_break: false,
                 revision: RevisionType::None,
+                math: None,
+                form_field: None,
+                field: None,
             }],
             heading: HeadingLevel::None,
             ..Default::default()
@@ -0,0 +1,842 @@
+//! HTML renderer implementation.
+//!
+//! Emits semantic HTML5 straight from the document model, so nothing is lost to an
+//! intermediate Markdown pass: merged cells keep their `colspan`/`rowspan`, list levels
+//! become nested `<ul>`/`<ol>` elements, and each slide or sheet is a `<section>` of
+//! its own.
+
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+
+use crate::detect::FormatType;
+use crate::error::Result;
+use crate::model::{
+    Block, Cell, CellAlignment, Document, ListType, MathMode, Paragraph, RevisionType, Section,
+    Table, TextRun,
+};
+
+use super::common::{
+    checkbox, escape_attribute, escape_html, note_definition, note_id, RunSpacing,
+};
+use super::options::{FieldHandling, HtmlOptions, RevisionHandling};
+
+/// EMUs per CSS pixel (914400 per inch, 96 pixels per inch).
+const EMU_PER_PIXEL: u32 = 9525;
+
+/// Convert a Document to HTML.
+///
+/// Footnote and endnote definitions — the `[^label]: text` paragraphs the parsers append
+/// to a section — become `<aside role="doc-footnote">` elements, and the `[^label]`
+/// markers in the text become links to them.
+pub fn to_html(doc: &Document, options: &HtmlOptions) -> Result<String> {
+    let fielded;
+    let doc = if options.field_handling == FieldHandling::ShowResults {
+        doc
+    } else {
+        fielded = super::filter::with_fields(doc, options.field_handling);
+        &fielded
+    };
+    let renderer = HtmlRenderer::new(doc, options);
+
+    let mut body = String::new();
+    for (i, section) in doc.sections.iter().enumerate() {
+        renderer.render_section(i, section, &mut body);
+    }
+
+    if !options.standalone {
+        return Ok(body);
+    }
+
+    let mut output = String::from("<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n");
+    if let Some(ref title) = doc.metadata.title {
+        output.push_str(&format!("<title>{}</title>\n", escape_html(title)));
+    }
+    let meta = [
+        ("author", doc.metadata.author.clone()),
+        ("description", doc.metadata.description.clone()),
+        (
+            "keywords",
+            Some(doc.metadata.keywords.join(", ")).filter(|k| !k.is_empty()),
+        ),
+    ];
+    for (name, content) in meta {
+        if let Some(content) = content {
+            output.push_str(&format!(
+                "<meta name=\"{}\" content=\"{}\">\n",
+                name,
+                escape_attribute(&content)
+            ));
+        }
+    }
+    output.push_str("</head>\n<body>\n");
+    output.push_str(&body);
+    output.push_str("</body>\n</html>\n");
+    Ok(output)
+}
+
+/// What a section of a document of this format is, for the `<section>` wrapper.
+/// Word-processing documents have none: their sections are page-layout boundaries,
+/// not units of content.
+fn section_kind(format: FormatType) -> Option<&'static str> {
+    match format {
+        FormatType::Pptx | FormatType::Ppt | FormatType::Odp => Some("slide"),
+        FormatType::Xlsx | FormatType::Xlsb | FormatType::Xls | FormatType::Ods => Some("sheet"),
+        FormatType::Docx | FormatType::Doc | FormatType::Odt => None,
+    }
+}
+
+/// A heading the renderer has written, for a table of contents.
+pub(super) struct OutlineEntry {
+    pub level: u8,
+    pub text: String,
+    /// The `id` the heading element carries
+    pub id: String,
+}
+
+pub(super) struct HtmlRenderer<'a> {
+    options: &'a HtmlOptions,
+    format: FormatType,
+    /// Resource ID → suggested filename
+    resource_map: HashMap<String, String>,
+    /// Labels of the notes defined anywhere in the document
+    notes: HashSet<String>,
+    /// Write XHTML for an EPUB content document: void elements close themselves,
+    /// headings get ids and go into `outline`, and note links carry `epub:type`.
+    xhtml: bool,
+    outline: RefCell<Vec<OutlineEntry>>,
+}
+
+impl<'a> HtmlRenderer<'a> {
+    pub(super) fn new(doc: &Document, options: &'a HtmlOptions) -> Self {
+        let resource_map = doc
+            .resources
+            .iter()
+            .map(|(id, resource)| (id.clone(), resource.suggested_filename(id)))
+            .collect();
+        let notes = doc
+            .sections
+            .iter()
+            .flat_map(|s| &s.content)
+            .filter_map(|block| match block {
+                Block::Paragraph(p) => note_definition(p).map(|(label, _)| label),
+                _ => None,
+            })
+            .collect();
+        Self {
+            options,
+            format: doc.format,
+            resource_map,
+            notes,
+            xhtml: false,
+            outline: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// A renderer for the content documents of an EPUB, whose images live at the
+    /// given file names (resource ID → file name) instead of the suggested ones.
+    pub(super) fn xhtml(
+        doc: &Document,
+        options: &'a HtmlOptions,
+        resource_map: HashMap<String, String>,
+    ) -> Self {
+        Self {
+            resource_map,
+            xhtml: true,
+            ..Self::new(doc, options)
+        }
+    }
+
+    /// The headings rendered since the last call, each with its `id`.
+    pub(super) fn take_outline(&self) -> Vec<OutlineEntry> {
+        self.outline.take()
+    }
+
+    /// The end of a void element's tag: `>` in HTML, `/>` in XHTML.
+    fn void_end(&self) -> &'static str {
+        if self.xhtml {
+            "/>"
+        } else {
+            ">"
+        }
+    }
+
+    fn render_section(&self, index: usize, section: &Section, out: &mut String) {
+        let kind = section_kind(self.format);
+        if let Some(kind) = kind {
+            out.push_str(&format!(
+                "<section class=\"{kind}\" id=\"{kind}-{}\">\n",
+                index + 1
+            ));
+        }
+
+        if let Some(ref name) = section.name {
+            out.push_str(&format!("<h2>{}</h2>\n", escape_html(name)));
+        }
+
+        if self.options.include_headers_footers {
+            if let Some(ref header) = section.header {
+                self.render_region("header", header, out);
+            }
+        }
+
+        self.render_blocks(&section.content, out);
+
+        if self.options.include_headers_footers {
+            if let Some(ref footer) = section.footer {
+                self.render_region("footer", footer, out);
+            }
+        }
+
+        if let Some(ref notes) = section.notes {
+            self.render_speaker_notes(notes, out);
+        }
+
+        if kind.is_some() {
+            out.push_str("</section>\n");
+        }
+    }
+
+    /// Render a slide's speaker notes as an `<aside class="notes">`.
+    pub(super) fn render_speaker_notes(&self, notes: &[Paragraph], out: &mut String) {
+        let paragraphs: Vec<String> = notes
+            .iter()
+            .map(|p| self.inline(p))
+            .filter(|t| !t.is_empty())
+            .collect();
+        if !paragraphs.is_empty() {
+            out.push_str("<aside class=\"notes\">\n");
+            for text in paragraphs {
+                out.push_str(&format!("<p>{}</p>\n", text));
+            }
+            out.push_str("</aside>\n");
+        }
+    }
+
+    /// Render a header or footer as a `<header>`/`<footer>` element.
+    fn render_region(&self, tag: &str, paragraphs: &[Paragraph], out: &mut String) {
+        let texts: Vec<String> = paragraphs
+            .iter()
+            .map(|p| self.inline(p))
+            .filter(|t| !t.is_empty())
+            .collect();
+        if !texts.is_empty() {
+            out.push_str(&format!("<{tag}>\n"));
+            for text in texts {
+                out.push_str(&format!("<p>{}</p>\n", text));
+            }
+            out.push_str(&format!("</{tag}>\n"));
+        }
+    }
+
+    pub(super) fn render_blocks(&self, blocks: &[Block], out: &mut String) {
+        let mut i = 0;
+        while i < blocks.len() {
+            match &blocks[i] {
+                Block::Paragraph(p) if p.list_info.is_some() => {
+                    // A list is every consecutive list paragraph, whatever its level.
+                    let items: Vec<&Paragraph> = blocks[i..]
+                        .iter()
+                        .map_while(|b| match b {
+                            Block::Paragraph(p) if p.list_info.is_some() => Some(p),
+                            _ => None,
+                        })
+                        .collect();
+                    i += items.len();
+                    self.render_list(&items, out);
+                    continue;
+                }
+                Block::Paragraph(p) => self.render_paragraph(p, out),
+                Block::Table(table) => self.render_table(table, out),
+                // HTML has no pages.
+                Block::PageBreak => {}
+                Block::SectionBreak => {
+                    out.push_str("<hr");
+                    out.push_str(self.void_end());
+                    out.push('\n');
+                }
+                Block::Image {
+                    resource_id,
+                    alt_text,
+                    width,
+                    height,
+                } => {
+                    out.push_str("<figure>");
+                    out.push_str(&self.image(resource_id, alt_text.as_deref(), *width, *height));
+                    out.push_str("</figure>\n");
+                }
+            }
+            i += 1;
+        }
+    }
+
+    fn render_paragraph(&self, para: &Paragraph, out: &mut String) {
+        if let Some((label, text)) = note_definition(para) {
+            let id = note_id(&label);
+            let epub_type = if self.xhtml {
+                " epub:type=\"footnote\""
+            } else {
+                ""
+            };
+            out.push_str(&format!(
+                "<aside id=\"fn-{id}\" class=\"footnote\" role=\"doc-footnote\"{epub_type}><p><a href=\"#fnref-{id}\">{}</a> {}</p></aside>\n",
+                escape_html(&label),
+                escape_html(text.trim())
+            ));
+            return;
+        }
+
+        let text = self.inline(para);
+        if text.is_empty() {
+            return;
+        }
+        let level = para.heading.level();
+        if level > 0 && self.xhtml {
+            let mut outline = self.outline.borrow_mut();
+            let id = format!("toc-{}", outline.len() + 1);
+            out.push_str(&format!("<h{level} id=\"{id}\">{text}</h{level}>\n"));
+            outline.push(OutlineEntry {
+                level,
+                text: para.plain_text().trim().to_string(),
+                id,
+            });
+        } else if level > 0 {
+            out.push_str(&format!("<h{level}>{text}</h{level}>\n"));
+        } else {
+            out.push_str(&format!("<p>{text}</p>\n"));
+        }
+    }
+
+    /// Render consecutive list paragraphs as nested lists.
+    ///
+    /// Every list on the stack holds exactly one open `<li>`: a deeper item opens its
+    /// list inside it, a sibling closes it and opens the next, and a shallower item
+    /// closes lists until it reaches its own level. An item more than one level deeper
+    /// than its predecessor gets an empty `<li>` for each level skipped, as nested
+    /// lists can only live inside an item.
+    fn render_list(&self, items: &[&Paragraph], out: &mut String) {
+        let mut stack: Vec<ListType> = Vec::new();
+
+        for item in items {
+            let Some(ref info) = item.list_info else {
+                continue;
+            };
+            let list_type = match info.list_type {
+                ListType::Numbered => ListType::Numbered,
+                ListType::Bullet | ListType::None => ListType::Bullet,
+            };
+            let depth = info.level as usize + 1;
+
+            while stack.len() > depth {
+                close_list(stack.pop(), out);
+            }
+            if stack.len() == depth {
+                if stack.last() == Some(&list_type) {
+                    out.push_str("</li>\n");
+                } else {
+                    close_list(stack.pop(), out);
+                }
+            }
+            while stack.len() < depth {
+                let innermost = stack.len() + 1 == depth;
+                let ty = if innermost {
+                    list_type
+                } else {
+                    ListType::Bullet
+                };
+                match (ty, info.number) {
+                    (ListType::Numbered, Some(start)) if innermost && start > 1 => {
+                        out.push_str(&format!("<ol start=\"{start}\">\n"))
+                    }
+                    (ListType::Numbered, _) => out.push_str("<ol>\n"),
+                    _ => out.push_str("<ul>\n"),
+                }
+                stack.push(ty);
+                if !innermost {
+                    out.push_str("<li>\n");
+                }
+            }
+
+            out.push_str("<li>");
+            out.push_str(&self.inline(item));
+        }
+
+        while let Some(ty) = stack.pop() {
+            close_list(Some(ty), out);
+        }
+    }
+
+    fn render_table(&self, table: &Table, out: &mut String) {
+        if table.is_empty() {
+            return;
+        }
+        out.push_str("<table>\n");
+        if let Some(ref caption) = table.caption {
+            out.push_str(&format!("<caption>{}</caption>\n", escape_html(caption)));
+        }
+
+        // Leading header rows form the <thead>; a header row further down stays in place.
+        let head_rows = table.rows.iter().take_while(|r| r.is_header).count();
+        let (head, body) = table.rows.split_at(head_rows);
+        for (tag, rows) in [("thead", head), ("tbody", body)] {
+            if rows.is_empty() {
+                continue;
+            }
+            out.push_str(&format!("<{tag}>\n"));
+            for row in rows {
+                out.push_str("<tr>");
+                for cell in &row.cells {
+                    self.render_cell(cell, row.is_header, out);
+                }
+                out.push_str("</tr>\n");
+            }
+            out.push_str(&format!("</{tag}>\n"));
+        }
+        out.push_str("</table>\n");
+    }
+
+    fn render_cell(&self, cell: &Cell, header_row: bool, out: &mut String) {
+        let tag = if cell.is_header || header_row {
+            "th"
+        } else {
+            "td"
+        };
+        let mut attrs = String::new();
+        if cell.col_span > 1 {
+            attrs.push_str(&format!(" colspan=\"{}\"", cell.col_span));
+        }
+        if cell.row_span > 1 {
+            attrs.push_str(&format!(" rowspan=\"{}\"", cell.row_span));
+        }
+        match super::markdown::effective_cell_alignment(cell) {
+            CellAlignment::Center => attrs.push_str(" style=\"text-align: center\""),
+            CellAlignment::Right => attrs.push_str(" style=\"text-align: right\""),
+            CellAlignment::Left => {}
+        }
+
+        let paragraphs: Vec<String> = cell
+            .content
+            .iter()
+            .map(|p| self.inline(p))
+            .filter(|t| !t.is_empty())
+            .collect();
+        let mut content = match paragraphs.len() {
+            // A single paragraph needs no <p> of its own.
+            0 | 1 => paragraphs.concat(),
+            _ => paragraphs.iter().map(|t| format!("<p>{t}</p>")).collect(),
+        };
+        for nested in &cell.nested_tables {
+            content.push('\n');
+            self.render_table(nested, &mut content);
+        }
+        out.push_str(&format!("<{tag}{attrs}>{content}</{tag}>"));
+    }
+
+    /// Render a paragraph's runs and inline images, without the enclosing element.
+    fn inline(&self, para: &Paragraph) -> String {
+        let merged = para.with_merged_runs();
+        let mut output = String::new();
+        if self.options.field_handling == FieldHandling::LinkReferences {
+            for name in &para.bookmarks {
+                output.push_str(&format!("<a id=\"{}\"></a>", escape_attribute(name)));
+            }
+        }
+        let mut spacing = RunSpacing::default();
+
+        for run in &merged.runs {
+            let Some(html) = self.render_run(run) else {
+                continue;
+            };
+            if spacing.space_before(&run.text) {
+                output.push(' ');
+            }
+            output.push_str(&html);
+        }
+
+        for image in &para.images {
+            output.push_str(&self.image(
+                &image.resource_id,
+                image.alt_text.as_deref(),
+                image.width,
+                image.height,
+            ));
+        }
+        output
+    }
+
+    /// Render a text run, or `None` when tracked-change handling hides it.
+    fn render_run(&self, run: &TextRun) -> Option<String> {
+        let hidden = matches!(
+            (run.revision, self.options.revision_handling),
+            (RevisionType::Deleted, RevisionHandling::AcceptAll)
+                | (RevisionType::Inserted, RevisionHandling::RejectAll)
+        );
+        if hidden {
+            return None;
+        }
+
+        if let Some((checked, label)) = checkbox(run) {
+            let checked = if checked { " checked=\"checked\"" } else { "" };
+            return Some(format!(
+                "<input type=\"checkbox\" disabled=\"disabled\"{checked}{}{}",
+                self.void_end(),
+                self.render_run(&label).unwrap_or_default()
+            ));
+        }
+        let mut text = match run.math {
+            // Delimited LaTeX, in the classes MathJax and KaTeX look for
+            Some(MathMode::Inline) => format!(
+                "<span class=\"math inline\">\\({}\\)</span>",
+                escape_html(&run.text)
+            ),
+            Some(MathMode::Display) => format!(
+                "<span class=\"math display\">\\[{}\\]</span>",
+                escape_html(&run.text)
+            ),
+            None => self.text_with_note_refs(&run.text),
+        };
+        if !text.trim().is_empty() {
+            let style = &run.style;
+            let wrappers = [
+                (style.code, "code"),
+                (style.superscript, "sup"),
+                (style.subscript, "sub"),
+                (style.underline, "u"),
+                (style.strikethrough, "s"),
+                (style.italic, "em"),
+                (style.bold, "strong"),
+            ];
+            // An equation is styled by its own markup
+            for (_, tag) in wrappers.iter().filter(|(on, _)| *on && run.math.is_none()) {
+                text = format!("<{tag}>{text}</{tag}>");
+            }
+            if let Some(ref url) = run.hyperlink {
+                text = format!("<a href=\"{}\">{}</a>", escape_attribute(url), text);
+            }
+            if self.options.revision_handling == RevisionHandling::ShowMarkup {
+                match run.revision {
+                    RevisionType::Inserted => text = format!("<ins>{text}</ins>"),
+                    RevisionType::Deleted => text = format!("<del>{text}</del>"),
+                    RevisionType::None => {}
+                }
+            }
+        }
+        if run.line_break {
+            text.push_str("<br");
+            text.push_str(self.void_end());
+        }
+        Some(text)
+    }
+
+    /// Escape `text`, turning each `[^label]` marker of a defined note into a link to it.
+    fn text_with_note_refs(&self, text: &str) -> String {
+        let mut output = String::new();
+        let mut rest = text;
+        while let Some(start) = rest.find("[^") {
+            let Some(len) = rest[start + 2..].find(']') else {
+                break;
+            };
+            let label = &rest[start + 2..start + 2 + len];
+            if !self.notes.contains(label) {
+                output.push_str(&escape_html(&rest[..start + 2]));
+                rest = &rest[start + 2..];
+                continue;
+            }
+            let id = note_id(label);
+            let epub_type = if self.xhtml {
+                " epub:type=\"noteref\""
+            } else {
+                ""
+            };
+            output.push_str(&escape_html(&rest[..start]));
+            output.push_str(&format!(
+                "<sup class=\"footnote-ref\"><a href=\"#fn-{id}\" id=\"fnref-{id}\"{epub_type}>{}</a></sup>",
+                escape_html(label)
+            ));
+            rest = &rest[start + 2 + len + 1..];
+        }
+        output.push_str(&escape_html(rest));
+        output
+    }
+
+    fn image(
+        &self,
+        resource_id: &str,
+        alt_text: Option<&str>,
+        width: Option<u32>,
+        height: Option<u32>,
+    ) -> String {
+        let filename = self
+            .resource_map
+            .get(resource_id)
+            .map_or(resource_id, String::as_str);
+        let mut tag = format!(
+            "<img src=\"{}\" alt=\"{}\"",
+            escape_attribute(&format!("{}{}", self.options.image_path_prefix, filename)),
+            escape_attribute(alt_text.unwrap_or(""))
+        );
+        for (name, emu) in [("width", width), ("height", height)] {
+            if let Some(px) = emu.map(|e| e / EMU_PER_PIXEL).filter(|&px| px > 0) {
+                tag.push_str(&format!(" {name}=\"{px}\""));
+            }
+        }
+        tag.push_str(self.void_end());
+        tag
+    }
+}
+
+fn close_list(list: Option<ListType>, out: &mut String) {
+    match list {
+        Some(ListType::Numbered) => out.push_str("</li>\n</ol>\n"),
+        Some(_) => out.push_str("</li>\n</ul>\n"),
+        None => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{FormField, HeadingLevel, ListInfo, Resource, Row, TextStyle};
+
+    fn list_item(text: &str, list_type: ListType, level: u8, number: Option<u32>) -> Block {
+        let mut p = Paragraph::with_text(text);
+        p.list_info = Some(ListInfo {
+            list_type,
+            level,
+            number,
+        });
+        Block::Paragraph(p)
+    }
+
+    fn fragment(doc: &Document) -> String {
+        to_html(doc, &HtmlOptions::new().with_standalone(false)).unwrap()
+    }
+
+    fn docx(blocks: Vec<Block>) -> Document {
+        let mut doc = Document::new();
+        let mut section = Section::new(0);
+        section.content = blocks;
+        doc.add_section(section);
+        doc
+    }
+
+    #[test]
+    fn test_headings_paragraphs_and_inline_styles() {
+        let mut para = Paragraph::new();
+        para.add_run(TextRun::plain("Plain <&>"));
+        para.add_run(TextRun::styled("bold", TextStyle::bold()));
+        para.add_run(TextRun::link("site", "https://example.com/?a=1&b=\"2\""));
+        let doc = docx(vec![
+            Block::Paragraph(Paragraph::heading(HeadingLevel::H1, "Title")),
+            Block::Paragraph(para),
+        ]);
+        assert_eq!(
+            fragment(&doc),
+            "<h1>Title</h1>\n<p>Plain &lt;&amp;&gt; <strong>bold</strong> \
+             <a href=\"https://example.com/?a=1&amp;b=&quot;2&quot;\">site</a></p>\n"
+        );
+    }
+
+    #[test]
+    fn test_lists_nest_by_level() {
+        let doc = docx(vec![
+            list_item("One", ListType::Numbered, 0, Some(1)),
+            list_item("Sub a", ListType::Bullet, 1, None),
+            list_item("Sub b", ListType::Bullet, 1, None),
+            list_item("Two", ListType::Numbered, 0, Some(2)),
+            Block::Paragraph(Paragraph::with_text("After")),
+            list_item("Resumed", ListType::Numbered, 0, Some(3)),
+        ]);
+        assert_eq!(
+            fragment(&doc),
+            "<ol>\n<li>One<ul>\n<li>Sub a</li>\n<li>Sub b</li>\n</ul>\n</li>\n<li>Two</li>\n</ol>\n\
+             <p>After</p>\n<ol start=\"3\">\n<li>Resumed</li>\n</ol>\n"
+        );
+    }
+
+    #[test]
+    fn test_list_skipping_a_level_stays_well_formed() {
+        let doc = docx(vec![
+            list_item("Top", ListType::Bullet, 0, None),
+            list_item("Deep", ListType::Bullet, 2, None),
+        ]);
+        let html = fragment(&doc);
+        assert_eq!(html.matches("<ul>").count(), 3);
+        assert_eq!(html.matches("</ul>").count(), 3);
+        assert_eq!(html.matches("<li>").count(), html.matches("</li>").count());
+    }
+
+    #[test]
+    fn test_tables_keep_merges() {
+        let mut merged = Cell::with_text("Merged");
+        merged.col_span = 2;
+        merged.row_span = 2;
+        let mut table = Table::new();
+        table.add_row(Row::header(vec![
+            Cell::header("A"),
+            Cell::header("B"),
+            Cell::header("C"),
+        ]));
+        let mut row = Row::new();
+        row.add_cell(merged);
+        row.add_cell(Cell::with_text("x"));
+        table.add_row(row);
+        let mut row = Row::new();
+        row.add_cell(Cell::with_text("y"));
+        table.add_row(row);
+
+        let html = fragment(&docx(vec![Block::Table(table)]));
+        assert!(
+            html.contains("<thead>\n<tr><th>A</th><th>B</th><th>C</th></tr>\n</thead>"),
+            "{html}"
+        );
+        assert!(
+            html.contains("<tr><td colspan=\"2\" rowspan=\"2\">Merged</td><td>x</td></tr>"),
+            "{html}"
+        );
+        assert!(html.contains("<tr><td>y</td></tr>\n</tbody>"), "{html}");
+    }
+
+    #[test]
+    fn test_images_become_figures() {
+        let mut doc = docx(vec![Block::Image {
+            resource_id: "rId5".to_string(),
+            alt_text: Some("A \"chart\"".to_string()),
+            width: Some(914_400),
+            height: None,
+        }]);
+        doc.add_resource(
+            "rId5",
+            Resource::image(b"\x89PNG".to_vec(), Some("chart.png".to_string())),
+        );
+        let html = to_html(
+            &doc,
+            &HtmlOptions::new()
+                .with_standalone(false)
+                .with_image_prefix("assets/"),
+        )
+        .unwrap();
+        assert_eq!(
+            html,
+            "<figure><img src=\"assets/chart.png\" alt=\"A &quot;chart&quot;\" width=\"96\"></figure>\n"
+        );
+    }
+
+    #[test]
+    fn test_footnotes_become_asides() {
+        let mut para = Paragraph::new();
+        para.add_run(TextRun::plain("Claim"));
+        para.add_run(TextRun::plain("[^1]"));
+        let doc = docx(vec![
+            Block::Paragraph(para),
+            Block::Paragraph(Paragraph::with_text("Literal [^9] stays")),
+            Block::Paragraph(Paragraph::with_text("[^1]: Source")),
+        ]);
+        let html = fragment(&doc);
+        assert!(
+            html.contains(
+                "<p>Claim<sup class=\"footnote-ref\"><a href=\"#fn-1\" id=\"fnref-1\">1</a></sup></p>"
+            ),
+            "{html}"
+        );
+        assert!(html.contains("<p>Literal [^9] stays</p>"), "{html}");
+        assert!(
+            html.contains(
+                "<aside id=\"fn-1\" class=\"footnote\" role=\"doc-footnote\"><p><a href=\"#fnref-1\">1</a> Source</p></aside>"
+            ),
+            "{html}"
+        );
+    }
+
+    #[test]
+    fn test_slides_and_sheets_get_sections() {
+        let mut doc = Document::new();
+        doc.format = FormatType::Pptx;
+        doc.metadata.title = Some("Deck & Co".to_string());
+        let mut slide = Section::with_name(0, "Intro");
+        slide.add_paragraph(Paragraph::with_text("Hello"));
+        slide.notes = Some(vec![Paragraph::with_text("Say hi")]);
+        doc.add_section(slide);
+
+        let html = to_html(&doc, &HtmlOptions::default()).unwrap();
+        assert!(html.starts_with("<!DOCTYPE html>\n"), "{html}");
+        assert!(html.contains("<title>Deck &amp; Co</title>"), "{html}");
+        assert!(
+            html.contains(
+                "<section class=\"slide\" id=\"slide-1\">\n<h2>Intro</h2>\n<p>Hello</p>\n\
+                 <aside class=\"notes\">\n<p>Say hi</p>\n</aside>\n</section>\n"
+            ),
+            "{html}"
+        );
+
+        doc.format = FormatType::Xlsx;
+        assert!(fragment(&doc).starts_with("<section class=\"sheet\" id=\"sheet-1\">"));
+        doc.format = FormatType::Docx;
+        assert!(!fragment(&doc).contains("<section"));
+    }
+
+    #[test]
+    fn test_revisions() {
+        let mut para = Paragraph::new();
+        para.add_run(TextRun::plain("keep"));
+        let mut inserted = TextRun::plain("new");
+        inserted.revision = RevisionType::Inserted;
+        let mut deleted = TextRun::plain("old");
+        deleted.revision = RevisionType::Deleted;
+        para.add_run(inserted);
+        para.add_run(deleted);
+        let doc = docx(vec![Block::Paragraph(para)]);
+
+        assert_eq!(fragment(&doc), "<p>keep new</p>\n");
+        let options = HtmlOptions::new()
+            .with_standalone(false)
+            .with_revision_handling(RevisionHandling::ShowMarkup);
+        assert_eq!(
+            to_html(&doc, &options).unwrap(),
+            "<p>keep <ins>new</ins> <del>old</del></p>\n"
+        );
+    }
+
+    #[test]
+    fn test_equations() {
+        let mut para = Paragraph::new();
+        para.add_run(TextRun::plain("So"));
+        para.add_run(TextRun::math("a<b", MathMode::Inline));
+        let display = Paragraph {
+            runs: vec![TextRun::math("\\frac{1}{2}", MathMode::Display)],
+            ..Default::default()
+        };
+        let doc = docx(vec![Block::Paragraph(para), Block::Paragraph(display)]);
+        assert_eq!(
+            fragment(&doc),
+            "<p>So <span class=\"math inline\">\\(a&lt;b\\)</span></p>\n\
+             <p><span class=\"math display\">\\[\\frac{1}{2}\\]</span></p>\n"
+        );
+    }
+
+    #[test]
+    fn test_checkbox_fields() {
+        let mut checkbox = TextRun::plain("☒");
+        checkbox.form_field = Some(Box::new(FormField {
+            field_type: crate::model::FormFieldType::Checkbox,
+            checked: Some(true),
+            ..Default::default()
+        }));
+        let mut labelled = checkbox.clone();
+        labelled.text = "☒ Option <A>".to_string();
+        let mut para = Paragraph::new();
+        para.add_run(checkbox);
+        para.add_run(TextRun::plain("Agreed"));
+        let doc = docx(vec![
+            Block::Paragraph(para),
+            Block::Paragraph(Paragraph {
+                runs: vec![labelled],
+                ..Default::default()
+            }),
+        ]);
+        assert_eq!(
+            fragment(&doc),
+            "<p><input type=\"checkbox\" disabled=\"disabled\" checked=\"checked\"> Agreed</p>\n\
+             <p><input type=\"checkbox\" disabled=\"disabled\" checked=\"checked\"> Option &lt;A&gt;</p>\n"
+        );
+    }
+}
@@ -1,9 +1,19 @@
 //! JSON renderer implementation.
+//!
+//! The JSON is the serde form of the [`Document`] model with a `schema_version` field
+//! in front, so a reader can tell which contract it is looking at.
+//! `schema/document.schema.json` describes that contract and
+//! [`Document::from_json`] reads it back.
+
+use std::collections::{BTreeMap, HashMap};
+
+use base64::Engine;
+use serde::Serialize;
 
 use crate::error::Result;
-use crate::model::Document;
+use crate::model::{Document, Resource, JSON_SCHEMA_VERSION};
 
-use super::options::RenderOptions;
+use super::options::{JsonResources, RenderOptions};
 
 /// JSON output format options.
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
@@ -15,29 +25,96 @@ pub enum JsonFormat {
     Pretty,
 }
 
-/// Convert a Document to JSON.
-pub fn to_json(doc: &Document, format: JsonFormat) -> Result<String> {
+/// The document as written: its fields, after the schema version.
+#[derive(Serialize)]
+struct Versioned<'a> {
+    schema_version: u32,
+    #[serde(flatten)]
+    document: &'a Document,
+    /// Set when the resources are written here rather than through `document`, as
+    /// [`to_json_with_options`] does.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    resources: Option<BTreeMap<&'a str, WrittenResource<'a>>>,
+}
+
+/// A resource, with its bytes when they go along.
+#[derive(Serialize)]
+struct WrittenResource<'a> {
+    #[serde(flatten)]
+    resource: &'a Resource,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    data: Option<String>,
+}
+
+fn write(versioned: &Versioned, format: JsonFormat) -> Result<String> {
     // Producing output is rendering — not XML parsing, which is what this used to
     // report and would have told a caller to look in the wrong place.
     let render_error =
         |e: serde_json::Error| crate::error::Error::Render(format!("JSON serialization: {e}"));
 
     match format {
-        JsonFormat::Compact => serde_json::to_string(doc).map_err(render_error),
-        JsonFormat::Pretty => serde_json::to_string_pretty(doc).map_err(render_error),
+        JsonFormat::Compact => serde_json::to_string(versioned).map_err(render_error),
+        JsonFormat::Pretty => serde_json::to_string_pretty(versioned).map_err(render_error),
     }
 }
 
+/// Convert a Document to JSON.
+///
+/// The whole model is written, resources without their bytes; see
+/// [`to_json_with_options`] to choose what goes in.
+pub fn to_json(doc: &Document, format: JsonFormat) -> Result<String> {
+    let versioned = Versioned {
+        schema_version: JSON_SCHEMA_VERSION,
+        document: doc,
+        resources: None,
+    };
+    write(&versioned, format)
+}
+
 /// Convert a Document to JSON with default formatting.
 pub fn to_json_default(doc: &Document) -> Result<String> {
     to_json(doc, JsonFormat::Pretty)
 }
 
-/// Convert a Document to JSON with render options (for consistency).
-pub fn to_json_with_options(doc: &Document, _options: &RenderOptions) -> Result<String> {
-    // RenderOptions doesn't affect JSON output directly,
-    // but we may add JSON-specific options in the future
-    to_json(doc, JsonFormat::Pretty)
+/// Convert a Document to pretty-printed JSON, taking the content decisions of
+/// `options` as the Markdown renderer does.
+///
+/// Tracked changes are resolved by `revision_handling` (the runs it rejects are left
+/// out, and under `AcceptAll` or `RejectAll` the rest are no longer marked as changes),
+/// headers and footers follow `include_headers_footers`, footnotes, endnotes and
+/// speaker notes follow `include_notes`, and with `cleanup` set the text of each run is
+/// cleaned, and paragraphs that cleanup would remove as a line are left out.
+/// `json_resources` chooses whether resources go in with their bytes, without them,
+/// or not at all.
+pub fn to_json_with_options(doc: &Document, options: &RenderOptions) -> Result<String> {
+    // The copy leaves the resources behind, so their bytes are not copied with it.
+    let filtered = Document {
+        format: doc.format,
+        variant: doc.variant,
+        metadata: doc.metadata.clone(),
+        sections: super::filter::sections(doc, options),
+        resources: HashMap::new(),
+        comments: doc.comments.clone(),
+    };
+    let embed = options.json_resources == JsonResources::Base64;
+    let resources = (options.json_resources != JsonResources::Omit && !doc.resources.is_empty())
+        .then(|| {
+            doc.resources
+                .iter()
+                .map(|(id, resource)| {
+                    let data = embed
+                        .then(|| base64::engine::general_purpose::STANDARD.encode(&resource.data));
+                    (id.as_str(), WrittenResource { resource, data })
+                })
+                .collect()
+        });
+
+    let versioned = Versioned {
+        schema_version: JSON_SCHEMA_VERSION,
+        document: &filtered,
+        resources,
+    };
+    write(&versioned, JsonFormat::Pretty)
 }
 
 #[cfg(test)]
@@ -94,4 +171,172 @@ mod tests {
         assert_eq!(parsed.metadata.author, doc.metadata.author);
         assert_eq!(parsed.sections.len(), 1);
     }
+
+    #[test]
+    fn test_schema_version_leads_the_output() {
+        let json = to_json(&Document::new(), JsonFormat::Compact).unwrap();
+        assert!(json.starts_with(&format!("{{\"schema_version\":{JSON_SCHEMA_VERSION},")));
+    }
+
+    #[test]
+    fn test_from_json_reads_the_output_back() {
+        let mut doc = Document::new();
+        doc.metadata.title = Some("Saved".to_string());
+        let mut section = Section::new(0);
+        section.add_paragraph(Paragraph::heading(HeadingLevel::H2, "Heading"));
+        section.add_paragraph(Paragraph::with_text("Body."));
+        doc.add_section(section);
+
+        let parsed = Document::from_json(&to_json_default(&doc).unwrap()).unwrap();
+        assert_eq!(parsed.metadata.title.as_deref(), Some("Saved"));
+        assert_eq!(parsed.plain_text(), doc.plain_text());
+    }
+
+    #[test]
+    fn test_from_json_checks_the_version() {
+        // Output from before the version field existed is still version 1.
+        assert!(Document::from_json(r#"{"format":"docx","metadata":{}}"#).is_ok());
+
+        let future = format!(
+            r#"{{"schema_version":{},"format":"docx","metadata":{{}}}}"#,
+            JSON_SCHEMA_VERSION + 1
+        );
+        assert!(matches!(
+            Document::from_json(&future),
+            Err(crate::error::Error::UnsupportedFormat(_))
+        ));
+        assert!(matches!(
+            Document::from_json("{"),
+            Err(crate::error::Error::InvalidData(_))
+        ));
+    }
+
+    fn image_document() -> Document {
+        let mut doc = Document::new();
+        let mut section = Section::new(0);
+        let mut para = Paragraph::with_text("Kept");
+        let mut deleted = crate::model::TextRun::plain(" deleted");
+        deleted.revision = crate::model::RevisionType::Deleted;
+        para.runs.push(deleted);
+        section.add_paragraph(para);
+        section.footer = Some(vec![Paragraph::with_text("Footer")]);
+        doc.add_section(section);
+        let image = Resource::image(vec![0x89, b'P', b'N', b'G'], Some("a.png".to_string()));
+        doc.resources.insert("rId1".to_string(), image);
+        doc
+    }
+
+    #[test]
+    fn test_options_decide_content() {
+        let doc = image_document();
+        let json = to_json_with_options(&doc, &RenderOptions::default()).unwrap();
+        assert!(json.contains("\"Kept\""));
+        assert!(!json.contains("deleted"));
+        assert!(!json.contains("Footer"));
+        assert!(json.contains("\"a.png\""));
+        assert!(!json.contains("\"data\""));
+
+        let options = RenderOptions::lossless().with_show_revisions();
+        let json = to_json_with_options(&doc, &options).unwrap();
+        assert!(json.contains(" deleted"));
+        assert!(json.contains("Footer"));
+    }
+
+    #[test]
+    fn test_resources_embedded_or_omitted() {
+        let doc = image_document();
+        let options = RenderOptions::new().with_json_resources(JsonResources::Base64);
+        let json = to_json_with_options(&doc, &options).unwrap();
+        assert!(json.contains("\"data\": \"iVBORw==\""));
+        let parsed = Document::from_json(&json).unwrap();
+        assert_eq!(parsed.resources["rId1"].data, doc.resources["rId1"].data);
+
+        let options = RenderOptions::new().with_json_resources(JsonResources::Omit);
+        let json = to_json_with_options(&doc, &options).unwrap();
+        assert!(!json.contains("\"resources\""));
+        assert!(Document::from_json(&json).unwrap().resources.is_empty());
+    }
+
+    #[test]
+    fn test_comments_carried_in_full() {
+        let mut doc = image_document();
+        if let crate::model::Block::Paragraph(ref mut para) = doc.sections[0].content[0] {
+            para.comment_ids.push("0".to_string());
+        }
+        doc.comments.push(crate::model::Comment {
+            id: "0".to_string(),
+            author: Some("Alice".to_string()),
+            date: Some("2024-03-01T09:00:00Z".to_string()),
+            text: "Why?".to_string(),
+            anchor: Some("Kept".to_string()),
+            resolved: true,
+            durable_id: Some("5A1B2C3D".to_string()),
+            ..Default::default()
+        });
+
+        let json = to_json_with_options(&doc, &RenderOptions::default()).unwrap();
+        assert!(json.contains("\"comment_ids\": [\n"));
+        let parsed = Document::from_json(&json).unwrap();
+        assert_eq!(parsed.comments, doc.comments);
+    }
+
+    /// The schema of `to_json` output: the model's serialized form plus `schema_version`.
+    fn document_schema() -> String {
+        let generator = schemars::generate::SchemaSettings::draft2020_12()
+            .for_serialize()
+            .into_generator();
+        let mut schema = generator.into_root_schema_for::<Document>();
+        let root = schema.as_object_mut().unwrap();
+        root.insert(
+            "$id".to_string(),
+            format!("https://github.com/iyulab/undoc/schema/document.v{JSON_SCHEMA_VERSION}.json")
+                .into(),
+        );
+        root.insert("title".to_string(), "undoc document".into());
+        let properties = root["properties"].as_object_mut().unwrap();
+        properties.insert(
+            "schema_version".to_string(),
+            serde_json::json!({
+                "description": "Version of this JSON contract.",
+                "type": "integer",
+                "const": JSON_SCHEMA_VERSION,
+            }),
+        );
+        let required = root["required"].as_array_mut().unwrap();
+        required.insert(0, "schema_version".into());
+        // Only `to_json_with_options` writes the bytes, outside the derived form.
+        let resource = root["$defs"]["Resource"]["properties"]
+            .as_object_mut()
+            .unwrap();
+        resource.insert(
+            "data".to_string(),
+            serde_json::json!({
+                "description": "The resource bytes, base64-encoded. Written only when \
+                                `RenderOptions::json_resources` is `Base64`.",
+                "type": "string",
+                "contentEncoding": "base64",
+            }),
+        );
+        serde_json::to_string_pretty(&schema).unwrap() + "\n"
+    }
+
+    #[test]
+    fn test_schema_file_is_current() {
+        let path = std::path::Path::new(file!())
+            .parent()
+            .unwrap()
+            .join("../../schema/document.schema.json");
+        let schema = document_schema();
+        if std::env::var_os("UNDOC_UPDATE_SCHEMA").is_some() {
+            std::fs::write(&path, &schema).unwrap();
+        }
+        let saved = std::fs::read_to_string(&path).unwrap_or_default();
+        assert!(
+            saved == schema,
+            "{} is out of date with the model; regenerate it with \
+             `UNDOC_UPDATE_SCHEMA=1 cargo test --lib test_schema_file_is_current`, \
+             and bump JSON_SCHEMA_VERSION if the change is not backward compatible",
+            path.display()
+        );
+    }
 }
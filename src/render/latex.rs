@@ -0,0 +1,948 @@
+//! LaTeX renderer implementation.
+//!
+//! Emits LaTeX for `pdflatex`, `xelatex` or `lualatex`. Headings become sectioning
+//! commands, lists `itemize`/`enumerate` environments, and tables `tabular`s in which a
+//! merged cell keeps its span through `\multicolumn` and `\multirow`.
+
+use std::cell::RefCell;
+use std::collections::{HashMap, HashSet};
+
+use crate::error::Result;
+use crate::model::{
+    Block, Cell, CellAlignment, Document, ListType, MathMode, Paragraph, RevisionType, Section,
+    Table, TextRun,
+};
+
+use super::common::{checkbox, note_definition, note_id, RunSpacing};
+use super::grid;
+use super::markdown::{self, ResourceMap};
+use super::options::{LatexOptions, RevisionHandling};
+
+/// EMUs per inch.
+const EMU_PER_INCH: f64 = 914_400.0;
+
+/// Images wider than this many inches are scaled to the line width instead.
+const MAX_IMAGE_INCHES: f64 = 6.0;
+
+/// Deepest list nesting LaTeX's standard classes allow.
+const MAX_LIST_DEPTH: usize = 4;
+
+/// Sectioning commands by heading level; H6 shares H5's.
+const SECTIONING: [&str; 6] = [
+    "section",
+    "subsection",
+    "subsubsection",
+    "paragraph",
+    "subparagraph",
+    "subparagraph",
+];
+
+/// Convert a Document to LaTeX.
+///
+/// Footnote and endnote definitions — the `[^label]: text` paragraphs the parsers append
+/// to a section — are folded into `\footnote` commands where their markers appear, so
+/// LaTeX numbers and places them itself. Images are referenced by the same file names
+/// the Markdown renderer uses, behind [`LatexOptions::image_path_prefix`].
+pub fn to_latex(doc: &Document, options: &LatexOptions) -> Result<String> {
+    let renderer = LatexRenderer::new(doc, options);
+
+    let mut body = String::new();
+    for section in &doc.sections {
+        renderer.render_section(section, &mut body);
+    }
+    let body = body.trim();
+
+    if !options.standalone {
+        return Ok(if body.is_empty() {
+            String::new()
+        } else {
+            format!("{body}\n")
+        });
+    }
+
+    let mut output = preamble(doc, options);
+    output.push_str("\\begin{document}\n");
+    if doc.metadata.title.is_some() {
+        output.push_str("\\maketitle\n");
+    }
+    let abstract_text = doc
+        .metadata
+        .description
+        .as_deref()
+        .filter(|d| !d.trim().is_empty());
+    if let Some(description) = abstract_text {
+        if matches!(options.document_class.as_str(), "article" | "report") {
+            output.push_str(&format!(
+                "\\begin{{abstract}}\n{}\n\\end{{abstract}}\n",
+                escape_text(description.trim())
+            ));
+        }
+    }
+    output.push('\n');
+    if !body.is_empty() {
+        output.push_str(body);
+        output.push_str("\n\n");
+    }
+    output.push_str("\\end{document}\n");
+    Ok(output)
+}
+
+/// The document class, the packages the body relies on, and the title block and PDF
+/// properties taken from the metadata.
+fn preamble(doc: &Document, options: &LatexOptions) -> String {
+    let meta = &doc.metadata;
+    let mut out = format!(
+        "\\documentclass{{{}}}\n\
+         \\usepackage[T1]{{fontenc}}\n\
+         \\usepackage[utf8]{{inputenc}}\n\
+         \\usepackage{{amsmath}}\n\
+         \\usepackage{{amssymb}}\n\
+         \\usepackage{{array}}\n\
+         \\usepackage{{graphicx}}\n\
+         \\usepackage{{multirow}}\n\
+         \\usepackage[normalem]{{ulem}}\n\
+         \\usepackage{{hyperref}}\n",
+        options.document_class
+    );
+
+    let properties: Vec<String> = [
+        ("pdftitle", meta.title.clone()),
+        ("pdfauthor", meta.author.clone()),
+        ("pdfsubject", meta.subject.clone()),
+        (
+            "pdfkeywords",
+            Some(meta.keywords.join(", ")).filter(|k| !k.is_empty()),
+        ),
+    ]
+    .into_iter()
+    .filter_map(|(key, value)| {
+        value
+            .filter(|v| !v.trim().is_empty())
+            .map(|v| format!("{key}={{{}}}", escape_text(&single_line(&v))))
+    })
+    .collect();
+    if !properties.is_empty() {
+        out.push_str(&format!("\\hypersetup{{{}}}\n", properties.join(", ")));
+    }
+
+    if let Some(ref title) = meta.title {
+        out.push_str(&format!(
+            "\\title{{{}}}\n",
+            escape_text(&single_line(title))
+        ));
+        if let Some(ref author) = meta.author {
+            out.push_str(&format!(
+                "\\author{{{}}}\n",
+                escape_text(&single_line(author))
+            ));
+        }
+        // The date the document was last saved, or none rather than the day it
+        // happened to be converted.
+        let date = meta
+            .modified
+            .as_deref()
+            .or(meta.created.as_deref())
+            .and_then(|d| d.get(..10))
+            .filter(|d| d.bytes().all(|b| b.is_ascii_digit() || b == b'-'))
+            .unwrap_or("");
+        out.push_str(&format!("\\date{{{date}}}\n"));
+    }
+    out
+}
+
+struct LatexRenderer<'a> {
+    options: &'a LatexOptions,
+    resource_map: ResourceMap,
+    /// Label → text of the notes defined anywhere in the document
+    notes: HashMap<String, String>,
+    /// Labels whose `\footnote` has been emitted; later markers refer back to it
+    cited: RefCell<HashSet<String>>,
+}
+
+impl<'a> LatexRenderer<'a> {
+    fn new(doc: &Document, options: &'a LatexOptions) -> Self {
+        let notes = doc
+            .sections
+            .iter()
+            .flat_map(|s| &s.content)
+            .filter_map(|block| match block {
+                Block::Paragraph(p) => note_definition(p),
+                _ => None,
+            })
+            .collect();
+        Self {
+            options,
+            resource_map: markdown::build_resource_map(doc),
+            notes,
+            cited: RefCell::new(HashSet::new()),
+        }
+    }
+
+    fn render_section(&self, section: &Section, out: &mut String) {
+        if let Some(ref name) = section.name {
+            out.push_str(&format!(
+                "\\section{{{}}}\n\n",
+                escape_text(&single_line(name))
+            ));
+        }
+
+        self.render_blocks(&section.content, out);
+
+        if let Some(ref notes) = section.notes {
+            let paragraphs: Vec<String> = notes
+                .iter()
+                .map(|p| self.inline(p))
+                .filter(|t| !t.is_empty())
+                .collect();
+            if !paragraphs.is_empty() {
+                out.push_str("\\begin{quote}\n\\small\n");
+                out.push_str(&paragraphs.join("\n\n"));
+                out.push_str("\n\\end{quote}\n\n");
+            }
+        }
+    }
+
+    fn render_blocks(&self, blocks: &[Block], out: &mut String) {
+        let mut i = 0;
+        while i < blocks.len() {
+            match &blocks[i] {
+                Block::Paragraph(p) if p.list_info.is_some() => {
+                    // A list is every consecutive list paragraph, whatever its level.
+                    let items: Vec<&Paragraph> = blocks[i..]
+                        .iter()
+                        .map_while(|b| match b {
+                            Block::Paragraph(p) if p.list_info.is_some() => Some(p),
+                            _ => None,
+                        })
+                        .collect();
+                    i += items.len();
+                    self.render_list(&items, out);
+                    continue;
+                }
+                Block::Paragraph(p) => self.render_paragraph(p, out),
+                Block::Table(table) => self.render_table_block(table, out),
+                Block::PageBreak => out.push_str("\\clearpage\n\n"),
+                Block::SectionBreak => out.push_str("\\noindent\\rule{\\linewidth}{0.4pt}\n\n"),
+                Block::Image {
+                    resource_id, width, ..
+                } => {
+                    out.push_str(&format!(
+                        "\\begin{{center}}\n{}\n\\end{{center}}\n\n",
+                        self.image(resource_id, *width)
+                    ));
+                }
+            }
+            i += 1;
+        }
+    }
+
+    fn render_paragraph(&self, para: &Paragraph, out: &mut String) {
+        // A definition whose marker became a `\footnote` has nothing left to say.
+        if let Some((label, _)) = note_definition(para) {
+            if self.cited.borrow().contains(&label) {
+                return;
+            }
+        }
+
+        let text = self.inline(para);
+        if text.is_empty() {
+            return;
+        }
+        let level = para.heading.level();
+        if level > 0 {
+            let command = SECTIONING[(level as usize).min(SECTIONING.len()) - 1];
+            let text = single_line(&text.replace("\\newline", " "));
+            out.push_str(&format!("\\{command}{{{text}}}\n\n"));
+        } else {
+            out.push_str(&text);
+            out.push_str("\n\n");
+        }
+    }
+
+    /// Render consecutive list paragraphs as nested `itemize`/`enumerate` environments.
+    ///
+    /// Every environment on the stack is inside an item of the one below it: a deeper
+    /// item opens its list in the current item, a shallower one closes lists until it
+    /// reaches its own level. An item more than one level deeper than its predecessor
+    /// gets an empty `\item[]` for each level skipped, as LaTeX only nests a list in an
+    /// item. Levels past the fourth stay at the fourth, the deepest LaTeX allows.
+    fn render_list(&self, items: &[&Paragraph], out: &mut String) {
+        let mut stack: Vec<ListType> = Vec::new();
+
+        for item in items {
+            let Some(ref info) = item.list_info else {
+                continue;
+            };
+            let list_type = match info.list_type {
+                ListType::Numbered => ListType::Numbered,
+                ListType::Bullet | ListType::None => ListType::Bullet,
+            };
+            let depth = (info.level as usize + 1).min(MAX_LIST_DEPTH);
+
+            while stack.len() > depth {
+                close_list(stack.pop(), out);
+            }
+            if stack.len() == depth && stack.last() != Some(&list_type) {
+                close_list(stack.pop(), out);
+            }
+            while stack.len() < depth {
+                let innermost = stack.len() + 1 == depth;
+                let ty = if innermost {
+                    list_type
+                } else {
+                    ListType::Bullet
+                };
+                stack.push(ty);
+                if ty == ListType::Numbered {
+                    out.push_str("\\begin{enumerate}\n");
+                    if let (true, Some(start)) = (innermost, info.number) {
+                        if start > 1 {
+                            // enumi, enumii, ... by how many enumerates are open.
+                            let nesting = stack.iter().filter(|t| **t == ListType::Numbered);
+                            let counter = ["enumi", "enumii", "enumiii", "enumiv"]
+                                [nesting.count().min(MAX_LIST_DEPTH) - 1];
+                            out.push_str(&format!("\\setcounter{{{counter}}}{{{}}}\n", start - 1));
+                        }
+                    }
+                } else {
+                    out.push_str("\\begin{itemize}\n");
+                }
+                if !innermost {
+                    out.push_str("\\item[]\n");
+                }
+            }
+
+            let text = self.inline(item);
+            // A leading bracket would be read as the item's optional label.
+            let guard = if text.starts_with('[') { "{}" } else { "" };
+            out.push_str(&format!("\\item {guard}{text}\n"));
+        }
+
+        while let Some(ty) = stack.pop() {
+            close_list(Some(ty), out);
+        }
+        out.push('\n');
+    }
+
+    /// Render a table, in a `table` float when it has a caption.
+    fn render_table_block(&self, table: &Table, out: &mut String) {
+        let tabular = self.render_table(table);
+        if tabular.is_empty() {
+            return;
+        }
+        match table.caption {
+            Some(ref caption) => out.push_str(&format!(
+                "\\begin{{table}}[htbp]\n\\centering\n\\caption{{{}}}\n{}\\end{{table}}\n\n",
+                escape_text(&single_line(caption)),
+                tabular
+            )),
+            None => out.push_str(&format!(
+                "\\begin{{center}}\n{}\\end{{center}}\n\n",
+                tabular
+            )),
+        }
+    }
+
+    /// Render a table as a ruled `tabular`.
+    ///
+    /// Cells are placed on the grid of [`grid::lay_out`]. A cell spanning columns is a
+    /// `\multicolumn`, one spanning rows a `\multirow` in its first row with empty cells
+    /// below it, and the rule under a row leaves out the columns a `\multirow` carries
+    /// on through.
+    fn render_table(&self, table: &Table) -> String {
+        if table.is_empty() {
+            return String::new();
+        }
+        let grid = grid::lay_out(table);
+        let rows = grid.len();
+        let width = grid.first().map_or(0, Vec::len);
+        if width == 0 {
+            return String::new();
+        }
+
+        // The (row, column) of the cell covering each slot.
+        let mut owners: Vec<Vec<Option<(usize, usize)>>> = vec![vec![None; width]; rows];
+        for (r, slots) in grid.iter().enumerate() {
+            for (c, slot) in slots.iter().enumerate() {
+                let Some(cell) = slot else { continue };
+                let row_end = (r + cell.row_span.max(1) as usize).min(rows);
+                let col_end = (c + cell.col_span.max(1) as usize).min(width);
+                for covered in &mut owners[r..row_end] {
+                    for owner in &mut covered[c..col_end] {
+                        owner.get_or_insert((r, c));
+                    }
+                }
+            }
+        }
+
+        let columns = Columns::new(table, width);
+        let spec: String = (0..width)
+            .map(|c| format!("{}|", columns.column(c, 1, CellAlignment::Left)))
+            .collect();
+        let mut out = format!("\\begin{{tabular}}{{|{spec}}}\n\\hline\n");
+
+        for r in 0..rows {
+            let header_row = table.rows[r].is_header;
+            let mut cells = Vec::new();
+            let mut c = 0;
+            while c < width {
+                let Some((owner_row, owner_col)) = owners[r][c] else {
+                    cells.push(String::new());
+                    c += 1;
+                    continue;
+                };
+                let Some(cell) = grid[owner_row][owner_col] else {
+                    c += 1;
+                    continue;
+                };
+                let span = (cell.col_span.max(1) as usize).min(width - owner_col);
+                let remaining = span - (c - owner_col);
+                if owner_row == r && owner_col == c {
+                    cells.push(self.render_cell(cell, c, span, rows - r, header_row, &columns));
+                } else if remaining > 1 {
+                    // The rows a multi-column `\multirow` continues through still need
+                    // the columns joined, or the vertical rules show between them.
+                    cells.push(format!(
+                        "\\multicolumn{{{remaining}}}{{{}}}{{}}",
+                        columns.multicolumn(c, remaining, CellAlignment::Left)
+                    ));
+                } else {
+                    cells.push(String::new());
+                }
+                c += remaining;
+            }
+            out.push_str(&cells.join(" & "));
+            out.push_str(" \\\\\n");
+
+            // Rule under the row, broken where a merge continues into the next one.
+            let open: Vec<bool> = (0..width)
+                .map(|c| {
+                    r + 1 == rows || owners[r + 1][c].is_none_or(|(owner_row, _)| owner_row > r)
+                })
+                .collect();
+            if open.iter().all(|&o| o) {
+                out.push_str("\\hline\n");
+            } else {
+                let mut c = 0;
+                while c < width {
+                    if !open[c] {
+                        c += 1;
+                        continue;
+                    }
+                    let start = c;
+                    while c < width && open[c] {
+                        c += 1;
+                    }
+                    out.push_str(&format!("\\cline{{{}-{}}}\n", start + 1, c));
+                }
+            }
+        }
+        out.push_str("\\end{tabular}\n");
+        out
+    }
+
+    /// Render a cell starting at column `col`, spanning `span` columns and (up to
+    /// `rows_left`) rows.
+    fn render_cell(
+        &self,
+        cell: &Cell,
+        col: usize,
+        span: usize,
+        rows_left: usize,
+        header_row: bool,
+        columns: &Columns,
+    ) -> String {
+        // Only a `p` column breaks lines; in an `l` column `\newline` would end the row.
+        let separator = if columns.fixed() { "\\newline " } else { " " };
+        let mut content = cell
+            .content
+            .iter()
+            .map(|p| self.inline(p).replace("\\newline\n", separator))
+            .filter(|t| !t.is_empty())
+            .collect::<Vec<_>>()
+            .join(separator);
+        for nested in &cell.nested_tables {
+            let tabular = self.render_table(nested);
+            if !tabular.is_empty() {
+                if !content.is_empty() {
+                    content.push_str(separator);
+                }
+                content.push_str(tabular.trim_end());
+            }
+        }
+        if (cell.is_header || header_row) && !content.is_empty() {
+            content = format!("\\textbf{{{content}}}");
+        }
+
+        let row_span = (cell.row_span.max(1) as usize).min(rows_left);
+        if row_span > 1 {
+            let width = if columns.fixed() { "=" } else { "*" };
+            content = format!("\\multirow{{{row_span}}}{{{width}}}{{{content}}}");
+        }
+        let alignment = markdown::effective_cell_alignment(cell);
+        if span > 1 || alignment != CellAlignment::Left {
+            content = format!(
+                "\\multicolumn{{{span}}}{{{}}}{{{content}}}",
+                columns.multicolumn(col, span, alignment)
+            );
+        }
+        content
+    }
+
+    /// Render a paragraph's runs and inline images, without the enclosing block.
+    fn inline(&self, para: &Paragraph) -> String {
+        let merged = para.with_merged_runs();
+        let mut output = String::new();
+        let mut spacing = RunSpacing::default();
+
+        for run in &merged.runs {
+            let Some(text) = self.render_run(run) else {
+                continue;
+            };
+            if spacing.space_before(&run.text) {
+                output.push(' ');
+            }
+            output.push_str(&text);
+            if run.line_break && !output.trim().is_empty() {
+                output.push_str("\\newline\n");
+            }
+        }
+
+        for image in &para.images {
+            if !output.is_empty() && !output.ends_with(char::is_whitespace) {
+                output.push(' ');
+            }
+            output.push_str(&self.image(&image.resource_id, image.width));
+        }
+
+        // A break with nothing after it would leave an empty line.
+        let mut output = output.trim();
+        while let Some(rest) = output.strip_suffix("\\newline") {
+            output = rest.trim_end();
+        }
+        output.to_string()
+    }
+
+    /// Render a text run, or `None` when tracked-change handling hides it.
+    fn render_run(&self, run: &TextRun) -> Option<String> {
+        let hidden = matches!(
+            (run.revision, self.options.revision_handling),
+            (RevisionType::Deleted, RevisionHandling::AcceptAll)
+                | (RevisionType::Inserted, RevisionHandling::RejectAll)
+        );
+        if hidden {
+            return None;
+        }
+
+        if let Some((checked, label)) = checkbox(run) {
+            let mark = if checked {
+                "$\\boxtimes$"
+            } else {
+                "$\\square$"
+            };
+            return Some(format!(
+                "{mark}{}",
+                self.render_run(&label).unwrap_or_default()
+            ));
+        }
+
+        // Commands go around the trimmed text, with the run's own whitespace outside.
+        let core = run.text.trim();
+        if core.is_empty() {
+            return Some(escape_text(&run.text));
+        }
+        let leading_len = run.text.len() - run.text.trim_start().len();
+        let leading = &run.text[..leading_len];
+        let trailing = &run.text[leading_len + core.len()..];
+
+        let mut text = match run.math {
+            Some(MathMode::Inline) => format!("${core}$"),
+            Some(MathMode::Display) => format!("\\[{core}\\]"),
+            None => self.text_with_note_refs(core),
+        };
+        let style = &run.style;
+        let wrappers = [
+            (style.code, "texttt"),
+            (style.superscript, "textsuperscript"),
+            (style.subscript, "textsubscript"),
+            (style.underline, "uline"),
+            (style.strikethrough, "sout"),
+            (style.italic, "textit"),
+            (style.bold, "textbf"),
+        ];
+        // An equation is styled by its own commands
+        for (_, command) in wrappers.iter().filter(|(on, _)| *on && run.math.is_none()) {
+            text = format!("\\{command}{{{text}}}");
+        }
+        if let Some(ref url) = run.hyperlink {
+            text = format!("\\href{{{}}}{{{}}}", escape_url(url), text);
+        }
+        if self.options.revision_handling == RevisionHandling::ShowMarkup {
+            match run.revision {
+                RevisionType::Inserted => text = format!("\\uline{{{text}}}"),
+                RevisionType::Deleted => text = format!("\\sout{{{text}}}"),
+                RevisionType::None => {}
+            }
+        }
+        Some(format!(
+            "{}{text}{}",
+            escape_text(leading),
+            escape_text(trailing)
+        ))
+    }
+
+    /// Escape `text`, turning each `[^label]` marker of a defined note into a
+    /// `\footnote`: the first carries the note's text and a label, later ones print
+    /// the first one's number.
+    fn text_with_note_refs(&self, text: &str) -> String {
+        let mut output = String::new();
+        let mut rest = text;
+        while let Some(start) = rest.find("[^") {
+            let Some(len) = rest[start + 2..].find(']') else {
+                break;
+            };
+            let label = &rest[start + 2..start + 2 + len];
+            let Some(note) = self.notes.get(label) else {
+                output.push_str(&escape_text(&rest[..start + 2]));
+                rest = &rest[start + 2..];
+                continue;
+            };
+            output.push_str(&escape_text(rest[..start].trim_end()));
+            let id = format!("fn:{}", note_id(label));
+            if self.cited.borrow_mut().insert(label.to_string()) {
+                output.push_str(&format!(
+                    "\\footnote{{\\label{{{id}}}{}}}",
+                    escape_text(note.trim())
+                ));
+            } else {
+                output.push_str(&format!("\\textsuperscript{{\\ref{{{id}}}}}"));
+            }
+            rest = &rest[start + 2 + len + 1..];
+        }
+        output.push_str(&escape_text(rest));
+        output
+    }
+
+    /// An `\includegraphics` command, scaled to the image's size when it is known and
+    /// fits on the page.
+    fn image(&self, resource_id: &str, width: Option<u32>) -> String {
+        let path = markdown::image_path(
+            resource_id,
+            &self.resource_map,
+            &self.options.image_path_prefix,
+        );
+        let size = match width.map(|emu| f64::from(emu) / EMU_PER_INCH) {
+            Some(inches) if inches > MAX_IMAGE_INCHES => "[width=\\linewidth]".to_string(),
+            Some(inches) if inches > 0.0 => format!("[width={inches:.2}in]"),
+            _ => String::new(),
+        };
+        format!("\\includegraphics{size}{{{path}}}")
+    }
+}
+
+/// Column types of a table: `p` columns sized in proportion to the widths the
+/// document gives, or `l` columns when it gives none.
+struct Columns {
+    /// Each column's share of the line width
+    fractions: Option<Vec<f64>>,
+}
+
+impl Columns {
+    fn new(table: &Table, width: usize) -> Self {
+        let fractions = table
+            .column_widths
+            .as_ref()
+            .filter(|w| w.len() == width && w.iter().all(|&w| w > 0))
+            .map(|widths| {
+                let total: u32 = widths.iter().sum();
+                widths
+                    .iter()
+                    .map(|&w| f64::from(w) / f64::from(total))
+                    .collect()
+            });
+        Self { fractions }
+    }
+
+    fn fixed(&self) -> bool {
+        self.fractions.is_some()
+    }
+
+    /// The column type of `span` columns from `start`, without rules.
+    fn column(&self, start: usize, span: usize, alignment: CellAlignment) -> String {
+        match self.fractions {
+            Some(ref fractions) => {
+                let share: f64 = fractions[start..start + span].iter().sum();
+                let align = match alignment {
+                    CellAlignment::Left => "",
+                    CellAlignment::Center => ">{\\centering\\arraybackslash}",
+                    CellAlignment::Right => ">{\\raggedleft\\arraybackslash}",
+                };
+                format!("{align}p{{\\dimexpr {share:.3}\\linewidth-2\\tabcolsep\\relax}}")
+            }
+            None => match alignment {
+                CellAlignment::Left => "l".to_string(),
+                CellAlignment::Center => "c".to_string(),
+                CellAlignment::Right => "r".to_string(),
+            },
+        }
+    }
+
+    /// The spec of a `\multicolumn` over `span` columns from `start`. It replaces the
+    /// rules of the columns it covers: the right one, and the left one in the first.
+    fn multicolumn(&self, start: usize, span: usize, alignment: CellAlignment) -> String {
+        let left = if start == 0 { "|" } else { "" };
+        format!("{left}{}|", self.column(start, span, alignment))
+    }
+}
+
+fn close_list(list: Option<ListType>, out: &mut String) {
+    match list {
+        Some(ListType::Numbered) => out.push_str("\\end{enumerate}\n"),
+        Some(_) => out.push_str("\\end{itemize}\n"),
+        None => {}
+    }
+}
+
+/// Escape LaTeX's special characters in literal text.
+fn escape_text(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => out.push_str("\\textbackslash{}"),
+            '&' | '%' | '$' | '#' | '_' | '{' | '}' => {
+                out.push('\\');
+                out.push(c);
+            }
+            '~' => out.push_str("\\textasciitilde{}"),
+            '^' => out.push_str("\\textasciicircum{}"),
+            '\u{00A0}' => out.push('~'),
+            // A blank line would end the paragraph.
+            '\n' | '\r' => out.push(' '),
+            c if c.is_control() && c != '\t' => {}
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// Escape a URL for `\href`, which reads it verbatim except for these.
+fn escape_url(url: &str) -> String {
+    url.replace('\\', "/")
+        .replace('#', "\\#")
+        .replace('%', "\\%")
+        .replace(['{', '}'], "")
+}
+
+/// Collapse line breaks, for the places LaTeX allows only one line.
+fn single_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::{HeadingLevel, ListInfo, Resource, Row, TextStyle};
+
+    fn list_item(text: &str, list_type: ListType, level: u8, number: Option<u32>) -> Block {
+        let mut p = Paragraph::with_text(text);
+        p.list_info = Some(ListInfo {
+            list_type,
+            level,
+            number,
+        });
+        Block::Paragraph(p)
+    }
+
+    fn docx(blocks: Vec<Block>) -> Document {
+        let mut doc = Document::new();
+        let mut section = Section::new(0);
+        section.content = blocks;
+        doc.add_section(section);
+        doc
+    }
+
+    fn body(doc: &Document) -> String {
+        to_latex(doc, &LatexOptions::new().with_standalone(false)).unwrap()
+    }
+
+    #[test]
+    fn test_headings_paragraphs_and_escaping() {
+        let mut para = Paragraph::new();
+        para.add_run(TextRun::plain("50% of $x_1 & {y} #2 ~ ^ \\"));
+        para.add_run(TextRun::styled("bold", TextStyle::bold()));
+        para.add_run(TextRun::link("site", "https://example.com/a%20b#top"));
+        let doc = docx(vec![
+            Block::Paragraph(Paragraph::heading(HeadingLevel::H1, "Intro")),
+            Block::Paragraph(Paragraph::heading(HeadingLevel::H4, "Detail")),
+            Block::Paragraph(para),
+        ]);
+        assert_eq!(
+            body(&doc),
+            "\\section{Intro}\n\n\\paragraph{Detail}\n\n\
+             50\\% of \\$x\\_1 \\& \\{y\\} \\#2 \\textasciitilde{} \\textasciicircum{} \
+             \\textbackslash{} \\textbf{bold} \\href{https://example.com/a\\%20b\\#top}{site}\n"
+        );
+    }
+
+    #[test]
+    fn test_lists_nest_environments() {
+        let doc = docx(vec![
+            list_item("One", ListType::Numbered, 0, Some(1)),
+            list_item("Sub", ListType::Bullet, 1, None),
+            list_item("Two", ListType::Numbered, 0, Some(2)),
+            Block::Paragraph(Paragraph::with_text("After")),
+            list_item("[x] Resumed", ListType::Numbered, 0, Some(3)),
+            list_item("Deep", ListType::Bullet, 2, None),
+        ]);
+        assert_eq!(
+            body(&doc),
+            "\\begin{enumerate}\n\\item One\n\\begin{itemize}\n\\item Sub\n\\end{itemize}\n\
+             \\item Two\n\\end{enumerate}\n\nAfter\n\n\
+             \\begin{enumerate}\n\\setcounter{enumi}{2}\n\\item {}[x] Resumed\n\
+             \\begin{itemize}\n\\item[]\n\\begin{itemize}\n\\item Deep\n\
+             \\end{itemize}\n\\end{itemize}\n\\end{enumerate}\n"
+        );
+    }
+
+    #[test]
+    fn test_tables_keep_merges() {
+        let mut head = Row::header(vec![Cell::header("Group"), Cell::header("C")]);
+        head.cells[0].col_span = 2;
+        let mut tall = Cell::with_text("Tall");
+        tall.row_span = 2;
+        tall.col_span = 2;
+        let mut right = Cell::with_text("1.5");
+        right.alignment = CellAlignment::Right;
+        let body_row = Row {
+            cells: vec![tall, right],
+            is_header: false,
+            height: None,
+        };
+        let last = Row {
+            cells: vec![Cell::with_text("y")],
+            is_header: false,
+            height: None,
+        };
+        let mut table = Table::new();
+        table.rows = vec![head, body_row, last];
+        table.caption = Some("Results".to_string());
+        assert_eq!(
+            body(&docx(vec![Block::Table(table)])),
+            "\\begin{table}[htbp]\n\\centering\n\\caption{Results}\n\
+             \\begin{tabular}{|l|l|l|}\n\\hline\n\
+             \\multicolumn{2}{|l|}{\\textbf{Group}} & \\textbf{C} \\\\\n\\hline\n\
+             \\multicolumn{2}{|l|}{\\multirow{2}{*}{Tall}} & \\multicolumn{1}{r|}{1.5} \\\\\n\
+             \\cline{3-3}\n\
+             \\multicolumn{2}{|l|}{} & y \\\\\n\\hline\n\
+             \\end{tabular}\n\\end{table}\n"
+        );
+    }
+
+    #[test]
+    fn test_column_widths_become_p_columns() {
+        let mut table = Table::new();
+        table.add_row(Row {
+            cells: vec![Cell::with_text("a"), Cell::with_text("b")],
+            is_header: false,
+            height: None,
+        });
+        table.column_widths = Some(vec![3000, 1000]);
+        let out = body(&docx(vec![Block::Table(table)]));
+        assert!(
+            out.contains(
+                "\\begin{tabular}{|p{\\dimexpr 0.750\\linewidth-2\\tabcolsep\\relax}|\
+                 p{\\dimexpr 0.250\\linewidth-2\\tabcolsep\\relax}|}"
+            ),
+            "{out}"
+        );
+    }
+
+    #[test]
+    fn test_footnotes_fold_into_commands() {
+        let mut para = Paragraph::new();
+        para.add_run(TextRun::plain("Claim"));
+        para.add_run(TextRun::plain("[^1]"));
+        para.add_run(TextRun::plain(" and again"));
+        para.add_run(TextRun::plain("[^1]"));
+        let doc = docx(vec![
+            Block::Paragraph(para),
+            Block::Paragraph(Paragraph::with_text("[^1]: Source, 50%")),
+        ]);
+        assert_eq!(
+            body(&doc),
+            "Claim\\footnote{\\label{fn:1}Source, 50\\%} and again\\textsuperscript{\\ref{fn:1}}\n"
+        );
+    }
+
+    #[test]
+    fn test_images_use_includegraphics() {
+        let mut doc = docx(vec![Block::Image {
+            resource_id: "rId5".to_string(),
+            alt_text: None,
+            width: Some(1_828_800),
+            height: None,
+        }]);
+        doc.add_resource(
+            "rId5",
+            Resource::image(b"\x89PNG".to_vec(), Some("chart.png".to_string())),
+        );
+        let options = LatexOptions::new()
+            .with_standalone(false)
+            .with_image_prefix("media/");
+        assert_eq!(
+            to_latex(&doc, &options).unwrap(),
+            "\\begin{center}\n\\includegraphics[width=2.00in]{media/chart.png}\n\\end{center}\n"
+        );
+    }
+
+    #[test]
+    fn test_preamble_from_metadata() {
+        let mut doc = docx(vec![Block::Paragraph(Paragraph::with_text("Hello"))]);
+        doc.metadata.title = Some("Q&A".to_string());
+        doc.metadata.author = Some("Ana".to_string());
+        doc.metadata.keywords = vec!["a".to_string(), "b".to_string()];
+        doc.metadata.modified = Some("2024-05-06T07:08:09Z".to_string());
+        doc.metadata.description = Some("Summary".to_string());
+        let out = to_latex(&doc, &LatexOptions::default()).unwrap();
+        assert!(out.starts_with("\\documentclass{article}\n"), "{out}");
+        for expected in [
+            "\\usepackage{multirow}\n",
+            "\\hypersetup{pdftitle={Q\\&A}, pdfauthor={Ana}, pdfkeywords={a, b}}\n",
+            "\\title{Q\\&A}\n\\author{Ana}\n\\date{2024-05-06}\n",
+            "\\begin{document}\n\\maketitle\n\\begin{abstract}\nSummary\n\\end{abstract}\n\nHello\n\n\\end{document}\n",
+        ] {
+            assert!(out.contains(expected), "{expected}\n{out}");
+        }
+    }
+
+    #[test]
+    fn test_revisions() {
+        let mut para = Paragraph::new();
+        para.add_run(TextRun::plain("keep"));
+        let mut inserted = TextRun::plain("new");
+        inserted.revision = RevisionType::Inserted;
+        let mut deleted = TextRun::plain("old");
+        deleted.revision = RevisionType::Deleted;
+        para.add_run(inserted);
+        para.add_run(deleted);
+        let doc = docx(vec![Block::Paragraph(para)]);
+
+        assert_eq!(body(&doc), "keep new\n");
+        let options = LatexOptions::new()
+            .with_standalone(false)
+            .with_revision_handling(RevisionHandling::ShowMarkup);
+        assert_eq!(
+            to_latex(&doc, &options).unwrap(),
+            "keep \\uline{new} \\sout{old}\n"
+        );
+    }
+
+    #[test]
+    fn test_equations() {
+        let mut para = Paragraph::new();
+        para.add_run(TextRun::plain("Area"));
+        para.add_run(TextRun::math("\\pi r^{2}", MathMode::Inline));
+        let doc = docx(vec![Block::Paragraph(para)]);
+        assert_eq!(body(&doc), "Area $\\pi r^{2}$\n");
+    }
+}
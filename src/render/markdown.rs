@@ -5,14 +5,19 @@ use std::collections::HashMap;
 use crate::detect::FormatType;
 use crate::error::Result;
 use crate::model::{
-    Block, Cell, CellAlignment, Document, HeadingLevel, Paragraph, RevisionType, Table, TextRun,
+    Block, Cell, CellAlignment, Document, HeadingLevel, MathMode, Paragraph, RevisionType, Table,
+    TextRun,
 };
 
+use super::common::{checkbox, escape_attribute, escape_html, is_no_space_before, note_definition};
 use super::heading_analyzer::{HeadingAnalyzer, HeadingDecision};
-use super::options::{RenderOptions, RevisionHandling, SectionMarkerStyle};
+use super::options::{
+    CommentStyle, FieldHandling, MarkdownDialect, RenderOptions, RevisionHandling,
+    SectionMarkerStyle,
+};
 
 /// Map of resource IDs to their filenames
-type ResourceMap = HashMap<String, String>;
+pub(super) type ResourceMap = HashMap<String, String>;
 
 /// Maximum character length for a heading.
 /// Text longer than this is unlikely to be a semantic heading.
@@ -34,6 +39,28 @@ const LIST_MARKERS: &[char] = &[
 
 /// Convert a Document to Markdown.
 pub fn to_markdown(doc: &Document, options: &RenderOptions) -> Result<String> {
+    let stripped;
+    let doc = if options.include_notes {
+        doc
+    } else {
+        stripped = super::filter::without_notes(doc);
+        &stripped
+    };
+    let commented;
+    let doc = if options.comments == CommentStyle::Omit {
+        doc
+    } else {
+        commented = super::filter::with_comments(doc, options.comments);
+        &commented
+    };
+    let fielded;
+    let doc = if options.field_handling == FieldHandling::ShowResults {
+        doc
+    } else {
+        fielded = super::filter::with_fields(doc, options.field_handling);
+        &fielded
+    };
+
     // If heading analysis is enabled, use the analyzer
     if let Some(ref config) = options.heading_config {
         return to_markdown_with_analyzer(doc, options, config);
@@ -59,6 +86,23 @@ pub fn render_section_to_string(
     options: &RenderOptions,
     resource_map: &HashMap<String, String>,
 ) -> String {
+    let stripped;
+    let section = if options.include_notes {
+        section
+    } else {
+        stripped = super::filter::section_without_notes(section);
+        &stripped
+    };
+    let fielded;
+    let section = if options.field_handling == FieldHandling::ShowResults {
+        section
+    } else {
+        let mut copy = section.clone();
+        super::filter::section_fields(&mut copy, options.field_handling);
+        fielded = copy;
+        &fielded
+    };
+
     let mut output = String::new();
     render_section_impl(
         section,
@@ -72,7 +116,7 @@ pub fn render_section_to_string(
 }
 
 /// Build a map from resource IDs to their suggested filenames.
-fn build_resource_map(doc: &Document) -> ResourceMap {
+pub(super) fn build_resource_map(doc: &Document) -> ResourceMap {
     doc.resources
         .iter()
         .map(|(id, resource)| (id.clone(), resource.suggested_filename(id)))
@@ -99,11 +143,16 @@ fn render_header_footer(
 
 /// Get image path from resource ID, resolving to actual filename if available.
 fn resolve_image_path(resource_id: &str, resource_map: &ResourceMap, prefix: &str) -> String {
+    format_link_destination(&image_path(resource_id, resource_map, prefix))
+}
+
+/// The path an image is referenced by: the prefix and the resource's filename, or its
+/// ID when the document has no such resource. Not yet escaped for any output syntax.
+pub(super) fn image_path(resource_id: &str, resource_map: &ResourceMap, prefix: &str) -> String {
     let filename = resource_map
         .get(resource_id)
-        .cloned()
-        .unwrap_or_else(|| resource_id.to_string());
-    format_link_destination(&format!("{}{}", prefix, filename))
+        .map_or(resource_id, String::as_str);
+    format!("{}{}", prefix, filename)
 }
 
 /// Format a link/image destination for `[text](destination)` syntax, wrapping it in
@@ -140,18 +189,22 @@ fn section_marker(
     if style == SectionMarkerStyle::None {
         return String::new();
     }
+    section_label(format, idx, name).map_or_else(String::new, |label| format!("<!-- {} -->", label))
+}
+
+/// The text of a section boundary marker, `slide N: Name` or `sheet N`; `None` for
+/// word-processing formats, whose sections are not slides or sheets.
+pub(super) fn section_label(format: FormatType, idx: usize, name: Option<&str>) -> Option<String> {
+    let kind = match format {
+        FormatType::Pptx | FormatType::Ppt | FormatType::Odp => "slide",
+        FormatType::Xlsx | FormatType::Xlsb | FormatType::Xls | FormatType::Ods => "sheet",
+        FormatType::Docx | FormatType::Doc | FormatType::Odt => return None,
+    };
     let n = idx + 1;
-    match format {
-        FormatType::Pptx => match name.filter(|s| !s.is_empty()) {
-            Some(name) => format!("<!-- slide {}: {} -->", n, name),
-            None => format!("<!-- slide {} -->", n),
-        },
-        FormatType::Xlsx => match name.filter(|s| !s.is_empty()) {
-            Some(name) => format!("<!-- sheet {}: {} -->", n, name),
-            None => format!("<!-- sheet {} -->", n),
-        },
-        FormatType::Docx => String::new(),
-    }
+    Some(match name.filter(|s| !s.is_empty()) {
+        Some(name) => format!("{} {}: {}", kind, n, name),
+        None => format!("{} {}", kind, n),
+    })
 }
 
 /// Core per-section render, shared by the batch and streaming paths.
@@ -254,7 +307,8 @@ fn to_markdown_standard(doc: &Document, options: &RenderOptions) -> Result<Strin
     let mut output = String::new();
     let resource_map = build_resource_map(doc);
 
-    if options.include_frontmatter {
+    // CommonMark has no metadata block: a YAML one reads as a rule and a heading.
+    if options.include_frontmatter && options.dialect != MarkdownDialect::CommonMark {
         output.push_str(&render_frontmatter(doc));
     }
 
@@ -307,7 +361,8 @@ fn to_markdown_with_analyzer(
     let resource_map = build_resource_map(doc);
 
     // Add frontmatter if requested
-    if options.include_frontmatter {
+    // CommonMark has no metadata block: a YAML one reads as a rule and a heading.
+    if options.include_frontmatter && options.dialect != MarkdownDialect::CommonMark {
         output.push_str(&render_frontmatter(doc));
     }
 
@@ -590,6 +645,7 @@ fn render_paragraph(
         in_table_cell: false,
         suppress_emphasis: suppress_heading_emphasis,
     };
+    let text_start = output.len();
     for (i, run) in merged_para.runs.iter().enumerate() {
         let run_text = render_run(run, options, run_ctx);
 
@@ -613,6 +669,19 @@ fn render_paragraph(
         output.push_str(&run_text);
     }
 
+    // Without footnotes, a definition's marker became a superscript number; the colon
+    // after it was footnote syntax.
+    if !options.dialect.has_footnotes() {
+        if let Some((label, _)) = note_definition(para) {
+            let marker = format!("<sup>{label}</sup>:");
+            if output.starts_with(&marker) {
+                output.remove(marker.len() - 1);
+            }
+        }
+    }
+
+    output.insert_str(text_start, &bookmark_anchors(para, options));
+
     // Render inline images
     for image in &para.images {
         if !output.is_empty() {
@@ -653,14 +722,6 @@ fn all_runs_uniformly_bold(para: &Paragraph) -> bool {
     saw_text
 }
 
-/// Check if a character should NOT have a space before it.
-fn is_no_space_before(c: char) -> bool {
-    matches!(
-        c,
-        '.' | ',' | ':' | ';' | '!' | '?' | ')' | ']' | '}' | '"' | '\'' | '…'
-    )
-}
-
 /// Context flags passed to [`render_run`].
 #[derive(Debug, Clone, Copy, Default)]
 struct RunContext {
@@ -719,6 +780,12 @@ fn render_run(run: &TextRun, options: &RenderOptions, ctx: RunContext) -> String
         _ => {}
     }
 
+    // A checkbox shows its state, whatever glyph the document draws it with.
+    if let Some((checked, label)) = checkbox(run) {
+        let mark = if checked { "[x]" } else { "[ ]" };
+        return format!("{mark}{}", render_run(&label, options, ctx));
+    }
+
     // A run with nothing to say — empty, or whitespace only — gets no markup: emphasis or a
     // link wrapped around nothing produces delimiters with no content between them. Its
     // whitespace is still emitted, because that whitespace is what separates its neighbours.
@@ -726,6 +793,26 @@ fn render_run(run: &TextRun, options: &RenderOptions, ctx: RunContext) -> String
     if core.is_empty() {
         return format!("{}{}", run.text, break_marker(run, options));
     }
+    // A review comment placed as an HTML comment is markup already.
+    if super::filter::is_html_comment(core) && run.hyperlink.is_none() {
+        return format!("{}{}", run.text, break_marker(run, options));
+    }
+    // An equation is LaTeX, which goes between math delimiters as it is: escaping it
+    // would change the formula.
+    if let Some(mode) = run.math {
+        let latex = if ctx.in_table_cell {
+            core.replace('|', "\\|")
+        } else {
+            core.to_string()
+        };
+        let math = match (options.dialect, mode) {
+            (MarkdownDialect::MultiMarkdown, MathMode::Inline) => format!("\\\\({latex}\\\\)"),
+            (MarkdownDialect::MultiMarkdown, MathMode::Display) => format!("\\\\[{latex}\\\\]"),
+            (_, MathMode::Inline) => format!("${latex}$"),
+            (_, MathMode::Display) => format!("$${latex}$$"),
+        };
+        return format!("{math}{}", break_marker(run, options));
+    }
 
     // OOXML stores the whitespace around a word in the runs themselves — often in a run of
     // its own, or flagged with `xml:space="preserve"`. That whitespace sits *between* runs,
@@ -737,27 +824,40 @@ fn render_run(run: &TextRun, options: &RenderOptions, ctx: RunContext) -> String
     let leading = &run.text[..leading_len];
     let trailing = &run.text[leading_len + core.len()..];
 
+    let dialect = options.dialect;
     let mut text = if options.escape_special_chars {
-        escape_markdown(core, ctx.in_table_cell)
+        escape_extensions(&escape_markdown(core, ctx.in_table_cell), dialect)
     } else {
         core.to_string()
     };
+    if !dialect.has_footnotes() && !run.style.code {
+        text = superscript_note_markers(&text);
+    }
 
     // Apply formatting (innermost first)
     if run.style.code {
         text = format!("`{}`", text.replace('`', "\\`"));
     }
     if run.style.superscript {
-        text = format!("<sup>{}</sup>", text);
+        text = match dialect {
+            MarkdownDialect::Pandoc => format!("^{}^", text.replace(' ', "\\ ")),
+            _ => format!("<sup>{}</sup>", text),
+        };
     }
     if run.style.subscript {
-        text = format!("<sub>{}</sub>", text);
+        text = match dialect {
+            MarkdownDialect::Pandoc => format!("~{}~", text.replace(' ', "\\ ")),
+            _ => format!("<sub>{}</sub>", text),
+        };
     }
     if run.style.underline {
-        text = format!("<u>{}</u>", text);
+        text = match dialect {
+            MarkdownDialect::Pandoc => format!("[{}]{{.underline}}", text),
+            _ => format!("<u>{}</u>", text),
+        };
     }
     if run.style.strikethrough {
-        text = format!("~~{}~~", text);
+        text = strike(&text, dialect);
     }
     let effective_bold = run.style.bold && !ctx.suppress_emphasis;
     let effective_italic = run.style.italic && !ctx.suppress_emphasis;
@@ -774,15 +874,21 @@ fn render_run(run: &TextRun, options: &RenderOptions, ctx: RunContext) -> String
         text = format!("[{}]({})", text, format_link_destination(url));
     }
 
-    // Apply revision markup for ShowMarkup mode
+    // Apply revision markup for ShowMarkup mode. MultiMarkdown has CriticMarkup for
+    // exactly this; elsewhere a deletion is struck through and an insertion falls back to
+    // HTML, since Markdown lacks insert markup.
     match (&run.revision, &options.revision_handling) {
         (RevisionType::Deleted, RevisionHandling::ShowMarkup) => {
-            // Show deleted text with strikethrough
-            text = format!("~~{}~~", text);
+            text = match dialect {
+                MarkdownDialect::MultiMarkdown => format!("{{--{}--}}", text),
+                _ => strike(&text, dialect),
+            };
         }
         (RevisionType::Inserted, RevisionHandling::ShowMarkup) => {
-            // Show inserted text with underline markers (using HTML since Markdown lacks insert markup)
-            text = format!("<ins>{}</ins>", text);
+            text = match dialect {
+                MarkdownDialect::MultiMarkdown => format!("{{++{}++}}", text),
+                _ => format!("<ins>{}</ins>", text),
+            };
         }
         _ => {}
     }
@@ -860,6 +966,80 @@ fn escape_markdown(s: &str, in_table_cell: bool) -> String {
     result
 }
 
+/// Strike text through: `~~text~~` where the dialect has it, `<del>` elsewhere.
+fn strike(text: &str, dialect: MarkdownDialect) -> String {
+    if dialect.has_strikethrough() {
+        format!("~~{}~~", text)
+    } else {
+        format!("<del>{}</del>", text)
+    }
+}
+
+/// Escape the characters a dialect's extensions give meaning to, in text
+/// [`escape_markdown`] has already escaped for CommonMark.
+///
+/// - GFM: `~` (strikethrough)
+/// - Pandoc: `~` and `^` (subscript, superscript), `$` (TeX math), and `@` where it
+///   starts a word (citations)
+/// - MultiMarkdown: `~` and `^`, and `{` where it opens CriticMarkup (`{++`, `{--`,
+///   `{~~`, `{==`, `{>>`)
+///
+/// The `^` of a `[^id]` footnote marker stays: it is footnote syntax in every dialect
+/// that has footnotes.
+fn escape_extensions(s: &str, dialect: MarkdownDialect) -> String {
+    let special: &[char] = match dialect {
+        MarkdownDialect::CommonMark => return s.to_string(),
+        MarkdownDialect::Gfm => &['~'],
+        MarkdownDialect::Pandoc => &['~', '^', '$', '@'],
+        MarkdownDialect::MultiMarkdown => &['~', '^', '{'],
+    };
+
+    let chars: Vec<char> = s.chars().collect();
+    let mut result = String::with_capacity(s.len());
+    for (i, &c) in chars.iter().enumerate() {
+        let prev = i.checked_sub(1).map(|j| chars[j]);
+        let next = chars.get(i + 1).copied();
+        let escape = special.contains(&c)
+            && match c {
+                '^' => prev != Some('['),
+                '@' => {
+                    !prev.is_some_and(char::is_alphanumeric)
+                        && next.is_some_and(|n| n.is_alphanumeric() || n == '_')
+                }
+                '{' => next.is_some_and(|n| matches!(n, '+' | '-' | '~' | '=' | '>')),
+                _ => true,
+            };
+        if escape {
+            result.push('\\');
+        }
+        result.push(c);
+    }
+    result
+}
+
+/// Replace `[^id]` footnote markers with `<sup>id</sup>`, for a dialect without
+/// footnotes.
+fn superscript_note_markers(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find("[^") {
+        let marker = rest[start..]
+            .find(']')
+            .map(|end| &rest[start..start + end + 1])
+            .filter(|marker| super::filter::is_note_reference(marker));
+        let Some(marker) = marker else {
+            result.push_str(&rest[..start + 2]);
+            rest = &rest[start + 2..];
+            continue;
+        };
+        result.push_str(&rest[..start]);
+        result.push_str(&format!("<sup>{}</sup>", &marker[2..marker.len() - 1]));
+        rest = &rest[start + marker.len()..];
+    }
+    result.push_str(rest);
+    result
+}
+
 /// Render a table cell's content with formatting preserved.
 /// Multiple paragraphs are joined with `<br>` for inline display.
 ///
@@ -873,6 +1053,45 @@ fn render_cell_content(
     resource_map: &ResourceMap,
     is_header_cell: bool,
 ) -> String {
+    let parts = render_cell_paragraphs(cell, options, resource_map, is_header_cell);
+
+    // NOTE: nested_tables are intentionally NOT rendered here.
+    // They are extracted as separate Table blocks during parsing and should
+    // be rendered independently to preserve structure and avoid duplication.
+    // See: render_nested_tables_as_blocks() for proper nested table rendering.
+
+    // Join paragraphs with <br> for markdown table cells
+    let text = parts.join("<br>");
+    let text = if options.preserve_line_breaks {
+        text.replace("  \n", "<br>")
+    } else {
+        text
+    };
+
+    // Only replace line breaks - pipes are already escaped by escape_markdown
+    // in render_run. Bare CR is treated as a line break too: a leaked CR
+    // splits the pipe-table row in most Markdown renderers.
+    text.replace("\r\n", "\n").replace(['\n', '\r'], " ")
+}
+
+/// Anchors for the paragraph's bookmarks, where linked references land.
+fn bookmark_anchors(para: &Paragraph, options: &RenderOptions) -> String {
+    if options.field_handling != FieldHandling::LinkReferences {
+        return String::new();
+    }
+    para.bookmarks
+        .iter()
+        .map(|name| format!("<a id=\"{}\"></a>", escape_attribute(name)))
+        .collect()
+}
+
+/// The Markdown of each paragraph of a cell, and of each image, in order.
+fn render_cell_paragraphs(
+    cell: &crate::model::Cell,
+    options: &RenderOptions,
+    resource_map: &ResourceMap,
+    is_header_cell: bool,
+) -> Vec<String> {
     let mut parts = Vec::new();
 
     for para in &cell.content {
@@ -913,6 +1132,7 @@ fn render_cell_content(
         }
 
         if !para_text.is_empty() {
+            para_text.insert_str(0, &bookmark_anchors(para, options));
             parts.push(para_text);
         }
 
@@ -925,23 +1145,7 @@ fn render_cell_content(
         }
     }
 
-    // NOTE: nested_tables are intentionally NOT rendered here.
-    // They are extracted as separate Table blocks during parsing and should
-    // be rendered independently to preserve structure and avoid duplication.
-    // See: render_nested_tables_as_blocks() for proper nested table rendering.
-
-    // Join paragraphs with <br> for markdown table cells
-    let text = parts.join("<br>");
-    let text = if options.preserve_line_breaks {
-        text.replace("  \n", "<br>")
-    } else {
-        text
-    };
-
-    // Only replace line breaks - pipes are already escaped by escape_markdown
-    // in render_run. Bare CR is treated as a line break too: a leaked CR
-    // splits the pipe-table row in most Markdown renderers.
-    text.replace("\r\n", "\n").replace(['\n', '\r'], " ")
+    parts
 }
 
 /// Effective alignment for a cell.
@@ -951,7 +1155,7 @@ fn render_cell_content(
 /// Authors normally set only the latter, so when the explicit cell
 /// alignment is `Left` (the default) we fall back to the first paragraph's
 /// alignment to recover the visual intent.
-fn effective_cell_alignment(cell: &crate::model::Cell) -> CellAlignment {
+pub(super) fn effective_cell_alignment(cell: &crate::model::Cell) -> CellAlignment {
     if cell.alignment != CellAlignment::Left {
         return cell.alignment;
     }
@@ -1010,29 +1214,7 @@ fn render_callout_blockquote(
     options: &RenderOptions,
     resource_map: &ResourceMap,
 ) -> Option<String> {
-    if table.rows.len() != 1 {
-        return None;
-    }
-    let row = &table.rows[0];
-    if row.cells.len() != 1 {
-        return None;
-    }
-    let cell = &row.cells[0];
-    if cell.content.is_empty() {
-        return None;
-    }
-    let all_bold = cell.content.iter().all(|p| {
-        p.runs
-            .iter()
-            .all(|r| r.text.trim().is_empty() || r.style.bold)
-    });
-    let any_text = cell
-        .content
-        .iter()
-        .any(|p| p.runs.iter().any(|r| !r.text.trim().is_empty()));
-    if !(all_bold && any_text) {
-        return None;
-    }
+    let cell = callout_cell(table)?;
 
     // Render the cell with bold suppressed, then prefix every line with `> `.
     let inner = render_cell_content(cell, options, resource_map, true);
@@ -1050,6 +1232,30 @@ fn render_callout_blockquote(
     Some(out)
 }
 
+/// The cell of a callout box: a single-row, single-column table whose content is fully
+/// emphasized (every non-empty run is bold). `None` when the table is anything else.
+pub(super) fn callout_cell(table: &Table) -> Option<&Cell> {
+    let [row] = table.rows.as_slice() else {
+        return None;
+    };
+    let [cell] = row.cells.as_slice() else {
+        return None;
+    };
+    if cell.content.is_empty() {
+        return None;
+    }
+    let all_bold = cell.content.iter().all(|p| {
+        p.runs
+            .iter()
+            .all(|r| r.text.trim().is_empty() || r.style.bold)
+    });
+    let any_text = cell
+        .content
+        .iter()
+        .any(|p| p.runs.iter().any(|r| !r.text.trim().is_empty()));
+    (all_bold && any_text).then_some(cell)
+}
+
 /// Render a table to Markdown.
 fn render_table(table: &Table, options: &RenderOptions, resource_map: &ResourceMap) -> String {
     if table.is_empty() {
@@ -1069,6 +1275,17 @@ fn render_table(table: &Table, options: &RenderOptions, resource_map: &ResourceM
         return render_table_html(table);
     }
 
+    if options.table_fallback == super::TableFallback::Ascii {
+        return render_table_ascii_block(table, options, resource_map);
+    }
+
+    match options.dialect {
+        // CommonMark has no tables of its own; an HTML block is the one it can read.
+        MarkdownDialect::CommonMark => return render_table_html(table),
+        MarkdownDialect::Pandoc => return render_grid_table(table, options, resource_map),
+        MarkdownDialect::Gfm | MarkdownDialect::MultiMarkdown => {}
+    }
+
     let mut output = String::new();
     let mut nested_tables: Vec<&Table> = Vec::new();
 
@@ -1081,6 +1298,9 @@ fn render_table(table: &Table, options: &RenderOptions, resource_map: &ResourceM
     if col_count == 0 {
         return String::new();
     }
+    // MultiMarkdown can say what covers those columns: `||` continues the cell to the
+    // left, `^^` the one above.
+    let spans = (options.dialect == MarkdownDialect::MultiMarkdown).then(|| merge_spans(&grid));
 
     for (i, slots) in grid.iter().enumerate() {
         output.push('|');
@@ -1089,7 +1309,7 @@ fn render_table(table: &Table, options: &RenderOptions, resource_map: &ResourceM
         // regardless of `row.is_header`. Treat it as a header cell for
         // emphasis-suppression purposes too.
         let is_header_row = i == 0 || table.rows[i].is_header;
-        for slot in slots {
+        for (j, slot) in slots.iter().enumerate() {
             match slot {
                 Some(cell) => {
                     let text = render_cell_content(cell, options, resource_map, is_header_row);
@@ -1101,7 +1321,11 @@ fn render_table(table: &Table, options: &RenderOptions, resource_map: &ResourceM
                     }
                 }
                 // A column covered by a merge, or one this row does not reach.
-                None => output.push_str("  |"),
+                None => match spans.as_ref().map(|spans| spans[i][j]) {
+                    Some(Span::Across) => output.push('|'),
+                    Some(Span::Down) => output.push_str(" ^^ |"),
+                    _ => output.push_str("  |"),
+                },
             }
         }
         output.push('\n');
@@ -1134,6 +1358,104 @@ fn render_table(table: &Table, options: &RenderOptions, resource_map: &ResourceM
     output
 }
 
+/// What covers a grid slot that no cell starts in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Span {
+    /// Nothing: the row ends before this column.
+    None,
+    /// A cell to the left spanning columns, in the row it starts in.
+    Across,
+    /// A cell above spanning rows, in the column it starts in; the columns it also
+    /// spans are [`Span::Across`].
+    Down,
+}
+
+/// The [`Span`] of every slot of a laid-out grid.
+fn merge_spans(grid: &[Vec<Option<&Cell>>]) -> Vec<Vec<Span>> {
+    let row_count = grid.len();
+    let col_count = grid.first().map_or(0, Vec::len);
+    let mut spans = vec![vec![Span::None; col_count]; row_count];
+    for (r, slots) in grid.iter().enumerate() {
+        for (c, slot) in slots.iter().enumerate() {
+            let Some(cell) = slot else { continue };
+            let rows = (cell.row_span.max(1) as usize).min(row_count - r);
+            let cols = (cell.col_span.max(1) as usize).min(col_count - c);
+            for (dr, covered) in spans[r..r + rows].iter_mut().enumerate() {
+                for (dc, span) in covered[c..c + cols].iter_mut().enumerate() {
+                    if grid[r + dr][c + dc].is_none() {
+                        *span = if dr > 0 && dc == 0 {
+                            Span::Down
+                        } else {
+                            Span::Across
+                        };
+                    }
+                }
+            }
+        }
+    }
+    spans
+}
+
+/// Render a table as a Pandoc grid table, whose cells keep their paragraphs apart and
+/// whose merged cells span, followed by its nested tables.
+fn render_grid_table(table: &Table, options: &RenderOptions, resource_map: &ResourceMap) -> String {
+    let lines = |cell: &Cell| {
+        let paragraphs = render_cell_paragraphs(cell, options, resource_map, cell.is_header);
+        let mut lines = Vec::new();
+        for paragraph in paragraphs {
+            if !lines.is_empty() {
+                lines.push(String::new());
+            }
+            // A hard break's trailing spaces would be lost among the padding.
+            let paragraph = paragraph.replace("  \n", "\\\n").replace("\r\n", "\n");
+            lines.extend(paragraph.split(['\n', '\r']).map(str::to_string));
+        }
+        lines
+    };
+    let mut output = super::ascii::render_table_grid(table, lines);
+    output.push('\n');
+
+    let nested = table
+        .rows
+        .iter()
+        .flat_map(|row| &row.cells)
+        .flat_map(|cell| &cell.nested_tables);
+    for nested in nested {
+        output.push('\n');
+        output.push_str(&render_table(nested, options, resource_map));
+    }
+    output
+}
+
+/// Render a table as a box-drawn table in a fenced code block, which keeps its
+/// alignment, followed by its nested tables.
+fn render_table_ascii_block(
+    table: &Table,
+    options: &RenderOptions,
+    resource_map: &ResourceMap,
+) -> String {
+    let body = super::ascii::render_table_ascii(table);
+    if body.is_empty() {
+        return String::new();
+    }
+
+    // The fence must be longer than any backtick run in the cells.
+    let longest_run = body.split(|c| c != '`').map(str::len).max().unwrap_or(0);
+    let fence = "`".repeat(longest_run.max(2) + 1);
+    let mut output = format!("{fence}text\n{body}\n{fence}\n");
+
+    let nested = table
+        .rows
+        .iter()
+        .flat_map(|row| &row.cells)
+        .flat_map(|cell| &cell.nested_tables);
+    for nested in nested {
+        output.push('\n');
+        output.push_str(&render_table(nested, options, resource_map));
+    }
+    output
+}
+
 /// Render a table as HTML (for complex layouts).
 fn render_table_html(table: &Table) -> String {
     let mut html = String::from("<table>\n");
@@ -1163,17 +1485,11 @@ fn render_table_html(table: &Table) -> String {
     html
 }
 
-fn escape_html(text: &str) -> String {
-    text.replace('&', "&amp;")
-        .replace('<', "&lt;")
-        .replace('>', "&gt;")
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::detect::FormatType;
-    use crate::model::{Cell, HeadingLevel, RevisionType, Row, Section, TextStyle};
+    use crate::model::{Cell, FormField, HeadingLevel, RevisionType, Row, Section, TextStyle};
     use crate::render::options::SectionMarkerStyle;
 
     fn two_section_doc(format: FormatType, names: [&str; 2]) -> Document {
@@ -1825,6 +2141,7 @@ mod tests {
             vertical_alignment: crate::model::VerticalAlignment::Top,
             is_header: false,
             background: None,
+            value: None,
         };
 
         table.add_row(Row {
@@ -1867,6 +2184,7 @@ mod tests {
             vertical_alignment: crate::model::VerticalAlignment::Top,
             is_header: false,
             background: None,
+            value: None,
         };
 
         table.add_row(Row {
@@ -1907,6 +2225,7 @@ mod tests {
             vertical_alignment: crate::model::VerticalAlignment::Top,
             is_header: false,
             background: None,
+            value: None,
         };
 
         table.add_row(Row {
@@ -1960,6 +2279,7 @@ mod tests {
             vertical_alignment: crate::model::VerticalAlignment::Top,
             is_header: false,
             background: None,
+            value: None,
         };
 
         let cell2 = Cell {
@@ -1971,6 +2291,7 @@ mod tests {
             vertical_alignment: crate::model::VerticalAlignment::Top,
             is_header: false,
             background: None,
+            value: None,
         };
 
         table.add_row(Row {
@@ -2068,6 +2389,9 @@ mod tests {
             line_break: true,
             page_break: false,
             revision: RevisionType::None,
+            math: None,
+            form_field: None,
+            field: None,
         });
         para.runs.push(TextRun::plain("Second line"));
 
@@ -2120,6 +2444,7 @@ mod tests {
                 vertical_alignment: crate::model::VerticalAlignment::Top,
                 is_header: false,
                 background: None,
+                value: None,
             }],
             is_header: false,
             height: None,
@@ -2148,6 +2473,7 @@ mod tests {
                 vertical_alignment: crate::model::VerticalAlignment::Top,
                 is_header: false,
                 background: None,
+                value: None,
             }],
             is_header: false,
             height: None,
@@ -2162,6 +2488,24 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_ascii_table_fallback_is_fenced() {
+        let mut table = Table::new();
+        table.add_row(Row::header(vec![Cell::header("Code")]));
+        table.add_row(Row {
+            cells: vec![Cell::with_text("a ``` b")],
+            is_header: false,
+            height: None,
+        });
+
+        let options = RenderOptions::new().with_table_fallback(crate::render::TableFallback::Ascii);
+        let md = render_table(&table, &options, &empty_resource_map());
+
+        assert!(md.starts_with("````text\n┌"), "got: {md}");
+        assert!(md.contains("│ a ``` b │"), "got: {md}");
+        assert!(md.ends_with("┘\n````\n"), "got: {md}");
+    }
+
     #[test]
     fn test_table_cell_alignment_rendering() {
         let mut table = Table::new();
@@ -2184,6 +2528,7 @@ mod tests {
             vertical_alignment: crate::model::VerticalAlignment::Top,
             is_header: false,
             background: None,
+            value: None,
         };
 
         let center_cell = Cell {
@@ -2195,6 +2540,7 @@ mod tests {
             vertical_alignment: crate::model::VerticalAlignment::Top,
             is_header: false,
             background: None,
+            value: None,
         };
 
         let right_cell = Cell {
@@ -2206,6 +2552,7 @@ mod tests {
             vertical_alignment: crate::model::VerticalAlignment::Top,
             is_header: false,
             background: None,
+            value: None,
         };
 
         table.add_row(Row {
@@ -2318,6 +2665,7 @@ mod tests {
                     vertical_alignment: crate::model::VerticalAlignment::Top,
                     is_header: false,
                     background: None,
+                    value: None,
                 },
                 Cell {
                     content: vec![Paragraph::with_text("B")],
@@ -2328,6 +2676,7 @@ mod tests {
                     vertical_alignment: crate::model::VerticalAlignment::Top,
                     is_header: false,
                     background: None,
+                    value: None,
                 },
             ],
             is_header: false,
@@ -2344,6 +2693,7 @@ mod tests {
                 vertical_alignment: crate::model::VerticalAlignment::Top,
                 is_header: false,
                 background: None,
+                value: None,
             }],
             is_header: false,
             height: None,
@@ -2409,4 +2759,276 @@ mod tests {
             "Expected 'format: docx' in frontmatter, got: {md}"
         );
     }
+
+    #[test]
+    fn test_notes_can_be_left_out() {
+        let mut doc = Document::new();
+        let mut section = Section::new(0);
+        let mut para = Paragraph::with_text("Body");
+        para.runs.push(TextRun::plain("[^1]"));
+        section.add_paragraph(para);
+        section.add_paragraph(Paragraph::with_text("[^1]: Footnote"));
+        section.notes = Some(vec![Paragraph::with_text("Speaker note")]);
+        doc.add_section(section);
+
+        let md = to_markdown(&doc, &RenderOptions::default()).unwrap();
+        assert!(md.contains("Footnote") && md.contains("Speaker note"));
+
+        let md = to_markdown(&doc, &RenderOptions::new().with_include_notes(false)).unwrap();
+        assert_eq!(md.trim(), "Body");
+    }
+
+    #[test]
+    fn test_comments_rendered_on_request() {
+        let mut doc = Document::new();
+        let mut section = Section::new(0);
+        let mut para = Paragraph::with_text("A claim");
+        para.comment_ids.push("4".to_string());
+        section.add_paragraph(para);
+        doc.add_section(section);
+        doc.comments.push(crate::model::Comment {
+            id: "4".to_string(),
+            author: Some("Alice".to_string()),
+            text: "Needs a_source".to_string(),
+            ..Default::default()
+        });
+
+        let md = to_markdown(&doc, &RenderOptions::default()).unwrap();
+        assert_eq!(md, "A claim");
+
+        let options = RenderOptions::new().with_comments(CommentStyle::Footnotes);
+        let md = to_markdown(&doc, &options).unwrap();
+        assert_eq!(md, "A claim[^c4]\n\n[^c4]: **Alice**: Needs a_source");
+
+        let options = options.with_dialect(MarkdownDialect::CommonMark);
+        let md = to_markdown(&doc, &options).unwrap();
+        assert!(
+            md.ends_with("<sup>c4</sup> **Alice**: Needs a_source"),
+            "{md}"
+        );
+
+        let options = RenderOptions::new().with_comments(CommentStyle::Html);
+        let md = to_markdown(&doc, &options).unwrap();
+        assert_eq!(md, "A claim<!-- Comment from Alice: Needs a_source -->");
+    }
+
+    #[test]
+    fn test_equations() {
+        let mut doc = Document::new();
+        let mut section = Section::new(0);
+        let mut para = Paragraph::with_text("Area ");
+        para.add_run(TextRun::math("\\pi r^{2}", MathMode::Inline));
+        section.add_paragraph(para);
+        section.add_paragraph(Paragraph {
+            runs: vec![TextRun::math("x_{1}*y_{1}", MathMode::Display)],
+            ..Default::default()
+        });
+        doc.add_section(section);
+
+        let md = to_markdown(&doc, &RenderOptions::default()).unwrap();
+        assert_eq!(md, "Area $\\pi r^{2}$\n\n$$x_{1}*y_{1}$$");
+        let mmd = to_markdown(&doc, &dialect(MarkdownDialect::MultiMarkdown)).unwrap();
+        assert_eq!(mmd, "Area \\\\(\\pi r^{2}\\\\)\n\n\\\\[x_{1}*y_{1}\\\\]");
+    }
+
+    #[test]
+    fn test_checkbox_fields_show_their_state() {
+        let checkbox = |checked, text| {
+            let mut run = TextRun::plain(text);
+            run.form_field = Some(Box::new(FormField {
+                field_type: crate::model::FormFieldType::Checkbox,
+                checked: Some(checked),
+                ..Default::default()
+            }));
+            run
+        };
+        let mut para = Paragraph::new();
+        para.add_run(checkbox(true, "☐"));
+        para.add_run(TextRun::plain(" Yes "));
+        para.add_run(checkbox(false, "☐"));
+        para.add_run(TextRun::plain(" No"));
+        let mut doc = Document::new();
+        let mut section = Section::new(0);
+        section.add_paragraph(para);
+        // The label sharing the glyph's run is kept
+        let mut para = Paragraph::new();
+        para.add_run(checkbox(false, "☐ Option A"));
+        section.add_paragraph(para);
+        doc.add_section(section);
+
+        let md = to_markdown(&doc, &RenderOptions::default()).unwrap();
+        assert_eq!(md, "[x] Yes [ ] No\n\n[ ] Option A");
+    }
+
+    fn dialect(dialect: MarkdownDialect) -> RenderOptions {
+        RenderOptions::new().with_dialect(dialect)
+    }
+
+    fn merged_table_doc() -> Document {
+        let mut group = Cell::with_text("Group");
+        group.col_span = 2;
+        let mut tall = Cell::with_text("Tall");
+        tall.row_span = 2;
+        let mut table = Table::new();
+        table.add_row(Row::header(vec![group, Cell::header("C")]));
+        table.add_row(Row {
+            cells: vec![tall, Cell::with_text("a"), Cell::with_text("b")],
+            is_header: false,
+            height: None,
+        });
+        table.add_row(Row {
+            cells: vec![Cell::with_text("c"), Cell::with_text("d")],
+            is_header: false,
+            height: None,
+        });
+        let mut doc = Document::new();
+        let mut section = Section::new(0);
+        section.add_table(table);
+        doc.add_section(section);
+        doc
+    }
+
+    #[test]
+    fn test_dialect_tables() {
+        let doc = merged_table_doc();
+
+        let gfm = to_markdown(&doc, &dialect(MarkdownDialect::Gfm)).unwrap();
+        assert!(gfm.starts_with("| Group |  | C |\n"), "{gfm}");
+
+        let mmd = to_markdown(&doc, &dialect(MarkdownDialect::MultiMarkdown)).unwrap();
+        assert_eq!(
+            mmd,
+            "| Group || C |\n| --- | --- | --- |\n| Tall | a | b |\n| ^^ | c | d |"
+        );
+
+        let commonmark = to_markdown(&doc, &dialect(MarkdownDialect::CommonMark)).unwrap();
+        assert!(commonmark.starts_with("<table>"), "{commonmark}");
+        assert!(commonmark.contains("<td rowspan=\"2\">Tall</td>"));
+
+        let pandoc = to_markdown(&doc, &dialect(MarkdownDialect::Pandoc)).unwrap();
+        assert_eq!(
+            pandoc,
+            "+------------+-----+\n\
+             | Group      | C   |\n\
+             +======+=====+=====+\n\
+             | Tall | a   | b   |\n\
+             |      +-----+-----+\n\
+             |      | c   | d   |\n\
+             +------+-----+-----+"
+        );
+    }
+
+    #[test]
+    fn test_pandoc_grid_cells_keep_paragraphs() {
+        let mut cell = Cell::with_text("first");
+        cell.content.push(Paragraph::with_text("second"));
+        let mut table = Table::new();
+        table.add_row(Row {
+            cells: vec![cell],
+            is_header: false,
+            height: None,
+        });
+        let mut doc = Document::new();
+        let mut section = Section::new(0);
+        section.add_table(table);
+        doc.add_section(section);
+
+        let pandoc = to_markdown(&doc, &dialect(MarkdownDialect::Pandoc)).unwrap();
+        assert_eq!(
+            pandoc,
+            "+--------+\n| first  |\n|        |\n| second |\n+--------+"
+        );
+    }
+
+    fn footnote_doc() -> Document {
+        let mut para = Paragraph::with_text("Body");
+        para.runs.push(TextRun::plain("[^1]"));
+        let mut struck = TextRun::plain(" gone");
+        struck.style.strikethrough = true;
+        para.runs.push(struck);
+        let mut doc = Document::new();
+        doc.format = FormatType::Docx;
+        let mut section = Section::new(0);
+        section.add_paragraph(para);
+        section.add_paragraph(Paragraph::with_text("[^1]: Note"));
+        doc.add_section(section);
+        doc
+    }
+
+    #[test]
+    fn test_dialect_footnotes_and_strikethrough() {
+        let doc = footnote_doc();
+
+        let gfm = to_markdown(&doc, &dialect(MarkdownDialect::Gfm)).unwrap();
+        assert_eq!(gfm, "Body[^1] ~~gone~~\n\n[^1]: Note");
+
+        let options = dialect(MarkdownDialect::CommonMark).with_frontmatter(true);
+        let commonmark = to_markdown(&doc, &options).unwrap();
+        assert_eq!(
+            commonmark,
+            "Body<sup>1</sup> <del>gone</del>\n\n<sup>1</sup> Note"
+        );
+
+        let mmd = to_markdown(&doc, &dialect(MarkdownDialect::MultiMarkdown)).unwrap();
+        assert_eq!(mmd, "Body[^1] <del>gone</del>\n\n[^1]: Note");
+    }
+
+    #[test]
+    fn test_dialect_revisions() {
+        let mut para = Paragraph::new();
+        let mut deleted = TextRun::plain("old");
+        deleted.revision = RevisionType::Deleted;
+        let mut inserted = TextRun::plain("new");
+        inserted.revision = RevisionType::Inserted;
+        para.runs = vec![deleted, inserted];
+        let mut doc = Document::new();
+        let mut section = Section::new(0);
+        section.add_paragraph(para);
+        doc.add_section(section);
+
+        let options = dialect(MarkdownDialect::MultiMarkdown).with_show_revisions();
+        assert_eq!(to_markdown(&doc, &options).unwrap(), "{--old--} {++new++}");
+        let options = dialect(MarkdownDialect::CommonMark).with_show_revisions();
+        assert_eq!(
+            to_markdown(&doc, &options).unwrap(),
+            "<del>old</del> <ins>new</ins>"
+        );
+    }
+
+    #[test]
+    fn test_dialect_escaping() {
+        let text = "~1 x^2 $5 @doe a@b.c {++";
+        assert_eq!(escape_extensions(text, MarkdownDialect::CommonMark), text);
+        assert_eq!(
+            escape_extensions(text, MarkdownDialect::Gfm),
+            "\\~1 x^2 $5 @doe a@b.c {++"
+        );
+        assert_eq!(
+            escape_extensions(text, MarkdownDialect::Pandoc),
+            "\\~1 x\\^2 \\$5 \\@doe a@b.c {++"
+        );
+        assert_eq!(
+            escape_extensions(text, MarkdownDialect::MultiMarkdown),
+            "\\~1 x\\^2 $5 @doe a@b.c \\{++"
+        );
+        // A footnote marker is syntax, not text.
+        assert_eq!(escape_extensions("a[^1]", MarkdownDialect::Pandoc), "a[^1]");
+    }
+
+    #[test]
+    fn test_pandoc_native_inline_styles() {
+        let mut para = Paragraph::new();
+        let mut sup = TextRun::plain("a b");
+        sup.style.superscript = true;
+        let mut under = TextRun::plain("u");
+        under.style.underline = true;
+        para.runs = vec![TextRun::plain("x"), sup, under];
+        let mut doc = Document::new();
+        let mut section = Section::new(0);
+        section.add_paragraph(para);
+        doc.add_section(section);
+
+        let pandoc = to_markdown(&doc, &dialect(MarkdownDialect::Pandoc)).unwrap();
+        assert_eq!(pandoc, "x ^a\\ b^ [u]{.underline}");
+    }
 }
@@ -1,7 +1,7 @@
 //! Output rendering for documents.
 //!
 //! This module provides renderers for converting Document models
-//! to various output formats: Markdown, plain text, and JSON.
+//! to various output formats: Markdown, plain text, HTML, AsciiDoc, LaTeX, CSV, EPUB, and JSON.
 //!
 //! # Example
 //!
@@ -16,27 +16,56 @@
 //! // Render to plain text
 //! let text = to_text(&doc, &RenderOptions::default())?;
 //!
+//! // Render to HTML
+//! let html = to_html(&doc, &HtmlOptions::default())?;
+//!
+//! // Render to AsciiDoc
+//! let adoc = to_asciidoc(&doc, &RenderOptions::default())?;
+//!
+//! // Render to LaTeX
+//! let tex = to_latex(&doc, &LatexOptions::default())?;
+//!
+//! // Render each sheet to CSV
+//! let sheets = to_csv(&doc, &CsvOptions::default())?;
+//!
+//! // Package as an EPUB 3 book
+//! let epub = to_epub(&doc, &EpubOptions::default())?;
+//!
 //! // Render to JSON
 //! let json = to_json(&doc, JsonFormat::Pretty)?;
 //! # Ok::<(), undoc::Error>(())
 //! ```
 
+mod ascii;
+mod asciidoc;
 mod cleanup;
+pub(crate) mod common;
+mod csv;
+mod epub;
+mod filter;
 mod grid;
 pub mod heading_analyzer;
+mod html;
 mod json;
+mod latex;
 mod markdown;
 mod options;
 pub mod style_mapping;
 mod text;
 
+pub use asciidoc::to_asciidoc;
 pub use cleanup::{clean_text, detect_mojibake};
+pub use csv::to_csv;
+pub use epub::to_epub;
 pub use heading_analyzer::{DocumentStats, HeadingAnalyzer, HeadingConfig, HeadingDecision};
+pub use html::to_html;
 pub use json::{to_json, to_json_default, to_json_with_options, JsonFormat};
+pub use latex::to_latex;
 pub use markdown::{render_section_to_string, to_markdown};
 pub use options::{
-    CleanupOptions, CleanupPreset, RenderOptions, RevisionHandling, SectionMarkerStyle,
-    TableFallback,
+    CleanupOptions, CleanupPreset, CommentStyle, CsvOptions, CsvQuoting, EpubOptions,
+    FieldHandling, HtmlOptions, JsonResources, LatexOptions, MarkdownDialect, MergedCells,
+    RenderOptions, RevisionHandling, SectionMarkerStyle, TableFallback,
 };
 pub use style_mapping::StyleMapping;
 pub use text::to_text;
@@ -19,7 +19,10 @@ pub enum TableFallback {
     /// Keeps `colspan`/`rowspan` rather than flattening them, at the cost of emitting
     /// HTML into the Markdown. Tables without merges still render as pipe tables.
     Html,
-    /// Use ASCII art tables
+    /// Render every table as a box-drawn text table in a fenced code block.
+    ///
+    /// Merged cells are drawn as one box across the columns and rows they cover, cells
+    /// keep their line breaks, and widths follow terminal columns, so CJK text aligns.
     Ascii,
 }
 
@@ -188,6 +191,22 @@ pub struct RenderOptions {
     /// Style for PPTX slide / XLSX sheet boundary markers.
     /// DOCX is unaffected regardless of this setting.
     pub section_markers: SectionMarkerStyle,
+
+    /// Include footnotes, endnotes and PPTX speaker notes. Enabled by default.
+    pub include_notes: bool,
+
+    /// How `Document.resources` appear in JSON output.
+    pub json_resources: JsonResources,
+
+    /// Markdown flavor to write: decides the syntax of tables, footnotes and
+    /// strikethrough, and which characters are escaped.
+    pub dialect: MarkdownDialect,
+
+    /// How review comments appear in Markdown output. Left out by default.
+    pub comments: CommentStyle,
+
+    /// How field results appear. Shown as stored by default.
+    pub field_handling: FieldHandling,
 }
 
 /// How to handle tracked changes in the output.
@@ -202,6 +221,86 @@ pub enum RevisionHandling {
     ShowMarkup,
 }
 
+/// Markdown flavor written by [`to_markdown`](super::to_markdown).
+///
+/// Each dialect only receives constructs it can parse; what it has no syntax for falls
+/// back to raw HTML, which all of them accept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum MarkdownDialect {
+    /// GitHub Flavored Markdown (default): pipe tables, `[^id]` footnotes and
+    /// `~~strikethrough~~`.
+    #[default]
+    Gfm,
+    /// Strict CommonMark, which has none of GFM's extensions: tables are written as
+    /// HTML, footnote markers as `<sup>` numbers, strikethrough as `<del>`, and YAML
+    /// frontmatter is left out.
+    CommonMark,
+    /// Pandoc Markdown: grid tables, which keep a cell's paragraphs apart and draw
+    /// merged cells as spans, and native superscript, subscript and underline.
+    Pandoc,
+    /// MultiMarkdown 6: pipe tables with `||` column spans and `^^` row spans, and
+    /// CriticMarkup for tracked changes.
+    MultiMarkdown,
+}
+
+impl MarkdownDialect {
+    /// Whether the dialect has `[^id]` footnotes.
+    pub fn has_footnotes(self) -> bool {
+        self != Self::CommonMark
+    }
+
+    /// Whether the dialect has `~~strikethrough~~`.
+    pub fn has_strikethrough(self) -> bool {
+        matches!(self, Self::Gfm | Self::Pandoc)
+    }
+}
+
+/// How [`to_json_with_options`](super::to_json_with_options) writes resources.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum JsonResources {
+    /// Type, file name, MIME type and size of each resource, without its bytes (default).
+    #[default]
+    Metadata,
+    /// The metadata plus the bytes, base64-encoded in a `data` field.
+    Base64,
+    /// No `resources` at all.
+    Omit,
+}
+
+/// How [`to_markdown`](super::to_markdown) writes review comments.
+///
+/// A comment goes where its anchored range ends, with its replies after it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CommentStyle {
+    /// No comments (default).
+    #[default]
+    Omit,
+    /// A `[^cN]` footnote marker, defined at the end of the document with the author,
+    /// date and text of the comment and its replies.
+    Footnotes,
+    /// An `<!-- ... -->` HTML comment for the comment and for each reply, which keeps
+    /// them in the source but out of the rendered page.
+    Html,
+}
+
+/// How fields — cross-references, page numbers, dates and the like — are rendered.
+///
+/// A field shows the result Word last computed for it, which is in the document as
+/// text. Hyperlink fields to a URL are links whatever the choice.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum FieldHandling {
+    /// Show every field's result as stored (default).
+    #[default]
+    ShowResults,
+    /// Leave out the results of fields that are only a page number (`PAGE`,
+    /// `NUMPAGES`, `SECTIONPAGES`, `PAGEREF`), which mean nothing without pages.
+    DropPageNumbers,
+    /// Link the results of `REF` and `PAGEREF` fields, and of `HYPERLINK` fields to a
+    /// place in the document, to their bookmark as `#bookmark`. The Markdown, HTML and
+    /// AsciiDoc renderers write an anchor where each bookmark starts.
+    LinkReferences,
+}
+
 /// Style for section boundary markers in Markdown output.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum SectionMarkerStyle {
@@ -237,6 +336,11 @@ impl Default for RenderOptions {
             include_headers_footers: false,
             callout_blockquote: false,
             section_markers: SectionMarkerStyle::None,
+            include_notes: true,
+            json_resources: JsonResources::Metadata,
+            dialect: MarkdownDialect::Gfm,
+            comments: CommentStyle::Omit,
+            field_handling: FieldHandling::ShowResults,
         }
     }
 }
@@ -373,6 +477,310 @@ impl RenderOptions {
         self.strip_redundant_emphasis_in_headings = enabled;
         self
     }
+
+    /// Toggle whether footnotes, endnotes and speaker notes appear in the output.
+    pub fn with_include_notes(mut self, enabled: bool) -> Self {
+        self.include_notes = enabled;
+        self
+    }
+
+    /// Set how resources are written in JSON output.
+    pub fn with_json_resources(mut self, resources: JsonResources) -> Self {
+        self.json_resources = resources;
+        self
+    }
+
+    /// Set the Markdown dialect to write.
+    pub fn with_dialect(mut self, dialect: MarkdownDialect) -> Self {
+        self.dialect = dialect;
+        self
+    }
+
+    /// Set how review comments appear in Markdown output.
+    pub fn with_comments(mut self, style: CommentStyle) -> Self {
+        self.comments = style;
+        self
+    }
+
+    /// Set how field results appear.
+    pub fn with_field_handling(mut self, handling: FieldHandling) -> Self {
+        self.field_handling = handling;
+        self
+    }
+}
+
+/// Options for rendering documents as HTML.
+#[derive(Debug, Clone)]
+pub struct HtmlOptions {
+    /// Emit a complete HTML document (`<!DOCTYPE html>`, `<head>` with the title and
+    /// metadata). When false, only the body content is emitted, for embedding.
+    pub standalone: bool,
+
+    /// Prefix for image `src` paths (e.g., "assets/")
+    pub image_path_prefix: String,
+
+    /// How to handle tracked changes (insertions and deletions).
+    pub revision_handling: RevisionHandling,
+
+    /// Render DOCX section headers/footers as `<header>`/`<footer>` elements.
+    /// Disabled by default, as for Markdown.
+    pub include_headers_footers: bool,
+
+    /// How field results appear. Shown as stored by default.
+    pub field_handling: FieldHandling,
+}
+
+impl Default for HtmlOptions {
+    fn default() -> Self {
+        Self {
+            standalone: true,
+            image_path_prefix: String::new(),
+            revision_handling: RevisionHandling::AcceptAll,
+            include_headers_footers: false,
+            field_handling: FieldHandling::ShowResults,
+        }
+    }
+}
+
+impl HtmlOptions {
+    /// Create new HTML options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Toggle whether a complete HTML document or a body fragment is emitted.
+    pub fn with_standalone(mut self, standalone: bool) -> Self {
+        self.standalone = standalone;
+        self
+    }
+
+    /// Set the image path prefix for `src` attributes.
+    pub fn with_image_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.image_path_prefix = prefix.into();
+        self
+    }
+
+    /// Set how to handle tracked changes (revisions).
+    pub fn with_revision_handling(mut self, handling: RevisionHandling) -> Self {
+        self.revision_handling = handling;
+        self
+    }
+
+    /// Toggle whether DOCX section headers/footers appear in the output.
+    pub fn with_include_headers_footers(mut self, enabled: bool) -> Self {
+        self.include_headers_footers = enabled;
+        self
+    }
+
+    /// Set how field results appear.
+    pub fn with_field_handling(mut self, handling: FieldHandling) -> Self {
+        self.field_handling = handling;
+        self
+    }
+}
+
+/// When to put a CSV field in quotes.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CsvQuoting {
+    /// Only fields holding the delimiter, a quote or a line break (default)
+    #[default]
+    Necessary,
+    /// Every field
+    Always,
+    /// Every field that is not a number
+    NonNumeric,
+    /// No field; the text is written as it is, delimiters and all
+    Never,
+}
+
+/// What the cells a merge covers hold in CSV output.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MergedCells {
+    /// The merge's first cell holds the value, the rest stay empty (default)
+    #[default]
+    Blank,
+    /// Every cell the merge covers repeats its value
+    Repeat,
+}
+
+/// Options for rendering tables as delimited text.
+#[derive(Debug, Clone)]
+pub struct CsvOptions {
+    /// Field delimiter: `,` by default, `\t` for TSV.
+    pub delimiter: char,
+
+    /// When fields are quoted.
+    pub quoting: CsvQuoting,
+
+    /// What the cells covered by a merge hold.
+    pub merged_cells: MergedCells,
+
+    /// Write the value a cell stores instead of the text it displays — the serial
+    /// number rather than the formatted date, for example.
+    pub raw_values: bool,
+}
+
+impl Default for CsvOptions {
+    fn default() -> Self {
+        Self {
+            delimiter: ',',
+            quoting: CsvQuoting::Necessary,
+            merged_cells: MergedCells::Blank,
+            raw_values: false,
+        }
+    }
+}
+
+impl CsvOptions {
+    /// Create new CSV options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the field delimiter.
+    pub fn with_delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Set when fields are quoted.
+    pub fn with_quoting(mut self, quoting: CsvQuoting) -> Self {
+        self.quoting = quoting;
+        self
+    }
+
+    /// Set what the cells covered by a merge hold.
+    pub fn with_merged_cells(mut self, merged_cells: MergedCells) -> Self {
+        self.merged_cells = merged_cells;
+        self
+    }
+
+    /// Toggle writing stored values instead of displayed text.
+    pub fn with_raw_values(mut self, raw: bool) -> Self {
+        self.raw_values = raw;
+        self
+    }
+}
+
+/// Options for packaging a document as an EPUB 3 book.
+#[derive(Debug, Clone)]
+pub struct EpubOptions {
+    /// Deepest heading level that starts a new chapter (1 by default). Every section
+    /// starts one as well; 0 splits at sections only.
+    pub chapter_level: u8,
+
+    /// Language of the content, as a BCP 47 tag (default "en").
+    pub language: String,
+
+    /// Unique identifier of the book, such as `urn:isbn:...` or `urn:uuid:...`.
+    /// When unset, one is derived from the content, so the same document always
+    /// gets the same identifier.
+    pub identifier: Option<String>,
+
+    /// How to handle tracked changes (insertions and deletions).
+    pub revision_handling: RevisionHandling,
+}
+
+impl Default for EpubOptions {
+    fn default() -> Self {
+        Self {
+            chapter_level: 1,
+            language: "en".to_string(),
+            identifier: None,
+            revision_handling: RevisionHandling::AcceptAll,
+        }
+    }
+}
+
+impl EpubOptions {
+    /// Create new EPUB options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the deepest heading level that starts a new chapter.
+    pub fn with_chapter_level(mut self, level: u8) -> Self {
+        self.chapter_level = level.min(6);
+        self
+    }
+
+    /// Set the language of the content.
+    pub fn with_language(mut self, language: impl Into<String>) -> Self {
+        self.language = language.into();
+        self
+    }
+
+    /// Set the unique identifier of the book.
+    pub fn with_identifier(mut self, identifier: impl Into<String>) -> Self {
+        self.identifier = Some(identifier.into());
+        self
+    }
+
+    /// Set how to handle tracked changes (revisions).
+    pub fn with_revision_handling(mut self, handling: RevisionHandling) -> Self {
+        self.revision_handling = handling;
+        self
+    }
+}
+
+/// Options for rendering LaTeX.
+#[derive(Debug, Clone)]
+pub struct LatexOptions {
+    /// Emit a complete document: a preamble with the packages the output uses and
+    /// the title, author and date from the metadata, then `\begin{document}`. When
+    /// false, only the body is emitted, for `\input` into another document.
+    pub standalone: bool,
+
+    /// Class of a standalone document (default "article").
+    pub document_class: String,
+
+    /// Prefix for `\includegraphics` paths (e.g., "media/")
+    pub image_path_prefix: String,
+
+    /// How to handle tracked changes (insertions and deletions).
+    pub revision_handling: RevisionHandling,
+}
+
+impl Default for LatexOptions {
+    fn default() -> Self {
+        Self {
+            standalone: true,
+            document_class: "article".to_string(),
+            image_path_prefix: String::new(),
+            revision_handling: RevisionHandling::AcceptAll,
+        }
+    }
+}
+
+impl LatexOptions {
+    /// Create new LaTeX options.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Toggle whether a complete document or only its body is emitted.
+    pub fn with_standalone(mut self, standalone: bool) -> Self {
+        self.standalone = standalone;
+        self
+    }
+
+    /// Set the class of a standalone document.
+    pub fn with_document_class(mut self, class: impl Into<String>) -> Self {
+        self.document_class = class.into();
+        self
+    }
+
+    /// Set the image path prefix for `\includegraphics`.
+    pub fn with_image_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.image_path_prefix = prefix.into();
+        self
+    }
+
+    /// Set how to handle tracked changes (revisions).
+    pub fn with_revision_handling(mut self, handling: RevisionHandling) -> Self {
+        self.revision_handling = handling;
+        self
+    }
 }
 
 #[cfg(test)]
@@ -4,10 +4,26 @@ use crate::error::Result;
 use crate::model::{Block, Document, Paragraph, Table};
 use unicode_width::UnicodeWidthStr;
 
-use super::options::RenderOptions;
+use super::common::is_no_space_before;
+use super::options::{FieldHandling, RenderOptions};
 
 /// Convert a Document to plain text.
 pub fn to_text(doc: &Document, options: &RenderOptions) -> Result<String> {
+    let stripped;
+    let doc = if options.include_notes {
+        doc
+    } else {
+        stripped = super::filter::without_notes(doc);
+        &stripped
+    };
+    let fielded;
+    let doc = if options.field_handling == FieldHandling::ShowResults {
+        doc
+    } else {
+        fielded = super::filter::with_fields(doc, options.field_handling);
+        &fielded
+    };
+
     let mut output = String::new();
 
     // Render each section
@@ -125,14 +141,6 @@ fn render_paragraph_text(para: &Paragraph) -> String {
     output
 }
 
-/// Check if a character should NOT have a space before it.
-fn is_no_space_before(c: char) -> bool {
-    matches!(
-        c,
-        '.' | ',' | ':' | ';' | '!' | '?' | ')' | ']' | '}' | '"' | '\'' | '…'
-    )
-}
-
 /// Flatten cell text onto one line: any line break (LF, CRLF, or a bare CR
 /// that would split the table row) becomes a single space.
 fn cell_single_line_text(cell: &crate::model::Cell) -> String {
@@ -0,0 +1,265 @@
+//! BIFF8 record framing, strings and cell values.
+//!
+//! The `Workbook` stream is a flat sequence of records, each a 2-byte type, a 2-byte
+//! length and that many bytes of payload. A record longer than 8224 bytes is split
+//! across `CONTINUE` records, and a string split that way starts each continuation with
+//! a fresh option byte — the only place where the framing leaks into the payload.
+
+use crate::ole::{u16_at, u32_at};
+
+/// Record types (MS-XLS 2.3).
+pub mod rt {
+    pub const FORMULA: u16 = 0x0006;
+    pub const EOF: u16 = 0x000A;
+    pub const FILEPASS: u16 = 0x002F;
+    pub const DATEMODE: u16 = 0x0022;
+    pub const CONTINUE: u16 = 0x003C;
+    pub const BOUNDSHEET: u16 = 0x0085;
+    pub const MULRK: u16 = 0x00BD;
+    pub const XF: u16 = 0x00E0;
+    pub const MERGEDCELLS: u16 = 0x00E5;
+    pub const SST: u16 = 0x00FC;
+    pub const LABELSST: u16 = 0x00FD;
+    pub const NUMBER: u16 = 0x0203;
+    pub const LABEL: u16 = 0x0204;
+    pub const BOOLERR: u16 = 0x0205;
+    pub const STRING: u16 = 0x0207;
+    pub const RK: u16 = 0x027E;
+    pub const FORMAT: u16 = 0x041E;
+    pub const BOF: u16 = 0x0809;
+}
+
+/// `BOF.vers` of a BIFF8 substream.
+pub const BIFF8_VERSION: u16 = 0x0600;
+
+/// One record of the stream.
+#[derive(Debug, Clone, Copy)]
+pub struct Record<'a> {
+    pub kind: u16,
+    pub data: &'a [u8],
+}
+
+/// Iterator over the records of a stream, starting at a byte offset.
+///
+/// A record whose declared length runs past the end of the stream is cut short rather
+/// than dropped, and ends the iteration: what a truncated file still holds is kept.
+pub struct Records<'a> {
+    stream: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Records<'a> {
+    pub fn at(stream: &'a [u8], pos: usize) -> Self {
+        Self { stream, pos }
+    }
+
+    /// Consume the `CONTINUE` records that follow the current one.
+    pub fn continuations(&mut self) -> Vec<&'a [u8]> {
+        let mut parts = Vec::new();
+        while u16_at(self.stream, self.pos) == Some(rt::CONTINUE) {
+            match self.next() {
+                Some(record) => parts.push(record.data),
+                None => break,
+            }
+        }
+        parts
+    }
+}
+
+impl<'a> Iterator for Records<'a> {
+    type Item = Record<'a>;
+
+    fn next(&mut self) -> Option<Record<'a>> {
+        let kind = u16_at(self.stream, self.pos)?;
+        let len = u16_at(self.stream, self.pos + 2)? as usize;
+        let start = self.pos + 4;
+        let end = (start + len).min(self.stream.len());
+        self.pos = if end < start + len {
+            self.stream.len()
+        } else {
+            end
+        };
+        Some(Record {
+            kind,
+            data: self.stream.get(start..end)?,
+        })
+    }
+}
+
+/// A cursor over a record payload and its `CONTINUE` parts.
+pub struct Payload<'a> {
+    parts: Vec<&'a [u8]>,
+    part: usize,
+    pos: usize,
+}
+
+impl<'a> Payload<'a> {
+    pub fn new(first: &'a [u8], continuations: Vec<&'a [u8]>) -> Self {
+        let mut parts = vec![first];
+        parts.extend(continuations);
+        Self {
+            parts,
+            part: 0,
+            pos: 0,
+        }
+    }
+
+    pub fn single(data: &'a [u8]) -> Self {
+        Self::new(data, Vec::new())
+    }
+
+    /// Step into the next part when the current one is used up.
+    fn available(&mut self) -> Option<&'a [u8]> {
+        while self.pos >= self.parts.get(self.part)?.len() {
+            self.part += 1;
+            self.pos = 0;
+        }
+        Some(&self.parts[self.part][self.pos..])
+    }
+
+    pub fn u8(&mut self) -> Option<u8> {
+        let byte = *self.available()?.first()?;
+        self.pos += 1;
+        Some(byte)
+    }
+
+    pub fn u16(&mut self) -> Option<u16> {
+        Some(u16::from_le_bytes([self.u8()?, self.u8()?]))
+    }
+
+    pub fn u32(&mut self) -> Option<u32> {
+        Some(u32::from_le_bytes([
+            self.u8()?,
+            self.u8()?,
+            self.u8()?,
+            self.u8()?,
+        ]))
+    }
+
+    pub fn skip(&mut self, mut count: usize) -> Option<()> {
+        while count > 0 {
+            let step = self.available()?.len().min(count);
+            self.pos += step;
+            count -= step;
+        }
+        Some(())
+    }
+
+    /// Read `count` characters, 8- or 16-bit as the option byte says.
+    ///
+    /// Characters that continue into the next part are preceded there by a new option
+    /// byte, which may switch the width mid-string.
+    fn chars(&mut self, mut count: usize, mut high_byte: bool) -> Option<String> {
+        let mut units: Vec<u16> = Vec::with_capacity(count);
+        while count > 0 {
+            if self.pos >= self.parts.get(self.part)?.len() {
+                self.part += 1;
+                self.pos = 0;
+                high_byte = self.u8()? & 0x01 != 0;
+                continue;
+            }
+            let rest = &self.parts[self.part][self.pos..];
+            let width = if high_byte { 2 } else { 1 };
+            let take = (rest.len() / width).min(count);
+            if take == 0 {
+                return None;
+            }
+            if high_byte {
+                units.extend(
+                    rest[..take * 2]
+                        .chunks_exact(2)
+                        .map(|pair| u16::from_le_bytes([pair[0], pair[1]])),
+                );
+            } else {
+                // 8-bit "compressed" strings hold the low byte of each UTF-16 unit.
+                units.extend(rest[..take].iter().map(|&b| b as u16));
+            }
+            self.pos += take * width;
+            count -= take;
+        }
+        Some(String::from_utf16_lossy(&units))
+    }
+
+    /// Read an `XLUnicodeRichExtendedString`, skipping its formatting runs and phonetic
+    /// data. Also reads a plain `XLUnicodeString`, whose option byte never sets those
+    /// flags.
+    pub fn unicode_string(&mut self) -> Option<String> {
+        let count = self.u16()? as usize;
+        self.string_body(count)
+    }
+
+    /// Read a `ShortXLUnicodeString` (8-bit character count).
+    pub fn short_unicode_string(&mut self) -> Option<String> {
+        let count = self.u8()? as usize;
+        self.string_body(count)
+    }
+
+    fn string_body(&mut self, count: usize) -> Option<String> {
+        let flags = self.u8()?;
+        let runs = if flags & 0x08 != 0 { self.u16()? } else { 0 };
+        let ext = if flags & 0x04 != 0 { self.u32()? } else { 0 };
+        let text = self.chars(count, flags & 0x01 != 0)?;
+        self.skip(runs as usize * 4)?;
+        self.skip(ext as usize)?;
+        Some(text)
+    }
+}
+
+/// Read the `(row, column, xf)` triple every cell record starts with.
+pub fn cell_header(data: &[u8]) -> Option<(u16, u16, u16)> {
+    Some((u16_at(data, 0)?, u16_at(data, 2)?, u16_at(data, 4)?))
+}
+
+/// Read an `RkRec` list of a `MULRK` record: `(column, xf, rk)` per cell.
+pub fn mulrk_cells(data: &[u8]) -> Vec<(u16, u16, u32)> {
+    let Some(first) = u16_at(data, 2) else {
+        return Vec::new();
+    };
+    // Row, first column, then 6-byte RkRecs, then the last column.
+    let count = data.len().saturating_sub(6) / 6;
+    (0..count)
+        .filter_map(|i| {
+            let at = 4 + i * 6;
+            Some((
+                first.checked_add(i as u16)?,
+                u16_at(data, at)?,
+                u32_at(data, at + 2)?,
+            ))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_records_stop_at_a_truncated_tail() {
+        let mut stream = vec![0x0A, 0x00, 0x02, 0x00, 1, 2];
+        stream.extend_from_slice(&[0x06, 0x00, 0x10, 0x00, 9]);
+        let records: Vec<_> = Records::at(&stream, 0).collect();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].data, &[1, 2]);
+        assert_eq!(records[1].data, &[9]);
+    }
+
+    #[test]
+    fn test_string_switches_width_across_continue() {
+        // "ab" compressed in the first part, then "€" as UTF-16 after a new option byte.
+        let first = [3u8, 0, 0x00, b'a', b'b'];
+        let second = [0x01u8, 0xAC, 0x20];
+        let mut payload = Payload::new(&first, vec![&second]);
+        assert_eq!(payload.unicode_string().as_deref(), Some("ab\u{20AC}"));
+    }
+
+    #[test]
+    fn test_rich_string_skips_runs_and_phonetics() {
+        let mut data = vec![2u8, 0, 0x0C, 1, 0, 2, 0, 0, 0, b'h', b'i'];
+        data.extend_from_slice(&[0; 4]);
+        data.extend_from_slice(&[0; 2]);
+        data.extend_from_slice(&[3, 0, 0, b'x', b'y', b'z']);
+        let mut payload = Payload::single(&data);
+        assert_eq!(payload.unicode_string().as_deref(), Some("hi"));
+        assert_eq!(payload.unicode_string().as_deref(), Some("xyz"));
+    }
+}
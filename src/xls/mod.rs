@@ -0,0 +1,25 @@
+//! XLS (Excel 97-2003) workbook parser.
+//!
+//! This module provides parsing for legacy Microsoft Excel workbooks in the
+//! binary (.xls) format: an OLE2 compound file whose `Workbook` stream holds
+//! BIFF8 records — the globals (sheets, shared strings, number formats)
+//! followed by one substream per sheet.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use undoc::xls::XlsParser;
+//!
+//! let mut parser = XlsParser::open("spreadsheet.xls")?;
+//! let doc = parser.parse()?;
+//!
+//! for section in &doc.sections {
+//!     println!("Sheet: {}", section.name.as_deref().unwrap_or("Unnamed"));
+//! }
+//! # Ok::<(), undoc::Error>(())
+//! ```
+
+mod biff;
+mod parser;
+
+pub use parser::XlsParser;
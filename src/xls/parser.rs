@@ -0,0 +1,676 @@
+//! XLS parser implementation.
+
+use std::collections::{BTreeMap, HashMap};
+
+use crate::error::{Error, Result};
+use crate::model::{Block, Document, Metadata, Section, Table};
+use crate::ole::{self, u16_at, u32_at, CompoundFile};
+use crate::xlsx::binary::{self, CellValue};
+use crate::xlsx::Styles;
+
+use super::biff::{self, rt, Payload, Records};
+
+/// `BoundSheet8.dt` of a worksheet or macro sheet; charts and VBA modules have no cells.
+const SHEET_TYPES_WITH_CELLS: [u8; 2] = [0x00, 0x01];
+
+/// Sheet info from a `BOUNDSHEET` record.
+#[derive(Debug, Clone)]
+struct SheetInfo {
+    name: String,
+    /// Stream offset of the sheet's `BOF` record.
+    offset: usize,
+}
+
+/// Parser for legacy Excel 97-2003 (.xls) workbooks.
+pub struct XlsParser {
+    container: CompoundFile,
+    workbook: Vec<u8>,
+    sheets: Vec<SheetInfo>,
+    shared_strings: Vec<String>,
+    styles: Styles,
+    date_1904: bool,
+}
+
+impl XlsParser {
+    /// Open an XLS file for parsing.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn open(path: impl AsRef<std::path::Path>) -> Result<Self> {
+        let data = std::fs::read(path)?;
+        Self::from_bytes(data)
+    }
+
+    /// Create a parser from bytes.
+    pub fn from_bytes(data: Vec<u8>) -> Result<Self> {
+        let mut container = ole::open_compound(data)?;
+
+        // Excel 5.0/95 wrote `Book`; BIFF8 writes `Workbook`. The BOF below tells them
+        // apart for certain, so either name is accepted here.
+        let workbook = match ole::read_stream_optional(&mut container, "/Workbook")? {
+            Some(stream) => stream,
+            None => ole::read_stream(&mut container, "/Book")?,
+        };
+
+        let mut parser = Self {
+            container,
+            workbook,
+            sheets: Vec::new(),
+            shared_strings: Vec::new(),
+            styles: Styles::default(),
+            date_1904: false,
+        };
+        parser.read_globals()?;
+        Ok(parser)
+    }
+
+    /// Read the workbook globals substream: sheets, shared strings and number formats.
+    fn read_globals(&mut self) -> Result<()> {
+        let workbook = std::mem::take(&mut self.workbook);
+        let mut records = Records::at(&workbook, 0);
+
+        let bof = records
+            .next()
+            .filter(|r| r.kind == rt::BOF)
+            .ok_or_else(|| Error::InvalidData("Workbook stream does not start with BOF".into()))?;
+        if u16_at(bof.data, 0) != Some(biff::BIFF8_VERSION) {
+            return Err(Error::UnsupportedFormat(
+                "Excel 5.0/95 workbook (only Excel 97-2003 is supported)".to_string(),
+            ));
+        }
+
+        let mut num_fmts = HashMap::new();
+        let mut cell_xfs = Vec::new();
+
+        while let Some(record) = records.next() {
+            match record.kind {
+                // RC4 or XOR obfuscation: every record after this one is ciphertext.
+                rt::FILEPASS => return Err(Error::Encrypted),
+                rt::DATEMODE => self.date_1904 = u16_at(record.data, 0) == Some(1),
+                rt::BOUNDSHEET => {
+                    let kind = record.data.get(5).copied();
+                    let name = Payload::single(record.data.get(6..).unwrap_or_default())
+                        .short_unicode_string();
+                    if let (Some(offset), Some(name)) = (u32_at(record.data, 0), name) {
+                        if kind.is_some_and(|k| SHEET_TYPES_WITH_CELLS.contains(&k)) {
+                            self.sheets.push(SheetInfo {
+                                name,
+                                offset: offset as usize,
+                            });
+                        }
+                    }
+                }
+                rt::SST => {
+                    let continuations = records.continuations();
+                    self.shared_strings = read_shared_strings(record.data, continuations);
+                }
+                rt::FORMAT => {
+                    let code =
+                        Payload::single(record.data.get(2..).unwrap_or_default()).unicode_string();
+                    if let (Some(id), Some(code)) = (u16_at(record.data, 0), code) {
+                        num_fmts.insert(id as u32, code);
+                    }
+                }
+                // Cell XFs are indexed together with style XFs, so every record counts.
+                rt::XF => cell_xfs.push(u16_at(record.data, 2).unwrap_or(0) as u32),
+                rt::EOF => break,
+                _ => {}
+            }
+        }
+
+        self.styles = Styles::from_formats(num_fmts, cell_xfs);
+        self.workbook = workbook;
+        Ok(())
+    }
+
+    /// Parse the workbook and return a Document model.
+    pub fn parse(&mut self) -> Result<Document> {
+        let mut doc = Document::new();
+        doc.format = crate::detect::FormatType::Xls;
+        doc.metadata = self.parse_metadata();
+
+        for (idx, sheet) in self.sheets.iter().enumerate() {
+            doc.add_section(self.parse_sheet_as_section(idx, sheet));
+        }
+
+        Ok(doc)
+    }
+
+    /// Get the number of sheets in the workbook.
+    pub fn sheet_count(&self) -> usize {
+        self.sheets.len()
+    }
+
+    /// Get sheet names.
+    pub fn sheet_names(&self) -> Vec<&str> {
+        self.sheets.iter().map(|s| s.name.as_str()).collect()
+    }
+
+    fn parse_metadata(&mut self) -> Metadata {
+        let mut meta = ole::parse_summary_information(&mut self.container);
+        meta.page_count = Some(self.sheets.len() as u32);
+        meta
+    }
+
+    /// Parse a single sheet into a Section.
+    fn parse_sheet_as_section(&self, idx: usize, sheet: &SheetInfo) -> Section {
+        let mut section = Section::with_name(idx, &sheet.name);
+        let table = self.parse_sheet(sheet.offset);
+        if !table.is_empty() {
+            section.add_block(Block::Table(table));
+        }
+        section
+    }
+
+    /// Read a worksheet substream into a table.
+    ///
+    /// Rows are those holding a value, each running from column A to its last value,
+    /// so formatting-only cells never widen or lengthen the table; see
+    /// [`binary::build_table`].
+    fn parse_sheet(&self, offset: usize) -> Table {
+        let mut cells: BTreeMap<(u16, u16), (CellValue, u16)> = BTreeMap::new();
+        let mut merges: Vec<(u16, u16, u16, u16)> = Vec::new();
+
+        let mut records = Records::at(&self.workbook, offset);
+        if records.next().is_none_or(|r| r.kind != rt::BOF) {
+            return Table::new();
+        }
+        // A FORMULA whose cached result is a string is followed by a STRING record.
+        let mut pending_string: Option<(u16, u16, u16)> = None;
+
+        while let Some(record) = records.next() {
+            let data = record.data;
+            match record.kind {
+                rt::LABELSST => {
+                    if let (Some((row, col, xf)), Some(idx)) =
+                        (biff::cell_header(data), u32_at(data, 6))
+                    {
+                        let text = self
+                            .shared_strings
+                            .get(idx as usize)
+                            .cloned()
+                            .unwrap_or_default();
+                        cells.insert((row, col), (CellValue::Text(text), xf));
+                    }
+                }
+                rt::LABEL => {
+                    let text = Payload::single(data.get(6..).unwrap_or_default()).unicode_string();
+                    if let (Some((row, col, xf)), Some(text)) = (biff::cell_header(data), text) {
+                        cells.insert((row, col), (CellValue::Text(text), xf));
+                    }
+                }
+                rt::NUMBER => {
+                    let value = data
+                        .get(6..14)
+                        .map(|b| f64::from_le_bytes(b.try_into().unwrap_or_default()));
+                    if let (Some((row, col, xf)), Some(value)) = (biff::cell_header(data), value) {
+                        cells.insert((row, col), (CellValue::Number(value), xf));
+                    }
+                }
+                rt::RK => {
+                    if let (Some((row, col, xf)), Some(rk)) =
+                        (biff::cell_header(data), u32_at(data, 6))
+                    {
+                        cells.insert((row, col), (CellValue::Number(binary::rk_value(rk)), xf));
+                    }
+                }
+                rt::MULRK => {
+                    if let Some(row) = u16_at(data, 0) {
+                        for (col, xf, rk) in biff::mulrk_cells(data) {
+                            cells.insert((row, col), (CellValue::Number(binary::rk_value(rk)), xf));
+                        }
+                    }
+                }
+                rt::BOOLERR => {
+                    if let (Some((row, col, xf)), Some(&value), Some(&is_error)) =
+                        (biff::cell_header(data), data.get(6), data.get(7))
+                    {
+                        let value = if is_error != 0 {
+                            CellValue::Error(value)
+                        } else {
+                            CellValue::Bool(value != 0)
+                        };
+                        cells.insert((row, col), (value, xf));
+                    }
+                }
+                rt::FORMULA => {
+                    let Some((row, col, xf)) = biff::cell_header(data) else {
+                        continue;
+                    };
+                    let Some(result) = data.get(6..14) else {
+                        continue;
+                    };
+                    // FormulaValue: a double, unless the top two bytes are 0xFFFF, in
+                    // which case the first byte says what the cached result is.
+                    if result[6..8] != [0xFF, 0xFF] {
+                        let value = f64::from_le_bytes(result.try_into().unwrap_or_default());
+                        cells.insert((row, col), (CellValue::Number(value), xf));
+                        continue;
+                    }
+                    match result[0] {
+                        0x00 => pending_string = Some((row, col, xf)),
+                        0x01 => {
+                            cells.insert((row, col), (CellValue::Bool(result[2] != 0), xf));
+                        }
+                        0x02 => {
+                            cells.insert((row, col), (CellValue::Error(result[2]), xf));
+                        }
+                        _ => {}
+                    }
+                }
+                rt::STRING => {
+                    let continuations = records.continuations();
+                    if let Some((row, col, xf)) = pending_string.take() {
+                        if let Some(text) = Payload::new(data, continuations).unicode_string() {
+                            cells.insert((row, col), (CellValue::Text(text), xf));
+                        }
+                    }
+                }
+                rt::MERGEDCELLS => {
+                    let count = u16_at(data, 0).unwrap_or(0) as usize;
+                    for i in 0..count {
+                        let at = 2 + i * 8;
+                        if let (Some(r1), Some(r2), Some(c1), Some(c2)) = (
+                            u16_at(data, at),
+                            u16_at(data, at + 2),
+                            u16_at(data, at + 4),
+                            u16_at(data, at + 6),
+                        ) {
+                            if r2 >= r1 && c2 >= c1 {
+                                merges.push((r1, r2, c1, c2));
+                            }
+                        }
+                    }
+                }
+                rt::EOF => break,
+                _ => {}
+            }
+        }
+
+        let cells = cells
+            .into_iter()
+            .map(|((row, col), (value, xf))| ((row as u32, col as u32), (value, xf as u32)))
+            .collect();
+        let merges: Vec<(u32, u32, u32, u32)> = merges
+            .iter()
+            .map(|&(r1, r2, c1, c2)| (r1 as u32, r2 as u32, c1 as u32, c2 as u32))
+            .collect();
+        binary::build_table(&self.styles, self.date_1904, cells, &merges)
+    }
+}
+
+/// Read the shared string table from an `SST` record and its continuations.
+///
+/// Stops at the first string that cannot be read; the indices before it stay valid.
+fn read_shared_strings<'a>(data: &'a [u8], continuations: Vec<&'a [u8]>) -> Vec<String> {
+    let unique = u32_at(data, 4).unwrap_or(0) as usize;
+    let mut payload = Payload::new(data, continuations);
+    let mut strings = Vec::with_capacity(unique.min(65_536));
+    if payload.skip(8).is_none() {
+        return strings;
+    }
+    for _ in 0..unique {
+        match payload.unicode_string() {
+            Some(s) => strings.push(s),
+            None => break,
+        }
+    }
+    strings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::render::{to_markdown, RenderOptions};
+
+    fn record(kind: u16, data: &[u8]) -> Vec<u8> {
+        let mut out = kind.to_le_bytes().to_vec();
+        out.extend_from_slice(&(data.len() as u16).to_le_bytes());
+        out.extend_from_slice(data);
+        out
+    }
+
+    /// `XLUnicodeString` in its compressed form.
+    fn xl_string(text: &str) -> Vec<u8> {
+        let mut out = (text.len() as u16).to_le_bytes().to_vec();
+        out.push(0);
+        out.extend_from_slice(text.as_bytes());
+        out
+    }
+
+    fn cell(row: u16, col: u16, xf: u16) -> Vec<u8> {
+        [row.to_le_bytes(), col.to_le_bytes(), xf.to_le_bytes()].concat()
+    }
+
+    /// A BIFF8 workbook assembled record by record.
+    #[derive(Default)]
+    struct XlsFixture {
+        strings: Vec<&'static str>,
+        /// Number format id of each XF, in order.
+        xfs: Vec<u16>,
+        formats: Vec<(u16, &'static str)>,
+        sheets: Vec<(&'static str, Vec<Vec<u8>>)>,
+        encrypted: bool,
+        date_1904: bool,
+    }
+
+    impl XlsFixture {
+        fn build(&self) -> Vec<u8> {
+            let bof = |dt: u16| {
+                record(
+                    rt::BOF,
+                    &[&0x0600u16.to_le_bytes()[..], &dt.to_le_bytes(), &[0; 12]].concat(),
+                )
+            };
+
+            let mut globals = bof(0x0005);
+            if self.encrypted {
+                globals.extend(record(rt::FILEPASS, &[0; 6]));
+            }
+            if self.date_1904 {
+                globals.extend(record(rt::DATEMODE, &1u16.to_le_bytes()));
+            }
+            for (id, code) in &self.formats {
+                globals.extend(record(
+                    rt::FORMAT,
+                    &[&id.to_le_bytes()[..], &xl_string(code)].concat(),
+                ));
+            }
+            for ifmt in &self.xfs {
+                let mut xf = vec![0u8; 20];
+                xf[2..4].copy_from_slice(&ifmt.to_le_bytes());
+                globals.extend(record(rt::XF, &xf));
+            }
+            let mut sst = (self.strings.len() as u32).to_le_bytes().to_vec();
+            sst.extend_from_slice(&(self.strings.len() as u32).to_le_bytes());
+            for s in &self.strings {
+                sst.extend(xl_string(s));
+            }
+            globals.extend(record(rt::SST, &sst));
+
+            // BOUNDSHEET offsets are absolute, so size the globals first.
+            let boundsheet_len: usize =
+                self.sheets.iter().map(|(name, _)| 4 + 8 + name.len()).sum();
+            let mut offset = globals.len() + boundsheet_len + 4;
+            let mut bodies = Vec::new();
+            let mut boundsheets = Vec::new();
+            for (name, records) in &self.sheets {
+                let mut data = (offset as u32).to_le_bytes().to_vec();
+                data.extend_from_slice(&[0, 0, name.len() as u8, 0]);
+                data.extend_from_slice(name.as_bytes());
+                boundsheets.extend(record(rt::BOUNDSHEET, &data));
+
+                let mut body = bof(0x0010);
+                for r in records {
+                    body.extend_from_slice(r);
+                }
+                body.extend(record(rt::EOF, &[]));
+                offset += body.len();
+                bodies.push(body);
+            }
+            globals.extend(boundsheets);
+            globals.extend(record(rt::EOF, &[]));
+            for body in bodies {
+                globals.extend(body);
+            }
+
+            let mut container = cfb::CompoundFile::create(std::io::Cursor::new(Vec::new()))
+                .expect("create CFB container");
+            use std::io::Write;
+            container
+                .create_stream("/Workbook")
+                .unwrap()
+                .write_all(&globals)
+                .unwrap();
+            container.flush().unwrap();
+            container.into_inner().into_inner()
+        }
+    }
+
+    fn label_sst(row: u16, col: u16, idx: u32) -> Vec<u8> {
+        record(
+            rt::LABELSST,
+            &[cell(row, col, 0), idx.to_le_bytes().to_vec()].concat(),
+        )
+    }
+
+    fn number(row: u16, col: u16, xf: u16, value: f64) -> Vec<u8> {
+        record(
+            rt::NUMBER,
+            &[cell(row, col, xf), value.to_le_bytes().to_vec()].concat(),
+        )
+    }
+
+    fn parse(fixture: &XlsFixture) -> Document {
+        XlsParser::from_bytes(fixture.build())
+            .unwrap()
+            .parse()
+            .unwrap()
+    }
+
+    fn only_table(section: &Section) -> &Table {
+        match &section.content[..] {
+            [Block::Table(table)] => table,
+            other => panic!("expected one table, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_one_section_per_sheet_with_shared_strings_and_numbers() {
+        let fixture = XlsFixture {
+            strings: vec!["Name", "Qty", "Apple"],
+            xfs: vec![0],
+            sheets: vec![
+                (
+                    "Stock",
+                    vec![
+                        label_sst(0, 0, 0),
+                        label_sst(0, 1, 1),
+                        label_sst(1, 0, 2),
+                        number(1, 1, 0, 12.5),
+                    ],
+                ),
+                ("Empty", Vec::new()),
+            ],
+            ..Default::default()
+        };
+
+        let doc = parse(&fixture);
+        assert_eq!(doc.format, crate::detect::FormatType::Xls);
+        assert_eq!(doc.sections.len(), 2);
+        assert_eq!(doc.sections[0].name.as_deref(), Some("Stock"));
+        assert_eq!(doc.sections[1].name.as_deref(), Some("Empty"));
+        assert!(doc.sections[1].content.is_empty());
+        assert_eq!(doc.metadata.page_count, Some(2));
+
+        let md = to_markdown(&doc, &RenderOptions::default()).unwrap();
+        assert!(md.contains("| Name | Qty |"), "{md}");
+        assert!(md.contains("| Apple | 12.5 |"), "{md}");
+    }
+
+    #[test]
+    fn test_rk_mulrk_and_sparse_columns() {
+        let mut mulrk = cell(0, 1, 0)[..4].to_vec();
+        for v in [1u32, 2] {
+            mulrk.extend_from_slice(&0u16.to_le_bytes());
+            mulrk.extend_from_slice(&((v << 2) | 0x02).to_le_bytes());
+        }
+        mulrk.extend_from_slice(&2u16.to_le_bytes());
+        let fixture = XlsFixture {
+            xfs: vec![0],
+            sheets: vec![(
+                "S",
+                vec![
+                    record(rt::MULRK, &mulrk),
+                    record(
+                        rt::RK,
+                        &[cell(1, 3, 0), ((7u32 << 2) | 0x02).to_le_bytes().to_vec()].concat(),
+                    ),
+                ],
+            )],
+            ..Default::default()
+        };
+
+        let doc = parse(&fixture);
+        let table = only_table(&doc.sections[0]);
+        assert_eq!(table.rows.len(), 2);
+        let texts: Vec<String> = table.rows[0].cells.iter().map(|c| c.plain_text()).collect();
+        assert_eq!(texts, vec!["", "1", "2"]);
+        assert_eq!(table.rows[1].cells[3].plain_text(), "7");
+    }
+
+    #[test]
+    fn test_only_rows_with_values_are_laid_out() {
+        let fixture = XlsFixture {
+            strings: vec!["Top", "Bottom"],
+            xfs: vec![0],
+            sheets: vec![("S", vec![label_sst(3, 0, 0), label_sst(65_535, 255, 1)])],
+            ..Default::default()
+        };
+
+        let doc = parse(&fixture);
+        let table = only_table(&doc.sections[0]);
+        assert_eq!(table.rows.len(), 2);
+        assert_eq!(table.rows[0].cells.len(), 1);
+        assert_eq!(table.rows[1].cells.len(), 256);
+        assert_eq!(table.rows[1].cells[255].plain_text(), "Bottom");
+    }
+
+    #[test]
+    fn test_formula_cached_results() {
+        let formula = |row: u16, col: u16, result: [u8; 8]| {
+            let mut data = cell(row, col, 0);
+            data.extend_from_slice(&result);
+            data.extend_from_slice(&[0; 6]);
+            record(rt::FORMULA, &data)
+        };
+        let fixture = XlsFixture {
+            xfs: vec![0],
+            sheets: vec![(
+                "S",
+                vec![
+                    formula(0, 0, 6.0f64.to_le_bytes()),
+                    formula(0, 1, [0, 0, 0, 0, 0, 0, 0xFF, 0xFF]),
+                    record(rt::STRING, &xl_string("cached")),
+                    formula(0, 2, [1, 0, 1, 0, 0, 0, 0xFF, 0xFF]),
+                    formula(0, 3, [2, 0, 0x07, 0, 0, 0, 0xFF, 0xFF]),
+                ],
+            )],
+            ..Default::default()
+        };
+
+        let doc = parse(&fixture);
+        let table = only_table(&doc.sections[0]);
+        let texts: Vec<String> = table.rows[0].cells.iter().map(|c| c.plain_text()).collect();
+        assert_eq!(texts, vec!["6", "cached", "TRUE", "#ERROR:#DIV/0!"]);
+    }
+
+    #[test]
+    fn test_date_formats_from_builtin_and_custom_formats() {
+        let fixture = XlsFixture {
+            xfs: vec![0, 14, 164, 2],
+            formats: vec![(164, "yyyy\\-mm\\-dd")],
+            sheets: vec![(
+                "S",
+                vec![
+                    number(0, 0, 1, 45292.0),
+                    number(0, 1, 2, 45292.0),
+                    number(0, 2, 3, 45292.0),
+                ],
+            )],
+            ..Default::default()
+        };
+
+        let doc = parse(&fixture);
+        let table = only_table(&doc.sections[0]);
+        let texts: Vec<String> = table.rows[0].cells.iter().map(|c| c.plain_text()).collect();
+        assert_eq!(texts, vec!["2024-01-01", "2024-01-01", "45292"]);
+    }
+
+    #[test]
+    fn test_1904_date_system() {
+        let fixture = XlsFixture {
+            xfs: vec![14],
+            date_1904: true,
+            sheets: vec![("S", vec![number(0, 0, 0, 0.0)])],
+            ..Default::default()
+        };
+
+        let doc = parse(&fixture);
+        assert_eq!(
+            only_table(&doc.sections[0]).rows[0].cells[0].plain_text(),
+            "1904-01-01"
+        );
+    }
+
+    #[test]
+    fn test_merged_cells_keep_the_origin_and_drop_covered_cells() {
+        let mut merges = 2u16.to_le_bytes().to_vec();
+        for (r1, r2, c1, c2) in [(0u16, 0u16, 0u16, 1u16), (1, 2, 2, 2)] {
+            for v in [r1, r2, c1, c2] {
+                merges.extend_from_slice(&v.to_le_bytes());
+            }
+        }
+        let fixture = XlsFixture {
+            strings: vec!["Group", "Tall", "a", "b", "c"],
+            xfs: vec![0],
+            sheets: vec![(
+                "S",
+                vec![
+                    label_sst(0, 0, 0),
+                    label_sst(0, 2, 2),
+                    label_sst(1, 0, 3),
+                    label_sst(1, 2, 1),
+                    label_sst(2, 0, 4),
+                    record(rt::MERGEDCELLS, &merges),
+                ],
+            )],
+            ..Default::default()
+        };
+
+        let doc = parse(&fixture);
+        let table = only_table(&doc.sections[0]);
+        assert_eq!(table.rows[0].cells.len(), 2);
+        assert_eq!(table.rows[0].cells[0].col_span, 2);
+        assert_eq!(table.rows[1].cells[2].row_span, 2);
+        assert_eq!(table.rows[2].cells.len(), 1);
+        assert!(table.rows[0].is_header && !table.rows[1].is_header);
+    }
+
+    #[test]
+    fn test_shared_strings_continue_across_records() {
+        let data = [
+            &2u32.to_le_bytes()[..],
+            &2u32.to_le_bytes(),
+            &[5, 0, 0],
+            b"hel",
+        ]
+        .concat();
+        let second = [&[0u8][..], b"lo", &[2, 0, 1], &[0x41, 0, 0x42, 0]].concat();
+        let strings = read_shared_strings(&data, vec![&second]);
+        assert_eq!(strings, vec!["hello", "AB"]);
+    }
+
+    #[test]
+    fn test_encrypted_workbook_reports_encrypted() {
+        let fixture = XlsFixture {
+            encrypted: true,
+            sheets: vec![("S", Vec::new())],
+            ..Default::default()
+        };
+
+        let err = XlsParser::from_bytes(fixture.build()).err().unwrap();
+        assert!(matches!(err, Error::Encrypted), "got: {err}");
+    }
+
+    #[test]
+    fn test_parse_bytes_dispatches_to_xls_parser() {
+        let fixture = XlsFixture {
+            strings: vec!["Hello from Excel 97"],
+            xfs: vec![0],
+            sheets: vec![("Sheet1", vec![label_sst(0, 0, 0)])],
+            ..Default::default()
+        };
+
+        let doc = crate::parse_bytes(&fixture.build()).unwrap();
+        assert_eq!(doc.plain_text().trim(), "Hello from Excel 97");
+    }
+}
@@ -0,0 +1,24 @@
+//! XLSB (Excel binary workbook) parser.
+//!
+//! This module provides parsing for Microsoft Excel workbooks in the binary
+//! (.xlsb) format: an OPC package laid out like an XLSX one, whose workbook,
+//! shared strings, styles and worksheet parts hold BIFF12 records instead of XML.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use undoc::xlsb::XlsbParser;
+//!
+//! let mut parser = XlsbParser::open("spreadsheet.xlsb")?;
+//! let doc = parser.parse()?;
+//!
+//! for section in &doc.sections {
+//!     println!("Sheet: {}", section.name.as_deref().unwrap_or("Unnamed"));
+//! }
+//! # Ok::<(), undoc::Error>(())
+//! ```
+
+mod parser;
+mod records;
+
+pub use parser::XlsbParser;
@@ -0,0 +1,531 @@
+//! XLSB parser implementation.
+
+use std::collections::{BTreeMap, HashMap};
+
+use crate::container::OoxmlContainer;
+use crate::error::Result;
+use crate::model::{Block, Document, Metadata, Section, Table};
+use crate::xlsx::binary::{self, CellValue};
+use crate::xlsx::Styles;
+
+use super::records::{self, rt, Payload, Records};
+
+/// Rows and columns of the largest sheet Excel can hold. Cells outside are ignored, so
+/// a damaged coordinate cannot stretch the table.
+const MAX_ROWS: u32 = 1 << 20;
+const MAX_COLUMNS: u32 = 1 << 14;
+
+/// Sheet info from a `BrtBundleSh` record.
+#[derive(Debug, Clone)]
+struct SheetInfo {
+    name: String,
+    /// Package path of the sheet part, when its relationship resolves.
+    path: Option<String>,
+}
+
+/// Parser for Excel binary (.xlsb) workbooks.
+pub struct XlsbParser {
+    container: OoxmlContainer,
+    sheets: Vec<SheetInfo>,
+    shared_strings: Vec<String>,
+    styles: Styles,
+    date_1904: bool,
+}
+
+impl XlsbParser {
+    /// Open an XLSB file for parsing.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn open(path: impl AsRef<std::path::Path>) -> Result<Self> {
+        let container = OoxmlContainer::open(path)?;
+        Self::from_container(container)
+    }
+
+    /// Create a parser from bytes.
+    pub fn from_bytes(data: Vec<u8>) -> Result<Self> {
+        let container = OoxmlContainer::from_bytes(data)?;
+        Self::from_container(container)
+    }
+
+    /// Create a parser from a container.
+    fn from_container(container: OoxmlContainer) -> Result<Self> {
+        let shared_strings = match container.read_binary_optional("xl/sharedStrings.bin")? {
+            Some(part) => read_shared_strings(&part),
+            None => Vec::new(),
+        };
+        let styles = match container.read_binary_optional("xl/styles.bin")? {
+            Some(part) => read_styles(&part),
+            None => Styles::default(),
+        };
+
+        let mut parser = Self {
+            container,
+            sheets: Vec::new(),
+            shared_strings,
+            styles,
+            date_1904: false,
+        };
+        parser.read_workbook()?;
+        Ok(parser)
+    }
+
+    /// Read `xl/workbook.bin` for the sheet list and the date system.
+    fn read_workbook(&mut self) -> Result<()> {
+        const WORKBOOK: &str = "xl/workbook.bin";
+        let targets = self
+            .container
+            .read_required_relationships_for_part(WORKBOOK)?
+            .into_targets_by_id();
+        let part = self.container.read_binary(WORKBOOK)?;
+
+        for record in Records::new(&part) {
+            let mut payload = Payload::new(record.data);
+            match record.kind {
+                rt::WB_PROP => {
+                    self.date_1904 = payload.u32().is_some_and(|flags| flags & 0x01 != 0);
+                }
+                rt::BUNDLE_SH => {
+                    // hsState and iTabID, then the relationship id and the name.
+                    if payload.u32().and(payload.u32()).is_none() {
+                        continue;
+                    }
+                    let (Some(rel_id), Some(name)) =
+                        (payload.nullable_wide_string(), payload.wide_string())
+                    else {
+                        continue;
+                    };
+                    let path = rel_id
+                        .and_then(|id| targets.get(&id))
+                        .map(|target| OoxmlContainer::resolve_path(WORKBOOK, target));
+                    self.sheets.push(SheetInfo { name, path });
+                }
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Parse the workbook and return a Document model.
+    pub fn parse(&mut self) -> Result<Document> {
+        let mut doc = Document::new();
+        doc.format = crate::detect::FormatType::Xlsb;
+        doc.metadata = self.parse_metadata()?;
+
+        for (idx, sheet) in self.sheets.iter().enumerate() {
+            doc.add_section(self.parse_sheet_as_section(idx, sheet)?);
+        }
+
+        Ok(doc)
+    }
+
+    /// Get the number of sheets in the workbook.
+    pub fn sheet_count(&self) -> usize {
+        self.sheets.len()
+    }
+
+    /// Get sheet names.
+    pub fn sheet_names(&self) -> Vec<&str> {
+        self.sheets.iter().map(|s| s.name.as_str()).collect()
+    }
+
+    fn parse_metadata(&self) -> Result<Metadata> {
+        // docProps stay XML in a binary workbook.
+        let mut meta = self.container.parse_core_metadata()?;
+        meta.page_count = Some(self.sheets.len() as u32);
+        Ok(meta)
+    }
+
+    /// Parse a single sheet into a Section.
+    fn parse_sheet_as_section(&self, idx: usize, sheet: &SheetInfo) -> Result<Section> {
+        let mut section = Section::with_name(idx, &sheet.name);
+        let part = match &sheet.path {
+            Some(path) => self.container.read_binary_optional(path)?,
+            None => None,
+        };
+        if let Some(part) = part {
+            let table = self.parse_sheet(&part);
+            if !table.is_empty() {
+                section.add_block(Block::Table(table));
+            }
+        }
+        Ok(section)
+    }
+
+    /// Read a worksheet part into a table.
+    ///
+    /// Rows are those holding a value, each running from column A to its last value;
+    /// see [`binary::build_table`].
+    fn parse_sheet(&self, part: &[u8]) -> Table {
+        let mut cells: BTreeMap<(u32, u32), (CellValue, u32)> = BTreeMap::new();
+        let mut merges: Vec<(u32, u32, u32, u32)> = Vec::new();
+        // Cell records carry only a column; the row is that of the last BrtRowHdr.
+        let mut row = 0u32;
+
+        for record in Records::new(part) {
+            let mut payload = Payload::new(record.data);
+            if record.kind == rt::ROW_HDR {
+                row = payload.u32().unwrap_or(row);
+                continue;
+            }
+            if record.kind == rt::MERGE_CELL {
+                // RfX: first row, last row, first column, last column.
+                if let (Some(r1), Some(r2), Some(c1), Some(c2)) =
+                    (payload.u32(), payload.u32(), payload.u32(), payload.u32())
+                {
+                    if r2 >= r1 && c2 >= c1 && r1 < MAX_ROWS && c1 < MAX_COLUMNS {
+                        merges.push((r1, r2.min(MAX_ROWS - 1), c1, c2.min(MAX_COLUMNS - 1)));
+                    }
+                }
+                continue;
+            }
+
+            let value = match record.kind {
+                rt::CELL_ISST => records::cell_header(&mut payload).zip(payload.u32().map(|idx| {
+                    let text = self.shared_strings.get(idx as usize);
+                    CellValue::Text(text.cloned().unwrap_or_default())
+                })),
+                rt::CELL_ST | rt::FMLA_STRING => records::cell_header(&mut payload)
+                    .zip(payload.wide_string().map(CellValue::Text)),
+                rt::CELL_RSTRING => records::cell_header(&mut payload)
+                    .zip(payload.rich_string().map(CellValue::Text)),
+                rt::CELL_REAL | rt::FMLA_NUM => {
+                    records::cell_header(&mut payload).zip(payload.f64().map(CellValue::Number))
+                }
+                rt::CELL_RK => records::cell_header(&mut payload).zip(
+                    payload
+                        .u32()
+                        .map(|rk| CellValue::Number(binary::rk_value(rk))),
+                ),
+                rt::CELL_BOOL | rt::FMLA_BOOL => records::cell_header(&mut payload)
+                    .zip(payload.u8().map(|b| CellValue::Bool(b != 0))),
+                rt::CELL_ERROR | rt::FMLA_ERROR => {
+                    records::cell_header(&mut payload).zip(payload.u8().map(CellValue::Error))
+                }
+                _ => None,
+            };
+            if let Some(((col, style), value)) = value {
+                if row < MAX_ROWS && col < MAX_COLUMNS {
+                    cells.insert((row, col), (value, style));
+                }
+            }
+        }
+
+        binary::build_table(&self.styles, self.date_1904, cells, &merges)
+    }
+}
+
+/// Read the shared string table from `xl/sharedStrings.bin`.
+fn read_shared_strings(part: &[u8]) -> Vec<String> {
+    Records::new(part)
+        .filter(|r| r.kind == rt::SST_ITEM)
+        .map(|r| Payload::new(r.data).rich_string().unwrap_or_default())
+        .collect()
+}
+
+/// Read the number formats and cell XFs from `xl/styles.bin`.
+fn read_styles(part: &[u8]) -> Styles {
+    let mut num_fmts = HashMap::new();
+    let mut cell_xfs = Vec::new();
+    // BrtXF also lists the cell style XFs; cells index only the ones in BrtBeginCellXFs.
+    let mut in_cell_xfs = false;
+
+    for record in Records::new(part) {
+        let mut payload = Payload::new(record.data);
+        match record.kind {
+            rt::FMT => {
+                if let (Some(id), Some(code)) = (payload.u16(), payload.wide_string()) {
+                    num_fmts.insert(id as u32, code);
+                }
+            }
+            rt::BEGIN_CELL_XFS => in_cell_xfs = true,
+            rt::END_CELL_XFS => in_cell_xfs = false,
+            // ixfeParent, then iFmt.
+            rt::XF if in_cell_xfs => {
+                let num_fmt_id = payload.u16().and(payload.u16()).unwrap_or(0);
+                cell_xfs.push(num_fmt_id as u32);
+            }
+            _ => {}
+        }
+    }
+
+    Styles::from_formats(num_fmts, cell_xfs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::model::Cell;
+    use std::io::{Cursor, Write};
+
+    /// Encode a record with its variable-length type and size.
+    fn record(kind: u16, data: &[u8]) -> Vec<u8> {
+        let mut out = Vec::new();
+        let mut var_int = |mut value: u32| loop {
+            let byte = (value & 0x7F) as u8;
+            value >>= 7;
+            if value == 0 {
+                out.push(byte);
+                break;
+            }
+            out.push(byte | 0x80);
+        };
+        var_int(kind as u32);
+        var_int(data.len() as u32);
+        out.extend_from_slice(data);
+        out
+    }
+
+    fn wide(text: &str) -> Vec<u8> {
+        let units: Vec<u16> = text.encode_utf16().collect();
+        let mut out = (units.len() as u32).to_le_bytes().to_vec();
+        for unit in units {
+            out.extend_from_slice(&unit.to_le_bytes());
+        }
+        out
+    }
+
+    fn row(index: u32) -> Vec<u8> {
+        let mut data = index.to_le_bytes().to_vec();
+        data.extend_from_slice(&[0; 13]);
+        record(rt::ROW_HDR, &data)
+    }
+
+    fn cell(kind: u16, col: u32, style: u32, value: &[u8]) -> Vec<u8> {
+        let mut data = col.to_le_bytes().to_vec();
+        data.extend_from_slice(&style.to_le_bytes());
+        data.extend_from_slice(value);
+        record(kind, &data)
+    }
+
+    fn sheet_entry(rel_id: &str, name: &str) -> Vec<u8> {
+        let mut data = vec![0; 8];
+        data.extend(wide(rel_id));
+        data.extend(wide(name));
+        record(rt::BUNDLE_SH, &data)
+    }
+
+    /// Build an XLSB package from its binary parts; `sheets` are `(name, part)` pairs.
+    fn xlsb(date_1904: bool, sheets: &[(&str, Vec<u8>)], strings: &[&str]) -> Vec<u8> {
+        let mut workbook = record(rt::WB_PROP, &(date_1904 as u32).to_le_bytes());
+        let mut rels = String::from(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">"#,
+        );
+        for (i, (name, _)) in sheets.iter().enumerate() {
+            workbook.extend(sheet_entry(&format!("rId{}", i + 1), name));
+            rels.push_str(&format!(
+                r#"<Relationship Id="rId{}" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/worksheet" Target="worksheets/sheet{}.bin"/>"#,
+                i + 1,
+                i + 1
+            ));
+        }
+        rels.push_str("</Relationships>");
+
+        let mut shared = Vec::new();
+        for text in strings {
+            let mut data = vec![0];
+            data.extend(wide(text));
+            shared.extend(record(rt::SST_ITEM, &data));
+        }
+
+        // Cell XF 0 is General, 1 the built-in date format 14, 2 a custom ISO date.
+        let mut styles = Vec::new();
+        let mut fmt = 164u16.to_le_bytes().to_vec();
+        fmt.extend(wide("yyyy-mm-dd"));
+        styles.extend(record(rt::FMT, &fmt));
+        styles.extend(record(rt::XF, &[0, 0, 7, 0]));
+        styles.extend(record(rt::BEGIN_CELL_XFS, &[]));
+        for num_fmt in [0u16, 14, 164] {
+            let mut xf = 0u16.to_le_bytes().to_vec();
+            xf.extend_from_slice(&num_fmt.to_le_bytes());
+            styles.extend(record(rt::XF, &xf));
+        }
+        styles.extend(record(rt::END_CELL_XFS, &[]));
+
+        let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+        let options = zip::write::SimpleFileOptions::default()
+            .compression_method(zip::CompressionMethod::Stored);
+        let content_types = r#"<?xml version="1.0" encoding="UTF-8"?>
+            <Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">
+              <Override PartName="/xl/workbook.bin" ContentType="application/vnd.ms-excel.sheet.binary.macroEnabled.main"/>
+            </Types>"#;
+        let mut parts: Vec<(String, Vec<u8>)> = vec![
+            ("[Content_Types].xml".into(), content_types.into()),
+            ("xl/workbook.bin".into(), workbook),
+            ("xl/_rels/workbook.bin.rels".into(), rels.into_bytes()),
+            ("xl/sharedStrings.bin".into(), shared),
+            ("xl/styles.bin".into(), styles),
+        ];
+        for (i, (_, part)) in sheets.iter().enumerate() {
+            parts.push((format!("xl/worksheets/sheet{}.bin", i + 1), part.clone()));
+        }
+        for (path, data) in parts {
+            zip.start_file(path, options).unwrap();
+            zip.write_all(&data).unwrap();
+        }
+        zip.finish().unwrap().into_inner()
+    }
+
+    fn parse(data: Vec<u8>) -> Document {
+        XlsbParser::from_bytes(data).unwrap().parse().unwrap()
+    }
+
+    fn only_table(section: &Section) -> &Table {
+        match section.content.as_slice() {
+            [Block::Table(table)] => table,
+            other => panic!("expected one table, got {other:?}"),
+        }
+    }
+
+    fn texts(table: &Table) -> Vec<Vec<String>> {
+        table
+            .rows
+            .iter()
+            .map(|r| r.cells.iter().map(Cell::plain_text).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_one_section_per_sheet_with_strings_numbers_and_formulas() {
+        let mut first = row(0);
+        first.extend(cell(rt::CELL_ISST, 0, 0, &0u32.to_le_bytes()));
+        first.extend(cell(rt::CELL_ST, 1, 0, &wide("Amount")));
+        first.extend(row(1));
+        first.extend(cell(rt::CELL_ISST, 0, 0, &1u32.to_le_bytes()));
+        first.extend(cell(
+            rt::CELL_RK,
+            1,
+            0,
+            &((42u32 << 2) | 0x02).to_le_bytes(),
+        ));
+        first.extend(row(2));
+        first.extend(cell(
+            rt::CELL_RSTRING,
+            0,
+            0,
+            &[&[1u8][..], &wide("Rich")].concat(),
+        ));
+        first.extend(cell(rt::CELL_REAL, 1, 0, &1.5f64.to_le_bytes()));
+        first.extend(row(3));
+        first.extend(cell(
+            rt::FMLA_STRING,
+            0,
+            0,
+            &[wide("Total"), vec![0; 6]].concat(),
+        ));
+        first.extend(cell(
+            rt::FMLA_NUM,
+            1,
+            0,
+            &[&43.5f64.to_le_bytes()[..], &[0; 6]].concat(),
+        ));
+        first.extend(cell(rt::FMLA_BOOL, 2, 0, &[1, 0, 0]));
+        first.extend(cell(rt::CELL_ERROR, 3, 0, &[0x07]));
+
+        let mut second = row(4);
+        second.extend(cell(rt::CELL_BOOL, 2, 0, &[0]));
+
+        let doc = parse(xlsb(
+            false,
+            &[("Data", first), ("Flags", second), ("Empty", Vec::new())],
+            &["Item", "Apples"],
+        ));
+        assert_eq!(doc.format, crate::detect::FormatType::Xlsb);
+        assert_eq!(doc.metadata.page_count, Some(3));
+        assert_eq!(doc.sections.len(), 3);
+        assert_eq!(doc.sections[0].name.as_deref(), Some("Data"));
+
+        let table = only_table(&doc.sections[0]);
+        assert_eq!(
+            texts(table),
+            [
+                vec!["Item", "Amount"],
+                vec!["Apples", "42"],
+                vec!["Rich", "1.5"],
+                vec!["Total", "43.5", "TRUE", "#ERROR:#DIV/0!"],
+            ]
+        );
+        assert!(table.rows[0].is_header && !table.rows[1].is_header);
+
+        // Rows before the first value are left out; columns before it are kept.
+        assert_eq!(texts(only_table(&doc.sections[1])), [["", "", "FALSE"]]);
+        assert!(doc.sections[2].content.is_empty());
+    }
+
+    #[test]
+    fn test_date_formats_from_builtin_and_custom_formats() {
+        let mut sheet = row(0);
+        sheet.extend(cell(rt::CELL_REAL, 0, 0, &44197.0f64.to_le_bytes()));
+        sheet.extend(cell(rt::CELL_REAL, 1, 1, &44197.0f64.to_le_bytes()));
+        sheet.extend(cell(rt::CELL_REAL, 2, 2, &44197.5f64.to_le_bytes()));
+
+        let doc = parse(xlsb(false, &[("Dates", sheet.clone())], &[]));
+        assert_eq!(
+            texts(only_table(&doc.sections[0])),
+            [["44197", "2021-01-01", "2021-01-01T12:00:00"]]
+        );
+
+        let doc = parse(xlsb(true, &[("Dates", sheet)], &[]));
+        assert_eq!(
+            texts(only_table(&doc.sections[0]))[0][1],
+            "2025-01-02",
+            "1904 date system"
+        );
+    }
+
+    #[test]
+    fn test_merged_cells_keep_the_origin_and_drop_covered_cells() {
+        let mut sheet = row(0);
+        sheet.extend(cell(rt::CELL_ST, 0, 0, &wide("Merged")));
+        sheet.extend(cell(rt::CELL_ST, 2, 0, &wide("C")));
+        sheet.extend(row(1));
+        sheet.extend(cell(rt::CELL_ST, 2, 0, &wide("D")));
+        let merge: Vec<u8> = [0u32, 1, 0, 1]
+            .iter()
+            .flat_map(|v| v.to_le_bytes())
+            .collect();
+        sheet.extend(record(rt::MERGE_CELL, &merge));
+
+        let doc = parse(xlsb(false, &[("Sheet1", sheet)], &[]));
+        let table = only_table(&doc.sections[0]);
+        assert_eq!(table.rows[0].cells.len(), 2);
+        assert_eq!(table.rows[0].cells[0].col_span, 2);
+        assert_eq!(table.rows[0].cells[0].row_span, 2);
+        assert_eq!(texts(table)[1], ["D"]);
+    }
+
+    #[test]
+    fn test_only_rows_with_values_are_laid_out() {
+        let mut sheet = row(0);
+        sheet.extend(cell(rt::CELL_ST, 0, 0, &wide("First")));
+        sheet.extend(row(MAX_ROWS - 1));
+        sheet.extend(cell(rt::CELL_ST, MAX_COLUMNS - 1, 0, &wide("Last")));
+        let merge: Vec<u8> = [0, MAX_ROWS - 1, 0, MAX_COLUMNS - 1]
+            .iter()
+            .flat_map(|v| v.to_le_bytes())
+            .collect();
+        sheet.extend(record(rt::MERGE_CELL, &merge));
+
+        let doc = parse(xlsb(false, &[("Sheet1", sheet)], &[]));
+        let table = only_table(&doc.sections[0]);
+        assert_eq!(table.rows.len(), 2);
+        assert_eq!(texts(table)[0], ["First"]);
+        assert_eq!(table.rows[0].cells[0].row_span, 2);
+        // The merge covers the whole second row.
+        assert!(table.rows[1].cells.is_empty());
+    }
+
+    #[test]
+    fn test_parse_bytes_dispatches_to_xlsb_parser() {
+        let mut sheet = row(0);
+        sheet.extend(cell(rt::CELL_ST, 0, 0, &wide("Hello")));
+        let data = xlsb(false, &[("Sheet1", sheet)], &[]);
+
+        assert_eq!(
+            crate::detect::detect_format_from_bytes(&data).unwrap(),
+            crate::detect::FormatType::Xlsb
+        );
+        let doc = crate::parse_bytes(&data).unwrap();
+        assert_eq!(doc.format, crate::detect::FormatType::Xlsb);
+        assert_eq!(doc.plain_text().trim(), "Hello");
+    }
+}
@@ -0,0 +1,184 @@
+//! BIFF12 record reading.
+//!
+//! Every `.bin` part of an XLSB package is a flat run of records. A record starts with
+//! its type and size, both variable-length integers of 7 bits per byte with the high
+//! bit marking that another byte follows: at most two bytes for the type and four for
+//! the size.
+
+/// Record types (`MS-XLSB` 2.3) of the records the parser reads.
+pub mod rt {
+    pub const ROW_HDR: u16 = 0x0000;
+    pub const CELL_RK: u16 = 0x0002;
+    pub const CELL_ERROR: u16 = 0x0003;
+    pub const CELL_BOOL: u16 = 0x0004;
+    pub const CELL_REAL: u16 = 0x0005;
+    pub const CELL_ST: u16 = 0x0006;
+    pub const CELL_ISST: u16 = 0x0007;
+    pub const FMLA_STRING: u16 = 0x0008;
+    pub const FMLA_NUM: u16 = 0x0009;
+    pub const FMLA_BOOL: u16 = 0x000A;
+    pub const FMLA_ERROR: u16 = 0x000B;
+    pub const SST_ITEM: u16 = 0x0013;
+    pub const FMT: u16 = 0x002C;
+    pub const XF: u16 = 0x002F;
+    pub const CELL_RSTRING: u16 = 0x003E;
+    pub const WB_PROP: u16 = 0x0099;
+    pub const BUNDLE_SH: u16 = 0x009C;
+    pub const MERGE_CELL: u16 = 0x00B0;
+    pub const BEGIN_CELL_XFS: u16 = 0x0269;
+    pub const END_CELL_XFS: u16 = 0x026A;
+}
+
+/// One record: its type and payload.
+#[derive(Debug, Clone, Copy)]
+pub struct Record<'a> {
+    pub kind: u16,
+    pub data: &'a [u8],
+}
+
+/// Iterator over the records of a part. Stops at a truncated record.
+pub struct Records<'a> {
+    part: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Records<'a> {
+    pub fn new(part: &'a [u8]) -> Self {
+        Self { part, pos: 0 }
+    }
+
+    /// Read a variable-length integer of at most `max_bytes` bytes.
+    fn var_int(&mut self, max_bytes: usize) -> Option<u32> {
+        let mut value = 0u32;
+        for i in 0..max_bytes {
+            let byte = *self.part.get(self.pos)?;
+            self.pos += 1;
+            value |= ((byte & 0x7F) as u32) << (7 * i);
+            if byte & 0x80 == 0 {
+                break;
+            }
+        }
+        Some(value)
+    }
+}
+
+impl<'a> Iterator for Records<'a> {
+    type Item = Record<'a>;
+
+    fn next(&mut self) -> Option<Record<'a>> {
+        let kind = self.var_int(2)? as u16;
+        let size = self.var_int(4)? as usize;
+        let data = self.part.get(self.pos..self.pos.checked_add(size)?)?;
+        self.pos += size;
+        Some(Record { kind, data })
+    }
+}
+
+/// Cursor over a record payload.
+pub struct Payload<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Payload<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    fn take(&mut self, count: usize) -> Option<&'a [u8]> {
+        let bytes = self.data.get(self.pos..self.pos.checked_add(count)?)?;
+        self.pos += count;
+        Some(bytes)
+    }
+
+    pub fn u8(&mut self) -> Option<u8> {
+        self.take(1).map(|b| b[0])
+    }
+
+    pub fn u16(&mut self) -> Option<u16> {
+        self.take(2).map(|b| u16::from_le_bytes([b[0], b[1]]))
+    }
+
+    pub fn u32(&mut self) -> Option<u32> {
+        self.take(4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    pub fn f64(&mut self) -> Option<f64> {
+        self.take(8)
+            .map(|b| f64::from_le_bytes(b.try_into().unwrap_or_default()))
+    }
+
+    /// Read an `XLWideString`: a character count and that many UTF-16 code units.
+    pub fn wide_string(&mut self) -> Option<String> {
+        let count = self.u32()? as usize;
+        let bytes = self.take(count.checked_mul(2)?)?;
+        let units: Vec<u16> = bytes
+            .chunks_exact(2)
+            .map(|c| u16::from_le_bytes([c[0], c[1]]))
+            .collect();
+        Some(String::from_utf16_lossy(&units))
+    }
+
+    /// Read an `XLNullableWideString`, whose count is `0xFFFFFFFF` when it is null.
+    pub fn nullable_wide_string(&mut self) -> Option<Option<String>> {
+        if self.data.get(self.pos..self.pos + 4) == Some(&[0xFF; 4]) {
+            self.pos += 4;
+            return Some(None);
+        }
+        self.wide_string().map(Some)
+    }
+
+    /// Read the text of a `RichStr`. The formatting runs and phonetic data after the
+    /// text are left unread.
+    pub fn rich_string(&mut self) -> Option<String> {
+        self.u8()?;
+        self.wide_string()
+    }
+}
+
+/// Read the `Cell` structure every cell record starts with: `(column, style index)`.
+pub fn cell_header(payload: &mut Payload<'_>) -> Option<(u32, u32)> {
+    let column = payload.u32()?;
+    // iStyleRef is the low 24 bits; the high byte holds display flags.
+    let style = payload.u32()? & 0x00FF_FFFF;
+    Some((column, style))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_headers_are_variable_length() {
+        // BrtBeginCellXFs (0x269) takes two type bytes; a 200-byte payload two size bytes.
+        let mut part = vec![0xE9, 0x04, 0xC8, 0x01];
+        part.extend_from_slice(&[0u8; 200]);
+        part.extend_from_slice(&[0x00, 0x01, 0x7F]);
+        let records: Vec<Record<'_>> = Records::new(&part).collect();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].kind, rt::BEGIN_CELL_XFS);
+        assert_eq!(records[0].data.len(), 200);
+        assert_eq!(records[1].kind, rt::ROW_HDR);
+        assert_eq!(records[1].data, [0x7F]);
+    }
+
+    #[test]
+    fn test_records_stop_at_a_truncated_tail() {
+        let part = [0x07, 0x02, 0xAA, 0xBB, 0x06, 0x10, 0x00];
+        let records: Vec<Record<'_>> = Records::new(&part).collect();
+        assert_eq!(records.len(), 1);
+        assert_eq!(records[0].kind, rt::CELL_ISST);
+    }
+
+    #[test]
+    fn test_wide_strings() {
+        let mut data = 2u32.to_le_bytes().to_vec();
+        data.extend_from_slice(&[b'h', 0, 0xE9, 0]);
+        data.extend_from_slice(&[0xFF; 4]);
+        let mut payload = Payload::new(&data);
+        assert_eq!(payload.wide_string().as_deref(), Some("hé"));
+        assert_eq!(payload.nullable_wide_string(), Some(None));
+        assert_eq!(payload.wide_string(), None);
+    }
+}
@@ -0,0 +1,212 @@
+//! Cell values of the binary workbook formats, XLS and XLSB.
+//!
+//! Their records differ, but the values they hold are the same: text, numbers (some
+//! packed as `RkNumber`s), booleans and error codes, shown through the number formats
+//! of the XLSX styles model and laid out the way the XLSX parser lays out rows.
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+
+use crate::decode::normalize_line_endings;
+use crate::model::{Cell, Paragraph, Row, Table, TextRun};
+
+use super::Styles;
+
+/// Days between the 1900 and 1904 date systems' epochs.
+const DATE_1904_OFFSET: f64 = 1462.0;
+
+/// A cell value as read from its record, before number formatting.
+#[derive(Debug, Clone)]
+pub(crate) enum CellValue {
+    Text(String),
+    Number(f64),
+    Bool(bool),
+    Error(u8),
+}
+
+/// Decode an `RkNumber`: a 30-bit integer or the top 30 bits of a double, optionally
+/// scaled by 1/100.
+pub(crate) fn rk_value(rk: u32) -> f64 {
+    let value = if rk & 0x02 != 0 {
+        ((rk as i32) >> 2) as f64
+    } else {
+        f64::from_bits(((rk & 0xFFFF_FFFC) as u64) << 32)
+    };
+    if rk & 0x01 != 0 {
+        value / 100.0
+    } else {
+        value
+    }
+}
+
+/// Spell a `BErr` code the way Excel displays it.
+pub(crate) fn error_text(code: u8) -> &'static str {
+    match code {
+        0x00 => "#NULL!",
+        0x07 => "#DIV/0!",
+        0x0F => "#VALUE!",
+        0x17 => "#REF!",
+        0x1D => "#NAME?",
+        0x24 => "#NUM!",
+        0x2A => "#N/A",
+        0x2B => "#GETTING_DATA",
+        _ => "#UNKNOWN!",
+    }
+}
+
+/// Format a number the way the `<v>` element of an XLSX cell would hold it.
+pub(crate) fn format_number(value: f64) -> String {
+    if value.fract() == 0.0 && value.abs() < 1e15 {
+        format!("{}", value as i64)
+    } else {
+        format!("{}", value)
+    }
+}
+
+/// Turn a cell value into its displayed text, formatting dates by the cell's XF.
+///
+/// A date also returns the number it is stored as, which the cell keeps as its value.
+pub(crate) fn resolve_cell_value(
+    styles: &Styles,
+    date_1904: bool,
+    value: CellValue,
+    xf: u32,
+) -> (String, Option<String>) {
+    match value {
+        CellValue::Text(text) => (normalize_line_endings(Cow::Owned(text)).into_owned(), None),
+        CellValue::Bool(true) => ("TRUE".to_string(), None),
+        CellValue::Bool(false) => ("FALSE".to_string(), None),
+        CellValue::Error(code) => (format!("#ERROR:{}", error_text(code)), None),
+        CellValue::Number(number) => {
+            if let Some(num_fmt_id) = styles.get_num_fmt_id(xf as usize) {
+                if styles.is_date_format(num_fmt_id) {
+                    let serial = if date_1904 {
+                        number + DATE_1904_OFFSET
+                    } else {
+                        number
+                    };
+                    if let Some(date_str) = Styles::serial_to_date(serial) {
+                        return (date_str, Some(format_number(number)));
+                    }
+                }
+            }
+            (format_number(number), None)
+        }
+    }
+}
+
+/// Resolve the text of a sheet's cells, keyed by `(row, column)` with their XF, and
+/// lay them out as rows.
+pub(crate) fn build_table(
+    styles: &Styles,
+    date_1904: bool,
+    cells: BTreeMap<(u32, u32), (CellValue, u32)>,
+    merges: &[(u32, u32, u32, u32)],
+) -> Table {
+    let mut values: BTreeMap<(u32, u32), (String, Option<String>)> = BTreeMap::new();
+    for (at, (value, xf)) in cells {
+        let (text, stored) = resolve_cell_value(styles, date_1904, value, xf);
+        if !text.is_empty() {
+            values.insert(at, (text, stored));
+        }
+    }
+    lay_out_rows(values, merges)
+}
+
+/// Lay cell values out as rows, applying merges.
+///
+/// Like the XLSX parser, only rows holding a value are laid out, each up to its last
+/// value, so neither empty rows nor one distant cell pad the table. Cells a merge
+/// covers are left out; the merge's first cell carries the span, clipped to the rows
+/// and columns laid out. A merge whose first cell is on no laid-out row is dropped.
+fn lay_out_rows(
+    mut values: BTreeMap<(u32, u32), (String, Option<String>)>,
+    merges: &[(u32, u32, u32, u32)],
+) -> Table {
+    let mut table = Table::new();
+    let Some(last_col) = values.keys().map(|&(_, c)| c).max() else {
+        return table;
+    };
+    let mut rows: Vec<u32> = values.keys().map(|&(r, _)| r).collect();
+    rows.dedup();
+    let last_row = rows.last().copied().unwrap_or_default();
+
+    // Merge anchor → (last row, last column).
+    let mut spans: BTreeMap<(u32, u32), (u32, u32)> = BTreeMap::new();
+    for &(r1, r2, c1, c2) in merges {
+        if c1 <= last_col && rows.binary_search(&r1).is_ok() {
+            spans.insert((r1, c1), (r2.min(last_row), c2.min(last_col)));
+        }
+    }
+
+    // The merge over each column, as (first row, last row, anchor column).
+    let mut cover: Vec<Option<(u32, u32, u32)>> = vec![None; last_col as usize + 1];
+    for (i, &r) in rows.iter().enumerate() {
+        let is_header = i == 0;
+        let mut row = Row {
+            is_header,
+            ..Row::new()
+        };
+        let on_row = (r, 0)..=(r, u32::MAX);
+        let row_last = [
+            values
+                .range(on_row.clone())
+                .next_back()
+                .map(|(&(_, c), _)| c),
+            spans.range(on_row).next_back().map(|(&(_, c), _)| c),
+        ]
+        .into_iter()
+        .flatten()
+        .max()
+        .unwrap_or_default();
+        for c in 0..=row_last {
+            if let Some((start, end, anchor)) = cover[c as usize] {
+                if (start..=end).contains(&r) && (r, c) != (start, anchor) {
+                    continue;
+                }
+            }
+            let mut cell = Cell {
+                is_header,
+                ..Cell::new()
+            };
+            if let Some((text, stored)) = values.remove(&(r, c)) {
+                cell.content = vec![Paragraph {
+                    runs: vec![TextRun::plain(text)],
+                    ..Default::default()
+                }];
+                cell.value = stored;
+            }
+            if let Some(&(r2, c2)) = spans.get(&(r, c)) {
+                cell.row_span = rows[i..].partition_point(|&rr| rr <= r2) as u32;
+                cell.col_span = c2 - c + 1;
+                for slot in &mut cover[c as usize..=c2 as usize] {
+                    *slot = Some((r, r2, c));
+                }
+            }
+            row.cells.push(cell);
+        }
+        table.add_row(row);
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rk_integer_and_scaled_forms() {
+        assert_eq!(rk_value((42 << 2) | 0x02), 42.0);
+        assert_eq!(rk_value(((-7i32 << 2) as u32) | 0x02), -7.0);
+        assert_eq!(rk_value((1234 << 2) | 0x03), 12.34);
+        let bits = (1.5f64.to_bits() >> 32) as u32;
+        assert_eq!(rk_value(bits), 1.5);
+    }
+
+    #[test]
+    fn test_number_formatting_matches_xlsx_values() {
+        assert_eq!(format_number(3.0), "3");
+        assert_eq!(format_number(-0.25), "-0.25");
+        assert_eq!(format_number(1e20), "100000000000000000000");
+    }
+}
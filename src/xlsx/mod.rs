@@ -17,8 +17,12 @@
 //! # Ok::<(), undoc::Error>(())
 //! ```
 
+#[cfg(any(feature = "xls", feature = "xlsb"))]
+pub(crate) mod binary;
 mod parser;
 mod shared_strings;
 mod styles;
 
 pub use parser::XlsxParser;
+#[cfg(any(feature = "xls", feature = "xlsb"))]
+pub(crate) use styles::Styles;
@@ -147,6 +147,7 @@ impl XlsxParser {
     pub fn parse(&mut self) -> Result<Document> {
         let mut doc = Document::new();
         doc.format = crate::detect::FormatType::Xlsx;
+        doc.variant = self.container.format_variant();
 
         // Parse metadata
         doc.metadata = self.parse_metadata()?;
@@ -618,6 +619,13 @@ impl XlsxParser {
     ) -> Result<Cell> {
         let value =
             self.resolve_cell_value(current_cell_value, current_cell_type, current_cell_style)?;
+        // A date keeps the serial number it is stored as. Strict stores ISO dates instead,
+        // which become the serial a Transitional workbook would hold for them.
+        let stored = match current_cell_type {
+            Some("d") => Styles::iso_to_serial(current_cell_value),
+            None | Some("n") if value != current_cell_value => Some(current_cell_value.to_string()),
+            _ => None,
+        };
 
         let (col_span, row_span) = current_cell_ref
             .and_then(|r| context.merge_map.get(r))
@@ -653,6 +661,7 @@ impl XlsxParser {
             vertical_alignment: Default::default(),
             is_header: context.is_header,
             background: None,
+            value: stored,
         })
     }
 
@@ -715,6 +724,10 @@ impl XlsxParser {
                 // Inline string
                 Ok(value.to_string())
             }
+            Some("d") => {
+                // ISO 8601 date, as Strict workbooks store them
+                Ok(Styles::iso_to_date(value))
+            }
             _ => {
                 // Number or general - check for date format
                 if let Some(style_idx) = style_index {
@@ -2031,6 +2044,32 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_date_cells_keep_their_stored_value() {
+        let mut parser = test_parser();
+        parser.styles = Styles::parse(
+            r#"<styleSheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">
+                <cellXfs count="2"><xf numFmtId="0"/><xf numFmtId="14"/></cellXfs>
+            </styleSheet>"#,
+        );
+        let sheet_xml = r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">
+                <sheetData><row r="1">
+                    <c r="A1" s="1"><v>44197</v></c>
+                    <c r="B1"><v>3.5</v></c>
+                </row></sheetData>
+            </worksheet>"#;
+
+        let table = parser
+            .parse_sheet(sheet_xml, &HashMap::new(), &HashMap::new(), &HashMap::new())
+            .unwrap();
+
+        let cells = &table.rows[0].cells;
+        assert_eq!(cells[0].plain_text(), "2021-01-01");
+        assert_eq!(cells[0].value.as_deref(), Some("44197"));
+        assert_eq!(cells[1].plain_text(), "3.5");
+        assert_eq!(cells[1].value, None);
+    }
+
     #[test]
     fn test_rels_path_for() {
         assert_eq!(
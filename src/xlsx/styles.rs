@@ -95,6 +95,15 @@ impl Styles {
         styles
     }
 
+    /// Build styles from number formats read elsewhere than `styles.xml`.
+    ///
+    /// The binary workbook formats store the same two tables as records; handing them
+    /// over here keeps one definition of what counts as a date.
+    #[cfg(any(feature = "xls", feature = "xlsb"))]
+    pub(crate) fn from_formats(num_fmts: HashMap<u32, String>, cell_xfs: Vec<u32>) -> Self {
+        Self { num_fmts, cell_xfs }
+    }
+
     /// Get the numFmtId for a cell style index.
     pub fn get_num_fmt_id(&self, style_index: usize) -> Option<u32> {
         self.cell_xfs.get(style_index).copied()
@@ -209,6 +218,63 @@ impl Styles {
             Some(format!("{:04}-{:02}-{:02}", year, month, day))
         }
     }
+
+    /// Convert an ISO 8601 cell value (`t="d"`, which Strict workbooks use in place of a
+    /// serial number) to the string [`Self::serial_to_date`] gives for the same moment:
+    /// the date alone at midnight, otherwise date and time to the second.
+    pub fn iso_to_date(value: &str) -> String {
+        let value = value.trim().trim_end_matches('Z');
+        match value.split_once('T') {
+            Some((date, time)) => {
+                let time = time.split('.').next().unwrap_or(time);
+                if time.chars().all(|c| c == '0' || c == ':') {
+                    date.to_string()
+                } else {
+                    format!("{}T{}", date, time)
+                }
+            }
+            None => value.to_string(),
+        }
+    }
+
+    /// Convert an ISO 8601 cell value to the serial number a Transitional workbook
+    /// stores for the same moment, the inverse of [`Self::serial_to_date`].
+    pub fn iso_to_serial(value: &str) -> Option<String> {
+        let value = value.trim().trim_end_matches('Z');
+        let (date, time) = value.split_once('T').unwrap_or((value, ""));
+        let mut parts = date.split('-').map(|p| p.parse::<u32>().ok());
+        let (Some(Some(year)), Some(Some(month)), Some(Some(day)), None) =
+            (parts.next(), parts.next(), parts.next(), parts.next())
+        else {
+            return None;
+        };
+        if year < 1900 {
+            return None;
+        }
+        // Days since 1899-12-31, which makes 1900-01-01 serial day 1.
+        let mut days = crate::model::days_from_civil(i64::from(year), month, day)? + 25_568;
+        // Excel's phantom 1900-02-29 sits before every date from March 1900 on.
+        if days >= 60 {
+            days += 1;
+        }
+
+        let mut seconds = 0.0;
+        if !time.is_empty() {
+            let mut fields = time.split(':').map(|f| f.parse::<f64>().ok());
+            for scale in [3600.0, 60.0, 1.0] {
+                match fields.next() {
+                    Some(Some(n)) => seconds += n * scale,
+                    Some(None) => return None,
+                    None => break,
+                }
+            }
+        }
+        if seconds == 0.0 {
+            Some(days.to_string())
+        } else {
+            Some((days as f64 + seconds / 86400.0).to_string())
+        }
+    }
 }
 
 /// Convert days since December 31, 1899 to (year, month, day).
@@ -316,4 +382,35 @@ mod tests {
             Some("2021-01-01T12:00:00".to_string())
         );
     }
+
+    #[test]
+    fn test_iso_to_serial_inverts_serial_to_date() {
+        assert_eq!(
+            Styles::iso_to_serial("2021-01-01T00:00:00"),
+            Some("44197".to_string())
+        );
+        assert_eq!(Styles::iso_to_serial("1900-02-28"), Some("59".to_string()));
+        assert_eq!(Styles::iso_to_serial("1900-03-01"), Some("61".to_string()));
+        assert_eq!(
+            Styles::iso_to_serial("2021-01-01T12:00:00Z"),
+            Some("44197.5".to_string())
+        );
+        assert_eq!(
+            Styles::iso_to_serial("2024-02-29").as_deref(),
+            Some("45351")
+        );
+        assert_eq!(Styles::iso_to_serial("2021-02-31"), None);
+        assert_eq!(Styles::iso_to_serial("2023-02-29"), None);
+        assert_eq!(Styles::iso_to_serial("not a date"), None);
+    }
+
+    #[test]
+    fn test_iso_to_date_matches_serial_rendering() {
+        assert_eq!(Styles::iso_to_date("2021-01-01T00:00:00"), "2021-01-01");
+        assert_eq!(Styles::iso_to_date("2021-01-01"), "2021-01-01");
+        assert_eq!(
+            Styles::iso_to_date("2021-01-01T12:00:00.000Z"),
+            "2021-01-01T12:00:00"
+        );
+    }
 }
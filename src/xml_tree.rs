@@ -0,0 +1,281 @@
+//! A small element tree for the XML that is read by walking it rather than streaming:
+//! the OpenDocument parts and Office Math equations.
+//!
+//! Both nest freely, and a crafted file can nest without end, so the tree stops
+//! growing at [`MAX_DEPTH`]: elements below it are not built, and their text is kept
+//! as text of the deepest element that was. Everything that walks the tree
+//! recursively — text collection, the ODF readers, the equation translator, even
+//! dropping it — is bounded by that depth.
+
+use quick_xml::events::{BytesStart, Event};
+
+use crate::decode::{attr_value_lossy, decode_text_lossy, reader_for, resolve_general_ref};
+use crate::error::{Error, Result};
+
+/// How deep the tree nests; deeper elements are read as their text.
+pub(crate) const MAX_DEPTH: usize = 256;
+
+/// How element and attribute names are written in the tree.
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Names {
+    /// The local name, prefix dropped (`m:f` is `f`).
+    #[cfg_attr(not(any(feature = "docx", feature = "pptx")), allow(dead_code))]
+    Local,
+    /// The conventional prefix of the namespace, from a table of namespace URI →
+    /// prefix. A name in a namespace the table does not know keeps the prefix it was
+    /// written with; an unprefixed attribute is in no namespace and keeps its bare
+    /// name.
+    #[cfg_attr(
+        not(any(feature = "odt", feature = "ods", feature = "odp")),
+        allow(dead_code)
+    )]
+    Canonical(&'static [(&'static str, &'static str)]),
+}
+
+/// A node of the tree.
+#[derive(Debug, Clone)]
+pub(crate) enum Node {
+    Element(Element),
+    Text(String),
+}
+
+/// An element with its attributes and children.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct Element {
+    /// Name as [`Names`] writes it, such as `text:p`.
+    pub name: String,
+    attributes: Vec<(String, String)>,
+    pub nodes: Vec<Node>,
+}
+
+impl Element {
+    /// Value of the attribute with the given name.
+    pub fn attr(&self, name: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// The child elements, in document order.
+    pub fn children(&self) -> impl Iterator<Item = &Element> {
+        self.nodes.iter().filter_map(|node| match node {
+            Node::Element(e) => Some(e),
+            Node::Text(_) => None,
+        })
+    }
+
+    /// The child elements with the given name, in document order.
+    #[cfg_attr(not(any(feature = "docx", feature = "pptx")), allow(dead_code))]
+    pub fn children_named<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a Element> {
+        self.children().filter(move |e| e.name == name)
+    }
+
+    /// The first child element with the given name.
+    pub fn child(&self, name: &str) -> Option<&Element> {
+        self.children().find(|e| e.name == name)
+    }
+
+    /// Follow a path of child names.
+    #[cfg_attr(
+        not(any(feature = "odt", feature = "ods", feature = "odp")),
+        allow(dead_code)
+    )]
+    pub fn path(&self, names: &[&str]) -> Option<&Element> {
+        names.iter().try_fold(self, |e, name| e.child(name))
+    }
+
+    /// All text below this element, concatenated.
+    pub fn text(&self) -> String {
+        let mut out = String::new();
+        self.collect_text(&mut out);
+        out
+    }
+
+    fn collect_text(&self, out: &mut String) {
+        for node in &self.nodes {
+            match node {
+                Node::Text(text) => out.push_str(text),
+                Node::Element(e) => e.collect_text(out),
+            }
+        }
+    }
+}
+
+/// Parse an XML document or fragment. The top-level nodes are the children of a
+/// nameless element, which is returned.
+pub(crate) fn parse(xml: &str, location: &str, names: Names) -> Result<Element> {
+    let mut reader = reader_for(xml);
+    reader.config_mut().trim_text(false);
+
+    // Prefix bindings in scope, innermost last; `""` is the default namespace.
+    let mut scopes: Vec<Vec<(String, String)>> = Vec::new();
+    let mut stack: Vec<Element> = vec![Element::default()];
+    // Open elements below the depth limit, read as text only.
+    let mut flattened = 0usize;
+
+    loop {
+        let event = reader
+            .read_event()
+            .map_err(|e| Error::xml_parse_with_context(e.to_string(), location))?;
+        match event {
+            Event::Start(_) if stack.len() > MAX_DEPTH => flattened += 1,
+            Event::Start(e) => {
+                scopes.push(namespace_bindings(&e));
+                stack.push(element(&e, &scopes, names));
+            }
+            Event::Empty(_) if stack.len() > MAX_DEPTH => {}
+            Event::Empty(e) => {
+                scopes.push(namespace_bindings(&e));
+                let element = element(&e, &scopes, names);
+                scopes.pop();
+                push_node(&mut stack, Node::Element(element));
+            }
+            Event::End(_) if flattened > 0 => flattened -= 1,
+            Event::End(_) => {
+                scopes.pop();
+                if stack.len() > 1 {
+                    let element = stack.pop().unwrap_or_default();
+                    push_node(&mut stack, Node::Element(element));
+                }
+            }
+            Event::Text(e) => push_text(&mut stack, &decode_text_lossy(&e)),
+            Event::GeneralRef(e) => push_text(&mut stack, &resolve_general_ref(&e)),
+            Event::CData(e) => push_text(&mut stack, &String::from_utf8_lossy(&e)),
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    // A truncated document leaves elements open; close them so what was read is kept.
+    while stack.len() > 1 {
+        let element = stack.pop().unwrap_or_default();
+        push_node(&mut stack, Node::Element(element));
+    }
+    Ok(stack.pop().unwrap_or_default())
+}
+
+fn push_node(stack: &mut [Element], node: Node) {
+    if let Some(parent) = stack.last_mut() {
+        parent.nodes.push(node);
+    }
+}
+
+fn push_text(stack: &mut [Element], text: &str) {
+    let Some(parent) = stack.last_mut() else {
+        return;
+    };
+    // Entity references arrive as separate events; keep one text node per run.
+    if let Some(Node::Text(last)) = parent.nodes.last_mut() {
+        last.push_str(text);
+    } else {
+        parent.nodes.push(Node::Text(text.to_string()));
+    }
+}
+
+fn namespace_bindings(e: &BytesStart<'_>) -> Vec<(String, String)> {
+    e.attributes()
+        .flatten()
+        .filter_map(|attr| {
+            let key = attr.key.as_ref();
+            let prefix = if key == b"xmlns" {
+                ""
+            } else {
+                std::str::from_utf8(key.strip_prefix(b"xmlns:")?).ok()?
+            };
+            Some((
+                prefix.to_string(),
+                String::from_utf8_lossy(&attr.value).into_owned(),
+            ))
+        })
+        .collect()
+}
+
+/// Write a qualified name the way `names` asks.
+fn name(
+    qname: &[u8],
+    scopes: &[Vec<(String, String)>],
+    names: Names,
+    is_attribute: bool,
+) -> String {
+    let qname = String::from_utf8_lossy(qname);
+    let (prefix, local) = match qname.split_once(':') {
+        Some((prefix, local)) => (prefix, local),
+        None if is_attribute => return qname.into_owned(),
+        None => ("", qname.as_ref()),
+    };
+    let namespaces = match names {
+        Names::Local => return local.to_string(),
+        Names::Canonical(namespaces) => namespaces,
+    };
+    let uri = scopes
+        .iter()
+        .rev()
+        .flat_map(|scope| scope.iter())
+        .find(|(bound, _)| bound == prefix)
+        .map(|(_, uri)| uri.as_str());
+    match uri.and_then(|uri| namespaces.iter().find(|(known, _)| *known == uri)) {
+        Some((_, canonical)) => format!("{canonical}:{local}"),
+        None => qname.into_owned(),
+    }
+}
+
+fn element(e: &BytesStart<'_>, scopes: &[Vec<(String, String)>], names: Names) -> Element {
+    let attributes = e
+        .attributes()
+        .flatten()
+        .filter(|attr| {
+            let key = attr.key.as_ref();
+            key != b"xmlns" && !key.starts_with(b"xmlns:")
+        })
+        .map(|attr| {
+            (
+                name(attr.key.as_ref(), scopes, names, true),
+                attr_value_lossy(&attr),
+            )
+        })
+        .collect();
+    Element {
+        name: name(e.name().as_ref(), scopes, names, false),
+        attributes,
+        nodes: Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_local_names() {
+        let root = parse(
+            r#"<m:r><m:t xml:space="preserve">x</m:t></m:r><m:r/>"#,
+            "",
+            Names::Local,
+        )
+        .unwrap();
+        assert_eq!(root.name, "");
+        assert_eq!(root.children_named("r").count(), 2);
+        let t = root.path(&["r", "t"]).unwrap();
+        assert_eq!(t.attr("space"), Some("preserve"));
+        assert_eq!(t.text(), "x");
+    }
+
+    #[test]
+    fn test_nesting_past_the_limit_is_read_as_text() {
+        let depth = 100_000;
+        let xml = format!("{}deep{}<b/>", "<a>".repeat(depth), "</a>".repeat(depth));
+        let root = parse(&xml, "", Names::Local).unwrap();
+
+        let mut element = &root;
+        let mut levels = 0;
+        while let Some(child) = element.child("a") {
+            element = child;
+            levels += 1;
+        }
+        assert_eq!(levels, MAX_DEPTH);
+        assert_eq!(element.text(), "deep");
+        // The elements after the deep ones are back at the top.
+        assert!(root.child("b").is_some());
+    }
+}
@@ -2,8 +2,8 @@
 //!
 //! Both an encrypted OOXML package and a legacy binary Office file arrive as an OLE/CFB
 //! container with an identical header, and the two answers send the caller in opposite
-//! directions: one supplies a password, the other looks for a converter. Reporting the
-//! disjunction leaves them to guess.
+//! directions: one supplies a password, the other is read by the matching legacy parser
+//! (or, without one, converted). Reporting the disjunction leaves them to guess.
 //!
 //! These go through the public entry points rather than the detection helper, because
 //! that is where a consumer meets the behaviour — `parse_bytes` runs detection first, so
@@ -42,21 +42,19 @@ fn parsing_an_ecma376_encrypted_package_reports_encrypted() {
 }
 
 /// The same header, the opposite answer — this is the pair the classification exists for.
+/// The legacy formats are all parsed by default now, so the CFB that is *not* encrypted
+/// is one that is not an Office document at all.
 #[test]
-fn parsing_a_legacy_binary_document_reports_an_unsupported_format() {
-    let doc = cfb_with_streams(&["/WordDocument"]);
+fn parsing_an_unrecognised_cfb_container_reports_an_unsupported_format() {
+    let other = cfb_with_streams(&["/Contents"]);
     let encrypted = cfb_with_streams(&["/EncryptedPackage"]);
 
-    assert_eq!(doc[..CFB_MAGIC.len()], CFB_MAGIC);
+    assert_eq!(other[..CFB_MAGIC.len()], CFB_MAGIC);
     assert_eq!(encrypted[..CFB_MAGIC.len()], CFB_MAGIC);
 
-    let err = parse_bytes(&doc).expect_err("a legacy binary document cannot be parsed");
+    let err = parse_bytes(&other).expect_err("a non-Office CFB container cannot be parsed");
 
     assert_eq!(err.kind(), ErrorKind::UnsupportedFormat, "got: {err}");
-    assert!(
-        err.to_string().contains("Word 97-2003"),
-        "the format should be named: {err}"
-    );
 }
 
 /// An unrecognised value must not be collapsed into a familiar one, and neither of these
@@ -0,0 +1,195 @@
+//! Macro-enabled, template and slide show packages parse as their base format and
+//! report which variant they are, both on the `Document` and in its JSON.
+
+use std::io::{Cursor, Write};
+
+use undoc::render::{to_json, to_text, JsonFormat, RenderOptions};
+use undoc::{parse_bytes, FormatType, FormatVariant};
+use zip::write::SimpleFileOptions;
+
+const R: &str = "http://schemas.openxmlformats.org/officeDocument/2006/relationships";
+
+fn package(main_part: &str, content_type: &str, parts: &[(&str, String)]) -> Vec<u8> {
+    let content_types = format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">
+<Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>
+<Default Extension="xml" ContentType="application/xml"/>
+<Override PartName="/{main_part}" ContentType="{content_type}"/>
+</Types>"#
+    );
+    let package_rels = rels(&[("rId1", "officeDocument", main_part)]);
+
+    let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+    let fixed = [
+        ("[Content_Types].xml", content_types),
+        ("_rels/.rels", package_rels),
+    ];
+    for (path, contents) in fixed.iter().chain(parts) {
+        zip.start_file(*path, options).unwrap();
+        zip.write_all(contents.as_bytes()).unwrap();
+    }
+    zip.finish().unwrap().into_inner()
+}
+
+fn rels(relationships: &[(&str, &str, &str)]) -> String {
+    let body: String = relationships
+        .iter()
+        .map(|(id, kind, target)| {
+            format!(r#"<Relationship Id="{id}" Type="{R}/{kind}" Target="{target}"/>"#)
+        })
+        .collect();
+    format!(
+        r#"<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">{body}</Relationships>"#
+    )
+}
+
+fn word(content_type: &str) -> Vec<u8> {
+    let document = r#"<w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main">
+<w:body><w:p><w:r><w:t>Hello</w:t></w:r></w:p></w:body></w:document>"#;
+    package(
+        "word/document.xml",
+        content_type,
+        &[
+            ("word/document.xml", document.to_string()),
+            ("word/_rels/document.xml.rels", rels(&[])),
+        ],
+    )
+}
+
+fn excel(content_type: &str) -> Vec<u8> {
+    let workbook = format!(
+        r#"<workbook xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main" xmlns:r="{R}">
+<sheets><sheet name="Data" sheetId="1" r:id="rId1"/></sheets></workbook>"#
+    );
+    let sheet = r#"<worksheet xmlns="http://schemas.openxmlformats.org/spreadsheetml/2006/main">
+<sheetData><row r="1"><c r="A1" t="inlineStr"><is><t>Hello</t></is></c></row></sheetData></worksheet>"#;
+    package(
+        "xl/workbook.xml",
+        content_type,
+        &[
+            ("xl/workbook.xml", workbook),
+            (
+                "xl/_rels/workbook.xml.rels",
+                rels(&[("rId1", "worksheet", "worksheets/sheet1.xml")]),
+            ),
+            ("xl/worksheets/sheet1.xml", sheet.to_string()),
+        ],
+    )
+}
+
+fn powerpoint(content_type: &str) -> Vec<u8> {
+    let presentation = format!(
+        r#"<p:presentation xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main" xmlns:r="{R}">
+<p:sldIdLst><p:sldId id="256" r:id="rId1"/></p:sldIdLst></p:presentation>"#
+    );
+    let slide = r#"<p:sld xmlns:p="http://schemas.openxmlformats.org/presentationml/2006/main" xmlns:a="http://schemas.openxmlformats.org/drawingml/2006/main">
+<p:cSld><p:spTree><p:sp><p:nvSpPr><p:cNvPr id="2" name="Text"/><p:cNvSpPr/><p:nvPr/></p:nvSpPr><p:spPr/>
+<p:txBody><a:bodyPr/><a:p><a:r><a:t>Hello</a:t></a:r></a:p></p:txBody></p:sp></p:spTree></p:cSld></p:sld>"#;
+    package(
+        "ppt/presentation.xml",
+        content_type,
+        &[
+            ("ppt/presentation.xml", presentation),
+            (
+                "ppt/_rels/presentation.xml.rels",
+                rels(&[("rId1", "slide", "slides/slide1.xml")]),
+            ),
+            ("ppt/slides/slide1.xml", slide.to_string()),
+        ],
+    )
+}
+
+fn assert_variant(data: &[u8], format: FormatType, variant: FormatVariant, name: &str) {
+    let doc = parse_bytes(data).unwrap();
+    assert_eq!(doc.format, format, "{name}");
+    assert_eq!(doc.variant, variant, "{name}");
+    let text = to_text(&doc, &RenderOptions::default()).unwrap();
+    assert!(text.contains("Hello"), "{name}: {text}");
+
+    let json: serde_json::Value =
+        serde_json::from_str(&to_json(&doc, JsonFormat::Compact).unwrap()).unwrap();
+    assert_eq!(json["format"], format.extension(), "{name}");
+    assert_eq!(
+        json["variant"],
+        serde_json::to_value(variant).unwrap(),
+        "{name}"
+    );
+}
+
+#[test]
+fn test_word_variants() {
+    assert_variant(
+        &word("application/vnd.openxmlformats-officedocument.wordprocessingml.document.main+xml"),
+        FormatType::Docx,
+        FormatVariant::Standard,
+        "docx",
+    );
+    assert_variant(
+        &word("application/vnd.ms-word.document.macroEnabled.main+xml"),
+        FormatType::Docx,
+        FormatVariant::MacroEnabled,
+        "docm",
+    );
+    assert_variant(
+        &word("application/vnd.openxmlformats-officedocument.wordprocessingml.template.main+xml"),
+        FormatType::Docx,
+        FormatVariant::Template,
+        "dotx",
+    );
+    assert_variant(
+        &word("application/vnd.ms-word.template.macroEnabledTemplate.main+xml"),
+        FormatType::Docx,
+        FormatVariant::MacroEnabledTemplate,
+        "dotm",
+    );
+}
+
+#[test]
+fn test_excel_variants() {
+    assert_variant(
+        &excel("application/vnd.ms-excel.sheet.macroEnabled.main+xml"),
+        FormatType::Xlsx,
+        FormatVariant::MacroEnabled,
+        "xlsm",
+    );
+    assert_variant(
+        &excel("application/vnd.openxmlformats-officedocument.spreadsheetml.template.main+xml"),
+        FormatType::Xlsx,
+        FormatVariant::Template,
+        "xltx",
+    );
+}
+
+#[test]
+fn test_powerpoint_variants() {
+    assert_variant(
+        &powerpoint("application/vnd.ms-powerpoint.presentation.macroEnabled.main+xml"),
+        FormatType::Pptx,
+        FormatVariant::MacroEnabled,
+        "pptm",
+    );
+    assert_variant(
+        &powerpoint(
+            "application/vnd.openxmlformats-officedocument.presentationml.template.main+xml",
+        ),
+        FormatType::Pptx,
+        FormatVariant::Template,
+        "potx",
+    );
+    assert_variant(
+        &powerpoint(
+            "application/vnd.openxmlformats-officedocument.presentationml.slideshow.main+xml",
+        ),
+        FormatType::Pptx,
+        FormatVariant::Slideshow,
+        "ppsx",
+    );
+}
+
+#[test]
+fn test_json_without_variant_reads_as_standard() {
+    let doc: undoc::Document = serde_json::from_str(r#"{"format":"docx","metadata":{}}"#).unwrap();
+    assert_eq!(doc.variant, FormatVariant::Standard);
+}
@@ -0,0 +1,356 @@
+//! Strict OOXML (ISO/IEC 29500 Strict) documents must parse exactly like Transitional ones.
+//!
+//! A Strict package keeps the content types, part names and element prefixes of its
+//! Transitional twin; what changes is every namespace URI (`http://purl.oclc.org/ooxml/`
+//! in place of `http://schemas.openxmlformats.org/`), every relationship type, and a
+//! few value vocabularies (`w:jc` says `end` rather than `right`, a date cell holds an
+//! ISO 8601 value rather than a serial number). Each fixture below is written once and
+//! rendered in both conformance classes, and the two parses are compared whole.
+
+use std::io::{Cursor, Write};
+
+use undoc::detect::{detect_format_from_bytes, FormatType};
+use undoc::{parse_bytes, Block, Document};
+use zip::write::SimpleFileOptions;
+
+#[derive(Clone, Copy, Debug)]
+enum Conformance {
+    Transitional,
+    Strict,
+}
+
+impl Conformance {
+    /// Namespace of a markup vocabulary such as `wordprocessingml`.
+    fn ns(self, vocabulary: &str) -> String {
+        match self {
+            Conformance::Transitional => {
+                format!("http://schemas.openxmlformats.org/{vocabulary}/2006/main")
+            }
+            Conformance::Strict => format!("http://purl.oclc.org/ooxml/{vocabulary}/main"),
+        }
+    }
+
+    /// Namespace of the `r:` attributes.
+    fn r(self) -> &'static str {
+        match self {
+            Conformance::Transitional => {
+                "http://schemas.openxmlformats.org/officeDocument/2006/relationships"
+            }
+            Conformance::Strict => "http://purl.oclc.org/ooxml/officeDocument/relationships",
+        }
+    }
+
+    /// A `Relationship` element of the given type.
+    fn rel(self, id: &str, kind: &str, target: &str) -> String {
+        let mode = if target.starts_with("http") {
+            r#" TargetMode="External""#
+        } else {
+            ""
+        };
+        format!(
+            r#"<Relationship Id="{id}" Type="{}/{kind}" Target="{target}"{mode}/>"#,
+            self.r()
+        )
+    }
+
+    /// The root element attribute declaring the conformance class; absent means Transitional.
+    fn attribute(self) -> &'static str {
+        match self {
+            Conformance::Transitional => "",
+            Conformance::Strict => r#"conformance="strict""#,
+        }
+    }
+}
+
+fn rels(relationships: &[String]) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Relationships xmlns="http://schemas.openxmlformats.org/package/2006/relationships">{}</Relationships>"#,
+        relationships.concat()
+    )
+}
+
+fn content_types(part: &str, content_type: &str) -> String {
+    format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<Types xmlns="http://schemas.openxmlformats.org/package/2006/content-types">
+<Default Extension="rels" ContentType="application/vnd.openxmlformats-package.relationships+xml"/>
+<Default Extension="xml" ContentType="application/xml"/>
+<Override PartName="{part}" ContentType="{content_type}"/>
+</Types>"#
+    )
+}
+
+fn package(parts: &[(&str, String)]) -> Vec<u8> {
+    let mut zip = zip::ZipWriter::new(Cursor::new(Vec::new()));
+    let options = SimpleFileOptions::default().compression_method(zip::CompressionMethod::Stored);
+    for (path, contents) in parts {
+        zip.start_file(*path, options).unwrap();
+        zip.write_all(contents.as_bytes()).unwrap();
+    }
+    zip.finish().unwrap().into_inner()
+}
+
+fn docx(c: Conformance) -> Vec<u8> {
+    let w = c.ns("wordprocessingml");
+    let right = match c {
+        Conformance::Transitional => "right",
+        Conformance::Strict => "end",
+    };
+    let document = format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<w:document xmlns:w="{w}" xmlns:r="{r}" {conformance}><w:body>
+<w:p><w:pPr><w:pStyle w:val="Heading1"/></w:pPr><w:r><w:t>Quarterly report</w:t></w:r></w:p>
+<w:p><w:pPr><w:jc w:val="{right}"/></w:pPr><w:r><w:rPr><w:b/></w:rPr><w:t>Signed</w:t></w:r></w:p>
+<w:p><w:hyperlink r:id="rId2"><w:r><w:t>Site</w:t></w:r></w:hyperlink></w:p>
+<w:tbl><w:tr>
+<w:tc><w:p><w:r><w:t>A</w:t></w:r></w:p></w:tc>
+<w:tc><w:p><w:r><w:t>B</w:t></w:r></w:p></w:tc>
+</w:tr></w:tbl>
+</w:body></w:document>"#,
+        r = c.r(),
+        conformance = c.attribute(),
+    );
+    let styles = format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<w:styles xmlns:w="{w}"><w:style w:type="paragraph" w:styleId="Heading1">
+<w:name w:val="heading 1"/><w:pPr><w:outlineLvl w:val="0"/></w:pPr></w:style></w:styles>"#
+    );
+    package(&[
+        (
+            "[Content_Types].xml",
+            content_types(
+                "/word/document.xml",
+                "application/vnd.openxmlformats-officedocument.wordprocessingml.document.main+xml",
+            ),
+        ),
+        (
+            "_rels/.rels",
+            rels(&[c.rel("rId1", "officeDocument", "word/document.xml")]),
+        ),
+        ("word/document.xml", document),
+        ("word/styles.xml", styles),
+        (
+            "word/_rels/document.xml.rels",
+            rels(&[
+                c.rel("rId1", "styles", "styles.xml"),
+                c.rel("rId2", "hyperlink", "https://example.com/"),
+            ]),
+        ),
+    ])
+}
+
+fn xlsx(c: Conformance) -> Vec<u8> {
+    let s = c.ns("spreadsheetml");
+    let date = match c {
+        Conformance::Transitional => r#"<c r="B2" s="1"><v>44197</v></c>"#,
+        Conformance::Strict => r#"<c r="B2" s="1" t="d"><v>2021-01-01T00:00:00</v></c>"#,
+    };
+    let workbook = format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<workbook xmlns="{s}" xmlns:r="{r}" {conformance}>
+<sheets><sheet name="Sales" sheetId="1" r:id="rId1"/></sheets></workbook>"#,
+        r = c.r(),
+        conformance = c.attribute(),
+    );
+    let shared_strings = format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<sst xmlns="{s}" count="3" uniqueCount="3">
+<si><t>Region</t></si><si><t>Since</t></si><si><t>North</t></si></sst>"#
+    );
+    let styles = format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<styleSheet xmlns="{s}"><cellXfs count="2"><xf numFmtId="0"/><xf numFmtId="14"/></cellXfs></styleSheet>"#
+    );
+    let sheet = format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<worksheet xmlns="{s}" xmlns:r="{r}"><sheetData>
+<row r="1"><c r="A1" t="s"><v>0</v></c><c r="B1" t="s"><v>1</v></c></row>
+<row r="2"><c r="A2" t="s"><v>2</v></c>{date}</row>
+</sheetData>
+<hyperlinks><hyperlink ref="A2" r:id="rId1"/></hyperlinks></worksheet>"#,
+        r = c.r(),
+    );
+    package(&[
+        (
+            "[Content_Types].xml",
+            content_types(
+                "/xl/workbook.xml",
+                "application/vnd.openxmlformats-officedocument.spreadsheetml.sheet.main+xml",
+            ),
+        ),
+        (
+            "_rels/.rels",
+            rels(&[c.rel("rId1", "officeDocument", "xl/workbook.xml")]),
+        ),
+        ("xl/workbook.xml", workbook),
+        (
+            "xl/_rels/workbook.xml.rels",
+            rels(&[
+                c.rel("rId1", "worksheet", "worksheets/sheet1.xml"),
+                c.rel("rId2", "sharedStrings", "sharedStrings.xml"),
+                c.rel("rId3", "styles", "styles.xml"),
+            ]),
+        ),
+        ("xl/sharedStrings.xml", shared_strings),
+        ("xl/styles.xml", styles),
+        ("xl/worksheets/sheet1.xml", sheet),
+        (
+            "xl/worksheets/_rels/sheet1.xml.rels",
+            rels(&[c.rel("rId1", "hyperlink", "https://example.com/north")]),
+        ),
+    ])
+}
+
+fn pptx(c: Conformance) -> Vec<u8> {
+    let p = c.ns("presentationml");
+    let a = c.ns("drawingml");
+    let shape = |id: u32, ph: &str, text: &str| {
+        let body = if text.is_empty() {
+            "<a:p/>".to_string()
+        } else {
+            format!("<a:p><a:r><a:t>{text}</a:t></a:r></a:p>")
+        };
+        format!(
+            r#"<p:sp><p:nvSpPr><p:cNvPr id="{id}" name="{ph}"/><p:cNvSpPr/><p:nvPr><p:ph type="{ph}"/></p:nvPr></p:nvSpPr>
+<p:spPr/><p:txBody><a:bodyPr/>{body}</p:txBody></p:sp>"#
+        )
+    };
+    let slide_part = |root: &str, shapes: String, conformance: &str| {
+        format!(
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<p:{root} xmlns:p="{p}" xmlns:a="{a}" xmlns:r="{r}" {conformance}><p:cSld><p:spTree>{shapes}</p:spTree></p:cSld></p:{root}>"#,
+            r = c.r(),
+        )
+    };
+    let presentation = format!(
+        r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<p:presentation xmlns:p="{p}" xmlns:r="{r}" {conformance}>
+<p:sldIdLst><p:sldId id="256" r:id="rId1"/></p:sldIdLst></p:presentation>"#,
+        r = c.r(),
+        conformance = c.attribute(),
+    );
+    // The slide leaves its body and subtitle placeholders empty: the body text comes
+    // from the layout and the subtitle from the master, each found through a typed relationship.
+    let slide = slide_part(
+        "sld",
+        [
+            shape(2, "title", "Roadmap"),
+            shape(3, "body", ""),
+            shape(4, "subTitle", ""),
+        ]
+        .concat(),
+        "",
+    );
+    let layout = slide_part("sldLayout", shape(2, "body", "Layout body text"), "");
+    let master = slide_part("sldMaster", shape(2, "subTitle", "Master subtitle"), "");
+    package(&[
+        (
+            "[Content_Types].xml",
+            content_types(
+                "/ppt/presentation.xml",
+                "application/vnd.openxmlformats-officedocument.presentationml.presentation.main+xml",
+            ),
+        ),
+        (
+            "_rels/.rels",
+            rels(&[c.rel("rId1", "officeDocument", "ppt/presentation.xml")]),
+        ),
+        ("ppt/presentation.xml", presentation),
+        (
+            "ppt/_rels/presentation.xml.rels",
+            rels(&[c.rel("rId1", "slide", "slides/slide1.xml")]),
+        ),
+        ("ppt/slides/slide1.xml", slide),
+        (
+            "ppt/slides/_rels/slide1.xml.rels",
+            rels(&[c.rel(
+                "rId1",
+                "slideLayout",
+                "../slideLayouts/slideLayout1.xml",
+            )]),
+        ),
+        ("ppt/slideLayouts/slideLayout1.xml", layout),
+        (
+            "ppt/slideLayouts/_rels/slideLayout1.xml.rels",
+            rels(&[c.rel(
+                "rId1",
+                "slideMaster",
+                "../slideMasters/slideMaster1.xml",
+            )]),
+        ),
+        ("ppt/slideMasters/slideMaster1.xml", master),
+    ])
+}
+
+/// Parse both conformance classes of a fixture and require identical documents.
+fn parse_both(build: fn(Conformance) -> Vec<u8>, format: FormatType) -> Document {
+    let transitional = build(Conformance::Transitional);
+    let strict = build(Conformance::Strict);
+    assert_eq!(detect_format_from_bytes(&transitional).unwrap(), format);
+    assert_eq!(detect_format_from_bytes(&strict).unwrap(), format);
+
+    let transitional = parse_bytes(&transitional).unwrap();
+    let strict = parse_bytes(&strict).unwrap();
+    assert_eq!(
+        serde_json::to_value(&strict).unwrap(),
+        serde_json::to_value(&transitional).unwrap(),
+        "Strict and Transitional parses differ"
+    );
+    strict
+}
+
+#[test]
+fn strict_docx_parses_like_transitional() {
+    let doc = parse_both(docx, FormatType::Docx);
+    let content = &doc.sections[0].content;
+
+    let Block::Paragraph(heading) = &content[0] else {
+        panic!("expected the heading first, got {:?}", content[0]);
+    };
+    assert_eq!(heading.plain_text(), "Quarterly report");
+    assert_eq!(heading.heading, undoc::HeadingLevel::H1);
+    let Block::Paragraph(signed) = &content[1] else {
+        panic!("expected a paragraph, got {:?}", content[1]);
+    };
+    assert_eq!(signed.alignment, undoc::TextAlignment::Right);
+    assert!(signed.runs[0].style.bold);
+    let Block::Paragraph(link) = &content[2] else {
+        panic!("expected a paragraph, got {:?}", content[2]);
+    };
+    assert_eq!(
+        link.runs[0].hyperlink.as_deref(),
+        Some("https://example.com/")
+    );
+    assert!(matches!(&content[3], Block::Table(t) if t.rows[0].cells.len() == 2));
+}
+
+#[test]
+fn strict_xlsx_parses_like_transitional() {
+    let doc = parse_both(xlsx, FormatType::Xlsx);
+    assert_eq!(doc.sections[0].name.as_deref(), Some("Sales"));
+    let Block::Table(table) = &doc.sections[0].content[0] else {
+        panic!("expected a table, got {:?}", doc.sections[0].content);
+    };
+    let row: Vec<String> = table.rows[1].cells.iter().map(|c| c.plain_text()).collect();
+    assert_eq!(row, ["North", "2021-01-01"]);
+    let north = &table.rows[1].cells[0].content[0].runs[0];
+    assert_eq!(
+        north.hyperlink.as_deref(),
+        Some("https://example.com/north")
+    );
+}
+
+#[test]
+fn strict_pptx_parses_like_transitional() {
+    let doc = parse_both(pptx, FormatType::Pptx);
+    let texts: Vec<String> = doc.sections[0]
+        .content
+        .iter()
+        .filter_map(|b| match b {
+            Block::Paragraph(p) => Some(p.plain_text()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(texts, ["Roadmap", "Layout body text", "Master subtitle"]);
+}